@@ -0,0 +1,45 @@
+//! Buffered against streaming parsing of a year of hourly data for 50 variables
+
+use std::fmt::Write;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use weathered::ForecastResponse;
+
+fn body() -> String {
+    const HOURS: i64 = 365 * 24;
+    let mut body = String::from(
+        r#"{"latitude":52.52,"longitude":13.41,"elevation":38.0,"generationtime_ms":1.0,"utc_offset_seconds":0,"timezone":"GMT","timezone_abbreviation":"GMT","hourly":{"time":["#,
+    );
+    for hour in 0..HOURS {
+        let separator = if hour == 0 { "" } else { "," };
+        write!(body, "{separator}{}", 1_672_531_200 + hour * 3600).unwrap();
+    }
+    body.push(']');
+    for variable in 0..50 {
+        write!(body, r#","variable_{variable}":["#).unwrap();
+        for hour in 0..HOURS {
+            let separator = if hour == 0 { "" } else { "," };
+            write!(body, "{separator}{}.{}", hour % 40, variable % 10).unwrap();
+        }
+        body.push(']');
+    }
+    body.push_str("}}");
+    body
+}
+
+fn parse(c: &mut Criterion) {
+    let body = body();
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    group.sample_size(10);
+    group.bench_function("from_json", |b| {
+        b.iter(|| ForecastResponse::from_json(&body).unwrap())
+    });
+    group.bench_function("from_reader", |b| {
+        b.iter(|| ForecastResponse::from_reader(body.as_bytes()).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,404 @@
+use std::fmt;
+
+use strum_macros::Display;
+
+use crate::{push_list, Coordinates, Endpoint, Forecast, NoCoordinates, Settings};
+
+pub(crate) const AIR_QUALITY_URL: &str = "https://air-quality-api.open-meteo.com/v1/air-quality";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[allow(non_camel_case_types)]
+/// Enumerate the hourly variables of the air quality API
+pub enum AirQualityVar {
+    pm10,
+    pm2_5,
+    carbon_monoxide,
+    nitrogen_dioxide,
+    sulphur_dioxide,
+    ozone,
+    aerosol_optical_depth,
+    dust,
+    uv_index,
+    uv_index_clear_sky,
+    ammonia,
+    european_aqi,
+    european_aqi_pm2_5,
+    european_aqi_pm10,
+    european_aqi_nitrogen_dioxide,
+    european_aqi_ozone,
+    european_aqi_sulphur_dioxide,
+    us_aqi,
+    us_aqi_pm2_5,
+    us_aqi_pm10,
+    us_aqi_nitrogen_dioxide,
+    us_aqi_ozone,
+    us_aqi_sulphur_dioxide,
+    us_aqi_carbon_monoxide,
+    /// Pollen, only served by the European domain
+    alder_pollen,
+    birch_pollen,
+    grass_pollen,
+    mugwort_pollen,
+    olive_pollen,
+    ragweed_pollen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[allow(non_camel_case_types)]
+/// Enumerate the CAMS domains serving air quality data
+pub enum Domain {
+    /// Europe where covered, the global domain elsewhere
+    auto,
+    cams_global,
+    cams_europe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// Enumerate the bands of the European Air Quality Index
+pub enum EuropeanAqiBand {
+    Good,
+    Fair,
+    Moderate,
+    Poor,
+    VeryPoor,
+    ExtremelyPoor,
+}
+
+impl EuropeanAqiBand {
+    /// Band of an index value: good up to 20, then one band per 20 up to
+    /// extremely poor above 100; `None` for negative or NaN values
+    pub fn from_value(value: f64) -> Option<Self> {
+        use EuropeanAqiBand::*;
+
+        Some(match value {
+            value if value.is_nan() || value < 0.0 => return None,
+            value if value <= 20.0 => Good,
+            value if value <= 40.0 => Fair,
+            value if value <= 60.0 => Moderate,
+            value if value <= 80.0 => Poor,
+            value if value <= 100.0 => VeryPoor,
+            _ => ExtremelyPoor,
+        })
+    }
+
+    /// Color of the band on EEA maps, as `#RRGGBB`
+    pub fn color(self) -> &'static str {
+        match self {
+            EuropeanAqiBand::Good => "#50F0E6",
+            EuropeanAqiBand::Fair => "#50CCAA",
+            EuropeanAqiBand::Moderate => "#F0E641",
+            EuropeanAqiBand::Poor => "#FF5050",
+            EuropeanAqiBand::VeryPoor => "#960032",
+            EuropeanAqiBand::ExtremelyPoor => "#7D2181",
+        }
+    }
+
+    /// Name of the band in English
+    pub fn description(self) -> &'static str {
+        match self {
+            EuropeanAqiBand::Good => "Good",
+            EuropeanAqiBand::Fair => "Fair",
+            EuropeanAqiBand::Moderate => "Moderate",
+            EuropeanAqiBand::Poor => "Poor",
+            EuropeanAqiBand::VeryPoor => "Very poor",
+            EuropeanAqiBand::ExtremelyPoor => "Extremely poor",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// Enumerate the categories of the US EPA Air Quality Index
+pub enum UsAqiBand {
+    Good,
+    Moderate,
+    UnhealthyForSensitiveGroups,
+    Unhealthy,
+    VeryUnhealthy,
+    Hazardous,
+}
+
+impl UsAqiBand {
+    /// Category of an index value: good up to 50, moderate up to 100, then
+    /// one category per 50 up to 200, very unhealthy up to 300 and hazardous
+    /// above; `None` for negative or NaN values
+    pub fn from_value(value: f64) -> Option<Self> {
+        use UsAqiBand::*;
+
+        Some(match value {
+            value if value.is_nan() || value < 0.0 => return None,
+            value if value <= 50.0 => Good,
+            value if value <= 100.0 => Moderate,
+            value if value <= 150.0 => UnhealthyForSensitiveGroups,
+            value if value <= 200.0 => Unhealthy,
+            value if value <= 300.0 => VeryUnhealthy,
+            _ => Hazardous,
+        })
+    }
+
+    /// Color of the category on AirNow maps, as `#RRGGBB`
+    pub fn color(self) -> &'static str {
+        match self {
+            UsAqiBand::Good => "#00E400",
+            UsAqiBand::Moderate => "#FFFF00",
+            UsAqiBand::UnhealthyForSensitiveGroups => "#FF7E00",
+            UsAqiBand::Unhealthy => "#FF0000",
+            UsAqiBand::VeryUnhealthy => "#8F3F97",
+            UsAqiBand::Hazardous => "#7E0023",
+        }
+    }
+
+    /// Name of the category in English
+    pub fn description(self) -> &'static str {
+        match self {
+            UsAqiBand::Good => "Good",
+            UsAqiBand::Moderate => "Moderate",
+            UsAqiBand::UnhealthyForSensitiveGroups => "Unhealthy for sensitive groups",
+            UsAqiBand::Unhealthy => "Unhealthy",
+            UsAqiBand::VeryUnhealthy => "Very unhealthy",
+            UsAqiBand::Hazardous => "Hazardous",
+        }
+    }
+}
+
+#[derive(Clone)]
+/// Air quality request, sharing the coordinates and settings of a `Forecast`
+pub struct AirQuality<C = Coordinates> {
+    forecast: Forecast<C>,
+    hourly: Vec<AirQualityVar>,
+    domains: Option<Domain>,
+}
+
+impl AirQuality<NoCoordinates> {
+    /// Initialize AirQuality object
+    pub fn new() -> Self {
+        AirQuality {
+            forecast: Forecast::new().endpoint(Endpoint::AirQuality),
+            hourly: Vec::new(),
+            domains: None,
+        }
+    }
+}
+
+impl Default for AirQuality<NoCoordinates> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> AirQuality<C> {
+    /// Specify coordinates (latitude, longitude)
+    pub fn coord(self, latitude: f32, longitude: f32) -> AirQuality<Coordinates> {
+        AirQuality {
+            forecast: self.forecast.coord(latitude, longitude),
+            hourly: self.hourly,
+            domains: self.domains,
+        }
+    }
+
+    /// Add optional settings
+    pub fn settings(mut self, setting: Settings) -> Self {
+        self.forecast = self.forecast.settings(setting);
+        self
+    }
+
+    /// Get hourly value for a specific pollutant or index
+    pub fn hourly(mut self, hourly: AirQualityVar) -> Self {
+        self.hourly.push(hourly);
+        self
+    }
+
+    /// Select the domain of the data, `auto` by default
+    pub fn domains(mut self, domains: Domain) -> Self {
+        self.domains = Some(domains);
+        self
+    }
+}
+
+impl AirQuality<Coordinates> {
+    /// Convert the request into a valid URL against the given base
+    pub(crate) fn to_url(&self, base: &str) -> String {
+        let mut url = self.forecast.to_url(base);
+        push_list(&mut url, "hourly", &self.hourly);
+        if let Some(domains) = self.domains {
+            url.push_str(&format!("&domains={domains}"));
+        }
+        url
+    }
+}
+
+impl fmt::Display for AirQuality<Coordinates> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_url(AIR_QUALITY_URL))
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl crate::EndpointRequest for AirQuality<Coordinates> {
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::AirQuality
+    }
+
+    fn to_url(&self, base: &str) -> String {
+        AirQuality::to_url(self, base)
+    }
+}
+
+#[cfg(feature = "response")]
+impl crate::ForecastResponse {
+    /// Values of an air quality variable, `None` if the response does not
+    /// contain it
+    pub fn air_quality(&self, variable: AirQualityVar) -> Option<&crate::Series> {
+        self.hourly_by_name(&variable.to_string())
+    }
+
+    /// Bands of the `european_aqi` series, `None` if the response does not
+    /// contain it
+    pub fn european_aqi_bands(&self) -> Option<Vec<Option<EuropeanAqiBand>>> {
+        let series = self.air_quality(AirQualityVar::european_aqi)?;
+        Some(
+            series
+                .iter()
+                .map(|value| EuropeanAqiBand::from_value((*value)?))
+                .collect(),
+        )
+    }
+
+    /// Categories of the `us_aqi` series, `None` if the response does not
+    /// contain it
+    pub fn us_aqi_bands(&self) -> Option<Vec<Option<UsAqiBand>>> {
+        let series = self.air_quality(AirQualityVar::us_aqi)?;
+        Some(
+            series
+                .iter()
+                .map(|value| UsAqiBand::from_value((*value)?))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn url_creation() {
+        let request = AirQuality::new()
+            .coord(52.52, 13.41)
+            .hourly(AirQualityVar::pm10)
+            .hourly(AirQualityVar::pm2_5)
+            .hourly(AirQualityVar::european_aqi)
+            .domains(Domain::cams_europe)
+            .settings(Settings::forecast_days(3));
+        assert_eq!(
+            request.to_string(),
+            "https://air-quality-api.open-meteo.com/v1/air-quality?latitude=52.52&longitude=13.41&forecast_days=3&hourly=,pm10,pm2_5,european_aqi&domains=cams_europe"
+        );
+    }
+
+    #[cfg(feature = "response")]
+    #[test]
+    fn fixture() {
+        let response =
+            crate::ForecastResponse::from_json(include_str!("../fixtures/air_quality.json"))
+                .unwrap();
+        let aqi = response.air_quality(AirQualityVar::european_aqi).unwrap();
+        assert_eq!(aqi.values(), [Some(38.0), Some(41.0), Some(44.0), None]);
+        let pm2_5 = response.air_quality(AirQualityVar::pm2_5).unwrap();
+        assert_eq!(pm2_5[2], None);
+        assert_eq!(pm2_5[3], Some(9.7));
+        assert_eq!(
+            response
+                .hourly_units
+                .as_ref()
+                .unwrap()
+                .get(AirQualityVar::pm2_5),
+            Some("μg/m³")
+        );
+        assert!(response.air_quality(AirQualityVar::ozone).is_none());
+
+        assert_eq!(
+            response.european_aqi_bands().unwrap(),
+            [
+                Some(EuropeanAqiBand::Fair),
+                Some(EuropeanAqiBand::Moderate),
+                Some(EuropeanAqiBand::Moderate),
+                None
+            ]
+        );
+        assert_eq!(response.us_aqi_bands(), None);
+    }
+
+    #[test]
+    fn pollen() {
+        let request = AirQuality::new()
+            .coord(48.14, 11.58)
+            .hourly(AirQualityVar::birch_pollen)
+            .hourly(AirQualityVar::grass_pollen)
+            .hourly(AirQualityVar::ragweed_pollen)
+            .domains(Domain::cams_europe);
+        assert_eq!(
+            request.to_string(),
+            "https://air-quality-api.open-meteo.com/v1/air-quality?latitude=48.14&longitude=11.58&hourly=,birch_pollen,grass_pollen,ragweed_pollen&domains=cams_europe"
+        );
+    }
+
+    #[test]
+    fn european_bands() {
+        use EuropeanAqiBand::*;
+
+        let bands: Vec<_> = [0.0, 20.0, 20.5, 40.0, 60.0, 79.9, 100.0, 100.1, 500.0]
+            .into_iter()
+            .map(EuropeanAqiBand::from_value)
+            .collect();
+        assert_eq!(
+            bands,
+            [
+                Some(Good),
+                Some(Good),
+                Some(Fair),
+                Some(Fair),
+                Some(Moderate),
+                Some(Poor),
+                Some(VeryPoor),
+                Some(ExtremelyPoor),
+                Some(ExtremelyPoor)
+            ]
+        );
+        assert_eq!(EuropeanAqiBand::from_value(-1.0), None);
+        assert_eq!(EuropeanAqiBand::from_value(f64::NAN), None);
+        assert_eq!(Fair.color(), "#50CCAA");
+        assert_eq!(VeryPoor.description(), "Very poor");
+        assert!(Good < ExtremelyPoor);
+    }
+
+    #[test]
+    fn us_bands() {
+        use UsAqiBand::*;
+
+        let bands: Vec<_> = [50.0, 51.0, 100.0, 101.0, 150.0, 200.0, 201.0, 300.0, 301.0]
+            .into_iter()
+            .map(|value| UsAqiBand::from_value(value).unwrap())
+            .collect();
+        assert_eq!(
+            bands,
+            [
+                Good,
+                Moderate,
+                Moderate,
+                UnhealthyForSensitiveGroups,
+                UnhealthyForSensitiveGroups,
+                Unhealthy,
+                VeryUnhealthy,
+                VeryUnhealthy,
+                Hazardous
+            ]
+        );
+        assert_eq!(UnhealthyForSensitiveGroups.color(), "#FF7E00");
+        assert_eq!(Hazardous.color(), "#7E0023");
+        assert_eq!(
+            UnhealthyForSensitiveGroups.description(),
+            "Unhealthy for sensitive groups"
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use crate::units::convert;
+use crate::{Daily, ForecastResponse, Hourly, Timestamp};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Forecast variable checked by an `AlertRule`
+pub enum Variable {
+    Hourly(Hourly),
+    Daily(Daily),
+}
+
+impl From<Hourly> for Variable {
+    fn from(variable: Hourly) -> Self {
+        Variable::Hourly(variable)
+    }
+}
+
+impl From<Daily> for Variable {
+    fn from(variable: Daily) -> Self {
+        Variable::Daily(variable)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate comparisons of forecast values against a threshold
+pub enum Comparison {
+    Above,
+    AtLeast,
+    Below,
+    AtMost,
+}
+
+impl Comparison {
+    pub(crate) fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Above => value > threshold,
+            Comparison::AtLeast => value >= threshold,
+            Comparison::Below => value < threshold,
+            Comparison::AtMost => value <= threshold,
+        }
+    }
+
+    /// Whether `value` is further past the threshold than `peak`
+    pub(crate) fn beyond(self, value: f64, peak: f64) -> bool {
+        match self {
+            Comparison::Above | Comparison::AtLeast => value > peak,
+            Comparison::Below | Comparison::AtMost => value < peak,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Condition on a forecast variable, e.g. gusts above 60 km/h for 2 hours
+pub struct AlertRule {
+    variable: Variable,
+    comparison: Comparison,
+    threshold: f64,
+    unit: Option<String>,
+    window: Option<(Timestamp, Timestamp)>,
+    consecutive: usize,
+}
+
+impl AlertRule {
+    /// Trigger on any sample of `variable` for which `comparison` holds against `threshold`
+    pub fn new(variable: impl Into<Variable>, comparison: Comparison, threshold: f64) -> Self {
+        AlertRule {
+            variable: variable.into(),
+            comparison,
+            threshold,
+            unit: None,
+            window: None,
+            consecutive: 1,
+        }
+    }
+
+    /// Unit of the threshold as spelled by the API, e.g. `km/h` or `°F`,
+    /// converted to the unit of the response; defaults to the latter
+    pub fn unit(mut self, unit: &str) -> Self {
+        self.unit = Some(unit.to_string());
+        self
+    }
+
+    /// Only consider samples from `start` to `end` included
+    pub fn between(mut self, start: Timestamp, end: Timestamp) -> Self {
+        self.window = Some((start, end));
+        self
+    }
+
+    /// Only trigger when the condition holds for at least `samples`
+    /// consecutive hours or days; a missing value ends the run
+    pub fn consecutive(mut self, samples: usize) -> Self {
+        self.consecutive = samples.max(1);
+        self
+    }
+
+    pub fn variable(&self) -> &Variable {
+        &self.variable
+    }
+
+    /// Matches of the rule against a response, one per run of consecutive samples
+    fn evaluate(&self, response: &ForecastResponse) -> Vec<AlertMatch> {
+        let (name, series, units) = match &self.variable {
+            Variable::Hourly(variable) => {
+                let name = variable.to_string();
+                let series = response
+                    .hourly
+                    .as_ref()
+                    .and_then(|hourly| Some((hourly.time.as_slice(), hourly.get(&name)?.clone())));
+                (name, series, &response.hourly_units)
+            }
+            Variable::Daily(variable) => {
+                let name = variable.to_string();
+                let series = response
+                    .daily
+                    .as_ref()
+                    .and_then(|daily| Some((daily.time.as_slice(), daily.numbers(&name)?)));
+                (name, series, &response.daily_units)
+            }
+        };
+        let Some((time, values)) = series else {
+            return Vec::new();
+        };
+        let unit = units
+            .as_ref()
+            .and_then(|units| units.get(&name))
+            .map(str::to_string);
+        let threshold = match (&self.unit, &unit) {
+            (Some(from), Some(to)) => match convert(self.threshold, from, to) {
+                Some(threshold) => threshold,
+                // Comparing a speed against a temperature would be meaningless
+                None => return Vec::new(),
+            },
+            _ => self.threshold,
+        };
+
+        let mut matches = Vec::new();
+        let mut run: Vec<(&Timestamp, f64)> = Vec::new();
+        for (time, value) in time.iter().zip(values) {
+            let in_window = self
+                .window
+                .as_ref()
+                .is_none_or(|(start, end)| start <= time && time <= end);
+            match value {
+                Some(value) if in_window && self.comparison.holds(value, threshold) => {
+                    run.push((time, value));
+                    continue;
+                }
+                _ => {}
+            }
+            self.close(&mut run, &unit, &mut matches);
+        }
+        self.close(&mut run, &unit, &mut matches);
+        matches
+    }
+
+    /// Turn a run of matching samples into a match if it is long enough
+    fn close(
+        &self,
+        run: &mut Vec<(&Timestamp, f64)>,
+        unit: &Option<String>,
+        matches: &mut Vec<AlertMatch>,
+    ) {
+        if run.len() >= self.consecutive {
+            let peak = run.iter().map(|(_, value)| *value).reduce(|peak, value| {
+                match self.comparison.beyond(value, peak) {
+                    true => value,
+                    false => peak,
+                }
+            });
+            if let Some(peak) = peak {
+                matches.push(AlertMatch {
+                    rule: self.clone(),
+                    timestamps: run.iter().map(|(time, _)| (*time).clone()).collect(),
+                    peak,
+                    unit: unit.clone(),
+                });
+            }
+        }
+        run.clear();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Run of consecutive samples triggering an `AlertRule`
+pub struct AlertMatch {
+    pub rule: AlertRule,
+    pub timestamps: Vec<Timestamp>,
+    /// Most extreme value of the run, in the unit of the response
+    pub peak: f64,
+    pub unit: Option<String>,
+}
+
+impl ForecastResponse {
+    /// Check the rules against the forecast, returning matches rule by rule
+    /// in chronological order
+    pub fn evaluate(&self, rules: &[AlertRule]) -> Vec<AlertMatch> {
+        rules.iter().flat_map(|rule| rule.evaluate(self)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../fixtures/alerts.json");
+
+    fn response() -> ForecastResponse {
+        serde_json::from_str(FIXTURE).unwrap()
+    }
+
+    fn hour(hour: u32) -> Timestamp {
+        Timestamp::Iso8601(format!("2023-10-20T{hour:02}:00"))
+    }
+
+    #[test]
+    fn gust_spike() {
+        let gusts = AlertRule::new(Hourly::windgusts_10m, Comparison::Above, 60.0);
+
+        let matches = response().evaluate(std::slice::from_ref(&gusts));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].rule, gusts);
+        assert_eq!(matches[0].timestamps, [hour(4), hour(5)]);
+        assert_eq!(matches[0].peak, 71.3);
+        assert_eq!(matches[0].unit.as_deref(), Some("km/h"));
+
+        // 16 m/s is 57.6 km/h
+        let in_ms = AlertRule::new(Hourly::windgusts_10m, Comparison::Above, 16.0).unit("m/s");
+        assert_eq!(
+            response().evaluate(&[in_ms])[0].timestamps,
+            [hour(4), hour(5)]
+        );
+        let later = gusts.clone().between(hour(5), hour(11));
+        assert_eq!(response().evaluate(&[later])[0].timestamps, [hour(5)]);
+        assert!(response().evaluate(&[gusts.unit("°C")]).is_empty());
+        let daily = AlertRule::new(Daily::windgusts_10m_max, Comparison::AtLeast, 70.0);
+        assert_eq!(response().evaluate(&[daily])[0].peak, 71.3);
+    }
+
+    #[test]
+    fn sustained_rain() {
+        let rain = AlertRule::new(Hourly::rain, Comparison::AtLeast, 1.0).consecutive(3);
+        let matches = response().evaluate(&[rain]);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].timestamps, [hour(6), hour(7), hour(8), hour(9)]);
+        assert_eq!(matches[0].peak, 3.4);
+
+        // Short wet spells and calm hours do not count
+        let drizzle = AlertRule::new(Hourly::rain, Comparison::Above, 0.0).consecutive(3);
+        assert_eq!(response().evaluate(&[drizzle]).len(), 1);
+        let calm = AlertRule::new(Hourly::windgusts_10m, Comparison::Below, 20.0);
+        let calm = response().evaluate(&[calm]);
+        assert_eq!(calm.len(), 2);
+        assert_eq!(calm[0].peak, 18.4);
+        assert_eq!(calm[1].timestamps, [hour(10), hour(11)]);
+    }
+}
@@ -0,0 +1,166 @@
+use std::fmt;
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+use crate::Endpoint;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Enumerate the versions of the API paths
+pub enum ApiVersion {
+    /// `/v1`, the only version published so far
+    #[default]
+    V1,
+    /// Any other segment, e.g. `v2`
+    Custom(String),
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiVersion::V1 => write!(f, "v1"),
+            ApiVersion::Custom(segment) => write!(f, "{}", segment.trim_matches('/')),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Where the clients find the endpoints, the Open-Meteo hosts by default
+///
+/// Endpoint URLs are composed as `base_url` + `path_prefix` + version +
+/// endpoint path, e.g. `https://meteo.example.com/open-meteo/v1/archive`
+pub struct ApiLocation {
+    base_url: Option<String>,
+    path_prefix: String,
+    version: ApiVersion,
+}
+
+impl ApiLocation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve every endpoint from this origin, e.g. a self-hosted instance,
+    /// instead of the host of each API
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Path the API is mounted under, before the version segment
+    pub fn path_prefix(mut self, prefix: &str) -> Self {
+        let prefix = prefix.trim_matches('/');
+        self.path_prefix = match prefix.is_empty() {
+            true => String::new(),
+            false => format!("/{prefix}"),
+        };
+        self
+    }
+
+    pub fn version(mut self, version: ApiVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// URL of the endpoint served by default at `default`, a `/v1/` URL
+    pub(crate) fn url(&self, default: &str) -> String {
+        let (host, path) = default.split_once("/v1/").unwrap_or((default, ""));
+        let host = self.base_url.as_deref().unwrap_or(host);
+        format!("{host}{}/{}/{path}", self.path_prefix, self.version)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg(any(feature = "client", feature = "blocking"))]
+/// URL of every endpoint, resolved once against an `ApiLocation`, shared
+/// by the async and blocking clients
+pub(crate) struct EndpointUrls {
+    pub(crate) forecast: String,
+    pub(crate) archive: String,
+    pub(crate) historical_forecast: String,
+    pub(crate) air_quality: String,
+    pub(crate) marine: String,
+    pub(crate) flood: String,
+    pub(crate) climate: String,
+    pub(crate) ensemble: String,
+    pub(crate) satellite: String,
+    pub(crate) seasonal: String,
+    pub(crate) geocoding: String,
+    pub(crate) elevation: String,
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl EndpointUrls {
+    pub(crate) fn new(location: &ApiLocation) -> Self {
+        EndpointUrls {
+            forecast: location.url(Endpoint::Forecast.url()),
+            archive: location.url(Endpoint::Archive.url()),
+            historical_forecast: location.url(Endpoint::HistoricalForecast.url()),
+            air_quality: location.url(Endpoint::AirQuality.url()),
+            marine: location.url(Endpoint::Marine.url()),
+            flood: location.url(Endpoint::Flood.url()),
+            climate: location.url(Endpoint::Climate.url()),
+            ensemble: location.url(Endpoint::Ensemble.url()),
+            satellite: location.url(Endpoint::Satellite.url()),
+            seasonal: location.url(Endpoint::Seasonal.url()),
+            geocoding: location.url(Endpoint::Geocoding.url()),
+            elevation: location.url(Endpoint::Elevation.url()),
+        }
+    }
+
+    /// URL requests to `endpoint` are sent to
+    pub(crate) fn get(&self, endpoint: Endpoint) -> &str {
+        match endpoint {
+            Endpoint::Forecast => &self.forecast,
+            Endpoint::Archive => &self.archive,
+            Endpoint::HistoricalForecast => &self.historical_forecast,
+            Endpoint::AirQuality => &self.air_quality,
+            Endpoint::Marine => &self.marine,
+            Endpoint::Flood => &self.flood,
+            Endpoint::Climate => &self.climate,
+            Endpoint::Ensemble => &self.ensemble,
+            Endpoint::Satellite => &self.satellite,
+            Endpoint::Seasonal => &self.seasonal,
+            Endpoint::Geocoding => &self.geocoding,
+            Endpoint::Elevation => &self.elevation,
+        }
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl Default for EndpointUrls {
+    fn default() -> Self {
+        Self::new(&ApiLocation::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::archive::ARCHIVE_URL;
+    use crate::BASE_URL;
+
+    #[test]
+    fn composed_paths() {
+        assert_eq!(ApiLocation::new().url(BASE_URL), BASE_URL);
+        assert_eq!(ApiLocation::new().url(ARCHIVE_URL), ARCHIVE_URL);
+
+        let location = ApiLocation::new()
+            .base_url("http://localhost:8080/")
+            .path_prefix("/weather/open-meteo/")
+            .version(ApiVersion::Custom("v2".into()));
+        assert_eq!(
+            location.url(BASE_URL),
+            "http://localhost:8080/weather/open-meteo/v2/forecast"
+        );
+        assert_eq!(
+            location.url(ARCHIVE_URL),
+            "http://localhost:8080/weather/open-meteo/v2/archive"
+        );
+
+        let prefixed = ApiLocation::new().path_prefix("mirror");
+        assert_eq!(
+            prefixed.url(ARCHIVE_URL),
+            "https://archive-api.open-meteo.com/mirror/v1/archive"
+        );
+    }
+}
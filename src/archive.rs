@@ -0,0 +1,236 @@
+use std::{error, fmt};
+
+use crate::{Coordinates, Daily, Endpoint, Forecast, Hourly, NoCoordinates, Settings};
+
+pub(crate) const ARCHIVE_URL: &str = "https://archive-api.open-meteo.com/v1/archive";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Enumerate the reasons `Archive::build` refuses a request
+pub enum ArchiveError {
+    /// `start_date` or `end_date` is missing, the archive needs both
+    MissingDates,
+    /// The variable or setting is only offered by the forecast API
+    ForecastOnly(String),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::MissingDates => write!(f, "archive requests need a start and end date"),
+            ArchiveError::ForecastOnly(name) => {
+                write!(f, "`{name}` is not available in the archive")
+            }
+        }
+    }
+}
+
+impl error::Error for ArchiveError {}
+
+#[derive(Clone)]
+/// Request of past weather from the ERA5 based archive, built like a `Forecast`
+pub struct Archive<C = Coordinates>(Forecast<C>);
+
+impl Archive<NoCoordinates> {
+    /// Initialize Archive object
+    pub fn new() -> Self {
+        Archive(Forecast::new().endpoint(Endpoint::Archive))
+    }
+}
+
+impl Default for Archive<NoCoordinates> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reuse the coordinates, settings and variables of a forecast; `build`
+/// rejects what the archive does not serve
+impl<C> From<Forecast<C>> for Archive<C> {
+    fn from(forecast: Forecast<C>) -> Self {
+        Archive(forecast.endpoint(Endpoint::Archive))
+    }
+}
+
+impl<C> Archive<C> {
+    /// Specify coordinates (latitude, longitude)
+    pub fn coord(self, latitude: f32, longitude: f32) -> Archive<Coordinates> {
+        Archive(self.0.coord(latitude, longitude))
+    }
+
+    /// Dates of the first and last day, `YYYY-MM-DD`
+    pub fn dates(self, start: &str, end: &str) -> Self {
+        self.settings(Settings::start_date(start.to_string()))
+            .settings(Settings::end_date(end.to_string()))
+    }
+
+    /// Add optional settings
+    pub fn settings(self, setting: Settings) -> Self {
+        Archive(self.0.settings(setting))
+    }
+
+    /// Get hourly value for a specific data
+    pub fn hourly(self, hourly: Hourly) -> Self {
+        Archive(self.0.hourly(hourly))
+    }
+
+    /// Get daily value for a specific data
+    pub fn daily(self, daily: Daily) -> Self {
+        Archive(self.0.daily(daily))
+    }
+}
+
+impl Archive<Coordinates> {
+    /// Check the request against what the archive serves
+    pub fn build(self) -> Result<ArchiveRequest, ArchiveError> {
+        let forecast = self.0;
+        if let Some(setting) = forecast.settings.iter().find(|setting| {
+            matches!(
+                setting,
+                Settings::past_days(_) | Settings::forecast_days(_) | Settings::current_weather(_)
+            )
+        }) {
+            return Err(ArchiveError::ForecastOnly(setting.to_string()));
+        }
+        if let Some(variable) = forecast.hourly.iter().find(|hourly| forecast_only(hourly)) {
+            return Err(ArchiveError::ForecastOnly(variable.to_string()));
+        }
+        if let Some(variable) = forecast
+            .daily
+            .iter()
+            .find(|daily| daily_forecast_only(daily))
+        {
+            return Err(ArchiveError::ForecastOnly(variable.to_string()));
+        }
+        if let Some(variable) = forecast.pressure_var.first() {
+            return Err(ArchiveError::ForecastOnly(variable.get()));
+        }
+        if forecast.date_range().is_none() {
+            return Err(ArchiveError::MissingDates);
+        }
+        Ok(ArchiveRequest(forecast))
+    }
+}
+
+#[derive(Clone)]
+/// Archive request checked by `Archive::build`
+pub struct ArchiveRequest(Forecast<Coordinates>);
+
+impl ArchiveRequest {
+    /// The request as the forecast it shares its parameters with
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    pub(crate) fn forecast(&self) -> &Forecast<Coordinates> {
+        &self.0
+    }
+}
+
+impl fmt::Display for ArchiveRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Hourly variables of the forecast models missing from the reanalysis
+fn forecast_only(hourly: &Hourly) -> bool {
+    use Hourly::*;
+
+    matches!(
+        hourly,
+        windspeed_80m
+            | windspeed_120m
+            | windspeed_180m
+            | windspeedtion_80m
+            | windspeedtion_120m
+            | windspeedtion_180m
+            | precipitation_probability
+            | showers
+            | freezinglevel_height
+            | visibility
+            | soil_temperature_0cm
+            | soil_temperature_6cm
+            | soil_temperature_18cm
+            | soil_temperature_54cm
+            | soil_moisture_0_1cm
+            | soil_moisture_1_3cm
+            | soil_moisture_4_9cm
+            | soil_moisture_9_27cm
+            | soil_moisture_27_81cm
+    )
+}
+
+fn daily_forecast_only(daily: &Daily) -> bool {
+    use Daily::*;
+
+    matches!(
+        daily,
+        showers_sum
+            | precipitation_probability_max
+            | precipitation_probability_min
+            | precipitation_probability_mean
+            | uv_index_max
+            | uv_index_clear_sky_max
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::PressureVar;
+
+    fn archive() -> Archive {
+        Archive::new()
+            .coord(52.52, 13.41)
+            .hourly(Hourly::temperature_2m)
+            .daily(Daily::precipitation_sum)
+    }
+
+    #[test]
+    fn url_creation() {
+        let request = archive().dates("2023-01-01", "2023-01-31").build().unwrap();
+        assert_eq!(
+            request.to_string(),
+            "https://archive-api.open-meteo.com/v1/archive?latitude=52.52&longitude=13.41&start_date=2023-01-01&end_date=2023-01-31&hourly=,temperature_2m&daily=,precipitation_sum"
+        );
+    }
+
+    #[test]
+    fn dates_are_mandatory() {
+        assert_eq!(archive().build().err(), Some(ArchiveError::MissingDates));
+        let start_only = archive().settings(Settings::start_date("2023-01-01".into()));
+        assert_eq!(start_only.build().err(), Some(ArchiveError::MissingDates));
+    }
+
+    #[test]
+    fn forecast_only_variables() {
+        let dated = archive().dates("2023-01-01", "2023-01-31");
+        let error = dated
+            .clone()
+            .hourly(Hourly::precipitation_probability)
+            .build()
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "`precipitation_probability` is not available in the archive"
+        );
+        assert_eq!(
+            dated.clone().daily(Daily::uv_index_max).build().err(),
+            Some(ArchiveError::ForecastOnly("uv_index_max".into()))
+        );
+        assert_eq!(
+            dated
+                .clone()
+                .settings(Settings::forecast_days(3))
+                .build()
+                .err(),
+            Some(ArchiveError::ForecastOnly("forecast_days".into()))
+        );
+        let mut pressure = dated.clone();
+        pressure.0 = pressure.0.pressure_var(PressureVar::temperature(850));
+        assert_eq!(
+            pressure.build().err(),
+            Some(ArchiveError::ForecastOnly("temperature_850hPa".into()))
+        );
+        assert!(dated.hourly(Hourly::snow_depth).build().is_ok());
+    }
+}
@@ -0,0 +1,224 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow_array::{
+    Array, ArrayRef, Float64Array, RecordBatch, StringArray, TimestampSecondArray, UInt8Array,
+};
+use arrow_schema::{ArrowError, Field, Schema};
+
+use crate::{response::daily_kind, ColumnKind, DailyValue, ForecastResponse, Timestamp, UnitsMap};
+
+impl ForecastResponse {
+    /// Hourly block as a record batch: a `time` column of UTC timestamps
+    /// tagged with the response timezone, then one nullable `Float64` column
+    /// per variable carrying its unit in the `unit` field metadata
+    ///
+    /// Without an hourly block, the batch has a `time` column and no rows
+    pub fn to_arrow(&self) -> Result<RecordBatch, ArrowError> {
+        let mut columns = vec![time_column(self, self.hourly.iter().flat_map(|h| &h.time))];
+        for (name, series) in self.hourly.iter().flat_map(|hourly| &hourly.series) {
+            let values: Float64Array = series.iter().copied().collect();
+            columns.push(field(name, &self.hourly_units, Arc::new(values)));
+        }
+        batch(self, columns)
+    }
+
+    /// Daily block as a record batch like `to_arrow`, with weather codes as
+    /// `UInt8`, sunrise and sunset as timestamps and values of variables
+    /// unknown to this crate as their JSON text
+    pub fn daily_to_arrow(&self) -> Result<RecordBatch, ArrowError> {
+        let mut columns = vec![time_column(self, self.daily.iter().flat_map(|d| &d.time))];
+        for (name, values) in self.daily.iter().flat_map(|daily| &daily.series) {
+            let array: ArrayRef = match daily_kind(name) {
+                ColumnKind::Code => Arc::new(
+                    values
+                        .iter()
+                        .map(|value| match value {
+                            Some(DailyValue::Code(code)) => Some(*code),
+                            _ => None,
+                        })
+                        .collect::<UInt8Array>(),
+                ),
+                ColumnKind::Time => Arc::new(timestamps(
+                    self,
+                    values.iter().map(|value| match value {
+                        Some(DailyValue::Time(time)) => Some(time),
+                        _ => None,
+                    }),
+                )),
+                ColumnKind::Raw => Arc::new(
+                    values
+                        .iter()
+                        .map(|value| match value {
+                            Some(DailyValue::Raw(raw)) => Some(raw.to_string()),
+                            _ => None,
+                        })
+                        .collect::<StringArray>(),
+                ),
+                ColumnKind::Float => Arc::new(
+                    values
+                        .iter()
+                        .map(|value| match value {
+                            Some(DailyValue::Float(value)) => Some(*value),
+                            _ => None,
+                        })
+                        .collect::<Float64Array>(),
+                ),
+            };
+            columns.push(field(name, &self.daily_units, array));
+        }
+        batch(self, columns)
+    }
+}
+
+fn time_column<'a>(
+    response: &ForecastResponse,
+    time: impl Iterator<Item = &'a Timestamp>,
+) -> (Field, ArrayRef) {
+    let array = timestamps(response, time.map(Some));
+    (
+        Field::new("time", array.data_type().clone(), false),
+        Arc::new(array),
+    )
+}
+
+/// Seconds since the epoch, reading ISO 8601 times in the response offset
+fn timestamps<'a>(
+    response: &ForecastResponse,
+    time: impl Iterator<Item = Option<&'a Timestamp>>,
+) -> TimestampSecondArray {
+    let timezone = match response.timezone.as_str() {
+        "" => "+00:00",
+        timezone => timezone,
+    };
+    time.map(|time| time?.to_unix(response.utc_offset_seconds))
+        .collect::<TimestampSecondArray>()
+        .with_timezone(timezone)
+}
+
+fn field(name: &str, units: &Option<UnitsMap>, array: ArrayRef) -> (Field, ArrayRef) {
+    let mut field = Field::new(name, array.data_type().clone(), true);
+    if let Some(unit) = units.as_ref().and_then(|units| units.get(name)) {
+        field.set_metadata(HashMap::from([("unit".to_string(), unit.to_string())]));
+    }
+    (field, array)
+}
+
+/// Batch of the given columns, the location in the schema metadata
+fn batch(
+    response: &ForecastResponse,
+    columns: Vec<(Field, ArrayRef)>,
+) -> Result<RecordBatch, ArrowError> {
+    let (fields, arrays): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+    let metadata = HashMap::from([
+        ("location_id".to_string(), response.location_id.to_string()),
+        ("latitude".to_string(), response.latitude.to_string()),
+        ("longitude".to_string(), response.longitude.to_string()),
+        ("elevation".to_string(), response.elevation.to_string()),
+        ("timezone".to_string(), response.timezone.clone()),
+    ]);
+    let schema = Schema::new_with_metadata(fields, metadata);
+    RecordBatch::try_new(Arc::new(schema), arrays)
+}
+
+/// Write `batch` as a Parquet file, keeping the units in the Arrow schema
+/// stored alongside it
+#[cfg(feature = "parquet")]
+pub fn write_parquet(
+    batch: &RecordBatch,
+    writer: impl std::io::Write + Send,
+) -> Result<(), parquet::errors::ParquetError> {
+    let mut writer = parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None)?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use arrow_array::{cast::AsArray, types::TimestampSecondType, Array};
+    use arrow_schema::{DataType, TimeUnit};
+
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../fixtures/mixed.json");
+
+    #[test]
+    fn hourly_batch() {
+        let response = ForecastResponse::from_json(FIXTURE).unwrap();
+        let batch = response.to_arrow().unwrap();
+        let schema = batch.schema();
+
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(
+            schema.field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Second, Some("Europe/Helsinki".into()))
+        );
+        let temperature = schema.field_with_name("temperature_2m").unwrap();
+        assert_eq!(temperature.data_type(), &DataType::Float64);
+        assert!(temperature.is_nullable());
+        assert_eq!(temperature.metadata()["unit"], "°C");
+        assert_eq!(schema.metadata()["timezone"], "Europe/Helsinki");
+
+        // 2024-06-20T12:00 at UTC+3
+        let time = batch.column(0).as_primitive::<TimestampSecondType>();
+        assert_eq!(time.value(0), 1_718_874_000);
+        let temperature = batch
+            .column(1)
+            .as_primitive::<arrow_array::types::Float64Type>();
+        assert_eq!(temperature.value(1), 20.3);
+        assert!(temperature.is_null(2));
+    }
+
+    #[test]
+    fn daily_batch() {
+        let response = ForecastResponse::from_json(FIXTURE).unwrap();
+        let batch = response.daily_to_arrow().unwrap();
+        let schema = batch.schema();
+
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(
+            schema.field_with_name("weather_code").unwrap().data_type(),
+            &DataType::UInt8
+        );
+        let codes = batch
+            .column(1)
+            .as_primitive::<arrow_array::types::UInt8Type>();
+        assert_eq!(codes.value(0), 61);
+        let sunrise = batch
+            .column_by_name("sunrise")
+            .unwrap()
+            .as_primitive::<TimestampSecondType>();
+        // 2024-06-21T03:54 at UTC+3
+        assert_eq!(sunrise.value(1), 1_718_931_240);
+
+        let empty = ForecastResponse {
+            daily: None,
+            ..response
+        };
+        assert_eq!(empty.daily_to_arrow().unwrap().num_rows(), 0);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_round_trip() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let batch = ForecastResponse::from_json(FIXTURE)
+            .unwrap()
+            .to_arrow()
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("weathered-{}.parquet", std::process::id()));
+        write_parquet(&batch, std::fs::File::create(&path).unwrap()).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].columns(), batch.columns());
+        assert_eq!(batches[0].schema().field(1).metadata()["unit"], "°C");
+    }
+}
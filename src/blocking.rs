@@ -0,0 +1,516 @@
+//! Blocking client, for programs without an async runtime
+
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::api::EndpointUrls;
+use crate::transport::default_agent;
+use crate::{
+    AirQuality, ApiLocation, ArchiveRequest, ClimateRequest, Coordinates, DecodeMode,
+    ElevationRequest, ElevationResponse, EndpointRequest, EnsembleForecast, Flood, Forecast,
+    ForecastResponse, GeocodingRequest, GeocodingResponse, GeocodingResult, HttpRequest,
+    HttpResponse, HttpTransport, Marine, RetryPolicy, Satellite, SeasonalRequest, UreqTransport,
+    WeatheredError, USER_AGENT,
+};
+
+#[derive(Clone)]
+/// Forecast client performing requests through a blocking transport
+pub struct Client<T = UreqTransport> {
+    transport: T,
+    urls: EndpointUrls,
+    retry: RetryPolicy,
+    /// Total timeout of each call, overriding the one of the transport
+    timeout: Option<Duration>,
+    user_agent: String,
+    check_consistency: bool,
+    decode_mode: DecodeMode,
+}
+
+impl Client {
+    /// Reuse the connection pool of an already configured `ureq::Agent`
+    pub fn new(agent: ureq::Agent) -> Self {
+        Self::with_transport(UreqTransport::new(agent))
+    }
+}
+
+impl<T: HttpTransport> Client<T> {
+    /// Perform requests through a custom transport
+    pub fn with_transport(transport: T) -> Self {
+        Self::with_location(transport, &ApiLocation::default())
+    }
+
+    fn with_location(transport: T, location: &ApiLocation) -> Self {
+        Client {
+            transport,
+            urls: EndpointUrls::new(location),
+            retry: RetryPolicy::none(),
+            timeout: None,
+            user_agent: USER_AGENT.to_string(),
+            check_consistency: true,
+            decode_mode: DecodeMode::Json,
+        }
+    }
+
+    /// Send requests to a self-hosted instance, under a path prefix or to
+    /// another version of the API
+    pub fn api_location(self, location: ApiLocation) -> Self {
+        Client {
+            urls: EndpointUrls::new(&location),
+            ..self
+        }
+    }
+
+    /// Retry failed requests following the policy, sleeping on the current
+    /// thread between attempts; no retries by default
+    pub fn retries(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Give up on each call after `timeout`, including the time to read the body
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Identify the application in the `User-Agent` header, before the crate itself
+    /// (e.g. `myapp/1.2 weathered/0.1.0`)
+    pub fn user_agent(mut self, application: &str) -> Self {
+        self.user_agent = format!("{application} {USER_AGENT}");
+        self
+    }
+
+    /// Send exactly `user_agent` in the `User-Agent` header, without the crate
+    /// identifier added by `user_agent`
+    pub fn replace_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Reject truncated responses and responses missing requested variables
+    /// with `WeatheredError::Inconsistent`, enabled by default
+    pub fn check_consistency(mut self, enabled: bool) -> Self {
+        self.check_consistency = enabled;
+        self
+    }
+
+    /// Ask the API for bodies in the given format, JSON by default
+    #[cfg(feature = "fb")]
+    pub fn decode_mode(mut self, mode: DecodeMode) -> Self {
+        self.decode_mode = mode;
+        self
+    }
+
+    /// Perform the request on the current thread and parse the returned forecast
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "weathered.fetch",
+            skip_all,
+            fields(
+                url = %forecast.to_url(&self.urls.forecast),
+                locations = 1,
+                variables = forecast.variable_count(),
+            )
+        )
+    )]
+    pub fn forecast(
+        &self,
+        forecast: &Forecast<Coordinates>,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        forecast.check_dates()?;
+        let (url, parsed) = self.get(forecast.to_url(self.urls.get(forecast.endpoint)))?;
+        if self.check_consistency {
+            parsed
+                .check_against(forecast)
+                .map_err(|source| WeatheredError::Inconsistent { url, source })?;
+        }
+        Ok(parsed)
+    }
+
+    /// Perform the archive request on the current thread and parse the
+    /// returned weather, like `forecast` against the archive host
+    pub fn archive(&self, archive: &ArchiveRequest) -> Result<ForecastResponse, WeatheredError> {
+        self.forecast(archive.forecast())
+    }
+
+    /// Perform the air quality request on the current thread and parse the
+    /// returned pollutants
+    pub fn air_quality(
+        &self,
+        air_quality: &AirQuality,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(air_quality)
+    }
+
+    /// Perform the marine request on the current thread and parse the
+    /// returned waves
+    pub fn marine(&self, marine: &Marine) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(marine)
+    }
+
+    /// Perform the flood request on the current thread and parse the
+    /// returned river discharge
+    pub fn flood(&self, flood: &Flood) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(flood)
+    }
+
+    /// Perform the climate request on the current thread and parse the
+    /// returned projections
+    pub fn climate(&self, climate: &ClimateRequest) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(climate)
+    }
+
+    /// Perform the ensemble request on the current thread and parse the
+    /// returned members
+    pub fn ensemble(
+        &self,
+        ensemble: &EnsembleForecast,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(ensemble)
+    }
+
+    /// Perform the satellite radiation request on the current thread and
+    /// parse the returned irradiance
+    pub fn satellite(&self, satellite: &Satellite) -> Result<ForecastResponse, WeatheredError> {
+        satellite.check_dates()?;
+        self.send_endpoint(satellite)
+    }
+
+    /// Perform the seasonal request on the current thread and parse the
+    /// returned members
+    pub fn seasonal(&self, seasonal: &SeasonalRequest) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(seasonal)
+    }
+
+    fn send_endpoint(
+        &self,
+        request: &impl EndpointRequest,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        let (url, parsed) = self.get(request.to_url(self.urls.get(request.endpoint())))?;
+        if self.check_consistency {
+            parsed
+                .check_consistency()
+                .map_err(|source| WeatheredError::Inconsistent { url, source })?;
+        }
+        Ok(parsed)
+    }
+
+    /// Fetch `url` in the configured format and decode the body, returning
+    /// the URL actually requested along the response
+    fn get(&self, url: String) -> Result<(String, ForecastResponse), WeatheredError> {
+        let (request, response) = self.execute(url + self.decode_mode.query())?;
+        let parsed = ForecastResponse::decode(&request.url, &response, self.decode_mode)?;
+        Ok((request.url, parsed))
+    }
+
+    /// Send `url`, retrying transient failures as the policy allows
+    fn execute(&self, url: String) -> Result<(HttpRequest, HttpResponse), WeatheredError> {
+        let request = HttpRequest {
+            url,
+            headers: vec![("User-Agent".into(), self.user_agent.clone())],
+            timeout: self.timeout,
+        };
+        let mut attempt = 1;
+        let mut rate_limit_waited = false;
+        loop {
+            let start = Instant::now();
+            let outcome = self.transport.get(&request);
+            let rate_limit_wait = match &outcome {
+                Ok(response) if !rate_limit_waited => self.retry.rate_limit_wait(response),
+                _ => None,
+            };
+            if let Some(wait) = rate_limit_wait {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(?wait, "rate limited by the API, waiting before retrying");
+                thread::sleep(wait);
+                rate_limit_waited = true;
+                continue;
+            }
+            if attempt < self.retry.max_attempts() && self.retry.is_retryable(&outcome) {
+                let delay = self.retry.delay(attempt);
+                #[cfg(feature = "tracing")]
+                tracing::warn!(attempt, ?delay, "transient failure, retrying");
+                thread::sleep(delay);
+                attempt += 1;
+                continue;
+            }
+            return match outcome {
+                Ok(response) => Ok((request, response)),
+                Err(source) => Err(WeatheredError::transport(
+                    request.url,
+                    source,
+                    start.elapsed(),
+                )),
+            };
+        }
+    }
+
+    /// Terrain height of each point of the request on the current thread, in
+    /// order, with one call per 100 points
+    pub fn elevation(&self, points: &ElevationRequest) -> Result<Vec<f64>, WeatheredError> {
+        let mut elevation = Vec::new();
+        for url in points.to_urls(&self.urls.elevation) {
+            let (request, response) = self.execute(url)?;
+            elevation.extend(ElevationResponse::decode(&request.url, &response)?);
+        }
+        Ok(elevation)
+    }
+
+    /// Search locations by name through the geocoding API on the current thread
+    pub fn geocode(
+        &self,
+        search: &GeocodingRequest,
+    ) -> Result<Vec<GeocodingResult>, WeatheredError> {
+        let (request, response) = self.execute(search.json().to_url(&self.urls.geocoding))?;
+        GeocodingResponse::decode(&request.url, &response)
+    }
+}
+
+impl Forecast<Coordinates> {
+    /// Perform the request on the current thread with a lazily created default client
+    pub fn fetch_blocking(&self) -> Result<ForecastResponse, WeatheredError> {
+        static DEFAULT: OnceLock<Client> = OnceLock::new();
+        DEFAULT
+            .get_or_init(|| Client::new(default_agent()))
+            .forecast(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+    use crate::testing::StaticTransport;
+    use crate::Hourly;
+
+    const FIXTURE: &str = include_str!("../fixtures/forecast.json");
+
+    fn forecast() -> Forecast {
+        Forecast::new()
+            .coord(52.52, 13.41)
+            .hourly(Hourly::temperature_2m)
+            .hourly(Hourly::rain)
+    }
+
+    fn client(base_url: &str) -> Client {
+        Client {
+            urls: EndpointUrls {
+                forecast: base_url.to_string(),
+                ..Default::default()
+            },
+            ..Client::with_transport(UreqTransport::default())
+        }
+    }
+
+    #[test]
+    fn api_location() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE),
+            MockResponse::ok(include_str!("../fixtures/air_quality.json")),
+        ]);
+        let client = Client::with_transport(UreqTransport::default()).api_location(
+            ApiLocation::new()
+                .base_url(server.url())
+                .path_prefix("/om/"),
+        );
+
+        client.forecast(&forecast()).unwrap();
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/om/v1/forecast?latitude=52.52&longitude=13.41&hourly=,temperature_2m,rain"
+        );
+        let air_quality = AirQuality::new().coord(52.52, 13.41);
+        client.air_quality(&air_quality).unwrap();
+        assert!(server
+            .last_path()
+            .unwrap()
+            .starts_with("/om/v1/air-quality?latitude=52.52"));
+    }
+
+    #[test]
+    fn fetch_blocking_decodes_fixture() {
+        let server = MockServer::start(vec![MockResponse::ok(FIXTURE)]);
+        let client = client(&format!("{}/v1/forecast", server.url()));
+
+        let response = client.forecast(&forecast()).unwrap();
+
+        assert_eq!(response.latitude, 52.52);
+        assert_eq!(response.timezone, "GMT");
+        assert_eq!(response.elevation, 38.0);
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/v1/forecast?latitude=52.52&longitude=13.41&hourly=,temperature_2m,rain"
+        );
+        assert_eq!(server.last_header("User-Agent").unwrap(), USER_AGENT);
+    }
+
+    #[test]
+    fn fetch_blocking_errors_carry_url() {
+        let server = MockServer::start(vec![
+            MockResponse::new(500, "oops"),
+            MockResponse::ok("not json"),
+        ]);
+        let client = client(server.url());
+        let url = forecast().to_url(server.url());
+
+        match client.forecast(&forecast()) {
+            Err(WeatheredError::Status {
+                url: failed,
+                status,
+            }) => {
+                assert_eq!(status, 500);
+                assert_eq!(failed, url);
+            }
+            other => panic!("expected status error, got {other:?}"),
+        }
+        match client.forecast(&forecast()) {
+            Err(WeatheredError::Decode { url: failed, .. }) => assert_eq!(failed, url),
+            other => panic!("expected decode error, got {other:?}"),
+        }
+        assert_eq!(server.requests(), 2);
+
+        // Nothing listens on the discard port
+        let unreachable = forecast().to_url("http://127.0.0.1:9");
+        match self::client("http://127.0.0.1:9").forecast(&forecast()) {
+            Err(WeatheredError::Network { url: failed, .. }) => assert_eq!(failed, unreachable),
+            other => panic!("expected network error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn retries_transient_failures() {
+        let server = MockServer::start(vec![
+            MockResponse::new(503, "busy"),
+            MockResponse::new(502, "bad gateway"),
+            MockResponse::ok(FIXTURE),
+        ]);
+        let client = client(server.url());
+
+        assert!(matches!(
+            client.forecast(&forecast()),
+            Err(WeatheredError::Status { status: 503, .. })
+        ));
+        let policy = RetryPolicy::new(3).base_delay(Duration::from_millis(1));
+        let response = client.retries(policy).forecast(&forecast()).unwrap();
+        assert_eq!(response.latitude, 52.52);
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[test]
+    fn timeouts() {
+        let slow = MockResponse::ok(FIXTURE).delayed(Duration::from_millis(500));
+        let server = MockServer::start(vec![slow]);
+
+        let client = client(server.url()).timeout(Duration::from_millis(100));
+        match client.forecast(&forecast()) {
+            Err(WeatheredError::Timeout { elapsed, url }) => {
+                assert!(elapsed < Duration::from_millis(500));
+                assert_eq!(url, forecast().to_url(server.url()));
+            }
+            other => panic!("expected timeout, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn user_agent_is_configurable() {
+        let server = MockServer::start(vec![MockResponse::ok(FIXTURE)]);
+
+        client(server.url())
+            .user_agent("myapp/1.2")
+            .forecast(&forecast())
+            .unwrap();
+        assert_eq!(
+            server.last_header("User-Agent").unwrap(),
+            format!("myapp/1.2 {USER_AGENT}")
+        );
+        client(server.url())
+            .replace_user_agent("myapp/1.2 (ops@example.com)")
+            .forecast(&forecast())
+            .unwrap();
+        assert_eq!(
+            server.last_header("User-Agent").unwrap(),
+            "myapp/1.2 (ops@example.com)"
+        );
+    }
+
+    #[test]
+    fn truncated_responses_are_rejected() {
+        let truncated = FIXTURE.replace("\"rain\":[0.0,", "\"rain\":[");
+        let server = MockServer::start(vec![
+            MockResponse::ok(truncated.clone()),
+            MockResponse::ok(truncated.clone()),
+        ]);
+
+        match client(server.url()).forecast(&forecast()) {
+            Err(WeatheredError::Inconsistent { source, .. }) => {
+                assert_eq!(source.problems.len(), 1)
+            }
+            other => panic!("expected inconsistent response, got {other:?}"),
+        }
+        let lenient = client(server.url()).check_consistency(false);
+        assert!(lenient.forecast(&forecast()).is_ok());
+    }
+
+    #[test]
+    fn static_transport() {
+        let transport = StaticTransport::new().route("latitude=52.52", FIXTURE);
+        let client = Client::with_transport(transport);
+
+        let response = client.forecast(&forecast()).unwrap();
+        assert_eq!(response.timezone_abbreviation, "GMT");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_bodies_are_decoded() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE),
+            MockResponse::gzip(FIXTURE.as_bytes()),
+        ]);
+        let client = client(server.url());
+
+        let identity = client.forecast(&forecast()).unwrap();
+        let compressed = client.forecast(&forecast()).unwrap();
+        assert_eq!(
+            server.last_header("Accept-Encoding").unwrap(),
+            "gzip, deflate"
+        );
+        assert_eq!(identity, compressed);
+
+        let plain = Client {
+            urls: EndpointUrls {
+                forecast: server.url().to_string(),
+                ..Default::default()
+            },
+            ..Client::with_transport(UreqTransport::default().compression(false))
+        };
+        let _ = plain.forecast(&forecast());
+        assert_eq!(server.last_header("Accept-Encoding"), None);
+    }
+
+    #[test]
+    fn geocode() {
+        let server = MockServer::start(vec![MockResponse::ok(include_str!(
+            "../fixtures/geocoding.json"
+        ))]);
+        let client = Client {
+            urls: EndpointUrls {
+                geocoding: server.url().to_string(),
+                ..Default::default()
+            },
+            ..Client::with_transport(UreqTransport::default())
+        };
+
+        let results = client
+            .geocode(&GeocodingRequest::new("Zurich").country_code("US"))
+            .unwrap();
+        assert_eq!(results[2].clone().into_latlon(), (40.77821, -88.64757));
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/?name=Zurich&countryCode=US&format=json"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use web_time::Instant;
+
+use crate::runtime::default_runtime;
+use crate::{AsyncRuntime, ForecastResponse};
+
+/// Storage for parsed responses, keyed by `Forecast::cache_key`
+pub trait ResponseCache: Send + Sync {
+    /// Response stored for the key, if still valid
+    fn get(&self, key: &str) -> Option<ForecastResponse>;
+    fn put(&self, key: &str, response: &ForecastResponse);
+}
+
+struct Entry {
+    response: ForecastResponse,
+    stored: Instant,
+    used: u64,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<String, Entry>,
+    tick: u64,
+}
+
+/// Response cache kept in memory, expiring entries after a TTL and
+/// evicting the least recently used one when full
+pub struct MemoryCache {
+    ttl: Duration,
+    max_entries: usize,
+    state: Mutex<State>,
+    runtime: Arc<dyn AsyncRuntime>,
+}
+
+impl MemoryCache {
+    /// Initialize an empty cache holding at most `max_entries` responses for `ttl` each
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self::with_runtime(ttl, max_entries, default_runtime())
+    }
+
+    pub(crate) fn with_runtime(
+        ttl: Duration,
+        max_entries: usize,
+        runtime: Arc<dyn AsyncRuntime>,
+    ) -> Self {
+        MemoryCache {
+            ttl,
+            max_entries: max_entries.max(1),
+            state: Default::default(),
+            runtime,
+        }
+    }
+}
+
+impl ResponseCache for MemoryCache {
+    fn get(&self, key: &str) -> Option<ForecastResponse> {
+        let now = self.runtime.now();
+        let mut state = self.state.lock().unwrap();
+        state.tick += 1;
+        let tick = state.tick;
+        let entry = state.entries.get_mut(key)?;
+        if now.saturating_duration_since(entry.stored) >= self.ttl {
+            state.entries.remove(key);
+            return None;
+        }
+        entry.used = tick;
+        Some(entry.response.clone())
+    }
+
+    fn put(&self, key: &str, response: &ForecastResponse) {
+        let now = self.runtime.now();
+        let mut state = self.state.lock().unwrap();
+        state.tick += 1;
+        let tick = state.tick;
+        if !state.entries.contains_key(key) && state.entries.len() >= self.max_entries {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        let entry = Entry {
+            response: response.clone(),
+            stored: now,
+            used: tick,
+        };
+        state.entries.insert(key.to_string(), entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::runtime::tests::ManualRuntime;
+
+    const FIXTURE: &str = include_str!("../fixtures/forecast.json");
+
+    #[test]
+    fn least_recently_used_is_evicted() {
+        let clock = ManualRuntime::new();
+        let cache = MemoryCache::with_runtime(Duration::from_secs(60), 2, clock.clone());
+        let response: ForecastResponse = serde_json::from_str(FIXTURE).unwrap();
+
+        cache.put("a", &response);
+        cache.put("b", &response);
+        assert!(cache.get("a").is_some());
+        cache.put("c", &response);
+
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+
+        clock.advance(Duration::from_secs(60));
+        assert!(cache.get("a").is_none());
+    }
+}
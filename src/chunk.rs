@@ -0,0 +1,120 @@
+use crate::date::{days_in_month, Date};
+use indexmap::IndexMap;
+
+use crate::{ForecastResponse, Timestamp};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Length of the date windows requested by `Client::fetch_chunked`
+pub enum ChunkSize {
+    Days(u32),
+    Years(u32),
+}
+
+/// Last day of the window of `size` starting at `start`
+fn window_end(start: Date, size: ChunkSize) -> Date {
+    match size {
+        ChunkSize::Days(days) => Date::from_days(start.days() + days.max(1) as i64 - 1),
+        ChunkSize::Years(years) => {
+            let year = start.year + years.max(1) as i32;
+            let day = start.day.min(days_in_month(year, start.month));
+            Date::from_days(Date { year, day, ..start }.days() - 1)
+        }
+    }
+}
+
+/// Split the inclusive `start..=end` range into consecutive windows,
+/// `None` if either date is invalid
+pub(crate) fn split(start: &str, end: &str, size: ChunkSize) -> Option<Vec<(String, String)>> {
+    let (mut start, end) = (Date::parse(start)?, Date::parse(end)?);
+    let mut windows = Vec::new();
+    while start <= end {
+        let last = window_end(start, size).min(end);
+        windows.push((start.to_string(), last.to_string()));
+        start = Date::from_days(last.days() + 1);
+    }
+    Some(windows)
+}
+
+/// Concatenate responses to consecutive windows, dropping samples repeated
+/// at the boundaries; fails if variables or units differ between chunks
+pub(crate) fn merge(chunks: Vec<ForecastResponse>) -> Result<ForecastResponse, (usize, String)> {
+    let mut chunks = chunks.into_iter().enumerate();
+    let Some((_, mut merged)) = chunks.next() else {
+        return Err((0, "no chunk to merge".to_string()));
+    };
+    for (index, chunk) in chunks {
+        if chunk.hourly_units != merged.hourly_units || chunk.daily_units != merged.daily_units {
+            return Err((index, "units differ from the previous chunks".to_string()));
+        }
+        let hourly = match (merged.hourly.as_mut(), chunk.hourly) {
+            (None, None) => Ok(()),
+            (Some(merged), Some(chunk)) => append(
+                (&mut merged.time, &mut merged.series),
+                (chunk.time, chunk.series),
+            ),
+            _ => Err(VARIABLES_DIFFER.to_string()),
+        };
+        let daily = match (merged.daily.as_mut(), chunk.daily) {
+            (None, None) => Ok(()),
+            (Some(merged), Some(chunk)) => append(
+                (&mut merged.time, &mut merged.series),
+                (chunk.time, chunk.series),
+            ),
+            _ => Err(VARIABLES_DIFFER.to_string()),
+        };
+        hourly.and(daily).map_err(|reason| (index, reason))?;
+    }
+    Ok(merged)
+}
+
+const VARIABLES_DIFFER: &str = "variables differ from the previous chunks";
+
+/// Append the time axis and values by variable of a chunk to the merged ones
+fn append<V: IntoIterator + Extend<V::Item>>(
+    (time, values): (&mut Vec<Timestamp>, &mut IndexMap<String, V>),
+    (chunk_time, chunk_values): (Vec<Timestamp>, IndexMap<String, V>),
+) -> Result<(), String> {
+    let same_variables = values.len() == chunk_values.len()
+        && chunk_values.keys().all(|name| values.contains_key(name));
+    if !same_variables {
+        return Err(VARIABLES_DIFFER.to_string());
+    }
+    // Samples up to the last merged timestamp are already there
+    let skip = match time.last() {
+        Some(last) => chunk_time.iter().take_while(|time| *time <= last).count(),
+        None => 0,
+    };
+    time.extend(chunk_time.into_iter().skip(skip));
+    for (name, chunk) in chunk_values {
+        values[&name].extend(chunk.into_iter().skip(skip));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn windows_cover_the_range() {
+        assert_eq!(
+            split("2020-01-01", "2022-06-30", ChunkSize::Years(1)).unwrap(),
+            [
+                ("2020-01-01".to_string(), "2020-12-31".to_string()),
+                ("2021-01-01".to_string(), "2021-12-31".to_string()),
+                ("2022-01-01".to_string(), "2022-06-30".to_string()),
+            ]
+        );
+        assert_eq!(
+            split("2024-02-27", "2024-03-02", ChunkSize::Days(2)).unwrap(),
+            [
+                ("2024-02-27".to_string(), "2024-02-28".to_string()),
+                ("2024-02-29".to_string(), "2024-03-01".to_string()),
+                ("2024-03-02".to_string(), "2024-03-02".to_string()),
+            ]
+        );
+        assert_eq!(split("2024-02-30", "2024-03-02", ChunkSize::Days(2)), None);
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+    }
+}
@@ -0,0 +1,3031 @@
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::ops::RangeInclusive;
+use std::pin::pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::Poll;
+use std::time::Duration;
+
+use crate::api::EndpointUrls;
+use crate::chunk::{self, ChunkSize};
+use crate::date::Date;
+use crate::geocoding;
+use crate::rate_limit::RateLimiter;
+use crate::runtime::default_runtime;
+use crate::seamless::{self, SeamlessResponse};
+use crate::{
+    AirQuality, ApiLocation, Archive, ArchiveRequest, AsyncHttpTransport, AsyncRuntime,
+    ClimateRequest, ConditionalEntry, ConditionalStore, Coordinates, DecodeMode, ElevationRequest,
+    ElevationResponse, Endpoint, EndpointRequest, EnsembleForecast, FetchOutcome, Flood, Forecast,
+    ForecastResponse, GeocodingRequest, GeocodingResponse, GeocodingResult, Hourly, HttpRequest,
+    HttpResponse, Marine, MaybeSync, MetricsSink, Model, ModelComparison, NoCoordinates, Normals,
+    Place, ProgressSink, RequestHook, RequestStats, ReqwestTransport, ResponseCache, ResponseHook,
+    RetryPolicy, Satellite, SeasonalRequest, Settings, TransportError, Watch, WeatheredError,
+    USER_AGENT,
+};
+
+#[derive(Clone)]
+/// Forecast client performing requests through an async transport
+pub struct Client<T = ReqwestTransport> {
+    transport: T,
+    urls: EndpointUrls,
+    /// Mirrors tried in order when the base URL is unavailable
+    fallback_base_urls: Vec<String>,
+    retry: RetryPolicy,
+    limiter: Option<RateLimiter>,
+    runtime: Arc<dyn AsyncRuntime>,
+    conditional: Option<Arc<dyn ConditionalStore>>,
+    cache: Option<Arc<dyn ResponseCache>>,
+    read_cache: bool,
+    /// Address of the explicitly configured proxy, to report its failures
+    proxy: Option<String>,
+    user_agent: String,
+    collapse_locations: bool,
+    /// Reject responses failing `ForecastResponse::check_against`
+    check_consistency: bool,
+    decode_mode: DecodeMode,
+    metrics: Option<Arc<dyn MetricsSink>>,
+    request_hooks: Vec<Arc<dyn RequestHook>>,
+    response_hooks: Vec<Arc<dyn ResponseHook>>,
+    /// Places resolved by `for_city`, by normalized name, shared by clones
+    places: Arc<Mutex<HashMap<String, Place>>>,
+}
+
+impl Client {
+    /// Reuse the connection pool of an already configured `reqwest::Client`
+    pub fn new(http: reqwest::Client) -> Self {
+        Self::with_transport(ReqwestTransport::new(http))
+    }
+
+    /// Configure a new client
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+}
+
+#[derive(Default)]
+/// Builder for a `Client` backed by reqwest
+pub struct ClientBuilder {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    fallback_base_urls: Vec<String>,
+    retry: RetryPolicy,
+    rate_limit: Option<(u32, u32)>,
+    runtime: Option<Arc<dyn AsyncRuntime>>,
+    conditional: Option<Arc<dyn ConditionalStore>>,
+    cache: Option<Arc<dyn ResponseCache>>,
+    no_compression: bool,
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    root_certificates: Vec<Vec<u8>>,
+    proxy: ProxyConfig,
+    user_agent: Option<String>,
+    collapse_locations: bool,
+    skip_consistency_checks: bool,
+    decode_mode: DecodeMode,
+    metrics: Option<Arc<dyn MetricsSink>>,
+    request_hooks: Vec<Arc<dyn RequestHook>>,
+    response_hooks: Vec<Arc<dyn ResponseHook>>,
+    location: ApiLocation,
+}
+
+#[derive(Default)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+enum ProxyConfig {
+    /// Follow the `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY` environment variables
+    #[default]
+    Environment,
+    Explicit(String),
+    Disabled,
+}
+
+impl ClientBuilder {
+    /// Total time allowed for each request, from connection to the end of the body
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Time allowed to establish each connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Retry transient failures according to the given policy
+    pub fn retries(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Throttle requests locally to stay within the API quota
+    /// (the free tier allows 600 calls per minute and 10,000 per day);
+    /// clones of the built client share the same quota
+    pub fn rate_limit(mut self, calls_per_minute: u32, calls_per_day: u32) -> Self {
+        self.rate_limit = Some((calls_per_minute, calls_per_day));
+        self
+    }
+
+    /// Drive retry backoff and rate limiting with the given runtime instead of tokio
+    pub fn runtime(mut self, runtime: impl AsyncRuntime + 'static) -> Self {
+        self.runtime = Some(Arc::new(runtime));
+        self
+    }
+
+    /// Revalidate repeated requests with `If-None-Match`/`If-Modified-Since`,
+    /// keeping validators and bodies in the given store
+    ///
+    /// Like `cache`, geocoding and elevation calls are not revalidated
+    pub fn conditional(mut self, store: impl ConditionalStore + 'static) -> Self {
+        self.conditional = Some(Arc::new(store));
+        self
+    }
+
+    /// Request compressed responses (the default with the `gzip` feature);
+    /// disabling it makes bodies readable on the wire when debugging
+    pub fn compression(mut self, enabled: bool) -> Self {
+        self.no_compression = !enabled;
+        self
+    }
+
+    /// Send every request through the given proxy, ignoring the environment
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = ProxyConfig::Explicit(url.to_string());
+        self
+    }
+
+    /// Connect directly, ignoring any proxy configured in the environment
+    pub fn no_proxy(mut self) -> Self {
+        self.proxy = ProxyConfig::Disabled;
+        self
+    }
+
+    /// Mirrors of the API, e.g. self-hosted replicas, tried in order when a request
+    /// still fails after retries because the host cannot be reached or answers
+    /// a server error
+    ///
+    /// Only requests to the forecast endpoint fall back: the other endpoints,
+    /// e.g. `Client::archive` or `Client::marine`, are always sent to their own
+    /// host since one mirror URL cannot tell where they are served
+    pub fn fallback_base_urls(mut self, urls: &[&str]) -> Self {
+        self.fallback_base_urls = urls.iter().map(|url| url.to_string()).collect();
+        self
+    }
+
+    /// Trust the PEM encoded certificate in addition to the backend's roots,
+    /// e.g. the private CA of a self-hosted instance
+    ///
+    /// Ignored on wasm, where the browser validates certificates
+    pub fn add_root_certificate(mut self, pem: &[u8]) -> Self {
+        self.root_certificates.push(pem.to_vec());
+        self
+    }
+
+    /// Identify the application in the `User-Agent` header, before the crate itself
+    /// (e.g. `myapp/1.2 weathered/0.1.0`)
+    pub fn user_agent(mut self, application: &str) -> Self {
+        self.user_agent = Some(format!("{application} {USER_AGENT}"));
+        self
+    }
+
+    /// Send exactly `user_agent` in the `User-Agent` header, without the crate
+    /// identifier added by `user_agent`
+    pub fn replace_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Report statistics of every call to the given sink
+    pub fn metrics(mut self, sink: impl MetricsSink + 'static) -> Self {
+        self.metrics = Some(Arc::new(sink));
+        self
+    }
+
+    /// Run the hook on every request before sending it, after the hooks registered before
+    pub fn with_middleware(mut self, hook: impl RequestHook + 'static) -> Self {
+        self.request_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Run the hook on every response before parsing it, after the hooks registered before
+    pub fn with_response_hook(mut self, hook: impl ResponseHook + 'static) -> Self {
+        self.response_hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Let `Client::forecast_many` merge requests differing only by their
+    /// coordinates into multi-location API calls
+    pub fn collapse_locations(mut self, enabled: bool) -> Self {
+        self.collapse_locations = enabled;
+        self
+    }
+
+    /// Reject truncated responses and responses missing requested variables
+    /// with `WeatheredError::Inconsistent`, enabled by default
+    pub fn check_consistency(mut self, enabled: bool) -> Self {
+        self.skip_consistency_checks = !enabled;
+        self
+    }
+
+    /// Ask the API for bodies in the given format, JSON by default
+    #[cfg(feature = "fb")]
+    pub fn decode_mode(mut self, mode: DecodeMode) -> Self {
+        self.decode_mode = mode;
+        self
+    }
+
+    /// Answer repeated requests from the given cache, without any network I/O
+    ///
+    /// Applies to every endpoint answering forecasts; geocoding and elevation
+    /// calls are always sent
+    pub fn cache(mut self, cache: impl ResponseCache + 'static) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Send requests to a self-hosted instance, under a path prefix or to
+    /// another version of the API
+    pub fn api_location(mut self, location: ApiLocation) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// Build the underlying `reqwest::Client`
+    pub fn build(self) -> Result<Client, TransportError> {
+        let (http, proxy) = self.http_client()?;
+        let runtime = self.runtime.unwrap_or_else(default_runtime);
+        Ok(Client {
+            fallback_base_urls: self.fallback_base_urls,
+            retry: self.retry,
+            limiter: self
+                .rate_limit
+                .map(|(minute, day)| RateLimiter::new(minute, day, runtime.clone())),
+            runtime,
+            conditional: self.conditional,
+            cache: self.cache,
+            proxy,
+            user_agent: self.user_agent.unwrap_or_else(|| USER_AGENT.to_string()),
+            collapse_locations: self.collapse_locations,
+            check_consistency: !self.skip_consistency_checks,
+            decode_mode: self.decode_mode,
+            metrics: self.metrics,
+            request_hooks: self.request_hooks,
+            response_hooks: self.response_hooks,
+            ..Client::with_location(ReqwestTransport::new(http), &self.location)
+        })
+    }
+
+    /// Configure reqwest, returning the explicit proxy address if any
+    #[cfg(not(target_arch = "wasm32"))]
+    fn http_client(&self) -> Result<(reqwest::Client, Option<String>), TransportError> {
+        let mut builder = reqwest::Client::builder();
+        #[cfg(feature = "tls-rustls")]
+        {
+            builder = builder.use_rustls_tls();
+        }
+        #[cfg(feature = "tls-native")]
+        {
+            builder = builder.use_native_tls();
+        }
+        #[cfg(any(feature = "tls-rustls", feature = "tls-native"))]
+        for pem in &self.root_certificates {
+            let certificate = reqwest::Certificate::from_pem(pem)
+                .map_err(|source| TransportError::Other(Box::new(source)))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if self.no_compression {
+            #[cfg(feature = "gzip")]
+            {
+                builder = builder.no_gzip().no_deflate();
+            }
+        }
+        let mut proxy = None;
+        match &self.proxy {
+            ProxyConfig::Environment => {}
+            ProxyConfig::Explicit(url) => {
+                let all = reqwest::Proxy::all(url)
+                    .map_err(|source| TransportError::Other(Box::new(source)))?;
+                builder = builder.proxy(all);
+                proxy = Some(url.clone());
+            }
+            ProxyConfig::Disabled => builder = builder.no_proxy(),
+        }
+        match builder.build() {
+            Ok(http) => Ok((http, proxy)),
+            Err(source) => Err(TransportError::Other(Box::new(source))),
+        }
+    }
+
+    /// Configure reqwest; in the browser, timeouts, compression and proxies are
+    /// handled by the fetch API and the corresponding settings are ignored
+    #[cfg(target_arch = "wasm32")]
+    fn http_client(&self) -> Result<(reqwest::Client, Option<String>), TransportError> {
+        match reqwest::Client::builder().build() {
+            Ok(http) => Ok((http, None)),
+            Err(source) => Err(TransportError::Other(Box::new(source))),
+        }
+    }
+}
+
+impl<T: AsyncHttpTransport> Client<T> {
+    /// Perform requests through a custom transport
+    pub fn with_transport(transport: T) -> Self {
+        Self::with_location(transport, &ApiLocation::default())
+    }
+
+    fn with_location(transport: T, location: &ApiLocation) -> Self {
+        Client {
+            transport,
+            urls: EndpointUrls::new(location),
+            fallback_base_urls: Vec::new(),
+            retry: RetryPolicy::none(),
+            limiter: None,
+            runtime: default_runtime(),
+            conditional: None,
+            cache: None,
+            read_cache: true,
+            proxy: None,
+            user_agent: USER_AGENT.to_string(),
+            collapse_locations: false,
+            check_consistency: true,
+            decode_mode: DecodeMode::Json,
+            metrics: None,
+            request_hooks: Vec::new(),
+            response_hooks: Vec::new(),
+            places: Arc::default(),
+        }
+    }
+
+    /// Perform the request and parse the returned forecast,
+    /// waiting for the rate limiter if needed
+    pub async fn forecast(
+        &self,
+        forecast: &Forecast<Coordinates>,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        Ok(self.send(forecast, None, true).await?.0)
+    }
+
+    /// Perform the request and tell whether the forecast was downloaded
+    /// or revalidated against the conditional store
+    pub async fn forecast_with_outcome(
+        &self,
+        forecast: &Forecast<Coordinates>,
+    ) -> Result<(ForecastResponse, FetchOutcome), WeatheredError> {
+        self.send(forecast, None, true).await
+    }
+
+    /// Perform the request only if the rate limiter has a permit available right now
+    pub async fn try_forecast(
+        &self,
+        forecast: &Forecast<Coordinates>,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        Ok(self.send(forecast, None, false).await?.0)
+    }
+
+    /// Perform the request overriding the configured timeout
+    pub async fn forecast_with_timeout(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        timeout: Duration,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        Ok(self.send(forecast, Some(timeout), true).await?.0)
+    }
+
+    /// Fetch the forecast of every model in a single request, to compare
+    /// their hourly variables
+    pub async fn compare_models(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        models: &[Model],
+    ) -> Result<ModelComparison, WeatheredError> {
+        let mut forecast = forecast.clone();
+        forecast
+            .settings
+            .retain(|setting| !matches!(setting, Settings::models(_)));
+        let forecast = forecast.settings(Settings::models(models.to_vec()));
+        let response = self.forecast(&forecast).await?;
+        Ok(ModelComparison::new(response, models))
+    }
+
+    /// Perform the archive request and parse the returned weather, like
+    /// `forecast` against the archive host
+    pub async fn archive(
+        &self,
+        archive: &ArchiveRequest,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        self.forecast(archive.forecast()).await
+    }
+
+    /// Perform the air quality request and parse the returned pollutants,
+    /// waiting for the rate limiter if needed
+    pub async fn air_quality(
+        &self,
+        air_quality: &AirQuality,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(air_quality).await
+    }
+
+    /// Perform the marine request and parse the returned waves, waiting for
+    /// the rate limiter if needed
+    pub async fn marine(&self, marine: &Marine) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(marine).await
+    }
+
+    /// Perform the flood request and parse the returned river discharge,
+    /// waiting for the rate limiter if needed
+    pub async fn flood(&self, flood: &Flood) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(flood).await
+    }
+
+    /// Perform the climate request and parse the returned projections,
+    /// waiting for the rate limiter if needed
+    pub async fn climate(
+        &self,
+        climate: &ClimateRequest,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(climate).await
+    }
+
+    /// Perform the ensemble request and parse the returned members, waiting
+    /// for the rate limiter if needed
+    pub async fn ensemble(
+        &self,
+        ensemble: &EnsembleForecast,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(ensemble).await
+    }
+
+    /// Perform the satellite radiation request and parse the returned
+    /// irradiance, waiting for the rate limiter if needed
+    pub async fn satellite(
+        &self,
+        satellite: &Satellite,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        satellite.check_dates()?;
+        self.send_endpoint(satellite).await
+    }
+
+    /// Perform the seasonal request and parse the returned members, waiting
+    /// for the rate limiter if needed
+    pub async fn seasonal(
+        &self,
+        seasonal: &SeasonalRequest,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        self.send_endpoint(seasonal).await
+    }
+
+    /// Perform a request to another endpoint than the forecast, through the
+    /// response cache and the conditional store like `forecast`
+    async fn send_endpoint(
+        &self,
+        request: &impl EndpointRequest,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        let start = self.runtime.now();
+        let mut stats = RequestStats {
+            endpoint: request.endpoint(),
+            ..RequestStats::new()
+        };
+        let check = |url: &str, parsed: &ForecastResponse| {
+            if !self.check_consistency {
+                return Ok(());
+            }
+            parsed
+                .check_consistency()
+                .map_err(|source| WeatheredError::Inconsistent {
+                    url: url.to_string(),
+                    source,
+                })
+        };
+        let result = self.cached(request, None, true, &mut stats, check).await;
+        stats.duration = self.runtime.now() - start;
+        stats.outcome = result.as_ref().ok().map(|(_, outcome)| *outcome);
+        if let Some(metrics) = &self.metrics {
+            metrics.on_request_complete(stats);
+        }
+        Ok(result?.0)
+    }
+
+    /// Send the query through `dispatch` and decode the response, reporting
+    /// the call to the metrics sink
+    async fn send_query<R>(
+        &self,
+        endpoint: Endpoint,
+        query: &str,
+        decode: impl FnOnce(&str, &HttpResponse) -> Result<R, WeatheredError>,
+    ) -> Result<R, WeatheredError> {
+        let start = self.runtime.now();
+        let mut stats = RequestStats {
+            endpoint,
+            ..RequestStats::new()
+        };
+        let template = HttpRequest {
+            url: String::new(),
+            headers: vec![("User-Agent".into(), self.user_agent.clone())],
+            timeout: None,
+        };
+        let result = match self
+            .dispatch(endpoint, query, template, true, &mut stats)
+            .await
+        {
+            Ok((request, response)) => decode(&request.url, &response),
+            Err(error) => Err(error),
+        };
+        stats.duration = self.runtime.now() - start;
+        stats.outcome = result.as_ref().ok().map(|_| FetchOutcome::Fresh);
+        if let Some(metrics) = &self.metrics {
+            metrics.on_request_complete(stats);
+        }
+        result
+    }
+
+    /// Search locations by name through the geocoding API, waiting for the
+    /// rate limiter if needed
+    pub async fn geocode(
+        &self,
+        search: &GeocodingRequest,
+    ) -> Result<Vec<GeocodingResult>, WeatheredError> {
+        let query = search.json().to_url("");
+        self.send_query(Endpoint::Geocoding, &query, GeocodingResponse::decode)
+            .await
+    }
+
+    /// Forecast for the city called `name`, configured by `configure`
+    ///
+    /// The most relevant populated place of the geocoding search is used,
+    /// unless several large cities share the name; it is remembered for the
+    /// next calls with the same name, on this client and its clones
+    pub async fn for_city(
+        &self,
+        name: &str,
+        configure: impl FnOnce(Forecast<NoCoordinates>) -> Forecast<NoCoordinates>,
+    ) -> Result<(Place, ForecastResponse), WeatheredError> {
+        let place = self.place(name).await?;
+        let (latitude, longitude) = place.clone().into_latlon();
+        let forecast = configure(Forecast::new()).coord(latitude, longitude);
+        Ok((place, self.forecast(&forecast).await?))
+    }
+
+    /// Place called `name`, from the cache or a geocoding search
+    async fn place(&self, name: &str) -> Result<Place, WeatheredError> {
+        let key = name.trim().to_lowercase();
+        let cached = self.places.lock().unwrap().get(&key).cloned();
+        if let Some(place) = cached {
+            return Ok(place);
+        }
+        let results = self.geocode(&GeocodingRequest::new(name.trim())).await?;
+        let place = geocoding::pick_city(name, results)?;
+        self.places.lock().unwrap().insert(key, place.clone());
+        Ok(place)
+    }
+
+    /// Terrain height of each point of the request, in order, through the
+    /// elevation API with one call per 100 points
+    pub async fn elevation(&self, points: &ElevationRequest) -> Result<Vec<f64>, WeatheredError> {
+        let mut elevation = Vec::new();
+        for query in points.to_urls("") {
+            let decoded = self
+                .send_query(Endpoint::Elevation, &query, ElevationResponse::decode)
+                .await?;
+            elevation.extend(decoded);
+        }
+        Ok(elevation)
+    }
+
+    /// Same request with `Settings::elevation` set to the terrain height the
+    /// elevation API gives for its coordinates
+    pub async fn resolve_elevation(
+        &self,
+        forecast: Forecast<Coordinates>,
+    ) -> Result<Forecast<Coordinates>, WeatheredError> {
+        let request = forecast.elevation_request();
+        let elevation = self.elevation(&request).await?;
+        match elevation[..] {
+            [elevation] => Ok(forecast.with_elevation(elevation as f32)),
+            _ => Err(WeatheredError::Decode {
+                url: request.to_urls(&self.urls.elevation).concat(),
+                source: serde::de::Error::invalid_length(elevation.len(), &"one elevation"),
+            }),
+        }
+    }
+
+    /// Perform the requests with at most `max_concurrent` in flight, returning
+    /// one result per request in input order
+    ///
+    /// With `ClientBuilder::collapse_locations`, requests differing only by their
+    /// coordinates are sent as a single multi-location call, bypassing the caches;
+    /// if such a call fails, its requests are retried one by one
+    pub async fn forecast_many(
+        &self,
+        requests: Vec<Forecast<Coordinates>>,
+        max_concurrent: usize,
+    ) -> Vec<Result<ForecastResponse, WeatheredError>> {
+        self.batch(requests, max_concurrent, None).await
+    }
+
+    /// Same as `forecast_many`, reporting to `progress` as each request completes
+    pub async fn forecast_many_with_progress(
+        &self,
+        requests: Vec<Forecast<Coordinates>>,
+        max_concurrent: usize,
+        progress: &dyn ProgressSink,
+    ) -> Vec<Result<ForecastResponse, WeatheredError>> {
+        self.batch(requests, max_concurrent, Some(progress)).await
+    }
+
+    async fn batch(
+        &self,
+        requests: Vec<Forecast<Coordinates>>,
+        max_concurrent: usize,
+        progress: Option<&dyn ProgressSink>,
+    ) -> Vec<Result<ForecastResponse, WeatheredError>> {
+        let total = requests.len();
+        let mut completed = 0;
+        let mut results: Vec<_> = requests.iter().map(|_| None).collect();
+        let mut jobs = self.plan(&requests).into_iter();
+        let mut in_flight = Vec::new();
+        poll_fn(|cx| loop {
+            while in_flight.len() < max_concurrent.max(1) {
+                match jobs.next() {
+                    Some(job) => in_flight.push(Box::pin(self.run(&requests, job))),
+                    None => break,
+                }
+            }
+            let running = in_flight.len();
+            in_flight.retain_mut(|job| match job.as_mut().poll(cx) {
+                Poll::Ready(done) => {
+                    for (index, result, stats) in done {
+                        completed += 1;
+                        report(progress, completed, total, &stats);
+                        results[index] = Some(result);
+                    }
+                    false
+                }
+                Poll::Pending => true,
+            });
+            if in_flight.is_empty() && jobs.len() == 0 {
+                return Poll::Ready(());
+            }
+            if in_flight.len() == running {
+                return Poll::Pending;
+            }
+        })
+        .await;
+        results.into_iter().flatten().collect()
+    }
+
+    /// Group the requests into API calls
+    fn plan(&self, requests: &[Forecast<Coordinates>]) -> Vec<Job> {
+        if !self.collapse_locations {
+            return (0..requests.len()).map(Job::Single).collect();
+        }
+        let mut groups: Vec<((Endpoint, String), Vec<usize>)> = Vec::new();
+        for (index, request) in requests.iter().enumerate() {
+            let params = (request.endpoint, request.params());
+            match groups
+                .iter_mut()
+                .find(|(key, group)| *key == params && group.len() < MAX_LOCATIONS_PER_CALL)
+            {
+                Some((_, group)) => group.push(index),
+                None => groups.push((params, vec![index])),
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(_, group)| match group[..] {
+                [index] => Job::Single(index),
+                _ => Job::Many(group),
+            })
+            .collect()
+    }
+
+    async fn run(
+        &self,
+        requests: &[Forecast<Coordinates>],
+        job: Job,
+    ) -> Vec<(
+        usize,
+        Result<ForecastResponse, WeatheredError>,
+        RequestStats,
+    )> {
+        let group = match job {
+            Job::Single(index) => {
+                let (result, stats) = self.send_recorded(&requests[index], None, true).await;
+                return vec![(index, result.map(|(response, _)| response), stats)];
+            }
+            Job::Many(group) => group,
+        };
+        let forecasts: Vec<_> = group.iter().map(|&index| &requests[index]).collect();
+        let query = forecasts[0].to_url_many("", &forecasts) + self.decode_mode.query();
+        let start = self.runtime.now();
+        let mut stats = RequestStats {
+            endpoint: forecasts[0].endpoint,
+            ..RequestStats::new()
+        };
+        if let Ok(responses) = self.fetch_many(forecasts[0], &query, &mut stats).await {
+            if responses.len() == group.len() {
+                stats.duration = self.runtime.now() - start;
+                stats.outcome = Some(FetchOutcome::Fresh);
+                return group
+                    .into_iter()
+                    .zip(responses)
+                    .map(|(index, response)| (index, Ok(response), stats.clone()))
+                    .collect();
+            }
+        }
+        let mut results = Vec::with_capacity(group.len());
+        for index in group {
+            let (result, stats) = self.send_recorded(&requests[index], None, true).await;
+            results.push((index, result.map(|(response, _)| response), stats));
+        }
+        results
+    }
+
+    /// Perform a multi-location call for locations sharing the variables of `forecast`
+    async fn fetch_many(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        query: &str,
+        stats: &mut RequestStats,
+    ) -> Result<Vec<ForecastResponse>, WeatheredError> {
+        forecast.check_dates()?;
+        let template = HttpRequest {
+            url: String::new(),
+            headers: vec![("User-Agent".into(), self.user_agent.clone())],
+            timeout: None,
+        };
+        let (request, response) = self
+            .dispatch(forecast.endpoint, query, template, true, stats)
+            .await?;
+        let parsed = ForecastResponse::decode_many(&request.url, &response, self.decode_mode)?;
+        for response in &parsed {
+            self.check(forecast, &request.url, response)?;
+        }
+        Ok(parsed)
+    }
+
+    /// Check `response` against the request unless consistency checks are disabled
+    fn check(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        url: &str,
+        response: &ForecastResponse,
+    ) -> Result<(), WeatheredError> {
+        if !self.check_consistency {
+            return Ok(());
+        }
+        response
+            .check_against(forecast)
+            .map_err(|source| WeatheredError::Inconsistent {
+                url: url.to_string(),
+                source,
+            })
+    }
+
+    /// Split the `start_date`..`end_date` range of the request into consecutive
+    /// windows, fetch them one after the other and merge the results
+    ///
+    /// Requests without a valid range are performed as is. A failing window
+    /// stops the fetch with the windows before it in `WeatheredError::ChunkFailed`
+    pub async fn fetch_chunked(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        chunk: ChunkSize,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        self.chunked(forecast, chunk, None).await
+    }
+
+    /// Same as `fetch_chunked`, reporting to `progress` as each window completes
+    pub async fn fetch_chunked_with_progress(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        chunk: ChunkSize,
+        progress: &dyn ProgressSink,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        self.chunked(forecast, chunk, Some(progress)).await
+    }
+
+    async fn chunked(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        chunk: ChunkSize,
+        progress: Option<&dyn ProgressSink>,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        let windows = forecast
+            .date_range()
+            .and_then(|(start, end)| chunk::split(start, end, chunk));
+        let Some(windows) = windows else {
+            let (result, stats) = self.send_recorded(forecast, None, true).await;
+            report(progress, 1, 1, &stats);
+            return Ok(result?.0);
+        };
+        let total = windows.len();
+        let mut chunks = Vec::with_capacity(total);
+        let mut urls = Vec::with_capacity(total);
+        for (index, (start, end)) in windows.into_iter().enumerate() {
+            let window = forecast.with_date_range(&start, &end);
+            let (result, stats) = self.send_recorded(&window, None, true).await;
+            report(progress, index + 1, total, &stats);
+            match result {
+                Ok((response, _)) => chunks.push(response),
+                Err(source) => {
+                    return Err(WeatheredError::ChunkFailed {
+                        window: index,
+                        fetched: chunks,
+                        source: Box::new(source),
+                    })
+                }
+            }
+            urls.push(window.to_url(&self.urls.forecast));
+        }
+        chunk::merge(chunks).map_err(|(index, reason)| WeatheredError::ChunkMismatch {
+            url: urls.swap_remove(index),
+            reason,
+        })
+    }
+
+    /// Per day of the year mean and spread of an hourly variable over the
+    /// `reference` years, from the archive fetched one year at a time
+    pub async fn climate_normals(
+        &self,
+        latitude: f32,
+        longitude: f32,
+        variable: Hourly,
+        reference: RangeInclusive<i32>,
+    ) -> Result<Normals, WeatheredError> {
+        let archive = Forecast::new()
+            .endpoint(Endpoint::Archive)
+            .coord(latitude, longitude)
+            .settings(Settings::timezone("auto".into()))
+            .settings(Settings::start_date(format!(
+                "{:04}-01-01",
+                reference.start()
+            )))
+            .settings(Settings::end_date(format!("{:04}-12-31", reference.end())))
+            .hourly(variable.clone());
+        let response = self.fetch_chunked(&archive, ChunkSize::Years(1)).await?;
+        Ok(Normals::from_response(&response, variable))
+    }
+
+    /// Fetch `start_date` to `end_date` from the archive up to a few days
+    /// ago and from the forecast API after, with the variables and settings
+    /// of `forecast`, and concatenate the two
+    ///
+    /// Variables the archive does not serve are rejected before any request
+    pub async fn fetch_seamless(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<SeamlessResponse, WeatheredError> {
+        let seconds = self
+            .runtime
+            .system_time()
+            .duration_since(web_time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        let today = Date::from_days(seconds.div_euclid(86_400));
+        let Some(split) = seamless::split(start_date, end_date, today) else {
+            return Err(WeatheredError::MissingDates {
+                url: forecast.with_date_range(start_date, end_date).to_string(),
+            });
+        };
+        let archive = match &split.archive {
+            Some((start, end)) => Some(
+                Archive::from(forecast.with_date_range(start, end))
+                    .build()
+                    .map_err(WeatheredError::Archive)?,
+            ),
+            None => None,
+        };
+        let recent = split.forecast.as_ref().map(|(start, end)| {
+            forecast
+                .with_date_range(start, end)
+                .endpoint(Endpoint::Forecast)
+        });
+
+        let mut parts = Vec::with_capacity(2);
+        let mut urls = Vec::with_capacity(2);
+        if let Some(archive) = &archive {
+            parts.push(self.archive(archive).await?);
+            urls.push(archive.forecast().to_url(&self.urls.archive));
+        }
+        if let Some(recent) = &recent {
+            parts.push(self.forecast(recent).await?);
+            urls.push(recent.to_url(&self.urls.forecast));
+        }
+        let archived = archive.as_ref().and(parts.first());
+        let archived_hours = archived.and_then(|part| Some(part.hourly.as_ref()?.len()));
+        let archived_days = archived.and_then(|part| Some(part.daily.as_ref()?.len()));
+        let stitch = match (&archive, split.forecast) {
+            (Some(_), Some((start, _))) => Some(start),
+            _ => None,
+        };
+        let response =
+            chunk::merge(parts).map_err(|(index, reason)| WeatheredError::ChunkMismatch {
+                url: urls.swap_remove(index),
+                reason,
+            })?;
+        Ok(SeamlessResponse {
+            stitch,
+            hourly_sources: seamless::sources(
+                archived_hours.unwrap_or_default(),
+                response.hourly.as_ref().map_or(0, |hourly| hourly.len()),
+            ),
+            daily_sources: seamless::sources(
+                archived_days.unwrap_or_default(),
+                response.daily.as_ref().map_or(0, |daily| daily.len()),
+            ),
+            response,
+        })
+    }
+
+    /// Fetch the forecast now and then every `interval`, roughly, yielding
+    /// only the forecasts that changed since the previous one
+    pub fn watch<'a>(
+        &'a self,
+        forecast: &'a Forecast<Coordinates>,
+        interval: Duration,
+    ) -> Watch<'a, T>
+    where
+        T: MaybeSync,
+    {
+        Watch::new(self, forecast, interval, self.runtime.clone())
+    }
+
+    /// Perform the requests one after the other until `stop` completes,
+    /// e.g. `token.cancelled()`; requests and retry waits in flight are abandoned
+    /// and the forecasts fetched so far are returned in `WeatheredError::Cancelled`
+    ///
+    /// A failing request, e.g. rate limited, ends the batch with the forecasts
+    /// fetched before it in `WeatheredError::Partial`
+    pub async fn fetch_all(
+        &self,
+        forecasts: &[Forecast<Coordinates>],
+        stop: impl Future<Output = ()>,
+    ) -> Result<Vec<ForecastResponse>, WeatheredError> {
+        let mut stop = pin!(stop);
+        let mut completed = Vec::with_capacity(forecasts.len());
+        for forecast in forecasts {
+            let mut send = pin!(self.send(forecast, None, true));
+            let outcome = poll_fn(|cx| {
+                if stop.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(None);
+                }
+                send.as_mut().poll(cx).map(Some)
+            })
+            .await;
+            match outcome {
+                Some(Ok((response, _))) => completed.push(response),
+                Some(Err(source)) => {
+                    return Err(WeatheredError::Partial {
+                        completed,
+                        source: Box::new(source),
+                    })
+                }
+                None => return Err(WeatheredError::Cancelled { completed }),
+            }
+        }
+        Ok(completed)
+    }
+
+    async fn send(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        timeout: Option<Duration>,
+        wait_for_permit: bool,
+    ) -> Result<(ForecastResponse, FetchOutcome), WeatheredError> {
+        self.send_recorded(forecast, timeout, wait_for_permit)
+            .await
+            .0
+    }
+
+    /// Perform the request, returning the statistics of the call along the result
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "weathered.fetch",
+            skip_all,
+            fields(
+                url = %forecast.to_url(&self.urls.forecast),
+                locations = 1,
+                variables = forecast.variable_count(),
+            )
+        )
+    )]
+    async fn send_recorded(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        timeout: Option<Duration>,
+        wait_for_permit: bool,
+    ) -> (
+        Result<(ForecastResponse, FetchOutcome), WeatheredError>,
+        RequestStats,
+    ) {
+        let start = self.runtime.now();
+        let mut stats = RequestStats {
+            endpoint: forecast.endpoint,
+            ..RequestStats::new()
+        };
+        let result = self
+            .lookup(forecast, timeout, wait_for_permit, &mut stats)
+            .await;
+        stats.duration = self.runtime.now() - start;
+        stats.outcome = result.as_ref().ok().map(|(_, outcome)| *outcome);
+        if let Some(metrics) = &self.metrics {
+            metrics.on_request_complete(stats.clone());
+        }
+        (result, stats)
+    }
+
+    /// Answer from the response cache, or fetch and store the forecast
+    async fn lookup(
+        &self,
+        forecast: &Forecast<Coordinates>,
+        timeout: Option<Duration>,
+        wait_for_permit: bool,
+        stats: &mut RequestStats,
+    ) -> Result<(ForecastResponse, FetchOutcome), WeatheredError> {
+        forecast.check_dates()?;
+        let check = |url: &str, parsed: &ForecastResponse| self.check(forecast, url, parsed);
+        self.cached(forecast, timeout, wait_for_permit, stats, check)
+            .await
+    }
+
+    /// Answer from the response cache, or fetch the response, accept it
+    /// with `check` and store it
+    async fn cached(
+        &self,
+        request: &impl EndpointRequest,
+        timeout: Option<Duration>,
+        wait_for_permit: bool,
+        stats: &mut RequestStats,
+        check: impl FnOnce(&str, &ForecastResponse) -> Result<(), WeatheredError>,
+    ) -> Result<(ForecastResponse, FetchOutcome), WeatheredError> {
+        let key = crate::cache_key(request.endpoint(), &request.to_url(""));
+        if let (true, Some(cache)) = (self.read_cache, &self.cache) {
+            if let Some(response) = cache.get(&key) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("cache hit");
+                return Ok((response, FetchOutcome::Cached));
+            }
+            #[cfg(feature = "tracing")]
+            tracing::debug!("cache miss");
+        }
+        let outcome = self
+            .fetch(request, &key, timeout, wait_for_permit, stats, check)
+            .await?;
+        if let Some(cache) = &self.cache {
+            cache.put(&key, &outcome.0);
+        }
+        Ok(outcome)
+    }
+
+    /// Download the response, revalidating it against the conditional store
+    async fn fetch(
+        &self,
+        request: &impl EndpointRequest,
+        key: &str,
+        timeout: Option<Duration>,
+        wait_for_permit: bool,
+        stats: &mut RequestStats,
+        check: impl FnOnce(&str, &ForecastResponse) -> Result<(), WeatheredError>,
+    ) -> Result<(ForecastResponse, FetchOutcome), WeatheredError> {
+        let mut template = HttpRequest {
+            url: String::new(),
+            headers: vec![("User-Agent".into(), self.user_agent.clone())],
+            timeout,
+        };
+        let stored = self.conditional.as_ref().and_then(|store| store.get(key));
+        if let Some(entry) = &stored {
+            if let Some(etag) = &entry.etag {
+                template
+                    .headers
+                    .push(("If-None-Match".into(), etag.clone()));
+            }
+            if let Some(date) = &entry.last_modified {
+                template
+                    .headers
+                    .push(("If-Modified-Since".into(), date.clone()));
+            }
+        }
+
+        let query = request.to_url("") + self.decode_mode.query();
+        let (request, response) = self
+            .dispatch(request.endpoint(), &query, template, wait_for_permit, stats)
+            .await?;
+        if let (304, Some(entry)) = (response.status, stored) {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("not modified, reusing the stored body");
+            let parsed = ForecastResponse::parse(&request.url, &entry.body, self.decode_mode)?;
+            return Ok((parsed, FetchOutcome::Revalidated));
+        }
+        #[cfg(feature = "tracing")]
+        let parse_start = self.runtime.now();
+        let parsed = ForecastResponse::decode(&request.url, &response, self.decode_mode)?;
+        check(&request.url, &parsed)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            bytes = response.body.len(),
+            parse_duration = ?(self.runtime.now() - parse_start),
+            "response parsed"
+        );
+        if let Some(store) = &self.conditional {
+            let etag = response.header("etag").map(str::to_string);
+            let last_modified = response.header("last-modified").map(str::to_string);
+            if etag.is_some() || last_modified.is_some() {
+                let entry = ConditionalEntry {
+                    etag,
+                    last_modified,
+                    body: response.body,
+                };
+                store.put(key, entry);
+            }
+        }
+        Ok((parsed, FetchOutcome::Fresh))
+    }
+
+    /// Send the query to the base URL, then to each fallback in turn while hosts
+    /// cannot be reached or answer server errors, and run the hooks
+    ///
+    /// Returns the request actually sent along its response
+    async fn dispatch(
+        &self,
+        endpoint: Endpoint,
+        query: &str,
+        template: HttpRequest,
+        wait_for_permit: bool,
+        stats: &mut RequestStats,
+    ) -> Result<(HttpRequest, HttpResponse), WeatheredError> {
+        // Mirrors are only configured for the forecast host
+        let fallbacks = match endpoint {
+            Endpoint::Forecast => &self.fallback_base_urls[..],
+            _ => &[],
+        };
+        let mirrors = fallbacks.len();
+        for attempt in 0..=mirrors {
+            let base = match attempt {
+                0 => self.urls.get(endpoint),
+                n => &fallbacks[n - 1],
+            };
+            let mut request = HttpRequest {
+                url: format!("{base}{query}"),
+                ..template.clone()
+            };
+            for hook in &self.request_hooks {
+                hook.on_request(&mut request);
+            }
+            let outcome = self
+                .execute(&request, wait_for_permit, &mut stats.retries)
+                .await;
+            let unavailable = match &outcome {
+                Ok(response) => response.status >= 500,
+                Err(WeatheredError::Network { source, .. }) => {
+                    matches!(source, TransportError::Connect(_))
+                }
+                Err(_) => false,
+            };
+            if unavailable && attempt < mirrors {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(%base, "host unavailable, trying the next base URL");
+                continue;
+            }
+            let response = outcome?;
+            stats.status = Some(response.status);
+            stats.bytes = response.body.len();
+            stats.base_url = Some(base.to_string());
+            for hook in &self.response_hooks {
+                hook.on_response(&response)
+                    .map_err(|source| WeatheredError::Rejected {
+                        url: request.url.clone(),
+                        source,
+                    })?;
+            }
+            return Ok((request, response));
+        }
+        unreachable!("the base URL is always tried")
+    }
+
+    async fn execute(
+        &self,
+        request: &HttpRequest,
+        wait_for_permit: bool,
+        retries: &mut u32,
+    ) -> Result<HttpResponse, WeatheredError> {
+        let mut permit_taken = false;
+        if let (false, Some(limiter)) = (wait_for_permit, &self.limiter) {
+            if let Err(retry_in) = limiter.try_acquire() {
+                return Err(WeatheredError::Throttled { retry_in });
+            }
+            permit_taken = true;
+        }
+        let mut attempt = 1;
+        let mut rate_limit_waited = false;
+        loop {
+            // Every call to the API needs its own permit
+            if let Some(limiter) = &self.limiter {
+                if !std::mem::take(&mut permit_taken) {
+                    limiter.acquire().await;
+                }
+            }
+            let start = self.runtime.now();
+            let outcome = self.transport.get(request).await;
+            let rate_limit_wait = match &outcome {
+                Ok(response) if !rate_limit_waited => self.retry.rate_limit_wait(response),
+                _ => None,
+            };
+            if let Some(wait) = rate_limit_wait {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(?wait, "rate limited by the API, waiting before retrying");
+                self.runtime.sleep(wait).await;
+                rate_limit_waited = true;
+                *retries += 1;
+                continue;
+            }
+            if attempt < self.retry.max_attempts() && self.retry.is_retryable(&outcome) {
+                let delay = self.retry.delay(attempt);
+                #[cfg(feature = "tracing")]
+                tracing::warn!(attempt, ?delay, "transient failure, retrying");
+                self.runtime.sleep(delay).await;
+                attempt += 1;
+                *retries += 1;
+                continue;
+            }
+            return outcome.map_err(|source| match (&self.proxy, source) {
+                (Some(proxy), TransportError::Connect(source)) => WeatheredError::Proxy {
+                    url: request.url.clone(),
+                    proxy: proxy.clone(),
+                    source: TransportError::Connect(source),
+                },
+                (_, source) => WeatheredError::transport(
+                    request.url.clone(),
+                    source,
+                    self.runtime.now() - start,
+                ),
+            });
+        }
+    }
+}
+
+impl<T: Clone> Client<T> {
+    /// Copy of the client skipping cache lookups; fetched forecasts still refresh the cache
+    pub fn bypass_cache(&self) -> Self {
+        Client {
+            read_cache: false,
+            ..self.clone()
+        }
+    }
+}
+
+/// Longest list of coordinates sent in a single multi-location call
+const MAX_LOCATIONS_PER_CALL: usize = 100;
+
+/// Report progress, ignoring panics so that a faulty sink cannot abort the fetch
+fn report(
+    progress: Option<&dyn ProgressSink>,
+    completed: usize,
+    total: usize,
+    last: &RequestStats,
+) {
+    if let Some(progress) = progress {
+        let call = std::panic::AssertUnwindSafe(|| progress.on_progress(completed, total, last));
+        if std::panic::catch_unwind(call).is_err() {
+            #[cfg(feature = "tracing")]
+            tracing::warn!("progress sink panicked");
+        }
+    }
+}
+
+/// API call planned by `Client::forecast_many`, as indices of the requests it answers
+enum Job {
+    Single(usize),
+    Many(Vec<usize>),
+}
+
+fn default_client() -> &'static Client {
+    static DEFAULT: OnceLock<Client> = OnceLock::new();
+    DEFAULT.get_or_init(|| Client::new(reqwest::Client::new()))
+}
+
+impl Forecast<Coordinates> {
+    /// Perform the request with a lazily created default client
+    pub async fn fetch(&self) -> Result<ForecastResponse, WeatheredError> {
+        default_client().forecast(self).await
+    }
+
+    /// Fill `Settings::elevation` from the elevation API with the default
+    /// client, see `Client::resolve_elevation`
+    pub async fn elevation_from_api(self) -> Result<Self, WeatheredError> {
+        default_client().resolve_elevation(self).await
+    }
+
+    /// Perform the request with the default client, giving up after `timeout`
+    pub async fn fetch_with_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        default_client().forecast_with_timeout(self, timeout).await
+    }
+
+    /// Perform the request through the given client, sharing its connection pool
+    ///
+    /// Only the `reqwest::Client` is reused: the request is sent once, without
+    /// retries, rate limiting, conditional requests or a response cache. Keep a
+    /// `Client` built with `Client::new` or `Client::builder` to have those
+    /// apply across calls
+    pub async fn fetch_with(
+        &self,
+        client: &reqwest::Client,
+    ) -> Result<ForecastResponse, WeatheredError> {
+        Client::new(client.clone()).forecast(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+    use crate::runtime::tests::ManualRuntime;
+    use crate::testing::StaticTransport;
+    use crate::{
+        AlertRule, ArchiveError, AtomicMetrics, Comparison, Daily, Hourly, Inconsistency,
+        MemoryCache, MemoryConditionalStore, PressureVar, Settings, Source,
+    };
+    use futures_core::Stream;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::SystemTime;
+
+    const FIXTURE: &str = include_str!("../fixtures/forecast.json");
+
+    fn forecast() -> Forecast {
+        Forecast::new()
+            .coord(52.52, 13.41)
+            .hourly(Hourly::temperature_2m)
+            .hourly(Hourly::rain)
+    }
+
+    fn client(base_url: &str) -> Client {
+        Client {
+            urls: EndpointUrls {
+                forecast: base_url.to_string(),
+                ..Default::default()
+            },
+            ..Client::with_transport(ReqwestTransport::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn fetched_series_allocated_once() {
+        const HOURS: i64 = 100_000;
+        let values = |offset: i64| {
+            (0..HOURS)
+                .map(|hour| ((hour + offset) % 40).to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let body = format!(
+            r#"{{"latitude":52.52,"longitude":13.41,"elevation":38.0,"generationtime_ms":1.0,"utc_offset_seconds":0,"timezone":"GMT","timezone_abbreviation":"GMT","hourly":{{"time":[{}],"temperature_2m":[{}],"rain":[{}]}}}}"#,
+            (0..HOURS)
+                .map(|hour| (1_672_531_200 + hour * 3600).to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            values(0),
+            values(7),
+        );
+        let client = Client::with_transport(StaticTransport::new().route("/v1/forecast", body));
+
+        let response = client.forecast(&forecast()).await.unwrap();
+
+        let hourly = response.hourly.unwrap();
+        assert_eq!(hourly.len(), HOURS as usize);
+        for (name, series) in &hourly.series {
+            assert_eq!(series.0.capacity(), hourly.len(), "{name}");
+        }
+        assert_eq!(hourly.get("rain").unwrap()[1], Some(8.0));
+    }
+
+    #[tokio::test]
+    async fn fetch_decodes_fixture() {
+        let server = MockServer::start(vec![MockResponse::ok(FIXTURE)]);
+        let client = client(&format!("{}/v1/forecast", server.url()));
+
+        let response = client.forecast(&forecast()).await.unwrap();
+
+        assert_eq!(response.latitude, 52.52);
+        assert_eq!(response.timezone, "GMT");
+        assert_eq!(response.elevation, 38.0);
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/v1/forecast?latitude=52.52&longitude=13.41&hourly=,temperature_2m,rain"
+        );
+    }
+
+    #[tokio::test]
+    async fn archive() {
+        let server = MockServer::start(vec![MockResponse::ok(FIXTURE)]);
+        let recorder = Arc::new(Recorder::default());
+        let client = Client {
+            urls: EndpointUrls {
+                archive: format!("{}/v1/archive", server.url()),
+                ..Default::default()
+            },
+            metrics: Some(recorder.clone()),
+            ..client(server.url())
+        };
+        let archive = crate::Archive::new()
+            .coord(52.52, 13.41)
+            .dates("2023-05-16", "2023-05-16")
+            .hourly(Hourly::temperature_2m)
+            .build()
+            .unwrap();
+
+        let response = client.archive(&archive).await.unwrap();
+        assert_eq!(response.latitude, 52.52);
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/v1/archive?latitude=52.52&longitude=13.41&start_date=2023-05-16&end_date=2023-05-16&hourly=,temperature_2m"
+        );
+        let recorded = recorder.0.lock().unwrap();
+        assert_eq!(recorded[0].endpoint, Endpoint::Archive);
+        assert_eq!(recorded[0].status, Some(200));
+    }
+
+    #[tokio::test]
+    async fn historical_forecast() {
+        let server = MockServer::start(vec![MockResponse::ok(FIXTURE)]);
+        let client = Client {
+            urls: EndpointUrls {
+                historical_forecast: format!("{}/historical", server.url()),
+                ..Default::default()
+            },
+            ..client("http://127.0.0.1:9")
+        };
+        let historical = forecast().endpoint(Endpoint::HistoricalForecast);
+
+        match client.forecast(&historical).await {
+            Err(WeatheredError::MissingDates { url }) => assert_eq!(url, historical.to_string()),
+            other => panic!("expected missing dates, got {other:?}"),
+        }
+        assert_eq!(server.requests(), 0);
+
+        let dated = historical
+            .settings(Settings::start_date("2023-05-16".into()))
+            .settings(Settings::end_date("2023-05-16".into()));
+        client.forecast(&dated).await.unwrap();
+        assert!(server
+            .last_path()
+            .unwrap()
+            .starts_with("/historical?latitude=52.52&longitude=13.41&start_date="));
+    }
+
+    #[tokio::test]
+    async fn air_quality() {
+        let server = MockServer::start(vec![MockResponse::ok(include_str!(
+            "../fixtures/air_quality.json"
+        ))]);
+        let client = Client {
+            urls: EndpointUrls {
+                air_quality: format!("{}/v1/air-quality", server.url()),
+                ..Default::default()
+            },
+            ..client(server.url())
+        };
+        let request = AirQuality::new()
+            .coord(52.52, 13.41)
+            .hourly(crate::AirQualityVar::pm2_5)
+            .domains(crate::Domain::auto);
+
+        let response = client.air_quality(&request).await.unwrap();
+        assert_eq!(response.hourly.unwrap().len(), 4);
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/v1/air-quality?latitude=52.52&longitude=13.41&hourly=,pm2_5&domains=auto"
+        );
+    }
+
+    #[tokio::test]
+    async fn marine() {
+        let server = MockServer::start(vec![MockResponse::ok(include_str!(
+            "../fixtures/marine.json"
+        ))]);
+        let recorder = Arc::new(Recorder::default());
+        let client = Client {
+            urls: EndpointUrls {
+                marine: format!("{}/v1/marine", server.url()),
+                ..Default::default()
+            },
+            metrics: Some(recorder.clone()),
+            ..client(server.url())
+        };
+        let request = Marine::new()
+            .coord(54.54, 10.23)
+            .hourly(crate::MarineHourly::wave_height);
+
+        let response = client.marine(&request).await.unwrap();
+        assert_eq!(response.daily.unwrap().time.len(), 2);
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/v1/marine?latitude=54.54&longitude=10.23&cell_selection=sea&hourly=,wave_height"
+        );
+        assert_eq!(recorder.0.lock().unwrap()[0].endpoint, Endpoint::Marine);
+    }
+
+    #[tokio::test]
+    async fn flood() {
+        let server = MockServer::start(vec![MockResponse::ok(include_str!(
+            "../fixtures/flood.json"
+        ))]);
+        let client = Client {
+            urls: EndpointUrls {
+                flood: format!("{}/v1/flood", server.url()),
+                ..Default::default()
+            },
+            ..client(server.url())
+        };
+        let request = Flood::new()
+            .coord(59.91, 10.75)
+            .daily(crate::FloodDaily::river_discharge)
+            .ensemble(true);
+
+        let response = client.flood(&request).await.unwrap();
+        assert_eq!(response.flood_series().unwrap().member_count(), 2);
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/v1/flood?latitude=59.91&longitude=10.75&daily=,river_discharge&ensemble=true"
+        );
+    }
+
+    #[tokio::test]
+    async fn satellite_without_dates() {
+        let server = MockServer::start(vec![]);
+        let request = Satellite::new()
+            .coord(47.37, 8.55)
+            .hourly(crate::SatelliteVar::shortwave_radiation);
+
+        let error = client(server.url()).satellite(&request).await.unwrap_err();
+        assert!(matches!(error, WeatheredError::MissingDates { .. }));
+        assert!(server.last_path().is_none());
+    }
+
+    #[tokio::test]
+    async fn elevation() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(r#"{"elevation":[38.0]}"#),
+            MockResponse::ok(r#"{"elevation":[3571.0]}"#),
+            MockResponse::ok(r#"{"elevation":[38.0]}"#),
+        ]);
+        let client = Client {
+            urls: EndpointUrls {
+                elevation: format!("{}/v1/elevation", server.url()),
+                ..Default::default()
+            },
+            ..client(server.url())
+        };
+
+        let points: Vec<_> = (0..101).map(|_| (46.55, 7.98)).collect();
+        let heights = client
+            .elevation(&ElevationRequest::new(&points))
+            .await
+            .unwrap();
+        assert_eq!(heights.len(), 2);
+        assert_eq!(server.requests(), 2);
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/v1/elevation?latitude=46.55&longitude=7.98"
+        );
+
+        let forecast = client.resolve_elevation(forecast()).await.unwrap();
+        assert!(forecast
+            .to_string()
+            .ends_with("&elevation=38&hourly=,temperature_2m,rain"));
+    }
+
+    #[tokio::test]
+    async fn geocode() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(include_str!("../fixtures/geocoding.json")),
+            MockResponse::new(
+                400,
+                r#"{"error":true,"reason":"Parameter count must be between 1 and 100."}"#,
+            ),
+        ]);
+        let client = Client {
+            urls: EndpointUrls {
+                geocoding: format!("{}/v1/search", server.url()),
+                ..Default::default()
+            },
+            ..client(server.url())
+        };
+
+        let search = GeocodingRequest::new("Zürich").count(3);
+        let results = client.geocode(&search).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].country.as_deref(), Some("Switzerland"));
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/v1/search?name=Z%C3%BCrich&count=3&format=json"
+        );
+        assert!(matches!(
+            client.geocode(&search).await,
+            Err(WeatheredError::Api { status: 400, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn geocode_and_elevation_run_hooks() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(include_str!("../fixtures/geocoding.json")),
+            MockResponse::ok(r#"{"elevation":[38.0]}"#),
+        ]);
+        let responses = Arc::new(AtomicUsize::new(0));
+        let counted = responses.clone();
+        let recorder = Arc::new(Recorder::default());
+        let client = Client {
+            urls: EndpointUrls {
+                geocoding: format!("{}/v1/search", server.url()),
+                elevation: format!("{}/v1/elevation", server.url()),
+                ..Default::default()
+            },
+            response_hooks: vec![Arc::new(move |_: &HttpResponse| {
+                counted.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })],
+            metrics: Some(recorder.clone()),
+            ..client(server.url())
+        };
+
+        client
+            .geocode(&GeocodingRequest::new("Zürich"))
+            .await
+            .unwrap();
+        client
+            .elevation(&ElevationRequest::new(&[(46.55, 7.98)]))
+            .await
+            .unwrap();
+
+        assert_eq!(responses.load(Ordering::SeqCst), 2);
+        let recorded = recorder.0.lock().unwrap();
+        let endpoints: Vec<_> = recorded.iter().map(|stats| stats.endpoint).collect();
+        assert_eq!(endpoints, [Endpoint::Geocoding, Endpoint::Elevation]);
+        assert_eq!(recorded[1].status, Some(200));
+    }
+
+    #[cfg(feature = "fb")]
+    #[tokio::test]
+    async fn fetch_flatbuffers() {
+        let body = include_bytes!("../fixtures/flatbuffers.bin");
+        let server = MockServer::start(vec![MockResponse::ok(&body[..])]);
+        let client = Client {
+            decode_mode: DecodeMode::FlatBuffers,
+            ..client(server.url())
+        };
+        let request = Forecast::new()
+            .coord(52.52, 13.41)
+            .hourly(Hourly::temperature_2m)
+            .hourly(Hourly::precipitation);
+
+        let response = client.forecast(&request).await.unwrap();
+
+        assert_eq!(response, ForecastResponse::from_flatbuffers(body).unwrap());
+        assert!(server.last_path().unwrap().ends_with("&format=flatbuffers"));
+        assert!(Client::builder()
+            .decode_mode(DecodeMode::FlatBuffers)
+            .build()
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn fetch_errors_carry_url() {
+        let server = MockServer::start(vec![
+            MockResponse::new(500, "oops"),
+            MockResponse::ok("not json"),
+        ]);
+        let client = client(server.url());
+        let url = forecast().to_url(server.url());
+
+        match client.forecast(&forecast()).await {
+            Err(WeatheredError::Status {
+                url: failed,
+                status,
+            }) => {
+                assert_eq!(status, 500);
+                assert_eq!(failed, url);
+            }
+            other => panic!("expected status error, got {other:?}"),
+        }
+        match client.forecast(&forecast()).await {
+            Err(WeatheredError::Decode { url: failed, .. }) => assert_eq!(failed, url),
+            other => panic!("expected decode error, got {other:?}"),
+        }
+        assert_eq!(server.requests(), 2);
+
+        // Nothing listens on the discard port
+        let unreachable = forecast().to_url("http://127.0.0.1:9");
+        match self::client("http://127.0.0.1:9")
+            .forecast(&forecast())
+            .await
+        {
+            Err(WeatheredError::Network { url: failed, .. }) => assert_eq!(failed, unreachable),
+            other => panic!("expected network error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn injected_client_reuses_connections() {
+        let server = MockServer::start(vec![MockResponse::ok(FIXTURE)]);
+        let http = reqwest::Client::builder()
+            .pool_max_idle_per_host(1)
+            .build()
+            .unwrap();
+        let wrap = |http: &reqwest::Client| Client {
+            urls: EndpointUrls {
+                forecast: server.url().to_string(),
+                ..Default::default()
+            },
+            ..Client::new(http.clone())
+        };
+        let client = wrap(&http);
+
+        client.forecast(&forecast()).await.unwrap();
+        client.forecast(&forecast()).await.unwrap();
+        // `fetch_with` wraps the injected client anew for each call
+        wrap(&http).forecast(&forecast()).await.unwrap();
+
+        assert_eq!(server.requests(), 3);
+        assert_eq!(server.connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn pressure_levels_pass_consistency_check() {
+        // The API only returns the columns of pressure variables listed in `hourly=`
+        let transport = StaticTransport::new().route(
+            "&hourly=,temperature_2m,temperature_850hPa,temperature_500hPa",
+            include_str!("../fixtures/profile.json"),
+        );
+        let client = Client::with_transport(transport);
+        let forecast = Forecast::new()
+            .coord(48.14, 11.58)
+            .hourly(Hourly::temperature_2m)
+            .pressure_var(PressureVar::temperature(850))
+            .pressure_var(PressureVar::temperature(500));
+
+        let response = client.forecast(&forecast).await.unwrap();
+        assert_eq!(
+            response.pressure(PressureVar::temperature(850)).unwrap()[0],
+            Some(5.8)
+        );
+    }
+
+    #[tokio::test]
+    async fn for_city() {
+        let transport = StaticTransport::new()
+            .route("name=", include_str!("../fixtures/geocoding.json"))
+            .route("latitude=47.36667&longitude=8.55", FIXTURE);
+        let searches = Arc::new(AtomicUsize::new(0));
+        let counter = searches.clone();
+        let client = Client {
+            request_hooks: vec![Arc::new(move |request: &mut HttpRequest| {
+                if request.url.contains("name=") {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            })],
+            ..Client::with_transport(transport)
+        };
+
+        let (place, response) = client
+            .for_city("Zürich", |forecast| {
+                forecast.hourly(Hourly::temperature_2m)
+            })
+            .await
+            .unwrap();
+        assert_eq!(place.id, 2657896);
+        assert_eq!(place.timezone.as_deref(), Some("Europe/Zurich"));
+        assert!(response.hourly.is_some());
+
+        let (again, _) = client.clone().for_city(" zürich", |f| f).await.unwrap();
+        assert_eq!(again, place);
+        assert_eq!(searches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn for_city_ambiguous_or_unknown() {
+        let transport = StaticTransport::new()
+            .route(
+                "name=C%C3%B3rdoba",
+                r#"{"results":[
+                    {"id":3860259,"name":"Córdoba","latitude":-31.4135,"longitude":-64.18105,"feature_code":"PPLA","country":"Argentina","admin1":"Cordoba","population":1428214},
+                    {"id":2519240,"name":"Córdoba","latitude":37.89155,"longitude":-4.77275,"feature_code":"PPLA2","country":"Spain","admin1":"Andalusia","population":328428}
+                ]}"#,
+            )
+            .route("name=Nowhere", r#"{"generationtime_ms":0.2}"#);
+        let client = Client::with_transport(transport);
+
+        match client.for_city("Córdoba", |f| f).await {
+            Err(error @ WeatheredError::AmbiguousPlace { .. }) => assert_eq!(
+                error.to_string(),
+                "\"Córdoba\" matches several cities: Córdoba (Cordoba, Argentina), Córdoba (Andalusia, Spain)"
+            ),
+            other => panic!("expected an ambiguous name, got {other:?}"),
+        }
+        assert!(matches!(
+            client.for_city("Nowhere", |f| f).await,
+            Err(WeatheredError::UnknownPlace { name }) if name == "Nowhere"
+        ));
+    }
+
+    #[tokio::test]
+    async fn climate_normals() {
+        let year = |year: i32, first: f64, second: f64| {
+            format!(
+                r#"{{"latitude":52.52,"longitude":13.42,"generationtime_ms":3.1,"utc_offset_seconds":3600,"timezone":"Europe/Berlin","timezone_abbreviation":"CET","elevation":38.0,"hourly":{{"time":["{year}-03-01T00:00","{year}-03-01T12:00"],"temperature_2m":[{first},{second}]}}}}"#
+            )
+        };
+        let transport = StaticTransport::new()
+            .route(
+                "start_date=2001-01-01&end_date=2001-12-31",
+                year(2001, 1.0, 3.0),
+            )
+            .route(
+                "start_date=2002-01-01&end_date=2002-12-31",
+                year(2002, 4.0, 6.0),
+            )
+            .route(
+                "start_date=2003-01-01&end_date=2003-12-31",
+                year(2003, 7.0, 9.0),
+            );
+        let client = Client::with_transport(transport);
+
+        let normals = client
+            .climate_normals(52.52, 13.41, Hourly::temperature_2m, 2001..=2003)
+            .await
+            .unwrap();
+        let march = normals.lookup("2024-03-01").unwrap();
+        assert_eq!((march.mean, march.years), (5.0, 3));
+        assert!((march.std_dev - 6f64.sqrt()).abs() < 1e-9);
+        assert_eq!(normals.lookup("2024-03-02"), None);
+    }
+
+    #[tokio::test]
+    async fn compare_models() {
+        let transport = StaticTransport::new().route(
+            "&models=icon_seamless,gfs_seamless",
+            include_str!("../fixtures/models.json"),
+        );
+        let client = Client::with_transport(transport);
+
+        let forecast = Forecast::new()
+            .coord(50.1, 8.68)
+            .hourly(Hourly::temperature_2m)
+            .settings(Settings::models(vec![Model::icon_d2]));
+        let comparison = client
+            .compare_models(&forecast, &[Model::icon_seamless, Model::gfs_seamless])
+            .await
+            .unwrap();
+        let spread = comparison.spread(Hourly::temperature_2m).unwrap();
+        assert!((spread[0].unwrap() - 0.4).abs() < 1e-9);
+    }
+
+    /// Runtime whose wall clock stands at noon on `day`, `YYYY-MM-DD`
+    struct OnDay(&'static str);
+
+    impl AsyncRuntime for OnDay {
+        fn sleep(&self, _: Duration) -> crate::BoxFuture<'static, ()> {
+            Box::pin(std::future::ready(()))
+        }
+
+        fn system_time(&self) -> web_time::SystemTime {
+            let days = Date::parse(self.0).unwrap().days() as u64;
+            web_time::UNIX_EPOCH + Duration::from_secs(days * 86_400 + 43_200)
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_seamless() {
+        let transport = StaticTransport::new()
+            .route(
+                "/v1/archive?latitude=52.52&longitude=13.41&start_date=2024-06-13&end_date=2024-06-15&",
+                include_str!("../fixtures/seamless_archive.json"),
+            )
+            .route(
+                "/v1/forecast?latitude=52.52&longitude=13.41&start_date=2024-06-16&end_date=2024-06-18&",
+                include_str!("../fixtures/seamless_forecast.json"),
+            );
+        let client = Client {
+            runtime: Arc::new(OnDay("2024-06-20")),
+            ..Client::with_transport(transport)
+        };
+        let forecast = Forecast::new()
+            .coord(52.52, 13.41)
+            .hourly(Hourly::temperature_2m)
+            .daily(Daily::temperature_2m_max);
+
+        let seamless = client
+            .fetch_seamless(&forecast, "2024-06-13", "2024-06-18")
+            .await
+            .unwrap();
+        assert_eq!(seamless.stitch.as_deref(), Some("2024-06-16"));
+        let hourly = seamless.response.hourly.as_ref().unwrap();
+        assert_eq!(hourly.len(), 6);
+        assert_eq!(hourly.time[3].as_iso8601(), Some("2024-06-16T12:00"));
+        assert_eq!(
+            seamless.hourly_sources,
+            [
+                Source::Archive,
+                Source::Archive,
+                Source::Archive,
+                Source::Forecast,
+                Source::Forecast,
+                Source::Forecast
+            ]
+        );
+        assert_eq!(
+            seamless.daily_sources[2..4],
+            [Source::Archive, Source::Forecast]
+        );
+        assert_eq!(
+            seamless
+                .response
+                .daily
+                .unwrap()
+                .numbers("temperature_2m_max")
+                .unwrap()[3],
+            Some(20.3)
+        );
+
+        let recent = client
+            .fetch_seamless(&forecast, "2024-06-16", "2024-06-18")
+            .await
+            .unwrap();
+        assert_eq!(recent.stitch, None);
+        assert!(recent
+            .hourly_sources
+            .iter()
+            .all(|source| *source == Source::Forecast));
+
+        // Rejected before anything is fetched, no route serving it
+        let gusts = forecast.hourly(Hourly::windspeed_80m);
+        match client
+            .fetch_seamless(&gusts, "2024-06-01", "2024-06-18")
+            .await
+        {
+            Err(WeatheredError::Archive(ArchiveError::ForecastOnly(variable))) => {
+                assert_eq!(variable, "windspeed_80m")
+            }
+            other => panic!("expected the archive to refuse, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn api_location() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE),
+            MockResponse::ok(include_str!("../fixtures/geocoding.json")),
+        ]);
+        let location = ApiLocation::new()
+            .base_url(server.url())
+            .path_prefix("meteo")
+            .version(crate::ApiVersion::Custom("v2".into()));
+        let client = Client::builder().api_location(location).build().unwrap();
+
+        client.forecast(&forecast()).await.unwrap();
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/meteo/v2/forecast?latitude=52.52&longitude=13.41&hourly=,temperature_2m,rain"
+        );
+        client
+            .geocode(&GeocodingRequest::new("Bern"))
+            .await
+            .unwrap();
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/meteo/v2/search?name=Bern&format=json"
+        );
+    }
+
+    #[tokio::test]
+    async fn static_transport() {
+        let transport = StaticTransport::new().route("latitude=52.52", FIXTURE);
+        let client = Client::with_transport(transport);
+
+        let response = client.forecast(&forecast()).await.unwrap();
+        assert_eq!(response.timezone_abbreviation, "GMT");
+
+        let elsewhere = Forecast::new().coord(48.85, 2.35);
+        match client.forecast(&elsewhere).await {
+            Err(WeatheredError::Status { status: 404, .. }) => {}
+            other => panic!("expected unrouted url to fail, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn timeouts() {
+        let slow = MockResponse::ok(FIXTURE).delayed(Duration::from_millis(500));
+        let server = MockServer::start(vec![slow]);
+        let url = forecast().to_url(server.url());
+
+        let mut client = Client::builder()
+            .timeout(Duration::from_millis(100))
+            .connect_timeout(Duration::from_secs(1))
+            .build()
+            .unwrap();
+        client.urls.forecast = server.url().to_string();
+        match client.forecast(&forecast()).await {
+            Err(WeatheredError::Timeout {
+                elapsed,
+                url: failed,
+            }) => {
+                assert!(elapsed >= Duration::from_millis(100));
+                assert_eq!(failed, url);
+            }
+            other => panic!("expected timeout, got {other:?}"),
+        }
+
+        // Per-call override on a client without timeout
+        let client = self::client(server.url());
+        match client
+            .forecast_with_timeout(&forecast(), Duration::from_millis(100))
+            .await
+        {
+            Err(WeatheredError::Timeout { elapsed, .. }) => {
+                assert!(elapsed < Duration::from_millis(500))
+            }
+            other => panic!("expected timeout, got {other:?}"),
+        }
+        assert!(client.forecast(&forecast()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn https_through_the_selected_tls_backend() {
+        let server = MockServer::start_tls(vec![MockResponse::ok(FIXTURE)]);
+        let builder = Client::builder();
+        let client = Client {
+            urls: EndpointUrls {
+                forecast: server.url().to_string(),
+                ..Default::default()
+            },
+            ..builder
+                .add_root_certificate(server.certificate().unwrap().as_bytes())
+                .build()
+                .unwrap()
+        };
+
+        let response = client.forecast(&forecast()).await.unwrap();
+        assert_eq!(response.latitude, 52.52);
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures() {
+        let server = MockServer::start(vec![
+            MockResponse::new(503, "busy"),
+            MockResponse::new(502, "bad gateway"),
+            MockResponse::ok(FIXTURE),
+        ]);
+        let policy = RetryPolicy::new(3).base_delay(Duration::from_millis(1));
+        let mut client = Client::builder().retries(policy).build().unwrap();
+        client.urls.forecast = server.url().to_string();
+
+        let response = client.forecast(&forecast()).await.unwrap();
+        assert_eq!(response.latitude, 52.52);
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn inconsistent_responses_are_rejected() {
+        let server = MockServer::start(vec![MockResponse::ok(FIXTURE), MockResponse::ok(FIXTURE)]);
+        let request = forecast().hourly(Hourly::snowfall);
+
+        match client(server.url()).forecast(&request).await {
+            Err(WeatheredError::Inconsistent { url, source }) => {
+                assert!(url.contains("snowfall"));
+                assert_eq!(
+                    source.problems,
+                    [Inconsistency::Missing {
+                        block: "hourly",
+                        variable: "snowfall".to_string()
+                    }]
+                );
+            }
+            other => panic!("expected inconsistent response, got {other:?}"),
+        }
+        let lenient = Client {
+            check_consistency: false,
+            ..client(server.url())
+        };
+        assert!(lenient.forecast(&request).await.is_ok());
+        assert!(Client::builder().check_consistency(false).build().is_ok());
+    }
+
+    #[tokio::test]
+    async fn unavailable_hosts_fall_back_to_mirrors() {
+        let server = MockServer::start(vec![MockResponse::ok(FIXTURE)]);
+        let broken = MockServer::start(vec![MockResponse::new(503, "down")]);
+        // Nothing listens on the discard port
+        let client = Client {
+            fallback_base_urls: vec![broken.url().to_string(), server.url().to_string()],
+            ..client("http://127.0.0.1:9")
+        };
+        let served = std::sync::Mutex::new(Vec::new());
+        let progress = |_: usize, _: usize, last: &RequestStats| {
+            served.lock().unwrap().push(last.base_url.clone());
+        };
+
+        let responses = client
+            .forecast_many_with_progress(vec![forecast()], 1, &progress)
+            .await;
+        assert_eq!(responses[0].as_ref().unwrap().latitude, 52.52);
+        assert_eq!(broken.requests(), 1);
+        assert_eq!(server.requests(), 1);
+        assert_eq!(
+            served.into_inner().unwrap(),
+            [Some(server.url().to_string())]
+        );
+
+        let primary = MockServer::start(vec![MockResponse::new(500, "oops")]);
+        let mirror = MockServer::start(vec![MockResponse::new(502, "bad gateway")]);
+        let client = Client {
+            fallback_base_urls: vec![mirror.url().to_string()],
+            ..self::client(primary.url())
+        };
+        match client.forecast(&forecast()).await {
+            Err(WeatheredError::Status { url, status }) => {
+                assert_eq!(status, 502);
+                assert!(url.starts_with(mirror.url()));
+            }
+            other => panic!("expected the last mirror's error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let server = MockServer::start(vec![MockResponse::new(400, "bad request")]);
+        let policy = RetryPolicy::new(3).base_delay(Duration::from_millis(1));
+        let mut client = Client::builder().retries(policy).build().unwrap();
+        client.urls.forecast = server.url().to_string();
+
+        match client.forecast(&forecast()).await {
+            Err(WeatheredError::Status { status: 400, .. }) => {}
+            other => panic!("expected status error, got {other:?}"),
+        }
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_is_propagated() {
+        let in_30s = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(30));
+        let server = MockServer::start(vec![
+            MockResponse::new(429, "slow down").header("Retry-After", "120"),
+            MockResponse::new(429, "slow down").header("Retry-After", &in_30s),
+        ]);
+        let client = client(server.url());
+
+        match client.forecast(&forecast()).await {
+            Err(WeatheredError::RateLimited { retry_after, .. }) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(120)))
+            }
+            other => panic!("expected rate limit, got {other:?}"),
+        }
+        match client.forecast(&forecast()).await {
+            Err(WeatheredError::RateLimited {
+                retry_after: Some(delay),
+                ..
+            }) => assert!(delay > Duration::from_secs(25) && delay <= Duration::from_secs(30)),
+            other => panic!("expected rate limit, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_limit_is_retried_within_cap() {
+        let server = MockServer::start(vec![
+            MockResponse::new(429, "slow down").header("Retry-After", "0"),
+            MockResponse::ok(FIXTURE),
+            MockResponse::new(429, "slow down").header("Retry-After", "120"),
+        ]);
+        let policy = RetryPolicy::none().honor_retry_after(Duration::from_secs(5));
+        let mut client = Client::builder().retries(policy).build().unwrap();
+        client.urls.forecast = server.url().to_string();
+
+        assert!(client.forecast(&forecast()).await.is_ok());
+        assert_eq!(server.requests(), 2);
+
+        // Waiting two minutes exceeds the cap
+        match client.forecast(&forecast()).await {
+            Err(WeatheredError::RateLimited { .. }) => {}
+            other => panic!("expected rate limit, got {other:?}"),
+        }
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn try_forecast_without_permit() {
+        let transport = StaticTransport::new().route("latitude=52.52", FIXTURE);
+        let clock = ManualRuntime::new();
+        let client = Client {
+            limiter: Some(RateLimiter::new(1, 100, clock.clone())),
+            ..Client::with_transport(transport)
+        };
+        let clone = client.clone();
+
+        assert!(client.try_forecast(&forecast()).await.is_ok());
+        match clone.try_forecast(&forecast()).await {
+            Err(WeatheredError::Throttled { retry_in }) => {
+                assert_eq!(retry_in, Duration::from_secs(60))
+            }
+            other => panic!("expected throttling, got {other:?}"),
+        }
+
+        clock.advance(Duration::from_secs(60));
+        assert!(clone.try_forecast(&forecast()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn not_modified_reuses_stored_body() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE).header("ETag", "\"v1\""),
+            MockResponse::new(304, ""),
+        ]);
+        let client = Client {
+            conditional: Some(Arc::new(MemoryConditionalStore::new())),
+            ..client(server.url())
+        };
+
+        let (first, outcome) = client.forecast_with_outcome(&forecast()).await.unwrap();
+        assert_eq!(outcome, FetchOutcome::Fresh);
+        assert_eq!(server.last_header("If-None-Match"), None);
+
+        let (second, outcome) = client.forecast_with_outcome(&forecast()).await.unwrap();
+        assert_eq!(outcome, FetchOutcome::Revalidated);
+        assert_eq!(server.last_header("If-None-Match").unwrap(), "\"v1\"");
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn changed_etag_replaces_stored_body() {
+        let updated = FIXTURE.replace("\"elevation\":38.0", "\"elevation\":40.0");
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE).header("ETag", "\"v1\""),
+            MockResponse::ok(updated).header("ETag", "\"v2\""),
+            MockResponse::new(304, ""),
+        ]);
+        let client = Client {
+            conditional: Some(Arc::new(MemoryConditionalStore::new())),
+            ..client(server.url())
+        };
+
+        client.forecast_with_outcome(&forecast()).await.unwrap();
+        let (response, outcome) = client.forecast_with_outcome(&forecast()).await.unwrap();
+        assert_eq!(outcome, FetchOutcome::Fresh);
+        assert_eq!(response.elevation, 40.0);
+
+        let (response, outcome) = client.forecast_with_outcome(&forecast()).await.unwrap();
+        assert_eq!(outcome, FetchOutcome::Revalidated);
+        assert_eq!(server.last_header("If-None-Match").unwrap(), "\"v2\"");
+        assert_eq!(response.elevation, 40.0);
+    }
+
+    #[tokio::test]
+    async fn cache_answers_within_ttl() {
+        let server = MockServer::start(vec![MockResponse::ok(FIXTURE)]);
+        let clock = ManualRuntime::new();
+        let cache = MemoryCache::with_runtime(Duration::from_secs(600), 16, clock.clone());
+        let client = Client {
+            cache: Some(Arc::new(cache)),
+            ..client(server.url())
+        };
+
+        let (_, outcome) = client.forecast_with_outcome(&forecast()).await.unwrap();
+        assert_eq!(outcome, FetchOutcome::Fresh);
+        let (response, outcome) = client.forecast_with_outcome(&forecast()).await.unwrap();
+        assert_eq!(outcome, FetchOutcome::Cached);
+        assert_eq!(response.elevation, 38.0);
+        assert_eq!(server.requests(), 1);
+
+        let (_, outcome) = client
+            .bypass_cache()
+            .forecast_with_outcome(&forecast())
+            .await
+            .unwrap();
+        assert_eq!(outcome, FetchOutcome::Fresh);
+        assert_eq!(server.requests(), 2);
+
+        clock.advance(Duration::from_secs(600));
+        let (_, outcome) = client.forecast_with_outcome(&forecast()).await.unwrap();
+        assert_eq!(outcome, FetchOutcome::Fresh);
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn endpoints_go_through_caches() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(include_str!("../fixtures/marine.json")).header("ETag", "\"v1\""),
+            MockResponse::new(304, ""),
+        ]);
+        let recorder = Arc::new(Recorder::default());
+        let client = Client {
+            urls: EndpointUrls {
+                marine: format!("{}/v1/marine", server.url()),
+                ..Default::default()
+            },
+            cache: Some(Arc::new(MemoryCache::new(Duration::from_secs(600), 16))),
+            conditional: Some(Arc::new(MemoryConditionalStore::new())),
+            metrics: Some(recorder.clone()),
+            ..client(server.url())
+        };
+        let request = Marine::new()
+            .coord(54.54, 10.23)
+            .hourly(crate::MarineHourly::wave_height);
+
+        let first = client.marine(&request).await.unwrap();
+        let cached = client.marine(&request).await.unwrap();
+        assert_eq!(server.requests(), 1);
+        let revalidated = client.bypass_cache().marine(&request).await.unwrap();
+        assert_eq!(server.requests(), 2);
+        assert_eq!(server.last_header("If-None-Match").unwrap(), "\"v1\"");
+        assert_eq!(first, cached);
+        assert_eq!(first, revalidated);
+
+        let outcomes: Vec<_> = recorder
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|stats| stats.outcome)
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                Some(FetchOutcome::Fresh),
+                Some(FetchOutcome::Cached),
+                Some(FetchOutcome::Revalidated)
+            ]
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn gzip_bodies_are_decoded() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE),
+            MockResponse::gzip(FIXTURE.as_bytes()),
+        ]);
+        let client = client(server.url());
+
+        let identity = client.forecast(&forecast()).await.unwrap();
+        let compressed = client.forecast(&forecast()).await.unwrap();
+        assert!(server
+            .last_header("Accept-Encoding")
+            .unwrap()
+            .contains("gzip"));
+        assert_eq!(identity, compressed);
+
+        let plain = Client {
+            urls: EndpointUrls {
+                forecast: server.url().to_string(),
+                ..Default::default()
+            },
+            ..Client::builder().compression(false).build().unwrap()
+        };
+        let _ = plain.forecast(&forecast()).await;
+        assert_eq!(server.last_header("Accept-Encoding"), None);
+    }
+
+    #[tokio::test]
+    async fn requests_go_through_proxy() {
+        let proxy = MockServer::start(vec![MockResponse::ok(FIXTURE)]);
+        let client = Client {
+            urls: EndpointUrls {
+                forecast: "http://weather.invalid/v1/forecast".to_string(),
+                ..Default::default()
+            },
+            ..Client::builder().proxy(proxy.url()).build().unwrap()
+        };
+
+        client.forecast(&forecast()).await.unwrap();
+        assert!(proxy
+            .last_path()
+            .unwrap()
+            .starts_with("http://weather.invalid/v1/forecast?latitude=52.52"));
+
+        // Nothing listens on the discard port
+        let client = Client {
+            urls: EndpointUrls {
+                forecast: "http://weather.invalid/v1/forecast".to_string(),
+                ..Default::default()
+            },
+            ..Client::builder()
+                .proxy("http://127.0.0.1:9")
+                .build()
+                .unwrap()
+        };
+        match client.forecast(&forecast()).await {
+            Err(error @ WeatheredError::Proxy { .. }) => {
+                assert!(error.to_string().contains("proxy http://127.0.0.1:9"))
+            }
+            other => panic!("expected proxy error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn user_agent_is_sent_on_every_request() {
+        let server = MockServer::start(vec![
+            MockResponse::new(503, ""),
+            MockResponse::ok(FIXTURE).header("ETag", "\"v1\""),
+            MockResponse::new(304, ""),
+        ]);
+        let default = concat!("weathered/", env!("CARGO_PKG_VERSION"));
+        let client = Client {
+            retry: RetryPolicy::new(2).base_delay(Duration::ZERO),
+            conditional: Some(Arc::new(MemoryConditionalStore::new())),
+            ..client(server.url())
+        };
+
+        client.forecast(&forecast()).await.unwrap();
+        assert_eq!(server.last_header("User-Agent").unwrap(), default);
+        client.forecast(&forecast()).await.unwrap();
+        assert_eq!(server.last_header("User-Agent").unwrap(), default);
+
+        let custom = Client {
+            urls: EndpointUrls {
+                forecast: server.url().to_string(),
+                ..Default::default()
+            },
+            ..Client::builder().user_agent("myapp/1.2").build().unwrap()
+        };
+        custom.forecast(&forecast()).await.unwrap_err();
+        assert_eq!(
+            server.last_header("User-Agent").unwrap(),
+            format!("myapp/1.2 {default}")
+        );
+
+        let replaced = Client {
+            urls: EndpointUrls {
+                forecast: server.url().to_string(),
+                ..Default::default()
+            },
+            ..Client::builder()
+                .user_agent("ignored/0.1")
+                .replace_user_agent("myapp/1.2 (ops@example.com)")
+                .build()
+                .unwrap()
+        };
+        replaced.forecast(&forecast()).await.unwrap_err();
+        assert_eq!(
+            server.last_header("User-Agent").unwrap(),
+            "myapp/1.2 (ops@example.com)"
+        );
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn fetch_is_traced() {
+        use std::sync::Mutex;
+
+        #[derive(Clone, Default)]
+        struct Output(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Output {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let output = Output::default();
+        let writer = output.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let server = MockServer::start(vec![MockResponse::new(503, ""), MockResponse::ok(FIXTURE)]);
+        let client = Client {
+            retry: RetryPolicy::new(2).base_delay(Duration::ZERO),
+            ..client(server.url())
+        };
+        client.forecast(&forecast()).await.unwrap();
+
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("weathered.fetch{"));
+        assert!(output.contains("variables=2"));
+        assert!(output.contains("transient failure, retrying attempt=1"));
+        assert!(output.contains("response parsed"));
+    }
+
+    #[derive(Default)]
+    struct Recorder(std::sync::Mutex<Vec<RequestStats>>);
+
+    impl MetricsSink for Recorder {
+        fn on_request_complete(&self, stats: RequestStats) {
+            self.0.lock().unwrap().push(stats);
+        }
+    }
+
+    #[tokio::test]
+    async fn metrics_tell_cached_from_network_calls() {
+        let server = MockServer::start(vec![MockResponse::new(503, ""), MockResponse::ok(FIXTURE)]);
+        let recorder = Arc::new(Recorder::default());
+        let totals = Arc::new(AtomicMetrics::new());
+        let cache = MemoryCache::new(Duration::from_secs(600), 16);
+        let mut client = Client {
+            retry: RetryPolicy::new(2).base_delay(Duration::ZERO),
+            cache: Some(Arc::new(cache)),
+            metrics: Some(recorder.clone()),
+            ..client(server.url())
+        };
+
+        client.forecast(&forecast()).await.unwrap();
+        client.forecast(&forecast()).await.unwrap();
+
+        let recorded = recorder.0.lock().unwrap().clone();
+        let (network, cached) = (&recorded[0], &recorded[1]);
+        assert_eq!(network.endpoint, Endpoint::Forecast);
+        assert_eq!(network.outcome, Some(FetchOutcome::Fresh));
+        assert_eq!(network.status, Some(200));
+        assert_eq!(network.bytes, FIXTURE.len());
+        assert_eq!(network.retries, 1);
+        assert_eq!(cached.outcome, Some(FetchOutcome::Cached));
+        assert_eq!(cached.status, None);
+        assert_eq!(cached.bytes, 0);
+        assert_eq!(cached.retries, 0);
+
+        client.metrics = Some(totals.clone());
+        client.forecast(&forecast()).await.unwrap();
+        client.bypass_cache().forecast(&forecast()).await.unwrap();
+        assert_eq!(totals.requests(), 2);
+        assert_eq!(totals.cache_hits(), 1);
+        assert_eq!(totals.failures(), 0);
+        assert_eq!(totals.bytes(), FIXTURE.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn hooks_run_in_registration_order() {
+        use std::sync::Mutex;
+
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE),
+            MockResponse::ok(FIXTURE).header("X-Reject", "yes"),
+        ]);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let (first, second) = (seen.clone(), seen.clone());
+        let client = Client {
+            urls: EndpointUrls {
+                forecast: server.url().to_string(),
+                ..Default::default()
+            },
+            ..Client::builder()
+                .with_middleware(|request: &mut HttpRequest| {
+                    request.headers.push(("X-Gateway".into(), "first".into()));
+                })
+                .with_middleware(|request: &mut HttpRequest| {
+                    if let Some((_, value)) =
+                        request.headers.iter_mut().find(|(n, _)| n == "X-Gateway")
+                    {
+                        value.push_str(",second");
+                    }
+                    request.url.push_str("&gateway=1");
+                })
+                .with_response_hook(move |response: &HttpResponse| {
+                    first.lock().unwrap().push(("first", response.body.len()));
+                    Ok(())
+                })
+                .with_response_hook(move |response: &HttpResponse| {
+                    second.lock().unwrap().push(("second", response.body.len()));
+                    match response.header("X-Reject") {
+                        Some(_) => Err("rejected by gateway".into()),
+                        None => Ok(()),
+                    }
+                })
+                .build()
+                .unwrap()
+        };
+
+        client.forecast(&forecast()).await.unwrap();
+        assert_eq!(server.last_header("X-Gateway").unwrap(), "first,second");
+        assert!(server.last_path().unwrap().ends_with("&gateway=1"));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [("first", FIXTURE.len()), ("second", FIXTURE.len())]
+        );
+
+        match client.forecast(&forecast()).await {
+            Err(WeatheredError::Rejected { source, .. }) => {
+                assert_eq!(source.to_string(), "rejected by gateway")
+            }
+            other => panic!("expected rejection, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn backoff_runs_on_virtual_time() {
+        let server = MockServer::start(vec![
+            MockResponse::new(503, ""),
+            MockResponse::new(503, ""),
+            MockResponse::ok(FIXTURE),
+        ]);
+        let runtime = ManualRuntime::new();
+        let start = runtime.now();
+        let client = Client {
+            retry: RetryPolicy::new(3)
+                .base_delay(Duration::from_secs(10))
+                .jitter(0.0),
+            runtime: runtime.clone(),
+            ..client(server.url())
+        };
+
+        client.forecast(&forecast()).await.unwrap();
+        assert_eq!(server.requests(), 3);
+        assert_eq!(runtime.now() - start, Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn fetch_all_stops_between_requests() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE),
+            MockResponse::ok(FIXTURE).delayed(Duration::from_secs(5)),
+        ]);
+        let client = client(server.url());
+        let forecasts = [forecast(), forecast().coord(48.85, 2.35), forecast()];
+
+        let stop = tokio::time::sleep(Duration::from_millis(300));
+        match client.fetch_all(&forecasts, stop).await {
+            Err(WeatheredError::Cancelled { completed }) => {
+                assert_eq!(completed.len(), 1);
+                assert_eq!(completed[0].timezone, "GMT");
+            }
+            other => panic!("expected cancellation, got {other:?}"),
+        }
+        assert_eq!(server.requests(), 2);
+
+        let transport = StaticTransport::new().route("latitude=", FIXTURE);
+        let all = Client::with_transport(transport)
+            .fetch_all(&forecasts, std::future::pending())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn fetch_all_keeps_responses_before_rate_limit() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE),
+            MockResponse::ok(FIXTURE),
+            MockResponse::new(429, "slow down").header("Retry-After", "120"),
+            MockResponse::ok(FIXTURE),
+            MockResponse::ok(FIXTURE),
+        ]);
+        let client = client(server.url());
+        let forecasts = [
+            forecast(),
+            forecast().coord(48.85, 2.35),
+            forecast().coord(40.41, -3.70),
+            forecast().coord(41.90, 12.50),
+            forecast().coord(59.33, 18.07),
+        ];
+
+        match client.fetch_all(&forecasts, std::future::pending()).await {
+            Err(WeatheredError::Partial { completed, source }) => {
+                assert_eq!(completed.len(), 2);
+                match *source {
+                    WeatheredError::RateLimited { url, retry_after } => {
+                        assert!(url.contains("latitude=40.41"));
+                        assert_eq!(retry_after, Some(Duration::from_secs(120)));
+                    }
+                    other => panic!("expected rate limiting, got {other:?}"),
+                }
+            }
+            other => panic!("expected partial results, got {other:?}"),
+        }
+        assert_eq!(server.requests(), 3);
+    }
+
+    /// Transport answering the fixture at the requested latitude after a short
+    /// delay, and 500 for latitude 0
+    #[derive(Clone, Default)]
+    struct SlowTransport {
+        in_flight: Arc<AtomicUsize>,
+        max_in_flight: Arc<AtomicUsize>,
+    }
+
+    impl AsyncHttpTransport for SlowTransport {
+        async fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+            let running = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            let latitude = request.url.split("latitude=").nth(1).unwrap();
+            let latitude = latitude.split('&').next().unwrap();
+            let (status, body) = match latitude {
+                "0" => (500, String::new()),
+                _ => (200, FIXTURE.replace("52.52", latitude)),
+            };
+            Ok(HttpResponse {
+                status,
+                headers: Vec::new(),
+                body: body.into(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn forecast_many_is_bounded_and_ordered() {
+        let transport = SlowTransport::default();
+        let client = Client::with_transport(transport.clone());
+        let requests: Vec<_> = (0..10)
+            .map(|latitude| forecast().coord(latitude as f32, 13.41))
+            .collect();
+
+        let many = client.forecast_many(requests, 3);
+        fn assert_send(_: &impl Send) {}
+        assert_send(&many);
+        let results = many.await;
+
+        assert_eq!(results.len(), 10);
+        assert!(matches!(
+            results[0],
+            Err(WeatheredError::Status { status: 500, .. })
+        ));
+        for (latitude, result) in results.iter().enumerate().skip(1) {
+            assert_eq!(result.as_ref().unwrap().latitude, latitude as f64);
+        }
+        assert_eq!(transport.max_in_flight.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn forecast_many_collapses_locations() {
+        let body = |latitude: &str| FIXTURE.replace("52.52", latitude);
+        // Answered out of order, matched back by `location_id`
+        let server = MockServer::start(vec![MockResponse::ok(format!(
+            "[{},{}]",
+            body("2").replacen('{', r#"{"location_id":1,"#, 1),
+            body("1").replacen('{', r#"{"location_id":0,"#, 1)
+        ))]);
+        let client = Client {
+            urls: EndpointUrls {
+                forecast: server.url().to_string(),
+                ..Default::default()
+            },
+            ..Client::builder().collapse_locations(true).build().unwrap()
+        };
+        let requests = vec![forecast().coord(1.0, 10.0), forecast().coord(2.0, 20.0)];
+
+        let results = client.forecast_many(requests, 4).await;
+        assert_eq!(results[0].as_ref().unwrap().latitude, 1.0);
+        assert_eq!(results[1].as_ref().unwrap().latitude, 2.0);
+        assert_eq!(server.requests(), 1);
+        assert_eq!(
+            server.last_path().unwrap(),
+            "/?latitude=1,2&longitude=10,20&hourly=,temperature_2m,rain"
+        );
+    }
+
+    /// Fixture restricted to the hourly samples in `hours`
+    fn chunk_body(hours: std::ops::RangeInclusive<usize>) -> String {
+        let mut body: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        for series in body["hourly"].as_object_mut().unwrap().values_mut() {
+            let values = series.as_array_mut().unwrap();
+            *values = values[hours.clone()].to_vec();
+        }
+        body.to_string()
+    }
+
+    #[tokio::test]
+    async fn fetch_chunked_merges_windows() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(chunk_body(0..=8)),
+            MockResponse::ok(chunk_body(8..=16)),
+            MockResponse::ok(chunk_body(16..=23)),
+        ]);
+        let client = self::client(server.url());
+        let request = forecast()
+            .settings(Settings::start_date("2023-05-16".to_string()))
+            .settings(Settings::end_date("2023-05-18".to_string()));
+
+        let merged = client
+            .fetch_chunked(&request, ChunkSize::Days(1))
+            .await
+            .unwrap();
+        assert_eq!(server.requests(), 3);
+        assert!(server
+            .last_path()
+            .unwrap()
+            .contains("start_date=2023-05-18&end_date=2023-05-18"));
+        let expected: ForecastResponse = serde_json::from_str(FIXTURE).unwrap();
+        let hourly = merged.hourly.as_ref().unwrap();
+        assert_eq!(hourly.time.len(), 24);
+        assert!(hourly.time.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(merged.hourly, expected.hourly);
+        assert_eq!(merged.daily.unwrap().time.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fetch_chunked_rejects_mismatched_chunks() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(chunk_body(0..=11)),
+            MockResponse::ok(chunk_body(12..=23).replace("\"rain\"", "\"showers\"")),
+        ]);
+        // The second chunk would fail the check for `rain` before being merged
+        let client = Client {
+            check_consistency: false,
+            ..self::client(server.url())
+        };
+        let request = forecast()
+            .settings(Settings::start_date("2023-05-16".to_string()))
+            .settings(Settings::end_date("2023-05-17".to_string()));
+
+        match client.fetch_chunked(&request, ChunkSize::Days(1)).await {
+            Err(WeatheredError::ChunkMismatch { url, .. }) => {
+                assert!(url.contains("start_date=2023-05-17"))
+            }
+            other => panic!("expected chunk mismatch, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_chunked_keeps_windows_before_failure() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(chunk_body(0..=8)),
+            MockResponse::ok(chunk_body(8..=16)),
+            MockResponse::new(429, "slow down"),
+        ]);
+        let client = self::client(server.url());
+        let request = forecast()
+            .settings(Settings::start_date("2023-05-16".to_string()))
+            .settings(Settings::end_date("2023-05-18".to_string()));
+
+        match client.fetch_chunked(&request, ChunkSize::Days(1)).await {
+            Err(WeatheredError::ChunkFailed {
+                window,
+                fetched,
+                source,
+            }) => {
+                assert_eq!(window, 2);
+                assert_eq!(fetched.len(), 2);
+                assert_eq!(fetched[1].hourly.as_ref().unwrap().time.len(), 9);
+                assert!(matches!(*source, WeatheredError::RateLimited { .. }));
+            }
+            other => panic!("expected a failed window, got {other:?}"),
+        }
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn fetch_chunked_reports_progress() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(chunk_body(6..=12)),
+            MockResponse::new(503, "busy"),
+            MockResponse::ok(chunk_body(0..=6)),
+            MockResponse::ok(chunk_body(12..=18)),
+            MockResponse::ok(chunk_body(18..=23)),
+        ]);
+        let client = Client {
+            urls: EndpointUrls {
+                forecast: server.url().to_string(),
+                ..Default::default()
+            },
+            ..Client::builder()
+                .retries(RetryPolicy::new(2).base_delay(Duration::from_millis(1)))
+                .cache(MemoryCache::new(Duration::from_secs(600), 16))
+                .build()
+                .unwrap()
+        };
+        let request = |start: &str, end: &str| {
+            forecast()
+                .settings(Settings::start_date(start.to_string()))
+                .settings(Settings::end_date(end.to_string()))
+        };
+        // Second window is answered by the cache
+        client
+            .forecast(&request("2023-05-17", "2023-05-17"))
+            .await
+            .unwrap();
+
+        let events = std::sync::Mutex::new(Vec::new());
+        let progress = |completed: usize, total: usize, last: &RequestStats| {
+            events
+                .lock()
+                .unwrap()
+                .push((completed, total, last.retries, last.outcome));
+            assert!(completed < total, "sink failures must not abort the fetch");
+        };
+        let merged = client
+            .fetch_chunked_with_progress(
+                &request("2023-05-16", "2023-05-19"),
+                ChunkSize::Days(1),
+                &progress,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(merged.hourly.unwrap().time.len(), 24);
+        assert_eq!(server.requests(), 5);
+        assert_eq!(
+            events.into_inner().unwrap(),
+            [
+                (1, 4, 1, Some(FetchOutcome::Fresh)),
+                (2, 4, 0, Some(FetchOutcome::Cached)),
+                (3, 4, 0, Some(FetchOutcome::Fresh)),
+                (4, 4, 0, Some(FetchOutcome::Fresh)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn forecast_many_reports_progress() {
+        let client = Client::with_transport(SlowTransport::default());
+        let requests: Vec<_> = (0..5)
+            .map(|latitude| forecast().coord(latitude as f32, 13.41))
+            .collect();
+        let events = std::sync::Mutex::new(Vec::new());
+        let progress = |completed: usize, total: usize, last: &RequestStats| {
+            events.lock().unwrap().push((completed, total, last.status));
+        };
+
+        client
+            .forecast_many_with_progress(requests, 2, &progress)
+            .await;
+        let events = events.into_inner().unwrap();
+        let completed: Vec<_> = events.iter().map(|event| event.0).collect();
+        assert_eq!(completed, [1, 2, 3, 4, 5]);
+        assert!(events.iter().all(|event| event.1 == 5));
+        assert_eq!(
+            events.iter().filter(|event| event.2 == Some(500)).count(),
+            1
+        );
+    }
+
+    async fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+        poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn watch_yields_changed_forecasts() {
+        let updated = FIXTURE.replace("\"elevation\":38.0", "\"elevation\":40.0");
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE).header("ETag", "\"v1\""),
+            MockResponse::new(304, ""),
+            MockResponse::new(304, ""),
+            MockResponse::ok(updated).header("ETag", "\"v2\""),
+            MockResponse::new(304, ""),
+        ]);
+        let runtime = ManualRuntime::new();
+        let start = runtime.now();
+        let client = Client {
+            runtime: runtime.clone(),
+            conditional: Some(Arc::new(MemoryConditionalStore::new())),
+            ..client(server.url())
+        };
+        let interval = Duration::from_secs(600);
+        let request = forecast();
+        let mut watch = client.watch(&request, interval);
+        let first = next(&mut watch).await.unwrap().unwrap();
+        assert_eq!(first.elevation, 38.0);
+        assert_eq!(runtime.now(), start);
+        // Both revalidations are skipped
+        let second = next(&mut watch).await.unwrap().unwrap();
+        assert_eq!(second.elevation, 40.0);
+        assert_eq!(server.requests(), 4);
+        let waited = runtime.now() - start;
+        assert!(waited >= interval.mul_f64(3.0 * 0.95) && waited <= interval.mul_f64(3.0 * 1.05));
+
+        let mut watch = client.watch(&request, interval).emit_unchanged(true);
+        let unchanged = next(&mut watch).await;
+        assert_eq!(unchanged.unwrap().unwrap(), second);
+        assert_eq!(server.requests(), 5);
+    }
+
+    #[tokio::test]
+    async fn watch_yields_alerts() {
+        let server = MockServer::start(vec![
+            MockResponse::ok(FIXTURE),
+            MockResponse::ok(include_str!("../fixtures/alerts.json")),
+        ]);
+        // The fixtures hold different variables
+        let client = Client {
+            runtime: ManualRuntime::new(),
+            check_consistency: false,
+            ..client(server.url())
+        };
+        let gusts = AlertRule::new(Hourly::windgusts_10m, Comparison::Above, 60.0);
+        let request = forecast().hourly(Hourly::windgusts_10m);
+        let mut alerts = client
+            .watch(&request, Duration::from_secs(600))
+            .alerts(vec![gusts.clone()]);
+
+        let matches = next(&mut alerts).await.unwrap().unwrap();
+        assert_eq!(server.requests(), 2);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].rule, gusts);
+        assert_eq!(matches[0].peak, 71.3);
+    }
+}
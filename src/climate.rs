@@ -0,0 +1,360 @@
+use std::{error, fmt};
+
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::{push_list, Coordinates, Endpoint, Forecast, NoCoordinates, Settings};
+
+pub(crate) const CLIMATE_URL: &str = "https://climate-api.open-meteo.com/v1/climate";
+
+/// First and last day covered by the CMIP6 runs
+const FIRST_DAY: &str = "1950-01-01";
+const LAST_DAY: &str = "2050-12-31";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumIter)]
+#[allow(non_camel_case_types)]
+/// Enumerate the CMIP6 HighResMIP models of the climate API
+pub enum ClimateModel {
+    CMCC_CM2_VHR4,
+    FGOALS_f3_H,
+    HiRAM_SIT_HR,
+    MRI_AGCM3_2_S,
+    EC_Earth3P_HR,
+    MPI_ESM1_2_XR,
+    NICAM16_8S,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[allow(non_camel_case_types)]
+/// Enumerate the daily variables of the climate API, the only ones it serves
+pub enum ClimateDaily {
+    temperature_2m_mean,
+    temperature_2m_max,
+    temperature_2m_min,
+    windspeed_10m_mean,
+    windspeed_10m_max,
+    cloudcover_mean,
+    shortwave_radiation_sum,
+    relative_humidity_2m_mean,
+    relative_humidity_2m_max,
+    relative_humidity_2m_min,
+    dewpoint_2m_mean,
+    dewpoint_2m_min,
+    dewpoint_2m_max,
+    precipitation_sum,
+    rain_sum,
+    snowfall_sum,
+    pressure_msl_mean,
+    soil_moisture_0_to_10cm_mean,
+    et0_fao_evapotranspiration_sum,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Enumerate the reasons `Climate::build` refuses a request
+pub enum ClimateError {
+    /// `start_date` or `end_date` is missing, the climate API needs both
+    MissingDates,
+    /// The date is outside of 1950-01-01 to 2050-12-31
+    OutOfRange(String),
+    /// The climate API has no hourly data
+    Hourly(String),
+    /// The daily variable or setting is not offered by the climate API
+    Unsupported(String),
+}
+
+impl fmt::Display for ClimateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClimateError::MissingDates => {
+                write!(f, "climate requests need a start and end date")
+            }
+            ClimateError::OutOfRange(date) => {
+                write!(f, "{date} is outside of {FIRST_DAY} to {LAST_DAY}")
+            }
+            ClimateError::Hourly(name) => {
+                write!(f, "hourly `{name}` is not available from the climate API")
+            }
+            ClimateError::Unsupported(name) => {
+                write!(f, "`{name}` is not available from the climate API")
+            }
+        }
+    }
+}
+
+impl error::Error for ClimateError {}
+
+#[derive(Clone)]
+/// Daily climate projection of CMIP6 models, sharing the coordinates and
+/// settings of a `Forecast`
+pub struct Climate<C = Coordinates> {
+    forecast: Forecast<C>,
+    daily: Vec<ClimateDaily>,
+    models: Vec<ClimateModel>,
+}
+
+impl Climate<NoCoordinates> {
+    /// Initialize Climate object
+    pub fn new() -> Self {
+        Forecast::new().into()
+    }
+}
+
+impl Default for Climate<NoCoordinates> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reuse the coordinates, settings and variables of a forecast; `build`
+/// rejects what the climate API does not serve
+impl<C> From<Forecast<C>> for Climate<C> {
+    fn from(forecast: Forecast<C>) -> Self {
+        Climate {
+            forecast: forecast.endpoint(Endpoint::Climate),
+            daily: Vec::new(),
+            models: Vec::new(),
+        }
+    }
+}
+
+impl<C> Climate<C> {
+    /// Specify coordinates (latitude, longitude)
+    pub fn coord(self, latitude: f32, longitude: f32) -> Climate<Coordinates> {
+        Climate {
+            forecast: self.forecast.coord(latitude, longitude),
+            daily: self.daily,
+            models: self.models,
+        }
+    }
+
+    /// Dates of the first and last day, `YYYY-MM-DD`
+    pub fn dates(self, start: &str, end: &str) -> Self {
+        self.settings(Settings::start_date(start.to_string()))
+            .settings(Settings::end_date(end.to_string()))
+    }
+
+    /// Add optional settings, e.g. `Settings::disable_bias_correction`
+    pub fn settings(mut self, setting: Settings) -> Self {
+        self.forecast = self.forecast.settings(setting);
+        self
+    }
+
+    /// Get daily value for a specific data
+    pub fn daily(mut self, daily: ClimateDaily) -> Self {
+        self.daily.push(daily);
+        self
+    }
+
+    /// Models to run; with several, daily columns are suffixed with the model
+    /// name
+    pub fn models(mut self, models: &[ClimateModel]) -> Self {
+        self.models.extend_from_slice(models);
+        self
+    }
+}
+
+impl Climate<Coordinates> {
+    /// Check the request against what the climate API serves
+    pub fn build(self) -> Result<ClimateRequest, ClimateError> {
+        let Climate {
+            mut forecast,
+            mut daily,
+            models,
+        } = self;
+        if let Some(variable) = forecast.hourly.first() {
+            return Err(ClimateError::Hourly(variable.to_string()));
+        }
+        if let Some(variable) = forecast.pressure_var.first() {
+            return Err(ClimateError::Hourly(variable.get()));
+        }
+        if let Some(setting) = forecast.settings.iter().find(|setting| {
+            matches!(
+                setting,
+                Settings::past_days(_)
+                    | Settings::forecast_days(_)
+                    | Settings::current_weather(_)
+                    | Settings::models(_)
+            )
+        }) {
+            return Err(ClimateError::Unsupported(setting.to_string()));
+        }
+        for variable in forecast.daily.drain(..) {
+            let name = variable.to_string();
+            daily.push(name.parse().map_err(|_| ClimateError::Unsupported(name))?);
+        }
+        let (start, end) = forecast.date_range().ok_or(ClimateError::MissingDates)?;
+        if let Some(date) = [start, end]
+            .into_iter()
+            .find(|date| *date < FIRST_DAY || *date > LAST_DAY)
+        {
+            return Err(ClimateError::OutOfRange(date.to_string()));
+        }
+        Ok(ClimateRequest {
+            forecast,
+            daily,
+            models,
+        })
+    }
+}
+
+#[derive(Clone)]
+/// Climate request checked by `Climate::build`
+pub struct ClimateRequest {
+    forecast: Forecast<Coordinates>,
+    daily: Vec<ClimateDaily>,
+    models: Vec<ClimateModel>,
+}
+
+impl ClimateRequest {
+    /// Convert the request into a valid URL against the given base
+    pub(crate) fn to_url(&self, base: &str) -> String {
+        let mut url = self.forecast.to_url(base);
+        if !self.models.is_empty() {
+            let models: Vec<_> = self.models.iter().map(ClimateModel::to_string).collect();
+            url.push_str(&format!("&models={}", models.join(",")));
+        }
+        push_list(&mut url, "daily", &self.daily);
+        url
+    }
+}
+
+impl fmt::Display for ClimateRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_url(CLIMATE_URL))
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl crate::EndpointRequest for ClimateRequest {
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::Climate
+    }
+
+    fn to_url(&self, base: &str) -> String {
+        ClimateRequest::to_url(self, base)
+    }
+}
+
+/// Name of a daily column without the climate model suffix, if any
+#[cfg(feature = "response")]
+pub(crate) fn strip_model(name: &str) -> &str {
+    use strum::IntoEnumIterator;
+
+    ClimateModel::iter()
+        .find_map(|model| name.strip_suffix(&model.to_string())?.strip_suffix('_'))
+        .unwrap_or(name)
+}
+
+#[cfg(feature = "response")]
+impl crate::ForecastResponse {
+    /// Values of a daily climate variable as run by `model`, from the plain
+    /// column when a single model was requested
+    pub fn climate(
+        &self,
+        variable: ClimateDaily,
+        model: ClimateModel,
+    ) -> Option<&[Option<crate::DailyValue>]> {
+        let daily = self.daily.as_ref()?;
+        daily
+            .get(&format!("{variable}_{model}"))
+            .or_else(|| daily.get(&variable.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Hourly;
+
+    fn climate() -> Climate {
+        Climate::new()
+            .coord(48.21, 16.37)
+            .dates("2030-01-01", "2030-12-31")
+            .daily(ClimateDaily::temperature_2m_max)
+    }
+
+    #[test]
+    fn url_creation() {
+        let request = climate()
+            .daily(ClimateDaily::precipitation_sum)
+            .models(&[ClimateModel::EC_Earth3P_HR, ClimateModel::MRI_AGCM3_2_S])
+            .settings(Settings::disable_bias_correction(true))
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.to_string(),
+            "https://climate-api.open-meteo.com/v1/climate?latitude=48.21&longitude=16.37&start_date=2030-01-01&end_date=2030-12-31&disable_bias_correction=true&models=EC_Earth3P_HR,MRI_AGCM3_2_S&daily=,temperature_2m_max,precipitation_sum"
+        );
+    }
+
+    #[test]
+    fn rejected_requests() {
+        let hourly: Climate = Forecast::new()
+            .coord(48.21, 16.37)
+            .hourly(Hourly::temperature_2m)
+            .into();
+        let error = hourly
+            .dates("2030-01-01", "2030-01-31")
+            .build()
+            .err()
+            .unwrap();
+        assert_eq!(error, ClimateError::Hourly("temperature_2m".into()));
+        assert_eq!(
+            error.to_string(),
+            "hourly `temperature_2m` is not available from the climate API"
+        );
+
+        let sunrise: Climate = Forecast::new()
+            .coord(48.21, 16.37)
+            .daily(crate::Daily::sunrise)
+            .into();
+        assert_eq!(
+            sunrise.dates("2030-01-01", "2030-01-31").build().err(),
+            Some(ClimateError::Unsupported("sunrise".into()))
+        );
+        assert_eq!(
+            Climate::new().coord(48.21, 16.37).build().err(),
+            Some(ClimateError::MissingDates)
+        );
+        assert_eq!(
+            climate().dates("1949-12-31", "2000-01-01").build().err(),
+            Some(ClimateError::OutOfRange("1949-12-31".into()))
+        );
+
+        let forecast_daily: Climate = Forecast::new()
+            .coord(48.21, 16.37)
+            .daily(crate::Daily::rain_sum)
+            .into();
+        let request = forecast_daily.dates("2050-01-01", "2050-12-31").build();
+        assert!(request.unwrap().to_string().ends_with("&daily=,rain_sum"));
+    }
+
+    #[cfg(feature = "response")]
+    #[test]
+    fn fixture() {
+        use crate::DailyValue;
+
+        let response =
+            crate::ForecastResponse::from_json(include_str!("../fixtures/climate.json")).unwrap();
+        let ec_earth = response
+            .climate(
+                ClimateDaily::temperature_2m_max,
+                ClimateModel::EC_Earth3P_HR,
+            )
+            .unwrap();
+        assert_eq!(
+            ec_earth,
+            [Some(DailyValue::Float(3.1)), Some(DailyValue::Float(4.6))]
+        );
+        assert_eq!(
+            response
+                .climate(ClimateDaily::precipitation_sum, ClimateModel::MRI_AGCM3_2_S)
+                .unwrap()[1],
+            None
+        );
+        assert!(response
+            .climate(ClimateDaily::temperature_2m_max, ClimateModel::NICAM16_8S)
+            .is_none());
+        assert_eq!(strip_model("rain_sum_FGOALS_f3_H"), "rain_sum");
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::{response::daily_kind, DailyValue, ForecastResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate the types of value a column can hold
+pub enum ColumnKind {
+    Float,
+    /// Time of day, e.g. `sunrise`
+    Time,
+    /// WMO weather code
+    Code,
+    /// Variable unknown to this crate
+    Raw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Values of one column, borrowed from the response
+pub enum ColumnRef<'a> {
+    /// Hourly or 15-minutely values, one per timestamp
+    Series(&'a [Option<f64>]),
+    /// Daily values, one per day
+    Daily(ColumnKind, &'a [Option<DailyValue>]),
+    /// Current value of a variable
+    Current(ColumnKind, &'a Value),
+}
+
+impl ColumnRef<'_> {
+    pub fn kind(&self) -> ColumnKind {
+        match self {
+            ColumnRef::Series(_) => ColumnKind::Float,
+            ColumnRef::Daily(kind, _) | ColumnRef::Current(kind, _) => *kind,
+        }
+    }
+
+    /// Number of values, `1` for current values
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnRef::Series(values) => values.len(),
+            ColumnRef::Daily(_, values) => values.len(),
+            ColumnRef::Current(..) => 1,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Column name, displayed as `block.variable`, e.g. `daily.sunrise`
+pub struct ColumnName<'a> {
+    /// `current`, `minutely_15`, `hourly` or `daily`
+    pub block: &'static str,
+    pub variable: &'a str,
+}
+
+impl fmt::Display for ColumnName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.block, self.variable)
+    }
+}
+
+/// Current values are numbers except weather codes, which share daily names
+fn current_kind(variable: &str, value: &Value) -> ColumnKind {
+    match daily_kind(variable) {
+        ColumnKind::Code => ColumnKind::Code,
+        _ if value.is_number() => ColumnKind::Float,
+        _ => ColumnKind::Raw,
+    }
+}
+
+impl ForecastResponse {
+    /// Every returned variable of every block, in the order of the response
+    pub fn columns(&self) -> impl Iterator<Item = (ColumnName<'_>, ColumnRef<'_>)> {
+        let current = self.current.iter().flat_map(move |current| {
+            current.values.iter().map(move |(variable, value)| {
+                let kind = current_kind(variable, value);
+                (
+                    ColumnName {
+                        block: "current",
+                        variable,
+                    },
+                    ColumnRef::Current(kind, value),
+                )
+            })
+        });
+        let hourly = [("minutely_15", &self.minutely_15), ("hourly", &self.hourly)]
+            .into_iter()
+            .flat_map(move |(block, hourly)| {
+                hourly.iter().flat_map(move |hourly| {
+                    hourly.series.iter().map(move |(variable, series)| {
+                        (ColumnName { block, variable }, ColumnRef::Series(series))
+                    })
+                })
+            });
+        let daily = self.daily.iter().flat_map(move |daily| {
+            daily.series.iter().map(move |(variable, values)| {
+                let kind = daily_kind(variable);
+                (
+                    ColumnName {
+                        block: "daily",
+                        variable,
+                    },
+                    ColumnRef::Daily(kind, values),
+                )
+            })
+        });
+        current.chain(hourly).chain(daily)
+    }
+
+    /// Column named `block.variable`, e.g. `hourly.temperature_2m`
+    pub fn column(&self, name: &str) -> Option<ColumnRef<'_>> {
+        let (block, variable) = name.split_once('.')?;
+        match block {
+            "current" => {
+                let value = self.current.as_ref()?.value(variable)?;
+                Some(ColumnRef::Current(current_kind(variable, value), value))
+            }
+            "minutely_15" => Some(ColumnRef::Series(self.minutely_15.as_ref()?.get(variable)?)),
+            "hourly" => Some(ColumnRef::Series(self.hourly_by_name(variable)?)),
+            "daily" => Some(ColumnRef::Daily(
+                daily_kind(variable),
+                self.daily.as_ref()?.get(variable)?,
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn mixed_columns() {
+        let response = ForecastResponse::from_json(include_str!("../fixtures/mixed.json")).unwrap();
+        let columns = response
+            .columns()
+            .map(|(name, column)| (name.to_string(), column.kind(), column.len()))
+            .collect::<Vec<_>>();
+        let expected = [
+            ("current.temperature_2m", ColumnKind::Float, 1),
+            ("current.weather_code", ColumnKind::Code, 1),
+            ("minutely_15.precipitation", ColumnKind::Float, 2),
+            ("hourly.temperature_2m", ColumnKind::Float, 3),
+            ("hourly.rain", ColumnKind::Float, 3),
+            ("daily.weather_code", ColumnKind::Code, 2),
+            ("daily.temperature_2m_max", ColumnKind::Float, 2),
+            ("daily.sunrise", ColumnKind::Time, 2),
+        ];
+        assert_eq!(
+            columns,
+            expected.map(|(name, kind, len)| (name.to_string(), kind, len))
+        );
+
+        assert_eq!(
+            response.column("hourly.temperature_2m"),
+            Some(ColumnRef::Series(&[Some(19.6), Some(20.3), None]))
+        );
+        assert_eq!(
+            response.column("daily.temperature_2m_max"),
+            Some(ColumnRef::Daily(
+                ColumnKind::Float,
+                &[Some(DailyValue::Float(21.4)), None]
+            ))
+        );
+        assert_eq!(
+            response.column("current.weathercode").unwrap().kind(),
+            ColumnKind::Code
+        );
+        assert!(response.column("hourly.snowfall").is_none());
+        assert!(response.column("rain").is_none());
+    }
+}
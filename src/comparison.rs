@@ -0,0 +1,438 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::date::Date;
+use crate::{ForecastResponse, Hourly, HourlyBlock, Model, Series, Timestamp, WmoCode};
+
+#[derive(Debug, Clone, PartialEq)]
+/// Hourly variables of several models on the time axis of one response, as
+/// fetched by `Client::compare_models`
+pub struct ModelComparison {
+    models: Vec<Model>,
+    hourly: HourlyBlock,
+}
+
+impl ModelComparison {
+    /// Compare `models` in a response to a request for all of them
+    pub fn new(response: ForecastResponse, models: &[Model]) -> Self {
+        ModelComparison {
+            models: models.to_vec(),
+            hourly: response.hourly.unwrap_or_default(),
+        }
+    }
+
+    pub fn models(&self) -> &[Model] {
+        &self.models
+    }
+
+    pub fn time(&self) -> &[Timestamp] {
+        &self.hourly.time
+    }
+
+    /// Values of a variable as forecast by `model`; a single model is
+    /// returned without suffix
+    pub fn series(&self, variable: Hourly, model: Model) -> Option<&Series> {
+        match self.models.as_slice() {
+            [only] if *only == model => self.hourly.get(&variable.to_string()),
+            _ => self.hourly.by_model(variable, model),
+        }
+    }
+
+    /// Largest minus smallest model value at each timestamp, `None` where
+    /// fewer than two models have data, or if no model has the variable
+    pub fn spread(&self, variable: Hourly) -> Option<Series> {
+        let series: Vec<_> = self
+            .models
+            .iter()
+            .filter_map(|model| self.series(variable.clone(), *model))
+            .collect();
+        if series.is_empty() {
+            return None;
+        }
+        let spread = (0..self.hourly.time.len()).map(|index| {
+            let values: Vec<f64> = series
+                .iter()
+                .filter_map(|series| series.get(index).copied().flatten())
+                .collect();
+            let max = values.iter().copied().reduce(f64::max)?;
+            let min = values.iter().copied().reduce(f64::min)?;
+            (values.len() > 1).then_some(max - min)
+        });
+        Some(spread.collect())
+    }
+
+    /// Value of `a` minus value of `b` at each timestamp, `None` if either
+    /// model misses the variable
+    pub fn pairwise_diff(&self, variable: Hourly, a: Model, b: Model) -> Option<Series> {
+        let a = self.series(variable.clone(), a)?;
+        let b = self.series(variable, b)?;
+        Some(
+            a.values()
+                .iter()
+                .zip(b.values())
+                .map(|(a, b)| Some(a.as_ref()? - b.as_ref()?))
+                .collect(),
+        )
+    }
+
+    /// Timestamp and value of each model, in the order of `models`, for
+    /// printing the variable as a table
+    pub fn rows(
+        &self,
+        variable: Hourly,
+    ) -> impl Iterator<Item = (&Timestamp, Vec<Option<f64>>)> + '_ {
+        let series: Vec<_> = self
+            .models
+            .iter()
+            .map(|model| self.series(variable.clone(), *model))
+            .collect();
+        self.hourly
+            .time
+            .iter()
+            .enumerate()
+            .map(move |(index, time)| {
+                let values = series
+                    .iter()
+                    .map(|series| series.and_then(|series| series.get(index).copied().flatten()))
+                    .collect();
+                (time, values)
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate the daily values locations are ranked on
+pub enum DayMetric {
+    /// Mean of `temperature_2m_max`
+    TemperatureMax,
+    /// Mean of `temperature_2m_min`
+    TemperatureMin,
+    /// Total of `precipitation_sum`
+    PrecipitationSum,
+    /// Highest WMO `weathercode`, the most severe weather
+    Weathercode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate the orders of a ranking
+pub enum Direction {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// Daily summary of one location, `None` where the response has no data
+pub struct DaySummary {
+    pub temperature_max: Option<f64>,
+    pub temperature_min: Option<f64>,
+    pub precipitation_sum: Option<f64>,
+    pub weathercode: Option<WmoCode>,
+}
+
+impl DaySummary {
+    fn metric(&self, metric: DayMetric) -> Option<f64> {
+        match metric {
+            DayMetric::TemperatureMax => self.temperature_max,
+            DayMetric::TemperatureMin => self.temperature_min,
+            DayMetric::PrecipitationSum => self.precipitation_sum,
+            DayMetric::Weathercode => self.weathercode.map(|code| f64::from(code.code())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Daily summaries of several locations, one row per local date, as built
+/// by `compare_locations`
+pub struct LocationComparison {
+    labels: Vec<String>,
+    /// Summary of each location by `YYYY-MM-DD` local date, in label order
+    days: BTreeMap<String, Vec<Option<DaySummary>>>,
+}
+
+/// Align the daily summaries of labelled responses on their local dates, so
+/// that locations in different time zones share rows
+pub fn compare_locations<L: AsRef<str>>(responses: &[(L, ForecastResponse)]) -> LocationComparison {
+    let mut days: BTreeMap<String, Vec<Option<DaySummary>>> = BTreeMap::new();
+    for (index, (_, response)) in responses.iter().enumerate() {
+        let Some(daily) = &response.daily else {
+            continue;
+        };
+        let numbers = |variable| daily.numbers(variable).unwrap_or_default();
+        let (max, min) = (numbers("temperature_2m_max"), numbers("temperature_2m_min"));
+        let precipitation = numbers("precipitation_sum");
+        let codes = daily.weathercode().unwrap_or_default();
+        for (day, time) in daily.time.iter().enumerate() {
+            let Some(date) = Date::local(time, response.utc_offset_seconds) else {
+                continue;
+            };
+            let row = days
+                .entry(date.to_string())
+                .or_insert_with(|| vec![None; responses.len()]);
+            row[index] = Some(DaySummary {
+                temperature_max: max.get(day).copied().flatten(),
+                temperature_min: min.get(day).copied().flatten(),
+                precipitation_sum: precipitation.get(day).copied().flatten(),
+                weathercode: codes.get(day).copied().flatten(),
+            });
+        }
+    }
+    LocationComparison {
+        labels: responses
+            .iter()
+            .map(|(label, _)| label.as_ref().to_string())
+            .collect(),
+        days,
+    }
+}
+
+impl LocationComparison {
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Local dates and the summary of each location, in label order
+    pub fn days(&self) -> impl Iterator<Item = (&str, &[Option<DaySummary>])> {
+        self.days
+            .iter()
+            .map(|(date, row)| (date.as_str(), row.as_slice()))
+    }
+
+    /// Summary of the location `label` on the local `date`, `YYYY-MM-DD`
+    pub fn cell(&self, date: &str, label: &str) -> Option<&DaySummary> {
+        let index = self.labels.iter().position(|name| name == label)?;
+        self.days.get(date)?[index].as_ref()
+    }
+
+    /// Labels with their aggregated `metric`, best first in `direction`
+    ///
+    /// Only the days on which every location has the metric count, so that
+    /// totals cover the same period; empty if there are none
+    pub fn rank_by(&self, metric: DayMetric, direction: Direction) -> Vec<(&str, f64)> {
+        let shared: Vec<Vec<f64>> = self
+            .days
+            .values()
+            .filter_map(|row| {
+                row.iter()
+                    .map(|summary| summary.as_ref()?.metric(metric))
+                    .collect()
+            })
+            .collect();
+        if shared.is_empty() {
+            return Vec::new();
+        }
+        let mut ranking: Vec<_> = self
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(index, label)| {
+                let values = shared.iter().map(|row| row[index]);
+                let value = match metric {
+                    DayMetric::TemperatureMax | DayMetric::TemperatureMin => {
+                        values.sum::<f64>() / shared.len() as f64
+                    }
+                    DayMetric::PrecipitationSum => values.sum(),
+                    DayMetric::Weathercode => values.fold(f64::MIN, f64::max),
+                };
+                (label.as_str(), value)
+            })
+            .collect();
+        ranking.sort_by(|(_, a), (_, b)| match direction {
+            Direction::Ascending => a.total_cmp(b),
+            Direction::Descending => b.total_cmp(a),
+        });
+        ranking
+    }
+}
+
+/// Table of one row per date, cells reading `max/min precipitation code`
+/// and `-` where a value is missing
+impl fmt::Display for LocationComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = |value: Option<f64>| value.map_or("-".into(), |value| format!("{value:.1}"));
+        let rows: Vec<(&str, Vec<String>)> = self
+            .days()
+            .map(|(date, row)| {
+                let cells = row
+                    .iter()
+                    .map(|summary| match summary {
+                        Some(summary) => format!(
+                            "{}/{} {} {}",
+                            number(summary.temperature_max),
+                            number(summary.temperature_min),
+                            number(summary.precipitation_sum),
+                            summary
+                                .weathercode
+                                .map_or("-".into(), |code| code.code().to_string())
+                        ),
+                        None => "-".into(),
+                    })
+                    .collect();
+                (date, cells)
+            })
+            .collect();
+        let widths: Vec<_> = self
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(index, label)| {
+                rows.iter()
+                    .map(|(_, cells)| cells[index].chars().count())
+                    .chain([label.chars().count()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let line = |first: &str, cells: &[String]| {
+            let mut line = format!("{first:10}");
+            for (cell, width) in cells.iter().zip(&widths) {
+                line.push_str(&format!("  {cell:width$}"));
+            }
+            line.trim_end().to_string()
+        };
+        write!(f, "{}", line("date", &self.labels))?;
+        for (date, cells) in rows {
+            write!(f, "\n{}", line(date, &cells))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testing::ResponseBuilder;
+    use crate::Daily;
+
+    fn comparison() -> ModelComparison {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/models.json")).unwrap();
+        ModelComparison::new(response, &[Model::icon_seamless, Model::gfs_seamless])
+    }
+
+    #[test]
+    fn spread_and_difference() {
+        let comparison = comparison();
+        let spread = comparison.spread(Hourly::temperature_2m).unwrap();
+        assert!((spread[0].unwrap() - 0.4).abs() < 1e-9);
+        assert_eq!(spread[1], None);
+        assert!(comparison.spread(Hourly::snowfall).is_none());
+
+        let diff = comparison
+            .pairwise_diff(
+                Hourly::temperature_2m,
+                Model::gfs_seamless,
+                Model::icon_seamless,
+            )
+            .unwrap();
+        assert!((diff[0].unwrap() - 0.4).abs() < 1e-9);
+        assert_eq!(diff[1], None);
+        assert!(comparison
+            .pairwise_diff(Hourly::temperature_2m, Model::icon_seamless, Model::icon_d2)
+            .is_none());
+    }
+
+    #[test]
+    fn table_rows() {
+        let comparison = comparison();
+        let rows: Vec<_> = comparison.rows(Hourly::temperature_2m).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].0.as_iso8601(), Some("2024-04-10T01:00"));
+        assert_eq!(rows[1].1, [Some(7.4), None]);
+
+        let single = ModelComparison::new(
+            ForecastResponse::from_json(include_str!("../fixtures/forecast.json")).unwrap(),
+            &[Model::icon_seamless],
+        );
+        let temperature = single.series(Hourly::temperature_2m, Model::icon_seamless);
+        assert!(temperature.is_some());
+        assert!(single
+            .spread(Hourly::temperature_2m)
+            .unwrap()
+            .values()
+            .iter()
+            .all(Option::is_none));
+    }
+
+    /// Daily maxima, minima, precipitation sums and weather codes from `start`
+    fn location<const N: usize>(
+        utc_offset_seconds: i32,
+        start: &str,
+        extremes: [[f64; N]; 2],
+        precipitation: [Option<f64>; N],
+        weathercode: [f64; N],
+    ) -> ForecastResponse {
+        let [max, min] = extremes;
+        ResponseBuilder::new()
+            .timezone("auto", "auto", utc_offset_seconds)
+            .start(start)
+            .daily(Daily::temperature_2m_max, max)
+            .daily(Daily::temperature_2m_min, min)
+            .daily(Daily::precipitation_sum, precipitation)
+            .daily(Daily::weathercode, weathercode)
+            .build()
+    }
+
+    fn locations() -> LocationComparison {
+        let mut tokyo = location(
+            32400,
+            "2024-06-01",
+            [[28.0, 27.0], [20.0, 21.0]],
+            [Some(5.0), Some(12.0)],
+            [3.0, 63.0],
+        );
+        // local midnights as unix time, so dates are grouped under either format
+        tokyo.daily.as_mut().unwrap().time =
+            vec![Timestamp::Unix(1717167600), Timestamp::Unix(1717254000)];
+        compare_locations(&[
+            (
+                "Paris",
+                location(
+                    7200,
+                    "2024-06-01",
+                    [[24.0, 26.0], [15.0, 16.0]],
+                    [Some(0.0), Some(2.5)],
+                    [1.0, 61.0],
+                ),
+            ),
+            ("Tokyo", tokyo),
+            (
+                "New York",
+                location(
+                    -14400,
+                    "2024-05-31",
+                    [[22.0, 21.0, 25.0], [12.0, 13.0, 14.0]],
+                    [Some(0.0), Some(0.0), None],
+                    [0.0, 2.0, 3.0],
+                ),
+            ),
+        ])
+    }
+
+    #[test]
+    fn locations_by_local_date() {
+        let comparison = locations();
+        assert_eq!(comparison.days().count(), 3);
+        let tokyo = comparison.cell("2024-06-01", "Tokyo").unwrap();
+        assert_eq!(tokyo.temperature_max, Some(28.0));
+        assert_eq!(tokyo.weathercode, Some(WmoCode::Overcast));
+        assert!(comparison.cell("2024-05-31", "Paris").is_none());
+        assert!(comparison.cell("2024-06-01", "Oslo").is_none());
+
+        assert_eq!(
+            comparison.rank_by(DayMetric::TemperatureMax, Direction::Descending),
+            [("Tokyo", 27.5), ("Paris", 25.0), ("New York", 23.0)]
+        );
+        assert_eq!(
+            comparison.rank_by(DayMetric::PrecipitationSum, Direction::Ascending),
+            [("Paris", 0.0), ("New York", 0.0), ("Tokyo", 5.0)]
+        );
+        assert_eq!(
+            comparison.to_string(),
+            "date        Paris             Tokyo              New York\n\
+             2024-05-31  -                 -                  22.0/12.0 0.0 0\n\
+             2024-06-01  24.0/15.0 0.0 1   28.0/20.0 5.0 3    21.0/13.0 0.0 2\n\
+             2024-06-02  26.0/16.0 2.5 61  27.0/21.0 12.0 63  25.0/14.0 - 3"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use bytes::Bytes;
+
+#[derive(Debug, Clone)]
+/// Validators and body of the last successful response to a request
+pub struct ConditionalEntry {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: Bytes,
+}
+
+/// Storage for conditional request validators, keyed by `Forecast::cache_key`
+pub trait ConditionalStore: Send + Sync {
+    fn get(&self, key: &str) -> Option<ConditionalEntry>;
+    fn put(&self, key: &str, entry: ConditionalEntry);
+}
+
+#[derive(Default)]
+/// Conditional store kept in memory for the lifetime of the client
+pub struct MemoryConditionalStore {
+    entries: Mutex<HashMap<String, ConditionalEntry>>,
+}
+
+impl MemoryConditionalStore {
+    /// Initialize an empty store
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl ConditionalStore for MemoryConditionalStore {
+    fn get(&self, key: &str) -> Option<ConditionalEntry> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    fn put(&self, key: &str, entry: ConditionalEntry) {
+        self.entries.lock().unwrap().insert(key.to_string(), entry);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate where a returned forecast comes from
+pub enum FetchOutcome {
+    /// Downloaded from the API
+    Fresh,
+    /// The API answered 304 Not Modified, the stored body was used
+    Revalidated,
+    /// Served by the response cache without any request
+    Cached,
+}
@@ -0,0 +1,323 @@
+use std::{error, fmt};
+
+use crate::{Forecast, ForecastResponse, Settings, Timestamp};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Enumerate the ways a response can contradict itself or its request
+pub enum Inconsistency {
+    /// A series does not have one value per timestamp
+    Length {
+        block: &'static str,
+        variable: String,
+        expected: usize,
+        found: usize,
+    },
+    /// The timestamp at `index` is not after the previous one
+    NotIncreasing { block: &'static str, index: usize },
+    /// The step before the timestamp at `index` differs from the first step
+    UnevenSpacing { block: &'static str, index: usize },
+    /// A requested variable is not in the response
+    Missing {
+        block: &'static str,
+        variable: String,
+    },
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Inconsistency::Length {
+                block,
+                variable,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{block} `{variable}` has {found} values for {expected} timestamps"
+            ),
+            Inconsistency::NotIncreasing { block, index } => {
+                write!(f, "{block} timestamp {index} is not after the previous one")
+            }
+            Inconsistency::UnevenSpacing { block, index } => {
+                write!(f, "{block} timestamp {index} breaks the regular spacing")
+            }
+            Inconsistency::Missing { block, variable } => {
+                write!(f, "requested {block} `{variable}` is missing")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Every problem found by `ForecastResponse::check_consistency`
+pub struct ConsistencyError {
+    pub problems: Vec<Inconsistency>,
+}
+
+impl fmt::Display for ConsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, problem) in self.problems.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for ConsistencyError {}
+
+impl ForecastResponse {
+    /// Check that every series has one value per timestamp and that timestamps
+    /// increase, at a regular step for hourly and 15-minutely data
+    pub fn check_consistency(&self) -> Result<(), ConsistencyError> {
+        into_result(self.problems())
+    }
+
+    /// Also check that every variable requested by `forecast` is in the response,
+    /// under its own name or suffixed by one of the requested models
+    pub fn check_against<C>(&self, forecast: &Forecast<C>) -> Result<(), ConsistencyError> {
+        let mut problems = self.problems();
+        let models = forecast
+            .settings
+            .iter()
+            .rev()
+            .find_map(|setting| match setting {
+                Settings::models(models) => Some(models.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let present = |variable: &str, found: &dyn Fn(&str) -> bool| {
+            found(variable)
+                || models
+                    .iter()
+                    .any(|model| found(&format!("{variable}_{model}")))
+        };
+        let hourly = forecast
+            .hourly
+            .iter()
+            .map(ToString::to_string)
+            .chain(forecast.pressure_var.iter().map(|variable| variable.get()))
+            .chain(forecast.previous_day.iter().map(ToString::to_string));
+        for variable in hourly {
+            if !present(&variable, &|name| self.hourly_by_name(name).is_some()) {
+                problems.push(Inconsistency::Missing {
+                    block: "hourly",
+                    variable,
+                });
+            }
+        }
+        let daily = |name: &str| {
+            self.daily
+                .as_ref()
+                .and_then(|daily| daily.get(name))
+                .is_some()
+        };
+        for variable in forecast.daily.iter() {
+            if !present(&variable.to_string(), &daily) {
+                problems.push(Inconsistency::Missing {
+                    block: "daily",
+                    variable: variable.to_string(),
+                });
+            }
+        }
+        into_result(problems)
+    }
+
+    fn problems(&self) -> Vec<Inconsistency> {
+        let mut problems = Vec::new();
+        let hourly = [
+            ("hourly", &self.hourly),
+            ("minutely_15", &self.minutely_15),
+            ("six_hourly", &self.six_hourly),
+        ];
+        for (block, series) in hourly {
+            if let Some(series) = series {
+                let lengths = series
+                    .series
+                    .iter()
+                    .map(|(name, values)| (name, values.len()));
+                check_lengths(block, series.len(), lengths, &mut problems);
+                check_time(block, &series.time, true, &mut problems);
+            }
+        }
+        if let Some(daily) = &self.daily {
+            let lengths = daily
+                .series
+                .iter()
+                .map(|(name, values)| (name, values.len()));
+            check_lengths("daily", daily.len(), lengths, &mut problems);
+            check_time("daily", &daily.time, false, &mut problems);
+        }
+        problems
+    }
+}
+
+fn into_result(problems: Vec<Inconsistency>) -> Result<(), ConsistencyError> {
+    match problems.is_empty() {
+        true => Ok(()),
+        false => Err(ConsistencyError { problems }),
+    }
+}
+
+fn check_lengths<'a>(
+    block: &'static str,
+    expected: usize,
+    lengths: impl Iterator<Item = (&'a String, usize)>,
+    problems: &mut Vec<Inconsistency>,
+) {
+    for (variable, found) in lengths {
+        if found != expected {
+            problems.push(Inconsistency::Length {
+                block,
+                variable: variable.clone(),
+                expected,
+                found,
+            });
+        }
+    }
+}
+
+fn check_time(
+    block: &'static str,
+    time: &[Timestamp],
+    regular: bool,
+    problems: &mut Vec<Inconsistency>,
+) {
+    // Differences do not depend on the offset the wall-clock times are read in
+    let Some(seconds) = time
+        .iter()
+        .map(|time| time.to_unix(0))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return;
+    };
+    let step = seconds.get(1).zip(seconds.first()).map(|(b, a)| b - a);
+    for (index, pair) in seconds.windows(2).enumerate() {
+        let index = index + 1;
+        if pair[1] <= pair[0] {
+            problems.push(Inconsistency::NotIncreasing { block, index });
+        } else if regular && Some(pair[1] - pair[0]) != step {
+            problems.push(Inconsistency::UnevenSpacing { block, index });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{Daily, Hourly, Model, PressureVar};
+    use serde_json::Value;
+
+    const FIXTURE: &str = include_str!("../fixtures/forecast.json");
+
+    /// Fixture altered by `corrupt`
+    fn corrupted(corrupt: impl FnOnce(&mut Value)) -> ForecastResponse {
+        let mut json: Value = serde_json::from_str(FIXTURE).unwrap();
+        corrupt(&mut json);
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn problems(response: &ForecastResponse) -> Vec<Inconsistency> {
+        response.check_consistency().unwrap_err().problems
+    }
+
+    #[test]
+    fn fixture_is_consistent() {
+        let response = ForecastResponse::from_json(FIXTURE).unwrap();
+        let forecast = Forecast::new()
+            .hourly(Hourly::temperature_2m)
+            .daily(Daily::sunrise);
+        assert_eq!(response.check_against(&forecast), Ok(()));
+    }
+
+    #[test]
+    fn truncated_series() {
+        let response = corrupted(|json| {
+            json["hourly"]["rain"].as_array_mut().unwrap().truncate(20);
+            json["daily"]["sunset"].as_array_mut().unwrap().clear();
+        });
+        assert_eq!(
+            problems(&response),
+            [
+                Inconsistency::Length {
+                    block: "hourly",
+                    variable: "rain".to_string(),
+                    expected: 24,
+                    found: 20
+                },
+                Inconsistency::Length {
+                    block: "daily",
+                    variable: "sunset".to_string(),
+                    expected: 1,
+                    found: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn time_goes_forward_regularly() {
+        let response = corrupted(|json| {
+            let time = json["hourly"]["time"].as_array_mut().unwrap();
+            time.swap(3, 4);
+            time[10] = "2023-05-16T10:30".into();
+        });
+        assert_eq!(
+            problems(&response),
+            [
+                Inconsistency::UnevenSpacing {
+                    block: "hourly",
+                    index: 3
+                },
+                Inconsistency::NotIncreasing {
+                    block: "hourly",
+                    index: 4
+                },
+                Inconsistency::UnevenSpacing {
+                    block: "hourly",
+                    index: 5
+                },
+                Inconsistency::UnevenSpacing {
+                    block: "hourly",
+                    index: 10
+                },
+                Inconsistency::UnevenSpacing {
+                    block: "hourly",
+                    index: 11
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn requested_variables_are_present() {
+        let response = ForecastResponse::from_json(FIXTURE).unwrap();
+        let forecast = Forecast::new()
+            .hourly(Hourly::rain)
+            .hourly(Hourly::snowfall)
+            .pressure_var(PressureVar::temperature(850))
+            .daily(Daily::uv_index_max);
+
+        let error = response.check_against(&forecast).unwrap_err();
+        assert_eq!(error.problems.len(), 3);
+        assert_eq!(
+            error.to_string(),
+            "requested hourly `snowfall` is missing; \
+             requested hourly `temperature_850hPa` is missing; \
+             requested daily `uv_index_max` is missing"
+        );
+
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/models.json")).unwrap();
+        let forecast = Forecast::new().hourly(Hourly::temperature_2m);
+        assert!(response.check_against(&forecast).is_err());
+        let forecast = forecast.settings(Settings::models(vec![
+            Model::icon_seamless,
+            Model::gfs_seamless,
+        ]));
+        response.check_against(&forecast).unwrap();
+    }
+}
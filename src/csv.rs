@@ -0,0 +1,587 @@
+use std::{borrow::Cow, error, fmt, io, io::BufRead, io::Write};
+
+use serde_json::{Map, Value};
+
+use crate::{DailyValue, ForecastResponse, ForecastResponses, Timestamp, UnitsMap};
+
+#[derive(Debug)]
+/// Enumerate the ways reading a CSV response can fail
+pub enum CsvError {
+    Io(io::Error),
+    /// The text does not follow the layout of the API's CSV output
+    Format {
+        line: usize,
+        message: String,
+    },
+    /// The values do not make a valid response, e.g. a non-numeric latitude
+    Decode(serde_json::Error),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(error) => write!(f, "cannot read CSV response: {error}"),
+            CsvError::Format { line, message } => {
+                write!(f, "invalid CSV at line {line}: {message}")
+            }
+            CsvError::Decode(error) => write!(f, "invalid CSV response: {error}"),
+        }
+    }
+}
+
+impl error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            CsvError::Io(error) => Some(error),
+            CsvError::Format { .. } => None,
+            CsvError::Decode(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(error: io::Error) -> Self {
+        CsvError::Io(error)
+    }
+}
+
+/// Lines separated by blank ones, with the number of their first line
+type Section = (usize, Vec<String>);
+
+impl ForecastResponse {
+    /// Parse the output of a `format=csv` request
+    ///
+    /// The API writes the location metadata, then one table per block separated
+    /// by blank lines. Tables are recognized by their time step (15 minutes,
+    /// days, otherwise hourly) and by an `interval` column for current values.
+    /// Quoted fields are not supported, the API does not emit them
+    pub fn from_csv(reader: impl BufRead) -> Result<Self, CsvError> {
+        let mut sections: Vec<Section> = Vec::new();
+        let mut blank = true;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank = true;
+            } else if blank {
+                sections.push((index + 1, vec![line.to_string()]));
+                blank = false;
+            } else if let Some((_, lines)) = sections.last_mut() {
+                lines.push(line.to_string());
+            }
+        }
+        let mut sections = sections.into_iter();
+        let (line, metadata) = sections.next().ok_or(CsvError::Format {
+            line: 1,
+            message: "missing location metadata".to_string(),
+        })?;
+        let [keys, values] = &metadata[..] else {
+            return Err(CsvError::Format {
+                line,
+                message: "location metadata is not one header and one value line".to_string(),
+            });
+        };
+        let mut response: Map<String, Value> = keys
+            .split(',')
+            .zip(values.split(','))
+            .map(|(key, value)| (key.to_string(), cell(value)))
+            .collect();
+        response.entry("generationtime_ms").or_insert(0.0.into());
+        for section in sections {
+            let (block, units, values) = table(section)?;
+            response.insert(format!("{block}_units"), units.into());
+            response.insert(block.to_string(), values.into());
+        }
+        serde_json::from_value(response.into()).map_err(CsvError::Decode)
+    }
+}
+
+/// Block name, units and values of a table
+type Table = (&'static str, Map<String, Value>, Map<String, Value>);
+
+fn table((line, lines): Section) -> Result<Table, CsvError> {
+    let mut header = lines[0].split(',');
+    if header.next() != Some("time") {
+        return Err(CsvError::Format {
+            line,
+            message: "table does not start with a time column".to_string(),
+        });
+    }
+    let columns: Vec<(String, String)> = header
+        .map(|column| match column.split_once(" (") {
+            Some((name, unit)) => (name.to_string(), unit.trim_end_matches(')').to_string()),
+            None if column == "interval" => (column.to_string(), "seconds".to_string()),
+            None => (column.to_string(), String::new()),
+        })
+        .collect();
+    let mut time = Vec::new();
+    let mut values: Vec<Vec<Value>> = vec![Vec::new(); columns.len()];
+    for (offset, row) in lines[1..].iter().enumerate() {
+        let cells: Vec<&str> = row.split(',').collect();
+        if cells.len() != columns.len() + 1 {
+            return Err(CsvError::Format {
+                line: line + 1 + offset,
+                message: format!(
+                    "expected {} fields, found {}",
+                    columns.len() + 1,
+                    cells.len()
+                ),
+            });
+        }
+        time.push(cell(cells[0]));
+        for (column, value) in values.iter_mut().zip(&cells[1..]) {
+            column.push(cell(value));
+        }
+    }
+
+    let timestamps: Vec<Timestamp> = time
+        .iter()
+        .filter_map(|time| serde_json::from_value(time.clone()).ok())
+        .collect();
+    let step = match &timestamps[..] {
+        [first, second, ..] => first.to_unix(0).zip(second.to_unix(0)).map(|(a, b)| b - a),
+        _ => None,
+    };
+    let date_only =
+        matches!(timestamps.first(), Some(Timestamp::Iso8601(time)) if !time.contains('T'));
+    let block = match (columns.first(), step) {
+        (Some((name, _)), _) if name == "interval" => "current",
+        (_, Some(900)) => "minutely_15",
+        (_, Some(step)) if step >= 86_400 => "daily",
+        _ if date_only => "daily",
+        _ => "hourly",
+    };
+
+    let time_unit = match timestamps.first() {
+        Some(Timestamp::Unix(_)) => "unixtime",
+        _ => "iso8601",
+    };
+    let mut units = Map::new();
+    units.insert("time".to_string(), time_unit.into());
+    let mut block_values = Map::new();
+    if block == "current" {
+        // A single row of values rather than one array per variable
+        block_values.insert(
+            "time".to_string(),
+            time.into_iter().next().unwrap_or(Value::Null),
+        );
+        for ((name, unit), column) in columns.into_iter().zip(values) {
+            units.insert(name.clone(), unit.into());
+            block_values.insert(name, column.into_iter().next().unwrap_or(Value::Null));
+        }
+    } else {
+        block_values.insert("time".to_string(), time.into());
+        for ((name, unit), column) in columns.into_iter().zip(values) {
+            units.insert(name.clone(), unit.into());
+            block_values.insert(name, column.into());
+        }
+    }
+    Ok((block, units, block_values))
+}
+
+/// JSON value of a field: `null` when empty, a number when it parses as one
+fn cell(field: &str) -> Value {
+    if field.is_empty() {
+        return Value::Null;
+    }
+    if let Ok(integer) = field.parse::<i64>() {
+        return integer.into();
+    }
+    match field.parse::<f64>() {
+        Ok(number) if number.is_finite() => number.into(),
+        _ => field.into(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate the blocks `ForecastResponse::to_csv` can write
+pub enum CsvBlock {
+    Current,
+    Minutely15,
+    Hourly,
+    Daily,
+}
+
+#[derive(Debug, Clone)]
+/// Layout of the CSV written by `ForecastResponse::to_csv`
+pub struct CsvOptions {
+    metadata: bool,
+    blocks: Vec<CsvBlock>,
+    null: String,
+}
+
+impl Default for CsvOptions {
+    /// The layout of the API: location metadata, then every block, with
+    /// empty fields for missing values
+    fn default() -> Self {
+        CsvOptions {
+            metadata: true,
+            blocks: vec![
+                CsvBlock::Current,
+                CsvBlock::Minutely15,
+                CsvBlock::Hourly,
+                CsvBlock::Daily,
+            ],
+            null: String::new(),
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Write the location metadata table first, enabled by default
+    pub fn metadata(mut self, enabled: bool) -> Self {
+        self.metadata = enabled;
+        self
+    }
+
+    /// Write these blocks, in this order, when the response has them; a single
+    /// block and no metadata gives one plain table, e.g. for a file per block
+    pub fn blocks(mut self, blocks: impl IntoIterator<Item = CsvBlock>) -> Self {
+        self.blocks = blocks.into_iter().collect();
+        self
+    }
+
+    /// Field written for missing values, empty by default
+    pub fn null(mut self, null: &str) -> Self {
+        self.null = null.to_string();
+        self
+    }
+}
+
+impl ForecastResponse {
+    /// Write the response as CSV, each table separated by a blank line
+    ///
+    /// Columns are headed by the variable name and its unit, e.g.
+    /// `temperature_2m (°C)`, so that the output reads back with `from_csv`
+    pub fn to_csv(&self, writer: impl Write, options: CsvOptions) -> io::Result<()> {
+        write_csv(std::slice::from_ref(self), false, writer, &options)
+    }
+}
+
+impl ForecastResponses {
+    /// Write the responses as CSV like `ForecastResponse::to_csv`, with the
+    /// rows of every location in each table, after a `location_id` column
+    pub fn to_csv(&self, writer: impl Write, options: CsvOptions) -> io::Result<()> {
+        write_csv(&self.0, true, writer, &options)
+    }
+}
+
+/// Header and rows of a table, fields not yet quoted, starting with the
+/// location id
+type Rows = (Vec<String>, Vec<Vec<String>>);
+
+fn write_csv(
+    responses: &[ForecastResponse],
+    location_id: bool,
+    mut writer: impl Write,
+    options: &CsvOptions,
+) -> io::Result<()> {
+    let mut tables: Vec<Rows> = Vec::new();
+    if options.metadata {
+        tables.push(metadata(responses));
+    }
+    for block in &options.blocks {
+        tables.extend(block_table(responses, *block, &options.null));
+    }
+    for (index, (mut header, mut rows)) in tables.into_iter().enumerate() {
+        if location_id {
+            header[0] = "location_id".to_string();
+        } else {
+            header.remove(0);
+            for row in &mut rows {
+                row.remove(0);
+            }
+        }
+        if index > 0 {
+            writeln!(writer)?;
+        }
+        write_row(&mut writer, &header)?;
+        for row in &rows {
+            write_row(&mut writer, row)?;
+        }
+    }
+    writer.flush()
+}
+
+fn metadata(responses: &[ForecastResponse]) -> Rows {
+    let header = [
+        "",
+        "latitude",
+        "longitude",
+        "elevation",
+        "utc_offset_seconds",
+        "timezone",
+        "timezone_abbreviation",
+    ];
+    let rows = responses
+        .iter()
+        .map(|response| {
+            vec![
+                response.location_id.to_string(),
+                format!("{:?}", response.latitude),
+                format!("{:?}", response.longitude),
+                format!("{:?}", response.elevation),
+                response.utc_offset_seconds.to_string(),
+                response.timezone.clone(),
+                response.timezone_abbreviation.clone(),
+            ]
+        })
+        .collect();
+    (header.map(str::to_string).to_vec(), rows)
+}
+
+/// Table of `block` over all responses having it, `None` if none has it
+fn block_table(responses: &[ForecastResponse], block: CsvBlock, null: &str) -> Option<Rows> {
+    // Union of the variables of all locations, in order of appearance
+    let mut names: Vec<&str> = Vec::new();
+    let mut units = None;
+    let mut present = false;
+    for response in responses {
+        let (variables, block_units): (Vec<&str>, _) = match block {
+            CsvBlock::Current => match &response.current {
+                Some(current) => (
+                    current.values.keys().map(String::as_str).collect(),
+                    &response.current_units,
+                ),
+                None => continue,
+            },
+            CsvBlock::Minutely15 => match &response.minutely_15 {
+                Some(block) => (block.variables().collect(), &response.minutely_15_units),
+                None => continue,
+            },
+            CsvBlock::Hourly => match &response.hourly {
+                Some(block) => (block.variables().collect(), &response.hourly_units),
+                None => continue,
+            },
+            CsvBlock::Daily => match &response.daily {
+                Some(block) => (
+                    block.series.keys().map(String::as_str).collect(),
+                    &response.daily_units,
+                ),
+                None => continue,
+            },
+        };
+        present = true;
+        units = units.or(block_units.as_ref());
+        for name in variables {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    if !present {
+        return None;
+    }
+
+    let empty = UnitsMap::default();
+    let units = units.unwrap_or(&empty);
+    let mut header = vec![String::new(), "time".to_string()];
+    if block == CsvBlock::Current {
+        header.push("interval".to_string());
+    }
+    header.extend(names.iter().map(|name| units.label(name)));
+
+    let mut rows = Vec::new();
+    for response in responses {
+        let id = response.location_id.to_string();
+        match block {
+            CsvBlock::Current => {
+                let Some(current) = &response.current else {
+                    continue;
+                };
+                let mut row = vec![
+                    id,
+                    current.time.to_string(),
+                    current.interval.map_or(null.to_string(), |i| i.to_string()),
+                ];
+                row.extend(names.iter().map(|name| match current.values.get(*name) {
+                    Some(value) => json_field(value, null),
+                    None => null.to_string(),
+                }));
+                rows.push(row);
+            }
+            CsvBlock::Minutely15 | CsvBlock::Hourly => {
+                let values = match block {
+                    CsvBlock::Minutely15 => &response.minutely_15,
+                    _ => &response.hourly,
+                };
+                let Some(values) = values else {
+                    continue;
+                };
+                for (index, time) in values.time.iter().enumerate() {
+                    let mut row = vec![id.clone(), time.to_string()];
+                    row.extend(names.iter().map(|name| {
+                        match values.series.get(*name).and_then(|s| s.get(index).copied()) {
+                            Some(Some(value)) => format!("{value:?}"),
+                            _ => null.to_string(),
+                        }
+                    }));
+                    rows.push(row);
+                }
+            }
+            CsvBlock::Daily => {
+                let Some(daily) = &response.daily else {
+                    continue;
+                };
+                for (index, time) in daily.time.iter().enumerate() {
+                    let mut row = vec![id.clone(), time.to_string()];
+                    row.extend(names.iter().map(|name| {
+                        match daily.series.get(*name).and_then(|s| s.get(index)) {
+                            Some(Some(value)) => daily_field(value, null),
+                            _ => null.to_string(),
+                        }
+                    }));
+                    rows.push(row);
+                }
+            }
+        }
+    }
+    Some((header, rows))
+}
+
+fn daily_field(value: &DailyValue, null: &str) -> String {
+    match value {
+        DailyValue::Float(value) => format!("{value:?}"),
+        DailyValue::Time(time) => time.to_string(),
+        DailyValue::Code(code) => code.to_string(),
+        DailyValue::Raw(value) => json_field(value, null),
+    }
+}
+
+fn json_field(value: &Value, null: &str) -> String {
+    match value {
+        Value::Null => null.to_string(),
+        Value::Number(number) => match number.as_f64() {
+            Some(float) if number.is_f64() => format!("{float:?}"),
+            _ => number.to_string(),
+        },
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Write one line, quoting fields as RFC 4180 requires
+fn write_row(writer: &mut impl Write, fields: &[String]) -> io::Result<()> {
+    let line: Vec<Cow<'_, str>> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\r', '\n']) {
+                Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+            } else {
+                Cow::Borrowed(field.as_str())
+            }
+        })
+        .collect();
+    writeln!(writer, "{}", line.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn same_as_json() {
+        let csv = ForecastResponse::from_csv(include_str!("../fixtures/forecast.csv").as_bytes())
+            .unwrap();
+        let mut json =
+            ForecastResponse::from_json(include_str!("../fixtures/forecast.json")).unwrap();
+        // Not part of the CSV output
+        json.generationtime_ms = 0.0;
+        json.current_weather = None;
+        json.current_weather_units = None;
+        assert_eq!(csv, json);
+        assert_eq!(
+            csv.daily.unwrap().sun_events()[0].daylight,
+            json.daily.unwrap().sun_events()[0].daylight
+        );
+    }
+
+    #[test]
+    fn writes_the_api_layout() {
+        let json = ForecastResponse::from_json(include_str!("../fixtures/forecast.json")).unwrap();
+        let mut csv = Vec::new();
+        json.to_csv(&mut csv, CsvOptions::default()).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            include_str!("../fixtures/forecast.csv")
+        );
+    }
+
+    #[test]
+    fn writes_locations_with_quoting() {
+        let mut responses = ForecastResponses(
+            ForecastResponses::from_json(include_str!("../fixtures/locations.json")).unwrap(),
+        );
+        responses.0[1].timezone_abbreviation = "GMT, \"Zurich\"".to_string();
+        let mut csv = Vec::new();
+        responses
+            .to_csv(
+                &mut csv,
+                CsvOptions::default()
+                    .blocks([CsvBlock::Hourly, CsvBlock::Current])
+                    .null("NaN"),
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            include_str!("../fixtures/locations.csv")
+        );
+
+        // One plain table, e.g. for a file of its own
+        let mut daily = Vec::new();
+        responses.0[0]
+            .to_csv(
+                &mut daily,
+                CsvOptions::default()
+                    .metadata(false)
+                    .blocks([CsvBlock::Daily]),
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(daily).unwrap(),
+            "time,weather_code (wmo code)\n2024-03-02,61\n"
+        );
+    }
+
+    #[test]
+    fn current_minutely_and_gaps() {
+        let csv =
+            "latitude,longitude,elevation,utc_offset_seconds,timezone,timezone_abbreviation\n\
+                   47.38,8.54,409.0,7200,Europe/Zurich,CEST\n\
+                   \n\
+                   time,interval,temperature_2m (°C)\n\
+                   1697792400,900,11.5\n\
+                   \n\
+                   time,precipitation (mm)\n\
+                   1697792400,0.1\n\
+                   1697793300,\n";
+        let response = ForecastResponse::from_csv(csv.as_bytes()).unwrap();
+        assert_eq!(response.timezone, "Europe/Zurich");
+        let current = response.current.unwrap();
+        assert_eq!(current.time, Timestamp::Unix(1697792400));
+        assert_eq!(current.interval, Some(900));
+        assert_eq!(current.get("temperature_2m"), Some(11.5));
+        assert_eq!(
+            response.current_units.unwrap().get("temperature_2m"),
+            Some("°C")
+        );
+        let minutely = response.minutely_15.unwrap();
+        assert_eq!(
+            minutely.get("precipitation").unwrap().values(),
+            [Some(0.1), None]
+        );
+        assert_eq!(
+            response.minutely_15_units.unwrap().get("time"),
+            Some("unixtime")
+        );
+
+        let error =
+            ForecastResponse::from_csv(&b"latitude\n1\n\ntime,rain (mm)\n2023-05-16T00:00\n"[..])
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid CSV at line 5: expected 2 fields, found 1"
+        );
+    }
+}
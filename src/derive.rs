@@ -0,0 +1,1132 @@
+//! Humidity, heat and cold stress indices, degree days, threshold events and
+//! irrigation needs computed from the variables of a response
+
+use std::fmt;
+
+#[cfg(feature = "chrono")]
+use chrono::NaiveDate;
+use indexmap::IndexMap;
+
+#[cfg(feature = "chrono")]
+use crate::date::Date;
+use crate::units::convert;
+use crate::{
+    Agg, Comparison, Daily, ForecastResponse, Hourly, HourlyBlock, Resample, Series, Timestamp,
+    UnitsMap,
+};
+
+/// Apparent temperature in °C of the NWS heat index, from the air temperature
+/// in °C and the relative humidity in %
+///
+/// Uses the simple Steadman formula below 80 °F, where the index is about the
+/// temperature, and the Rothfusz regression with its low and high humidity
+/// adjustments above
+pub fn heat_index(temp_c: f64, rh: f64) -> f64 {
+    let t = temp_c * 9.0 / 5.0 + 32.0;
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let index = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        let mut index = -42.379 + 2.04901523 * t + 10.14333127 * rh
+            - 0.22475541 * t * rh
+            - 6.83783e-3 * t * t
+            - 5.481717e-2 * rh * rh
+            + 1.22874e-3 * t * t * rh
+            + 8.5282e-4 * t * rh * rh
+            - 1.99e-6 * t * t * rh * rh;
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            index -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            index += (rh - 85.0) / 10.0 * (87.0 - t) / 5.0;
+        }
+        index
+    };
+    (index - 32.0) * 5.0 / 9.0
+}
+
+/// Humidex of Environment Canada, from the air temperature and dew point in °C
+pub fn humidex(temp_c: f64, dewpoint_c: f64) -> f64 {
+    let vapour_pressure = 6.11 * (5417.753 * (1.0 / 273.16 - 1.0 / (273.15 + dewpoint_c))).exp();
+    temp_c + 0.5555 * (vapour_pressure - 10.0)
+}
+
+/// Coefficients of the Magnus formula over water, from Sonntag (1990), valid
+/// between -45 °C and 60 °C
+const MAGNUS_B: f64 = 17.62;
+const MAGNUS_C: f64 = 243.12;
+
+/// Dew point in °C from the air temperature in °C and the relative humidity
+/// in %, by the Magnus formula
+///
+/// Accurate to about 0.1 °C between -45 °C and 60 °C. The humidity is clamped
+/// to (0, 100], 0 % or less being taken as 0.01 %
+pub fn dewpoint_from_rh(temp_c: f64, rh_percent: f64) -> f64 {
+    let rh = rh_percent.clamp(0.01, 100.0);
+    let gamma = (rh / 100.0).ln() + MAGNUS_B * temp_c / (MAGNUS_C + temp_c);
+    MAGNUS_C * gamma / (MAGNUS_B - gamma)
+}
+
+/// Relative humidity in % from the air temperature and dew point in °C, the
+/// inverse of `dewpoint_from_rh`, at most 100 % if the dew point is above the
+/// temperature
+pub fn rh_from_dewpoint(temp_c: f64, dewpoint_c: f64) -> f64 {
+    let saturation = |t: f64| MAGNUS_B * t / (MAGNUS_C + t);
+    (100.0 * (saturation(dewpoint_c) - saturation(temp_c)).exp()).min(100.0)
+}
+
+/// Range of relative humidity in % over which `wet_bulb_stull` was fitted,
+/// for temperatures between -20 °C and 50 °C
+pub const STULL_RH: std::ops::RangeInclusive<f64> = 5.0..=99.0;
+
+/// Wet-bulb temperature in °C from the air temperature in °C and the relative
+/// humidity in %, by the empirical formula of Stull (2011)
+///
+/// Fitted at sea level pressure for -20 °C to 50 °C and a humidity in
+/// `STULL_RH`, where it is within about 1 °C of the psychrometric value and
+/// usually much closer; outside, in particular in cold and very dry air, the
+/// error grows quickly
+pub fn wet_bulb_stull(temp_c: f64, rh_percent: f64) -> f64 {
+    let (t, rh) = (temp_c, rh_percent);
+    t * (0.151977 * (rh + 8.313659).sqrt()).atan() + (t + rh).atan() - (rh - 1.676331).atan()
+        + 0.00391838 * rh.powf(1.5) * (0.023101 * rh).atan()
+        - 4.686035
+}
+
+/// Wind chill temperature in °C of the JAG/TI formula used in Canada and the
+/// US, from the air temperature in °C and the wind speed at 10 m in km/h
+///
+/// `None` outside the range the formula was fitted on, above 10 °C or below
+/// 4.8 km/h
+pub fn wind_chill(temp_c: f64, wind_kmh: f64) -> Option<f64> {
+    if temp_c > 10.0 || wind_kmh < 4.8 {
+        return None;
+    }
+    let wind = wind_kmh.powf(0.16);
+    Some(13.12 + 0.6215 * temp_c - 11.37 * wind + 0.3965 * temp_c * wind)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// Heating and cooling degree days, one value per day and `None` for days
+/// without data
+pub struct DegreeDays {
+    /// Degrees below the base
+    pub heating: Series,
+    /// Degrees above the base
+    pub cooling: Series,
+}
+
+impl DegreeDays {
+    pub fn heating_total(&self) -> f64 {
+        self.heating.dropped().sum()
+    }
+
+    pub fn cooling_total(&self) -> f64 {
+        self.cooling.dropped().sum()
+    }
+
+    /// Running total of `heating`, still `None` on days without data
+    pub fn cumulative_heating(&self) -> Series {
+        cumulative(&self.heating)
+    }
+
+    /// Running total of `cooling`, still `None` on days without data
+    pub fn cumulative_cooling(&self) -> Series {
+        cumulative(&self.cooling)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Run of consecutive samples past a threshold, e.g. a frost night
+pub struct Event {
+    /// First sample of the run
+    pub start: Timestamp,
+    /// Last sample of the run, included
+    pub end: Timestamp,
+    /// Value furthest past the threshold, in the unit of the series
+    pub extreme_value: f64,
+    /// The run lasts until the last sample, so it may go on beyond the data
+    pub open_ended: bool,
+}
+
+/// Runs of at least `min_consecutive` samples of `series` for which
+/// `comparison` holds against `threshold`, a missing value ending a run
+pub fn threshold_events(
+    series: &Series,
+    time_axis: &[Timestamp],
+    comparison: Comparison,
+    threshold: f64,
+    min_consecutive: usize,
+) -> Vec<Event> {
+    let samples = time_axis.len().min(series.len());
+    let mut events = Vec::new();
+    let mut run: Option<(usize, f64)> = None;
+    for index in 0..=samples {
+        let value = series
+            .get(index)
+            .copied()
+            .flatten()
+            .filter(|_| index < samples);
+        match (value, &mut run) {
+            (Some(value), Some((_, extreme))) if comparison.holds(value, threshold) => {
+                if comparison.beyond(value, *extreme) {
+                    *extreme = value;
+                }
+            }
+            (Some(value), None) if comparison.holds(value, threshold) => run = Some((index, value)),
+            _ => {
+                if let Some((start, extreme_value)) = run.take() {
+                    if index - start >= min_consecutive.max(1) {
+                        events.push(Event {
+                            start: time_axis[start].clone(),
+                            end: time_axis[index - 1].clone(),
+                            extreme_value,
+                            open_ended: index == samples,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    events
+}
+
+/// Frosts, hours below 0 °C of the hourly `temperature_2m`, or else days
+/// whose `temperature_2m_min` is below 0 °C, in any temperature unit
+pub fn frost_events(response: &ForecastResponse) -> Vec<Event> {
+    let hourly = response.hourly.as_ref().and_then(|block| {
+        let series = block.get(&Hourly::temperature_2m.to_string())?;
+        let unit = unit(&response.hourly_units, &Hourly::temperature_2m);
+        Some((series.clone(), &block.time, unit))
+    });
+    let daily = || {
+        let block = response.daily.as_ref()?;
+        let series = block.numbers(&Daily::temperature_2m_min.to_string())?;
+        let unit = unit(&response.daily_units, &Daily::temperature_2m_min);
+        Some((series, &block.time, unit))
+    };
+    match hourly.or_else(daily) {
+        Some((series, time, unit)) => match convert(0.0, "°C", unit) {
+            Some(threshold) => threshold_events(&series, time, Comparison::Below, threshold, 1),
+            None => Vec::new(),
+        },
+        None => Vec::new(),
+    }
+}
+
+/// Hours of the hourly `temperature_2m` above `threshold` in °C, whatever the
+/// temperature unit of the response
+pub fn heat_events(response: &ForecastResponse, threshold: f64) -> Vec<Event> {
+    let Some(block) = &response.hourly else {
+        return Vec::new();
+    };
+    let Some(series) = block.get(&Hourly::temperature_2m.to_string()) else {
+        return Vec::new();
+    };
+    let unit = unit(&response.hourly_units, &Hourly::temperature_2m);
+    match convert(threshold, "°C", unit) {
+        Some(threshold) => threshold_events(series, &block.time, Comparison::Above, threshold, 1),
+        None => Vec::new(),
+    }
+}
+
+/// Unit of a variable, °C when the response has none
+fn unit<'a>(units: &'a Option<UnitsMap>, variable: &dyn fmt::Display) -> &'a str {
+    units
+        .as_ref()
+        .and_then(|units| units.get(variable))
+        .unwrap_or("°C")
+}
+
+/// Share of the precipitation `irrigation_need` counts as reaching the roots,
+/// the rest running off or evaporating
+pub const EFFECTIVE_PRECIPITATION: f64 = 0.8;
+
+/// Reference evapotranspiration in mm of the Hargreaves equation (FAO-56,
+/// equation 52), from the extremes of a day in °C, the latitude in degrees
+/// and the day of the year, January 1 being 1
+pub fn hargreaves_et0(t_min_c: f64, t_max_c: f64, latitude: f64, day_of_year: u32) -> f64 {
+    let mean = (t_min_c + t_max_c) / 2.0;
+    let range = (t_max_c - t_min_c).max(0.0);
+    // Radiation as the equivalent evaporation, 0.408 mm per MJ/m²
+    0.0023
+        * (mean + 17.8)
+        * range.sqrt()
+        * 0.408
+        * extraterrestrial_radiation(latitude, day_of_year)
+}
+
+/// Daily extraterrestrial radiation in MJ/m² (FAO-56, equation 21)
+fn extraterrestrial_radiation(latitude: f64, day_of_year: u32) -> f64 {
+    use std::f64::consts::PI;
+    let angle = 2.0 * PI * f64::from(day_of_year) / 365.0;
+    let distance = 1.0 + 0.033 * angle.cos();
+    let declination = 0.409 * (angle - 1.39).sin();
+    let latitude = latitude.to_radians();
+    // Polar days and nights clamp the sunset hour angle
+    let sunset = (-latitude.tan() * declination.tan())
+        .clamp(-1.0, 1.0)
+        .acos();
+    24.0 * 60.0 / PI
+        * 0.0820
+        * distance
+        * (sunset * latitude.sin() * declination.sin()
+            + latitude.cos() * declination.cos() * sunset.sin())
+}
+
+/// `irrigation_need_with` counting `EFFECTIVE_PRECIPITATION` of the rain
+#[cfg(feature = "chrono")]
+pub fn irrigation_need(
+    response: &ForecastResponse,
+    crop_coefficient: f64,
+) -> Vec<(NaiveDate, f64)> {
+    irrigation_need_with(response, crop_coefficient, EFFECTIVE_PRECIPITATION)
+}
+
+/// Water to supply each day, the crop evapotranspiration `crop_coefficient`
+/// times the daily `et0_fao_evapotranspiration` minus the `effective_fraction`
+/// of `precipitation_sum`, at least 0
+///
+/// Days without ET0 fall back to `hargreaves_et0` from the daily
+/// `temperature_2m_min` and `temperature_2m_max`; days without precipitation
+/// or any way to estimate ET0 are left out. Needs are in the unit of the
+/// precipitation, mm or inch.
+#[cfg(feature = "chrono")]
+pub fn irrigation_need_with(
+    response: &ForecastResponse,
+    crop_coefficient: f64,
+    effective_fraction: f64,
+) -> Vec<(NaiveDate, f64)> {
+    let Some(block) = &response.daily else {
+        return Vec::new();
+    };
+    let units = &response.daily_units;
+    let column = |variable: Daily, to: &str| -> Series {
+        let from = units
+            .as_ref()
+            .and_then(|units| units.get(&variable))
+            .unwrap_or(to);
+        let values = block.numbers(&variable.to_string()).unwrap_or_default();
+        values
+            .iter()
+            .map(|value| convert((*value)?, from, to))
+            .collect()
+    };
+    let (et0, t_min, t_max) = (
+        column(Daily::et0_fao_evapotranspiration, "mm"),
+        column(Daily::temperature_2m_min, "°C"),
+        column(Daily::temperature_2m_max, "°C"),
+    );
+    let precipitation_unit = units
+        .as_ref()
+        .and_then(|units| units.get(Daily::precipitation_sum))
+        .unwrap_or("mm");
+    let precipitation = column(Daily::precipitation_sum, "mm");
+    let value = |series: &Series, index: usize| series.get(index).copied().flatten();
+
+    let mut needs = Vec::new();
+    for (index, time) in block.time.iter().enumerate() {
+        let Some(date) = Date::local(time, response.utc_offset_seconds) else {
+            continue;
+        };
+        let estimated = || {
+            let new_year = Date::parse(&format!("{}-01-01", date.year))?;
+            let day_of_year = (date.days() - new_year.days() + 1) as u32;
+            let (t_min, t_max) = (value(&t_min, index)?, value(&t_max, index)?);
+            Some(hargreaves_et0(t_min, t_max, response.latitude, day_of_year))
+        };
+        let (Some(et0), Some(precipitation)) = (
+            value(&et0, index).or_else(estimated),
+            value(&precipitation, index),
+        ) else {
+            continue;
+        };
+        let need = (crop_coefficient * et0 - effective_fraction * precipitation).max(0.0);
+        let date = NaiveDate::from_ymd_opt(date.year, date.month, date.day);
+        if let (Some(date), Some(need)) = (date, convert(need, "mm", precipitation_unit)) {
+            needs.push((date, need));
+        }
+    }
+    needs
+}
+
+/// Usual base of degree days for temperatures in `unit`, 65 °F or 18 °C
+pub fn default_base(unit: &str) -> f64 {
+    match unit {
+        "°F" => 65.0,
+        _ => 18.0,
+    }
+}
+
+/// Degree days from the mean temperature of each day
+pub fn degree_days(daily_mean_temps: &Series, base: f64) -> DegreeDays {
+    DegreeDays {
+        heating: daily_mean_temps
+            .iter()
+            .map(|mean| Some((base - (*mean)?).max(0.0)))
+            .collect(),
+        cooling: daily_mean_temps
+            .iter()
+            .map(|mean| Some(((*mean)? - base).max(0.0)))
+            .collect(),
+    }
+}
+
+/// Degree days integrated over the hourly temperatures of each local day,
+/// with the days they fall on
+///
+/// Unlike the daily mean, a day of cold nights and warm afternoons counts both
+/// heating and cooling degrees. Each day is the mean of its hours, so missing
+/// hours are left out rather than counted at the base.
+pub fn degree_days_hourly(
+    block: &HourlyBlock,
+    variable: Hourly,
+    utc_offset_seconds: i32,
+    base: f64,
+) -> Option<(Vec<Timestamp>, DegreeDays)> {
+    let hourly = degree_days(block.get(&variable.to_string())?, base);
+    let excess = HourlyBlock {
+        time: block.time.clone(),
+        series: IndexMap::from_iter([
+            ("heating".to_string(), hourly.heating),
+            ("cooling".to_string(), hourly.cooling),
+        ]),
+    };
+    let mut daily = excess.resample(Resample::CalendarDay { utc_offset_seconds }, Agg::Mean);
+    let heating = daily.series.shift_remove("heating").unwrap_or_default();
+    let cooling = daily.series.shift_remove("cooling").unwrap_or_default();
+    Some((daily.time, DegreeDays { heating, cooling }))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Enumerate the ways of averaging the extremes of a day into growing degrees
+pub enum GddMethod {
+    /// Mean of the extremes minus the base, counted when above it; the cap
+    /// only limits the extremes
+    #[default]
+    Average,
+    /// Extremes clamped between the base and the cap before averaging, so a
+    /// cold night does not cancel a warm afternoon, e.g. the 86/50 method
+    /// for corn
+    Clamped,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// Growing degree days, one value per day and `None` for days without data
+pub struct GrowingDegreeDays {
+    pub daily: Series,
+    /// Running total of `daily`, still `None` on days without data
+    pub cumulative: Series,
+}
+
+/// Growing degree days from the daily extremes, above `base` and with the
+/// extremes limited to `cap` if any, e.g. 10 °C and 30 °C for corn
+///
+/// Every day is `None` if `cap` is below `base` or either is NaN
+pub fn growing_degree_days(
+    t_min: &Series,
+    t_max: &Series,
+    base: f64,
+    cap: Option<f64>,
+    method: GddMethod,
+) -> GrowingDegreeDays {
+    let cap = cap.unwrap_or(f64::INFINITY);
+    if cap.is_nan() || base.is_nan() || cap < base {
+        let days = t_min.len().min(t_max.len());
+        return GrowingDegreeDays {
+            daily: vec![None; days].into(),
+            cumulative: vec![None; days].into(),
+        };
+    }
+    let day = |min: f64, max: f64| match method {
+        GddMethod::Average => ((min.min(cap) + max.min(cap)) / 2.0 - base).max(0.0),
+        GddMethod::Clamped => (min.clamp(base, cap) + max.clamp(base, cap)) / 2.0 - base,
+    };
+    let daily = combine(t_min, t_max, day);
+    GrowingDegreeDays {
+        cumulative: cumulative(&daily),
+        daily,
+    }
+}
+
+impl ForecastResponse {
+    /// Growing degree days with the days they are for, from the daily
+    /// `temperature_2m_min` and `temperature_2m_max` if requested, otherwise
+    /// from the extremes of the hourly `temperature_2m` of each local day
+    ///
+    /// `base` and `cap` are in the unit of the response.
+    pub fn growing_degree_days(
+        &self,
+        base: f64,
+        cap: Option<f64>,
+        method: GddMethod,
+    ) -> Option<(Vec<Timestamp>, GrowingDegreeDays)> {
+        let daily = |variable| -> Option<Series> {
+            let values = self.daily(variable)?;
+            Some(
+                values
+                    .iter()
+                    .map(|value| value.as_ref()?.as_f64())
+                    .collect(),
+            )
+        };
+        let (time, t_min, t_max) = match (
+            &self.daily,
+            daily(Daily::temperature_2m_min),
+            daily(Daily::temperature_2m_max),
+        ) {
+            (Some(block), Some(t_min), Some(t_max)) => (block.time.clone(), t_min, t_max),
+            _ => {
+                let hourly = self.hourly.as_ref()?;
+                let temperature = hourly.get(&Hourly::temperature_2m.to_string())?;
+                let block = HourlyBlock {
+                    time: hourly.time.clone(),
+                    series: IndexMap::from_iter([("t".to_string(), temperature.clone())]),
+                };
+                let rule = Resample::CalendarDay {
+                    utc_offset_seconds: self.utc_offset_seconds,
+                };
+                let extreme = |agg| {
+                    let mut daily = block.resample(rule, agg);
+                    (
+                        daily.time,
+                        daily.series.shift_remove("t").unwrap_or_default(),
+                    )
+                };
+                let ((time, t_min), (_, t_max)) = (extreme(Agg::Min), extreme(Agg::Max));
+                (time, t_min, t_max)
+            }
+        };
+        Some((time, growing_degree_days(&t_min, &t_max, base, cap, method)))
+    }
+
+    /// Degree days above and below `base`, by default `default_base` for the
+    /// unit of the response, with the days they are for
+    ///
+    /// Uses the daily `temperature_2m_mean` if requested, otherwise integrates
+    /// the hourly `temperature_2m`; `None` if neither is in the response
+    pub fn degree_days(&self, base: Option<f64>) -> Option<(Vec<Timestamp>, DegreeDays)> {
+        let base = |units: &Option<UnitsMap>, variable: &dyn fmt::Display| {
+            base.unwrap_or_else(|| default_base(unit(units, variable)))
+        };
+        if let (Some(block), Some(means)) = (&self.daily, self.daily(Daily::temperature_2m_mean)) {
+            let means: Series = means.iter().map(|mean| mean.as_ref()?.as_f64()).collect();
+            let base = base(&self.daily_units, &Daily::temperature_2m_mean);
+            return Some((block.time.clone(), degree_days(&means, base)));
+        }
+        let base = base(&self.hourly_units, &Hourly::temperature_2m);
+        degree_days_hourly(
+            self.hourly.as_ref()?,
+            Hourly::temperature_2m,
+            self.utc_offset_seconds,
+            base,
+        )
+    }
+
+    /// Heat index in °C at each timestamp, from `temperature_2m` and
+    /// `relative_humidity_2m`; `None` if either is missing
+    pub fn heat_index(&self) -> Option<Series> {
+        let temperature = self.converted(Hourly::temperature_2m, "°C")?;
+        let humidity = self.hourly(Hourly::relative_humidity_2m)?;
+        Some(combine(&temperature, humidity, heat_index))
+    }
+
+    /// Humidex at each timestamp, from `temperature_2m` and `dewpoint_2m`;
+    /// `None` if either is missing
+    pub fn humidex(&self) -> Option<Series> {
+        let temperature = self.converted(Hourly::temperature_2m, "°C")?;
+        let dewpoint = self.converted(Hourly::dewpoint_2m, "°C")?;
+        Some(combine(&temperature, &dewpoint, humidex))
+    }
+
+    /// Dew point in °C at each timestamp, from `temperature_2m` and
+    /// `relative_humidity_2m`, for models or levels without `dewpoint_2m`
+    pub fn dewpoint_from_rh(&self) -> Option<Series> {
+        let temperature = self.converted(Hourly::temperature_2m, "°C")?;
+        let humidity = self.hourly(Hourly::relative_humidity_2m)?;
+        Some(combine(&temperature, humidity, dewpoint_from_rh))
+    }
+
+    /// Relative humidity in % at each timestamp, from `temperature_2m` and
+    /// `dewpoint_2m`
+    pub fn rh_from_dewpoint(&self) -> Option<Series> {
+        let temperature = self.converted(Hourly::temperature_2m, "°C")?;
+        let dewpoint = self.converted(Hourly::dewpoint_2m, "°C")?;
+        Some(combine(&temperature, &dewpoint, rh_from_dewpoint))
+    }
+
+    /// Wet-bulb temperature in °C at each timestamp by `wet_bulb_stull`, from
+    /// `temperature_2m` and `relative_humidity_2m`, when
+    /// `wet_bulb_temperature_2m` was not requested
+    pub fn wet_bulb_stull(&self) -> Option<Series> {
+        let temperature = self.converted(Hourly::temperature_2m, "°C")?;
+        let humidity = self.hourly(Hourly::relative_humidity_2m)?;
+        Some(combine(&temperature, humidity, wet_bulb_stull))
+    }
+
+    /// Wind chill in °C at each timestamp, from `temperature_2m` and
+    /// `windspeed_10m` in any unit; `None` if either is missing, and empty
+    /// values where the formula does not apply
+    pub fn wind_chill(&self) -> Option<Series> {
+        let temperature = self.converted(Hourly::temperature_2m, "°C")?;
+        let wind = self.converted(Hourly::windspeed_10m, "km/h")?;
+        Some(
+            temperature
+                .iter()
+                .zip(wind.iter())
+                .map(|(temperature, wind)| wind_chill((*temperature)?, (*wind)?))
+                .collect(),
+        )
+    }
+
+    /// Hourly variable expressed in `unit`, assumed to be in it already if
+    /// the response has no units
+    fn converted(&self, variable: Hourly, unit: &str) -> Option<Series> {
+        let series = self.hourly(variable.clone())?;
+        let from = self
+            .hourly_units
+            .as_ref()
+            .and_then(|units| units.get(variable));
+        Some(match from {
+            Some(from) if from != unit => series
+                .iter()
+                .map(|value| convert((*value)?, from, unit))
+                .collect(),
+            _ => series.clone(),
+        })
+    }
+}
+
+/// Running sum of the values, skipping but keeping the gaps
+fn cumulative(values: &Series) -> Series {
+    let mut total = 0.0;
+    values
+        .iter()
+        .map(|value| {
+            total += (*value)?;
+            Some(total)
+        })
+        .collect()
+}
+
+/// `derive` of both values at each index, `None` where either is missing
+fn combine(first: &Series, second: &Series, derive: impl Fn(f64, f64) -> f64) -> Series {
+    first
+        .iter()
+        .zip(second.iter())
+        .map(|(first, second)| Some(derive((*first)?, (*second)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testing::ResponseBuilder;
+
+    fn fahrenheit(value: f64) -> f64 {
+        (value - 32.0) * 5.0 / 9.0
+    }
+
+    #[test]
+    fn heat_index_table() {
+        // NWS heat index chart, °F
+        for (t, rh, expected) in [
+            (80.0, 40.0, 80.0),
+            (84.0, 60.0, 88.0),
+            (90.0, 70.0, 106.0),
+            (96.0, 45.0, 104.0),
+            (100.0, 50.0, 118.0),
+            (86.0, 90.0, 105.0),
+        ] {
+            let index = heat_index(fahrenheit(t), rh);
+            assert!(
+                (index - fahrenheit(expected)).abs() < 0.5,
+                "{t} °F at {rh} %: {index}"
+            );
+        }
+        // Below the regression, about the air temperature
+        assert!((heat_index(15.0, 50.0) - 14.3).abs() < 0.5);
+    }
+
+    #[test]
+    fn humidex_table() {
+        // Environment Canada humidex table, by temperature and dew point
+        for (t, dewpoint, expected) in [
+            (30.0, 25.0, 42.0),
+            (35.0, 20.0, 43.0),
+            (25.0, 15.0, 29.0),
+            (40.0, 10.0, 41.0),
+        ] {
+            let value = humidex(t, dewpoint);
+            assert!(
+                (value - expected).abs() < 0.5,
+                "{t} °C, {dewpoint} °C: {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn dewpoint_values() {
+        for (t, rh, expected) in [
+            (20.0, 50.0, 9.3),
+            (25.0, 60.0, 16.7),
+            (30.0, 80.0, 26.2),
+            (0.0, 100.0, 0.0),
+            (-10.0, 70.0, -14.4),
+        ] {
+            let dewpoint = dewpoint_from_rh(t, rh);
+            assert!(
+                (dewpoint - expected).abs() < 0.1,
+                "{t} °C at {rh} %: {dewpoint}"
+            );
+        }
+        assert_eq!(dewpoint_from_rh(15.0, 120.0), dewpoint_from_rh(15.0, 100.0));
+        assert!(dewpoint_from_rh(15.0, 0.0).is_finite());
+        assert_eq!(rh_from_dewpoint(10.0, 12.0), 100.0);
+    }
+
+    #[test]
+    fn dewpoint_round_trip() {
+        for t in [-40.0, -15.0, 0.0, 12.5, 25.0, 45.0] {
+            for rh in [1.0, 10.0, 35.0, 50.0, 80.0, 100.0] {
+                let back = rh_from_dewpoint(t, dewpoint_from_rh(t, rh));
+                assert!((back - rh).abs() < 1e-6, "{t} °C at {rh} %: {back}");
+            }
+        }
+
+        let response = ResponseBuilder::new()
+            .hourly(Hourly::temperature_2m, [Some(68.0), None])
+            .hourly_unit(Hourly::temperature_2m, "°F")
+            .hourly(Hourly::relative_humidity_2m, [50.0, 65.0])
+            .hourly_unit(Hourly::relative_humidity_2m, "%")
+            .hourly(Hourly::dewpoint_2m, [50.0, 50.0])
+            .hourly_unit(Hourly::dewpoint_2m, "°F")
+            .build();
+        let dewpoint = response.dewpoint_from_rh().unwrap();
+        assert!((dewpoint[0].unwrap() - dewpoint_from_rh(20.0, 50.0)).abs() < 1e-9);
+        assert_eq!(dewpoint[1], None);
+        let humidity = response.rh_from_dewpoint().unwrap();
+        assert!((humidity[0].unwrap() - rh_from_dewpoint(20.0, 10.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn wet_bulb_against_api() {
+        // Response in the API format, its wet-bulb column psychrometric values
+        // at sea level rather than a recorded answer
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/wet_bulb.json")).unwrap();
+        let stull = response.wet_bulb_stull().unwrap();
+        let api = response.hourly(Hourly::wet_bulb_temperature_2m).unwrap();
+        let humidity = response.hourly(Hourly::relative_humidity_2m).unwrap();
+
+        let mut compared = 0;
+        for ((stull, api), rh) in stull.iter().zip(api.iter()).zip(humidity.iter()) {
+            if let (Some(stull), Some(api), Some(rh)) = (stull, api, rh) {
+                if STULL_RH.contains(rh) {
+                    assert!((stull - api).abs() < 0.5, "{stull} against {api}");
+                    compared += 1;
+                }
+            }
+        }
+        assert_eq!(compared, 7);
+    }
+
+    #[test]
+    fn degree_days_daily() {
+        let celsius: Series = vec![
+            Some(10.0),
+            Some(15.0),
+            Some(18.0),
+            Some(20.0),
+            Some(25.0),
+            None,
+            Some(16.0),
+        ]
+        .into();
+        let days = degree_days(&celsius, default_base("°C"));
+        assert_eq!(
+            days.heating.values(),
+            [
+                Some(8.0),
+                Some(3.0),
+                Some(0.0),
+                Some(0.0),
+                Some(0.0),
+                None,
+                Some(2.0)
+            ]
+        );
+        assert_eq!(
+            days.cooling.values(),
+            [
+                Some(0.0),
+                Some(0.0),
+                Some(0.0),
+                Some(2.0),
+                Some(7.0),
+                None,
+                Some(0.0)
+            ]
+        );
+        assert_eq!((days.heating_total(), days.cooling_total()), (13.0, 9.0));
+        assert_eq!(
+            days.cumulative_heating().values(),
+            [
+                Some(8.0),
+                Some(11.0),
+                Some(11.0),
+                Some(11.0),
+                Some(11.0),
+                None,
+                Some(13.0)
+            ]
+        );
+
+        let response = ResponseBuilder::new()
+            .start("2024-01-01")
+            .daily(
+                Daily::temperature_2m_mean,
+                [50.0, 60.0, 65.0, 70.0, 80.0, 40.0, 66.0],
+            )
+            .daily_unit(Daily::temperature_2m_mean, "°F")
+            .build();
+        let (time, days) = response.degree_days(None).unwrap();
+        assert_eq!(time.len(), 7);
+        assert_eq!((days.heating_total(), days.cooling_total()), (45.0, 21.0));
+        assert_eq!(days.cumulative_cooling()[6], Some(21.0));
+        let (_, days) = response.degree_days(Some(60.0)).unwrap();
+        assert_eq!((days.heating_total(), days.cooling_total()), (30.0, 41.0));
+    }
+
+    #[test]
+    fn degree_days_integrated() {
+        // A day of 10 °C nights and 22 °C afternoons, mean 16 °C, then a day
+        // at the base and a day with gaps
+        let temperature = (0..72).map(|hour| match (hour / 24, hour % 24) {
+            (0, 6..=17) => Some(22.0),
+            (0, _) => Some(10.0),
+            (1, _) => Some(18.0),
+            (_, 0..=11) => None,
+            _ => Some(14.0),
+        });
+        let response = ResponseBuilder::new()
+            .start("2024-01-01")
+            .hourly(Hourly::temperature_2m, temperature)
+            .hourly_unit(Hourly::temperature_2m, "°C")
+            .build();
+
+        let (time, days) = response.degree_days(None).unwrap();
+        assert_eq!(
+            time,
+            ["2024-01-01", "2024-01-02", "2024-01-03"].map(|day| Timestamp::Iso8601(day.into()))
+        );
+        assert_eq!(days.heating.values(), [Some(4.0), Some(0.0), Some(4.0)]);
+        assert_eq!(days.cooling.values(), [Some(2.0), Some(0.0), Some(0.0)]);
+        // The daily mean misses the warm afternoon
+        let mean: Series = vec![Some(16.0)].into();
+        assert_eq!(degree_days(&mean, 18.0).cooling.values(), [Some(0.0)]);
+    }
+
+    #[test]
+    fn growing_degree_days_corn() {
+        // Corn, base 10 °C and cap 30 °C: a hot day, a mild day with a cold
+        // night, a day below the base and a missing day
+        let t_min: Series = vec![Some(18.0), Some(5.0), Some(2.0), None, Some(15.0)].into();
+        let t_max: Series = vec![Some(33.0), Some(25.0), Some(8.0), Some(20.0), Some(25.0)].into();
+
+        let average = growing_degree_days(&t_min, &t_max, 10.0, Some(30.0), GddMethod::Average);
+        assert_eq!(
+            average.daily.values(),
+            [Some(14.0), Some(5.0), Some(0.0), None, Some(10.0)]
+        );
+        assert_eq!(average.cumulative[4], Some(29.0));
+        let clamped = growing_degree_days(&t_min, &t_max, 10.0, Some(30.0), GddMethod::Clamped);
+        assert_eq!(
+            clamped.daily.values(),
+            [Some(14.0), Some(7.5), Some(0.0), None, Some(10.0)]
+        );
+        assert_eq!(
+            clamped.cumulative.values(),
+            [Some(14.0), Some(21.5), Some(21.5), None, Some(31.5)]
+        );
+        let uncapped = growing_degree_days(&t_min, &t_max, 10.0, None, GddMethod::Average);
+        assert_eq!(uncapped.daily[0], Some(15.5));
+        for method in [GddMethod::Average, GddMethod::Clamped] {
+            for cap in [5.0, f64::NAN] {
+                let invalid = growing_degree_days(&t_min, &t_max, 10.0, Some(cap), method);
+                assert_eq!(invalid.daily.values(), [None; 5]);
+                assert_eq!(invalid.cumulative.values(), [None; 5]);
+            }
+        }
+
+        // The 86/50 °F method: a 92 °F high and 68 °F low give 27
+        let response = ResponseBuilder::new()
+            .daily(Daily::temperature_2m_max, [92.0, 70.0])
+            .daily_unit(Daily::temperature_2m_max, "°F")
+            .daily(Daily::temperature_2m_min, [68.0, 45.0])
+            .daily_unit(Daily::temperature_2m_min, "°F")
+            .build();
+        let (time, gdd) = response
+            .growing_degree_days(50.0, Some(86.0), GddMethod::Clamped)
+            .unwrap();
+        assert_eq!(time.len(), 2);
+        assert_eq!(gdd.daily.values(), [Some(27.0), Some(10.0)]);
+    }
+
+    #[test]
+    fn growing_degree_days_hourly() {
+        // 12 °C to 35 °C on the first day, 8 °C to 19 °C on the second
+        let temperature = (0..48).map(|hour| match (hour / 24, hour % 24) {
+            (0, 14) => 35.0,
+            (0, _) => 12.0,
+            (_, 14) => 19.0,
+            _ => 8.0,
+        });
+        let response = ResponseBuilder::new()
+            .start("2024-07-01")
+            .hourly(Hourly::temperature_2m, temperature)
+            .hourly_unit(Hourly::temperature_2m, "°C")
+            .build();
+
+        let (time, gdd) = response
+            .growing_degree_days(10.0, Some(30.0), GddMethod::Clamped)
+            .unwrap();
+        assert_eq!(
+            time,
+            ["2024-07-01", "2024-07-02"].map(|day| Timestamp::Iso8601(day.into()))
+        );
+        assert_eq!(gdd.daily.values(), [Some(11.0), Some(4.5)]);
+        assert_eq!(gdd.cumulative[1], Some(15.5));
+    }
+
+    #[test]
+    fn frost_nights() {
+        // Two frost nights, a single hour at -0.5 °C in between and a frost
+        // still going at the end of the data
+        let temperature = [
+            3.0, -1.0, -2.5, -1.5, 1.0, 4.0, 2.0, -0.5, 2.0, 1.0, -3.0, -4.0, 0.0, 5.0, 1.0, -1.0,
+        ];
+        let time: Vec<Timestamp> = (0..temperature.len())
+            .map(|hour| Timestamp::Iso8601(format!("2024-03-01T{hour:02}:00")))
+            .collect();
+        let series: Series = temperature.iter().copied().map(Some).collect();
+
+        let events = threshold_events(&series, &time, Comparison::Below, 0.0, 2);
+        assert_eq!(
+            events,
+            [
+                Event {
+                    start: time[1].clone(),
+                    end: time[3].clone(),
+                    extreme_value: -2.5,
+                    open_ended: false,
+                },
+                Event {
+                    start: time[10].clone(),
+                    end: time[11].clone(),
+                    extreme_value: -4.0,
+                    open_ended: false,
+                },
+            ]
+        );
+        let all = threshold_events(&series, &time, Comparison::Below, 0.0, 1);
+        assert_eq!(all.len(), 4);
+        assert_eq!(
+            (all[1].start == all[1].end, all[1].extreme_value),
+            (true, -0.5)
+        );
+        assert!(all[3].open_ended);
+
+        let mut gap = series.clone();
+        gap.0[2] = None;
+        assert_eq!(
+            threshold_events(&gap, &time, Comparison::Below, 0.0, 2).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn frost_and_heat_from_response() {
+        let hourly = ResponseBuilder::new()
+            .start("2024-07-01")
+            .hourly(Hourly::temperature_2m, [90.0, 96.0, 98.5, 94.0])
+            .hourly_unit(Hourly::temperature_2m, "°F")
+            .build();
+        let heat = heat_events(&hourly, 35.0);
+        assert_eq!(heat.len(), 1);
+        assert_eq!(heat[0].start, Timestamp::Iso8601("2024-07-01T01:00".into()));
+        assert_eq!((heat[0].extreme_value, heat[0].open_ended), (98.5, false));
+        assert!(frost_events(&hourly).is_empty());
+
+        let daily = ResponseBuilder::new()
+            .start("2024-04-01")
+            .daily(Daily::temperature_2m_min, [-1.0, 2.0, -0.5])
+            .daily_unit(Daily::temperature_2m_min, "°C")
+            .build();
+        let frost = frost_events(&daily);
+        assert_eq!(frost.len(), 2);
+        assert_eq!(frost[1].end, Timestamp::Iso8601("2024-04-03".into()));
+        assert!(frost[1].open_ended);
+        assert!(heat_events(&daily, 35.0).is_empty());
+    }
+
+    #[test]
+    fn hargreaves() {
+        // FAO-56 example 8: 20° S on September 3
+        assert!((extraterrestrial_radiation(-20.0, 246) - 32.2).abs() < 0.05);
+        // 0.0023 × (22.5 + 17.8) × √15 × 0.408 × 32.2
+        assert!((hargreaves_et0(15.0, 30.0, -20.0, 246) - 4.716).abs() < 0.01);
+        // No sun in the polar night
+        assert_eq!(hargreaves_et0(-30.0, -20.0, 80.0, 355), 0.0);
+    }
+
+    /// A week from July 15 2024 at 45° N, with extremes in °C
+    #[cfg(feature = "chrono")]
+    fn water_balance(
+        unit: &str,
+        et0: [Option<f64>; 7],
+        precipitation: [Option<f64>; 7],
+        extremes: [[Option<f64>; 7]; 2],
+    ) -> ForecastResponse {
+        let [t_min, t_max] = extremes;
+        ResponseBuilder::new()
+            .coord(45.0, 7.0)
+            .start("2024-07-15")
+            .daily(Daily::et0_fao_evapotranspiration, et0)
+            .daily_unit(Daily::et0_fao_evapotranspiration, unit)
+            .daily(Daily::precipitation_sum, precipitation)
+            .daily_unit(Daily::precipitation_sum, unit)
+            .daily(Daily::temperature_2m_min, t_min)
+            .daily_unit(Daily::temperature_2m_min, "°C")
+            .daily(Daily::temperature_2m_max, t_max)
+            .daily_unit(Daily::temperature_2m_max, "°C")
+            .build()
+    }
+
+    #[cfg(feature = "chrono")]
+    fn week(values: [f64; 7]) -> [Option<f64>; 7] {
+        values.map(Some)
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn irrigation_dry_and_rainy_weeks() {
+        let none = [[None; 7]; 2];
+        let dry = water_balance(
+            "mm",
+            week([4.0, 5.0, 5.0, 6.0, 6.0, 5.0, 4.0]),
+            week([0.0; 7]),
+            none,
+        );
+        let needs = irrigation_need(&dry, 1.2);
+        assert_eq!(needs[0].0, NaiveDate::from_ymd_opt(2024, 7, 15).unwrap());
+        let needs: Vec<f64> = needs.into_iter().map(|(_, need)| need).collect();
+        for (need, expected) in needs.iter().zip([4.8, 6.0, 6.0, 7.2, 7.2, 6.0, 4.8]) {
+            assert!((need - expected).abs() < 1e-9, "{need} against {expected}");
+        }
+
+        // Kc 1, 80 % of the rain effective: 3 - 8, 3 - 0, 2 - 4, 3 - 16,
+        // 4 - 0.8, 3 - 0, 3 - 1.6
+        let et0 = [3.0, 3.0, 2.0, 3.0, 4.0, 3.0, 3.0];
+        let rain = [10.0, 0.0, 5.0, 20.0, 1.0, 0.0, 2.0];
+        let rainy = water_balance("mm", week(et0), week(rain), none);
+        let expected = [0.0, 3.0, 0.0, 0.0, 3.2, 3.0, 1.4];
+        let needs = irrigation_need(&rainy, 1.0);
+        for ((_, need), expected) in needs.iter().zip(expected) {
+            assert!((need - expected).abs() < 1e-9, "{need} against {expected}");
+        }
+        let all_rain = irrigation_need_with(&rainy, 1.0, 1.0);
+        assert_eq!(all_rain[4].1, 3.0);
+
+        // The same week in inches
+        let inch = |values: [f64; 7]| week(values.map(|value| value / 25.4));
+        let rainy_inch = water_balance("inch", inch(et0), inch(rain), none);
+        let needs = irrigation_need(&rainy_inch, 1.0);
+        assert_eq!(needs.len(), 7);
+        for ((_, need), expected) in needs.iter().zip(expected) {
+            assert!((need - expected / 25.4).abs() < 1e-9);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn irrigation_hargreaves_fallback() {
+        let mut t_min = week([15.0; 7]);
+        t_min[1] = None;
+        let mut et0 = week([5.0; 7]);
+        et0[..2].fill(None);
+        let mut precipitation = week([0.0; 7]);
+        precipitation[6] = None;
+        let response = water_balance("mm", et0, precipitation, [t_min, week([30.0; 7])]);
+        let needs = irrigation_need(&response, 1.0);
+        // July 15 2024 is day 197; the 16th has neither ET0 nor both
+        // extremes and the 21st no precipitation
+        assert_eq!(needs.len(), 5);
+        assert!((needs[0].1 - hargreaves_et0(15.0, 30.0, 45.0, 197)).abs() < 1e-9);
+        assert_eq!(needs[1].0, NaiveDate::from_ymd_opt(2024, 7, 17).unwrap());
+        assert_eq!(needs[1].1, 5.0);
+    }
+
+    #[test]
+    fn wind_chill_table() {
+        // Environment Canada wind chill index table, °C by km/h
+        for (t, wind, expected) in [
+            (5.0, 5.0, 4.0),
+            (0.0, 10.0, -3.0),
+            (-10.0, 20.0, -18.0),
+            (-20.0, 30.0, -33.0),
+            (-30.0, 50.0, -49.0),
+            (-45.0, 80.0, -74.0),
+        ] {
+            let value = wind_chill(t, wind).unwrap();
+            assert!(
+                (value - expected).abs() < 0.5,
+                "{t} °C, {wind} km/h: {value}"
+            );
+        }
+        assert_eq!(wind_chill(10.5, 20.0), None);
+        assert_eq!(wind_chill(-5.0, 4.0), None);
+        assert!(wind_chill(10.0, 4.8).is_some());
+    }
+
+    #[test]
+    fn wind_chill_units() {
+        let response = ResponseBuilder::new()
+            .hourly(Hourly::temperature_2m, [14.0, 14.0, 59.0])
+            .hourly_unit(Hourly::temperature_2m, "°F")
+            .hourly(Hourly::windspeed_10m, [5.0, 1.0, 5.0])
+            .hourly_unit(Hourly::windspeed_10m, "m/s")
+            .build();
+        let chill = response.wind_chill().unwrap();
+        assert!((chill[0].unwrap() - wind_chill(-10.0, 18.0).unwrap()).abs() < 1e-9);
+        assert_eq!(chill[1], None);
+        assert_eq!(chill[2], None);
+    }
+
+    #[test]
+    fn series_in_fahrenheit() {
+        let humid = |unit: &str, temperature: f64, dewpoint: [f64; 2]| {
+            ResponseBuilder::new()
+                .hourly(Hourly::temperature_2m, [Some(temperature), None])
+                .hourly_unit(Hourly::temperature_2m, unit)
+                .hourly(Hourly::relative_humidity_2m, [70.0, 65.0])
+                .hourly_unit(Hourly::relative_humidity_2m, "%")
+                .hourly(Hourly::dewpoint_2m, dewpoint)
+                .hourly_unit(Hourly::dewpoint_2m, unit)
+                .build()
+        };
+        let celsius = humid("°C", 32.0, [25.8, 25.0]);
+        let fahrenheit = humid("°F", 89.6, [78.44, 77.0]);
+
+        for response in [&celsius, &fahrenheit] {
+            let index = response.heat_index().unwrap();
+            assert!((index[0].unwrap() - heat_index(32.0, 70.0)).abs() < 1e-6);
+            assert_eq!(index[1], None);
+            let humidex = response.humidex().unwrap();
+            assert!((humidex[0].unwrap() - super::humidex(32.0, 25.8)).abs() < 1e-6);
+        }
+        let without_humidity =
+            ForecastResponse::from_json(include_str!("../fixtures/forecast.json"));
+        assert!(without_humidity.unwrap().heat_index().is_none());
+    }
+}
@@ -0,0 +1,166 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{fnv1a, ForecastResponse, ResponseCache};
+
+#[derive(Serialize, Deserialize)]
+/// Content of a cache file
+struct Record {
+    key: String,
+    fetched_at: u64,
+    ttl_secs: u64,
+    response: ForecastResponse,
+}
+
+impl Record {
+    fn is_expired(&self, now: SystemTime) -> bool {
+        let age = now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            .saturating_sub(self.fetched_at);
+        age >= self.ttl_secs
+    }
+}
+
+/// Response cache persisted as one JSON file per request under a directory,
+/// surviving between runs and shareable between processes
+pub struct DiskCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl DiskCache {
+    /// Store responses under `dir`, creating it if needed, for `ttl` each
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(DiskCache { dir, ttl })
+    }
+
+    /// Remove expired and unreadable entries, returning how many were removed
+    pub fn purge_expired(&self) -> io::Result<usize> {
+        let now = SystemTime::now();
+        let mut removed = 0;
+        for file in fs::read_dir(&self.dir)? {
+            let path = file?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let expired = read_record(&path).is_none_or(|record| record.is_expired(now));
+            if expired && fs::remove_file(&path).is_ok() {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(key)))
+    }
+
+    fn write(&self, key: &str, response: &ForecastResponse) -> io::Result<()> {
+        static WRITES: AtomicU64 = AtomicU64::new(0);
+
+        let record = Record {
+            key: key.to_string(),
+            fetched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            ttl_secs: self.ttl.as_secs(),
+            response: response.clone(),
+        };
+        let path = self.path(key);
+        // Readers only ever see complete files
+        let temp = path.with_extension(format!(
+            "{}.{}.tmp",
+            process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp, serde_json::to_vec(&record)?)?;
+        fs::rename(&temp, &path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp);
+        })
+    }
+}
+
+fn read_record(path: &Path) -> Option<Record> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+impl ResponseCache for DiskCache {
+    fn get(&self, key: &str) -> Option<ForecastResponse> {
+        let record = read_record(&self.path(key))?;
+        (record.key == key && !record.is_expired(SystemTime::now())).then_some(record.response)
+    }
+
+    fn put(&self, key: &str, response: &ForecastResponse) {
+        // A cache that cannot be written behaves as a cache miss
+        let _ = self.write(key, response);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../fixtures/forecast.json");
+
+    fn disk_cache(name: &str, ttl: Duration) -> DiskCache {
+        let dir = std::env::temp_dir().join(format!("weathered-{name}-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        DiskCache::new(dir, ttl).unwrap()
+    }
+
+    #[test]
+    fn disk_cache_round_trip() {
+        let cache = disk_cache("round-trip", Duration::from_secs(600));
+        let response: ForecastResponse = serde_json::from_str(FIXTURE).unwrap();
+
+        assert!(cache.get("a").is_none());
+        cache.put("a", &response);
+
+        // A new instance reads what a previous run stored
+        let reopened = DiskCache::new(&cache.dir, Duration::from_secs(600)).unwrap();
+        assert_eq!(reopened.get("a"), Some(response));
+        assert!(reopened.get("b").is_none());
+        assert_eq!(reopened.purge_expired().unwrap(), 0);
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn disk_cache_expiry() {
+        let cache = disk_cache("expiry", Duration::ZERO);
+        let response: ForecastResponse = serde_json::from_str(FIXTURE).unwrap();
+
+        cache.put("a", &response);
+        assert!(cache.path("a").exists());
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.purge_expired().unwrap(), 1);
+        assert!(!cache.path("a").exists());
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn truncated_file_is_a_miss() {
+        let cache = disk_cache("truncated", Duration::from_secs(600));
+        let response: ForecastResponse = serde_json::from_str(FIXTURE).unwrap();
+
+        cache.put("a", &response);
+        let path = cache.path("a");
+        let content = fs::read(&path).unwrap();
+        fs::write(&path, &content[..content.len() / 2]).unwrap();
+
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.purge_expired().unwrap(), 1);
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+}
@@ -0,0 +1,138 @@
+use serde::Deserialize;
+
+use crate::{Coordinates, Forecast, ELEVATION_URL};
+
+/// Most coordinates the API accepts in a single call
+const MAX_COORDINATES: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Terrain height of a list of points, see https://open-meteo.com/en/docs/elevation-api
+pub struct ElevationRequest {
+    coordinates: Vec<(f32, f32)>,
+}
+
+impl ElevationRequest {
+    /// Request the elevation of each (latitude, longitude) pair, in order
+    pub fn new(coordinates: &[(f32, f32)]) -> Self {
+        ElevationRequest {
+            coordinates: coordinates.to_vec(),
+        }
+    }
+
+    /// URLs of the calls answering the request, one per 100 coordinates
+    pub fn urls(&self) -> Vec<String> {
+        self.to_urls(ELEVATION_URL)
+    }
+
+    /// Same as `urls` against the given base
+    pub(crate) fn to_urls(&self, base: &str) -> Vec<String> {
+        let join = |chunk: &[(f32, f32)], coordinate: fn(&(f32, f32)) -> f32| {
+            chunk
+                .iter()
+                .map(|point| coordinate(point).to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        self.coordinates
+            .chunks(MAX_COORDINATES)
+            .map(|chunk| {
+                format!(
+                    "{base}?latitude={}&longitude={}",
+                    join(chunk, |point| point.0),
+                    join(chunk, |point| point.1)
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+/// Body of an elevation call
+pub struct ElevationResponse {
+    /// Metres above sea level, in the order of the coordinates
+    pub elevation: Vec<f64>,
+}
+
+impl ElevationResponse {
+    /// Parse the JSON body of a call
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Check the status of a raw response fetched from `url` and decode its body
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    pub(crate) fn decode(
+        url: &str,
+        response: &crate::HttpResponse,
+    ) -> Result<Vec<f64>, crate::WeatheredError> {
+        crate::response::check_status(url, response)?;
+        serde_json::from_slice::<Self>(&response.body)
+            .map(|response| response.elevation)
+            .map_err(|source| crate::WeatheredError::Decode {
+                url: url.to_string(),
+                source,
+            })
+    }
+}
+
+impl Forecast<Coordinates> {
+    /// Elevation request for the coordinates of the forecast
+    #[cfg(feature = "client")]
+    pub(crate) fn elevation_request(&self) -> ElevationRequest {
+        ElevationRequest::new(&[(self.coordinates.latitude, self.coordinates.longitude)])
+    }
+
+    /// Same request with `Settings::elevation` set to `elevation`, replacing
+    /// any elevation set before
+    #[cfg(any(test, feature = "client"))]
+    pub(crate) fn with_elevation(mut self, elevation: f32) -> Self {
+        use crate::Settings;
+
+        self.settings
+            .retain(|setting| !matches!(setting, Settings::elevation(_)));
+        self.settings(Settings::elevation(elevation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Settings;
+
+    #[test]
+    fn several_points() {
+        let request = ElevationRequest::new(&[(52.52, 13.41), (46.55, 7.98)]);
+        assert_eq!(
+            request.urls(),
+            ["https://api.open-meteo.com/v1/elevation?latitude=52.52,46.55&longitude=13.41,7.98"]
+        );
+    }
+
+    #[test]
+    fn chunks_of_100() {
+        let points: Vec<_> = (0..101).map(|index| (index as f32, 0.5)).collect();
+        let urls = ElevationRequest::new(&points).to_urls("");
+        assert_eq!(urls.len(), 2);
+        assert!(urls[0].starts_with("?latitude=0,1,2,"));
+        assert!(urls[0].contains(",99&longitude="));
+        assert_eq!(urls[1], "?latitude=100&longitude=0.5");
+        assert!(ElevationRequest::new(&[]).urls().is_empty());
+    }
+
+    #[test]
+    fn fixture() {
+        let response =
+            ElevationResponse::from_json(include_str!("../fixtures/elevation.json")).unwrap();
+        assert_eq!(response.elevation, [38.0, 3571.0, -28.0]);
+
+        let forecast = Forecast::new()
+            .coord(52.52, 13.41)
+            .settings(Settings::elevation(10.0))
+            .with_elevation(38.0);
+        assert_eq!(
+            forecast.to_string(),
+            "https://api.open-meteo.com/v1/forecast?latitude=52.52&longitude=13.41&elevation=38"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{EnsembleSeries, Series};
+
+impl EnsembleSeries<'_> {
+    /// Series of the control run, if any, followed by the members
+    fn runs(&self) -> impl Iterator<Item = &Series> {
+        self.control
+            .into_iter()
+            .chain(self.members.iter().map(|(_, series)| *series))
+    }
+
+    /// Apply `statistic` to the values of all runs at each timestamp, skipping
+    /// runs without data there; `None` where no run has any
+    fn per_time(&self, statistic: impl Fn(&mut [f64]) -> f64) -> Series {
+        (0..self.time.len())
+            .map(|index| {
+                let mut values: Vec<f64> = self
+                    .runs()
+                    .filter_map(|series| series.get(index).copied().flatten())
+                    .collect();
+                (!values.is_empty()).then(|| statistic(&mut values))
+            })
+            .collect()
+    }
+
+    /// Mean across the control run and the members at each timestamp
+    pub fn mean(&self) -> Series {
+        self.per_time(|values| mean(values))
+    }
+
+    /// Population standard deviation across runs at each timestamp
+    pub fn std_dev(&self) -> Series {
+        self.per_time(|values| {
+            let mean = mean(values);
+            let variance = values
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / values.len() as f64;
+            variance.sqrt()
+        })
+    }
+
+    pub fn min(&self) -> Series {
+        self.per_time(|values| values.iter().copied().fold(f64::INFINITY, f64::min))
+    }
+
+    pub fn max(&self) -> Series {
+        self.per_time(|values| values.iter().copied().fold(f64::NEG_INFINITY, f64::max))
+    }
+
+    /// `p`-th percentile across runs at each timestamp, interpolating linearly
+    /// between the closest ranks; `p` is in percent and clamped to 0..=100
+    pub fn percentile(&self, p: f64) -> Series {
+        let p = p.clamp(0.0, 100.0) / 100.0;
+        self.per_time(|values| {
+            values.sort_unstable_by(f64::total_cmp);
+            let rank = p * (values.len() - 1) as f64;
+            let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+            values[low] + (values[high] - values[low]) * (rank - low as f64)
+        })
+    }
+
+    /// Lower and upper bounds of the `p_low`..`p_high` percentile band
+    pub fn band(&self, p_low: f64, p_high: f64) -> (Series, Series) {
+        (self.percentile(p_low), self.percentile(p_high))
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Timestamp;
+
+    fn assert_close(series: Series, expected: &[Option<f64>]) {
+        assert_eq!(series.len(), expected.len());
+        for (value, expected) in series.iter().zip(expected) {
+            match (value, expected) {
+                (Some(value), Some(expected)) => {
+                    assert!((value - expected).abs() < 1e-9, "{value} != {expected}")
+                }
+                _ => assert_eq!(value, expected),
+            }
+        }
+    }
+
+    #[test]
+    fn statistics_across_runs() {
+        let time: Vec<_> = (0..3).map(|hour| Timestamp::Unix(hour * 3600)).collect();
+        let control = Series::from(vec![Some(1.0), Some(2.0), None]);
+        let members = [
+            Series::from(vec![Some(2.0), None, None]),
+            Series::from(vec![Some(3.0), Some(6.0), None]),
+            Series::from(vec![Some(6.0), Some(4.0), None]),
+        ];
+        let ensemble = EnsembleSeries {
+            time: &time,
+            control: Some(&control),
+            members: members
+                .iter()
+                .enumerate()
+                .map(|(index, series)| (index as u32 + 1, series))
+                .collect(),
+        };
+
+        // At 1h member 1 has no data, leaving 2, 6 and 4
+        assert_close(ensemble.mean(), &[Some(3.0), Some(4.0), None]);
+        assert_close(
+            ensemble.std_dev(),
+            &[Some(3.5f64.sqrt()), Some((8.0f64 / 3.0).sqrt()), None],
+        );
+        assert_close(ensemble.min(), &[Some(1.0), Some(2.0), None]);
+        assert_close(ensemble.max(), &[Some(6.0), Some(6.0), None]);
+        assert_close(ensemble.percentile(50.0), &[Some(2.5), Some(4.0), None]);
+        assert_close(ensemble.percentile(0.0), &[Some(1.0), Some(2.0), None]);
+        assert_close(ensemble.percentile(150.0), &[Some(6.0), Some(6.0), None]);
+
+        let (low, high) = ensemble.band(10.0, 90.0);
+        assert_close(low, &[Some(1.3), Some(2.4), None]);
+        assert_close(high, &[Some(5.1), Some(5.6), None]);
+    }
+}
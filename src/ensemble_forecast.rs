@@ -0,0 +1,180 @@
+use std::fmt;
+
+use strum_macros::Display;
+
+use crate::{Coordinates, Endpoint, Forecast, Hourly, NoCoordinates, Settings};
+
+pub(crate) const ENSEMBLE_URL: &str = "https://ensemble-api.open-meteo.com/v1/ensemble";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display)]
+#[allow(non_camel_case_types)]
+/// Enumerate the ensemble models, see https://open-meteo.com/en/docs/ensemble-api
+pub enum EnsembleModel {
+    icon_seamless,
+    icon_global,
+    icon_eu,
+    icon_d2,
+    gfs_seamless,
+    gfs025,
+    gfs05,
+    ecmwf_ifs04,
+    ecmwf_ifs025,
+    gem_global,
+    bom_access_global_ensemble,
+}
+
+impl Hourly {
+    /// Whether the ensemble models output the variable
+    pub fn in_ensemble(&self) -> bool {
+        use Hourly::*;
+
+        !matches!(
+            self,
+            cloudcover_low
+                | cloudcover_mid
+                | cloudcover_high
+                | windspeed_180m
+                | windspeedtion_180m
+                | direct_normal_irradiance
+                | evapotranspiration
+                | precipitation_probability
+                | showers
+                | soil_temperature_6cm
+                | soil_temperature_18cm
+                | soil_temperature_54cm
+                | soil_moisture_0_1cm
+                | soil_moisture_1_3cm
+                | soil_moisture_4_9cm
+                | soil_moisture_9_27cm
+                | soil_moisture_27_81cm
+                | is_day
+        )
+    }
+}
+
+#[derive(Clone)]
+/// Request of every member of ensemble models, sharing the coordinates,
+/// settings and hourly variables of a `Forecast`
+///
+/// Members come back as `_memberNN` columns, see `ForecastResponse::ensemble`
+pub struct EnsembleForecast<C = Coordinates> {
+    forecast: Forecast<C>,
+    models: Vec<EnsembleModel>,
+}
+
+impl EnsembleForecast<NoCoordinates> {
+    /// Initialize EnsembleForecast object
+    pub fn new() -> Self {
+        EnsembleForecast {
+            forecast: Forecast::new().endpoint(Endpoint::Ensemble),
+            models: Vec::new(),
+        }
+    }
+}
+
+impl Default for EnsembleForecast<NoCoordinates> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> EnsembleForecast<C> {
+    /// Specify coordinates (latitude, longitude)
+    pub fn coord(self, latitude: f32, longitude: f32) -> EnsembleForecast<Coordinates> {
+        EnsembleForecast {
+            forecast: self.forecast.coord(latitude, longitude),
+            models: self.models,
+        }
+    }
+
+    /// Add optional settings
+    pub fn settings(mut self, setting: Settings) -> Self {
+        self.forecast = self.forecast.settings(setting);
+        self
+    }
+
+    /// Get hourly value for a specific data, see `Hourly::in_ensemble`
+    pub fn hourly(mut self, hourly: Hourly) -> Self {
+        self.forecast = self.forecast.hourly(hourly);
+        self
+    }
+
+    /// Models to run; with several, columns are suffixed with the model name
+    pub fn models(mut self, models: &[EnsembleModel]) -> Self {
+        self.models.extend_from_slice(models);
+        self
+    }
+}
+
+impl EnsembleForecast<Coordinates> {
+    /// Convert the request into a valid URL against the given base
+    pub(crate) fn to_url(&self, base: &str) -> String {
+        let mut url = self.forecast.to_url(base);
+        if !self.models.is_empty() {
+            let models: Vec<_> = self.models.iter().map(EnsembleModel::to_string).collect();
+            url.push_str(&format!("&models={}", models.join(",")));
+        }
+        url
+    }
+}
+
+impl fmt::Display for EnsembleForecast<Coordinates> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_url(ENSEMBLE_URL))
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl crate::EndpointRequest for EnsembleForecast<Coordinates> {
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::Ensemble
+    }
+
+    fn to_url(&self, base: &str) -> String {
+        EnsembleForecast::to_url(self, base)
+    }
+}
+
+#[cfg(feature = "response")]
+impl crate::ForecastResponse {
+    /// Control run and members of an hourly variable, `None` if the response
+    /// does not contain it
+    pub fn ensemble(&self, variable: Hourly) -> Option<crate::EnsembleSeries<'_>> {
+        self.hourly.as_ref()?.ensemble(variable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn url_creation() {
+        let request = EnsembleForecast::new()
+            .coord(52.52, 13.41)
+            .hourly(Hourly::temperature_2m)
+            .hourly(Hourly::rain)
+            .models(&[EnsembleModel::gfs025]);
+        assert_eq!(
+            request.to_string(),
+            "https://ensemble-api.open-meteo.com/v1/ensemble?latitude=52.52&longitude=13.41&hourly=,temperature_2m,rain&models=gfs025"
+        );
+        assert!(Hourly::windgusts_10m.in_ensemble());
+        assert!(!Hourly::precipitation_probability.in_ensemble());
+    }
+
+    #[cfg(feature = "response")]
+    #[test]
+    fn fixture() {
+        let response =
+            crate::ForecastResponse::from_json(include_str!("../fixtures/ensemble.json")).unwrap();
+        let temperature = response.ensemble(Hourly::temperature_2m).unwrap();
+        assert!(temperature.member_count() > 1);
+        assert_eq!(
+            temperature.max().values(),
+            [Some(5.4), Some(5.3), Some(4.9)]
+        );
+        assert!(response.ensemble(Hourly::snowfall).is_none());
+    }
+}
@@ -0,0 +1,256 @@
+use std::time::Duration;
+use std::{error, fmt};
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+use crate::TransportError;
+
+#[derive(Debug)]
+/// Enumerate failures while fetching a forecast
+pub enum WeatheredError {
+    /// The request could not be sent or its body could not be read
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    Network { url: String, source: TransportError },
+    /// The request did not complete within the configured timeout
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    Timeout { elapsed: Duration, url: String },
+    /// The configured proxy could not be reached
+    #[cfg(feature = "client")]
+    Proxy {
+        url: String,
+        proxy: String,
+        source: TransportError,
+    },
+    /// A response hook rejected the response
+    #[cfg(feature = "client")]
+    Rejected {
+        url: String,
+        source: Box<dyn error::Error + Send + Sync>,
+    },
+    /// The API answered 429 Too Many Requests
+    RateLimited {
+        url: String,
+        retry_after: Option<Duration>,
+    },
+    /// The client-side rate limiter has no permit available
+    Throttled { retry_in: Duration },
+    /// Responses to the chunks of a long request cannot be merged
+    #[cfg(feature = "client")]
+    ChunkMismatch { url: String, reason: String },
+    /// The request for a window of a long request failed, the windows before
+    /// it having been fetched
+    #[cfg(feature = "client")]
+    ChunkFailed {
+        /// Index of the failing window, from 0
+        window: usize,
+        /// Responses to the windows before it, in order
+        fetched: Vec<crate::ForecastResponse>,
+        source: Box<WeatheredError>,
+    },
+    /// The stop signal fired before all requests completed
+    #[cfg(feature = "client")]
+    Cancelled {
+        completed: Vec<crate::ForecastResponse>,
+    },
+    /// A request of a batch failed, e.g. rate limited, after the ones before
+    /// it completed
+    #[cfg(feature = "client")]
+    Partial {
+        completed: Vec<crate::ForecastResponse>,
+        source: Box<WeatheredError>,
+    },
+    /// No populated place matches the name given to `Client::for_city`
+    #[cfg(feature = "client")]
+    UnknownPlace { name: String },
+    /// Several large cities match the name given to `Client::for_city`
+    #[cfg(feature = "client")]
+    AmbiguousPlace {
+        name: String,
+        candidates: Vec<crate::Place>,
+    },
+    /// The archive cannot serve its part of `Client::fetch_seamless`
+    #[cfg(feature = "client")]
+    Archive(crate::ArchiveError),
+    /// The API rejected the request, explaining why
+    Api {
+        url: String,
+        status: u16,
+        reason: String,
+    },
+    /// The API answered with a non-success status code
+    Status { url: String, status: u16 },
+    /// The endpoint of the request needs a start and end date
+    MissingDates { url: String },
+    /// A variable is not reported in the unit a computation expects
+    UnitMismatch {
+        variable: String,
+        expected: String,
+        found: Option<String>,
+    },
+    /// The forecast contradicts itself or the request, e.g. it is truncated
+    Inconsistent {
+        url: String,
+        source: crate::ConsistencyError,
+    },
+    /// The body is not a valid forecast response
+    Decode {
+        url: String,
+        source: serde_json::Error,
+    },
+    /// The body is not a valid `format=flatbuffers` response
+    #[cfg(feature = "fb")]
+    FlatBuffers {
+        url: String,
+        source: crate::FlatBuffersError,
+    },
+}
+
+impl fmt::Display for WeatheredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(any(feature = "client", feature = "blocking"))]
+            WeatheredError::Network { url, source } => {
+                write!(f, "request to {url} failed: {source}")
+            }
+            #[cfg(any(feature = "client", feature = "blocking"))]
+            WeatheredError::Timeout { elapsed, url } => {
+                write!(f, "request to {url} timed out after {elapsed:?}")
+            }
+            #[cfg(feature = "client")]
+            WeatheredError::Proxy { url, proxy, source } => {
+                write!(
+                    f,
+                    "request to {url} failed to reach proxy {proxy}: {source}"
+                )
+            }
+            #[cfg(feature = "client")]
+            WeatheredError::Rejected { url, source } => {
+                write!(f, "response from {url} rejected: {source}")
+            }
+            WeatheredError::RateLimited { url, retry_after } => match retry_after {
+                Some(delay) => write!(f, "request to {url} rate limited, retry after {delay:?}"),
+                None => write!(f, "request to {url} rate limited"),
+            },
+            WeatheredError::Throttled { retry_in } => {
+                write!(f, "rate limit reached, next permit in {retry_in:?}")
+            }
+            #[cfg(feature = "client")]
+            WeatheredError::ChunkMismatch { url, reason } => {
+                write!(f, "cannot merge response from {url}: {reason}")
+            }
+            #[cfg(feature = "client")]
+            WeatheredError::ChunkFailed { window, source, .. } => {
+                write!(f, "window {window} of the request failed: {source}")
+            }
+            #[cfg(feature = "client")]
+            WeatheredError::Cancelled { completed } => {
+                write!(f, "cancelled after {} completed requests", completed.len())
+            }
+            #[cfg(feature = "client")]
+            WeatheredError::Partial { completed, source } => {
+                write!(f, "{source} after {} completed requests", completed.len())
+            }
+            #[cfg(feature = "client")]
+            WeatheredError::UnknownPlace { name } => write!(f, "no city matches {name:?}"),
+            #[cfg(feature = "client")]
+            WeatheredError::AmbiguousPlace { name, candidates } => {
+                write!(f, "{name:?} matches several cities:")?;
+                for (i, place) in candidates.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { ", " };
+                    write!(f, "{separator}{}", place.name)?;
+                    let area: Vec<_> = [&place.admin1, &place.country]
+                        .into_iter()
+                        .flatten()
+                        .map(String::as_str)
+                        .collect();
+                    if !area.is_empty() {
+                        write!(f, " ({})", area.join(", "))?;
+                    }
+                }
+                Ok(())
+            }
+            #[cfg(feature = "client")]
+            WeatheredError::Archive(source) => {
+                write!(f, "archive part of the request rejected: {source}")
+            }
+            WeatheredError::Api {
+                url,
+                status,
+                reason,
+            } => write!(
+                f,
+                "request to {url} rejected with status {status}: {reason}"
+            ),
+            WeatheredError::Status { url, status } => {
+                write!(f, "request to {url} returned status {status}")
+            }
+            WeatheredError::MissingDates { url } => {
+                write!(f, "request to {url} needs a start and end date")
+            }
+            WeatheredError::UnitMismatch {
+                variable,
+                expected,
+                found,
+            } => match found {
+                Some(found) => write!(f, "{variable} is in {found}, expected {expected}"),
+                None => write!(f, "no unit reported for {variable}, expected {expected}"),
+            },
+            WeatheredError::Inconsistent { url, source } => {
+                write!(f, "inconsistent response from {url}: {source}")
+            }
+            WeatheredError::Decode { url, source } => {
+                write!(f, "invalid response from {url}: {source}")
+            }
+            #[cfg(feature = "fb")]
+            WeatheredError::FlatBuffers { url, source } => {
+                write!(f, "invalid response from {url}: {source}")
+            }
+        }
+    }
+}
+
+impl error::Error for WeatheredError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            #[cfg(any(feature = "client", feature = "blocking"))]
+            WeatheredError::Network { source, .. } => Some(source),
+            #[cfg(any(feature = "client", feature = "blocking"))]
+            WeatheredError::Timeout { .. } => None,
+            #[cfg(feature = "client")]
+            WeatheredError::Proxy { source, .. } => Some(source),
+            #[cfg(feature = "client")]
+            WeatheredError::Rejected { source, .. } => Some(source.as_ref()),
+            #[cfg(feature = "client")]
+            WeatheredError::ChunkMismatch { .. }
+            | WeatheredError::Cancelled { .. }
+            | WeatheredError::UnknownPlace { .. }
+            | WeatheredError::AmbiguousPlace { .. } => None,
+            #[cfg(feature = "client")]
+            WeatheredError::ChunkFailed { source, .. } | WeatheredError::Partial { source, .. } => {
+                Some(source.as_ref())
+            }
+            #[cfg(feature = "client")]
+            WeatheredError::Archive(source) => Some(source),
+            WeatheredError::RateLimited { .. }
+            | WeatheredError::Throttled { .. }
+            | WeatheredError::Api { .. }
+            | WeatheredError::Status { .. }
+            | WeatheredError::MissingDates { .. }
+            | WeatheredError::UnitMismatch { .. } => None,
+            WeatheredError::Inconsistent { source, .. } => Some(source),
+            WeatheredError::Decode { source, .. } => Some(source),
+            #[cfg(feature = "fb")]
+            WeatheredError::FlatBuffers { source, .. } => Some(source),
+        }
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl WeatheredError {
+    /// Wrap a transport failure, singling out timeouts
+    pub(crate) fn transport(url: String, source: TransportError, elapsed: Duration) -> Self {
+        match source {
+            TransportError::Timeout(_) => WeatheredError::Timeout { elapsed, url },
+            source => WeatheredError::Network { url, source },
+        }
+    }
+}
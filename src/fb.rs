@@ -0,0 +1,492 @@
+use std::{error, fmt, fmt::Write};
+
+use flatbuffers::InvalidFlatbuffer;
+use serde_json::{Map, Value};
+
+use crate::{ForecastResponse, ForecastResponses};
+
+mod schema;
+
+use schema::{
+    VariableWithValues, VariablesWithTime, WeatherApiResponse, AGGREGATION_NAMES, UNIT_NAMES,
+    VARIABLE_NAMES,
+};
+
+#[derive(Debug)]
+/// Enumerate the ways decoding a `format=flatbuffers` response can fail
+pub enum FlatBuffersError {
+    /// The payload ends inside a message or its size prefix
+    Truncated { offset: usize },
+    /// A message does not follow the schema
+    Invalid(InvalidFlatbuffer),
+    /// The values do not make a valid response, e.g. a daily weather code above 255
+    Decode(serde_json::Error),
+}
+
+impl fmt::Display for FlatBuffersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlatBuffersError::Truncated { offset } => {
+                write!(f, "FlatBuffers payload truncated at byte {offset}")
+            }
+            FlatBuffersError::Invalid(error) => write!(f, "invalid FlatBuffers message: {error}"),
+            FlatBuffersError::Decode(error) => write!(f, "invalid FlatBuffers response: {error}"),
+        }
+    }
+}
+
+impl error::Error for FlatBuffersError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            FlatBuffersError::Truncated { .. } => None,
+            FlatBuffersError::Invalid(error) => Some(error),
+            FlatBuffersError::Decode(error) => Some(error),
+        }
+    }
+}
+
+impl ForecastResponse {
+    /// Parse the output of a `format=flatbuffers` request, keeping the first
+    /// location of a multi-location payload
+    ///
+    /// Variables are named as in JSON responses, e.g. `temperature_2m_max`, and
+    /// timestamps are always `Timestamp::Unix`
+    pub fn from_flatbuffers(bytes: &[u8]) -> Result<Self, FlatBuffersError> {
+        let (root, _) = message(bytes, 0)?;
+        response(root)
+    }
+}
+
+impl ForecastResponses {
+    /// Parse the output of a `format=flatbuffers` request, one message per
+    /// location, in the order the locations were given
+    pub fn from_flatbuffers(bytes: &[u8]) -> Result<Vec<ForecastResponse>, FlatBuffersError> {
+        let mut responses = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() || responses.is_empty() {
+            let (root, next) = message(bytes, offset)?;
+            responses.push(response(root)?);
+            offset = next;
+        }
+        responses.sort_by_key(|response| response.location_id);
+        Ok(responses)
+    }
+}
+
+/// Size-prefixed message starting at `offset`, and the offset of the next one
+fn message(
+    bytes: &[u8],
+    offset: usize,
+) -> Result<(WeatherApiResponse<'_>, usize), FlatBuffersError> {
+    let rest = &bytes[offset..];
+    let size = rest
+        .get(..4)
+        .map(|prefix| u32::from_le_bytes(prefix.try_into().unwrap()) as usize)
+        .ok_or(FlatBuffersError::Truncated { offset })?;
+    let message = rest
+        .get(..4 + size)
+        .ok_or(FlatBuffersError::Truncated { offset })?;
+    let root = flatbuffers::size_prefixed_root::<WeatherApiResponse>(message)
+        .map_err(FlatBuffersError::Invalid)?;
+    Ok((root, offset + 4 + size))
+}
+
+fn response(root: WeatherApiResponse<'_>) -> Result<ForecastResponse, FlatBuffersError> {
+    let mut response = Map::new();
+    response.insert("location_id".to_string(), root.location_id().into());
+    response.insert("latitude".to_string(), widen(root.latitude()));
+    response.insert("longitude".to_string(), widen(root.longitude()));
+    response.insert("elevation".to_string(), widen(root.elevation()));
+    response.insert(
+        "generationtime_ms".to_string(),
+        widen(root.generation_time_milliseconds()),
+    );
+    response.insert(
+        "utc_offset_seconds".to_string(),
+        root.utc_offset_seconds().into(),
+    );
+    response.insert(
+        "timezone".to_string(),
+        root.timezone().unwrap_or_default().into(),
+    );
+    response.insert(
+        "timezone_abbreviation".to_string(),
+        root.timezone_abbreviation().unwrap_or_default().into(),
+    );
+    if let Some(current) = root.current() {
+        let (units, values) = current_block(current);
+        response.insert("current_units".to_string(), units.into());
+        response.insert("current".to_string(), values.into());
+    }
+    let blocks = [
+        ("minutely_15", root.minutely_15()),
+        ("hourly", root.hourly()),
+        ("daily", root.daily()),
+    ];
+    for (block, variables) in blocks {
+        if let Some(variables) = variables {
+            let (units, values) = series_block(variables);
+            response.insert(format!("{block}_units"), units.into());
+            response.insert(block.to_string(), values.into());
+        }
+    }
+    serde_json::from_value(response.into()).map_err(FlatBuffersError::Decode)
+}
+
+/// Units and values of the current block, one value per variable
+fn current_block(block: VariablesWithTime<'_>) -> (Map<String, Value>, Map<String, Value>) {
+    let mut units = Map::new();
+    let mut values = Map::new();
+    units.insert("time".to_string(), "unixtime".into());
+    units.insert("interval".to_string(), "seconds".into());
+    values.insert("time".to_string(), block.time().into());
+    values.insert("interval".to_string(), block.interval().into());
+    for variable in block.variables().into_iter().flatten() {
+        let name = name(&variable);
+        units.insert(name.clone(), unit(variable.unit()).into());
+        values.insert(name, number(variable.value(), variable.unit()));
+    }
+    (units, values)
+}
+
+/// Units and values of a block with one value per timestamp
+fn series_block(block: VariablesWithTime<'_>) -> (Map<String, Value>, Map<String, Value>) {
+    let mut units = Map::new();
+    let mut values = Map::new();
+    units.insert("time".to_string(), "unixtime".into());
+    let step = usize::try_from(block.interval()).unwrap_or(0).max(1);
+    let time: Vec<Value> = (block.time()..block.time_end())
+        .step_by(step)
+        .map(Value::from)
+        .collect();
+    values.insert("time".to_string(), time.into());
+    for variable in block.variables().into_iter().flatten() {
+        let name = name(&variable);
+        let column: Vec<Value> = match (variable.values(), variable.values_int64()) {
+            (Some(floats), _) => floats
+                .iter()
+                .map(|value| number(value, variable.unit()))
+                .collect(),
+            (None, Some(integers)) => integers.iter().map(Value::from).collect(),
+            (None, None) => Vec::new(),
+        };
+        units.insert(name.clone(), unit(variable.unit()).into());
+        values.insert(name, column.into());
+    }
+    (units, values)
+}
+
+/// Name of the variable as in a JSON response, e.g. `temperature_850hPa`,
+/// `soil_moisture_0_to_1cm` or `wind_direction_10m_dominant`
+fn name(variable: &VariableWithValues<'_>) -> String {
+    let mut name = match VARIABLE_NAMES.get(usize::from(variable.variable())) {
+        Some(name) => name.to_string(),
+        None => format!("variable_{}", variable.variable()),
+    };
+    // `write!` to a `String` cannot fail
+    if variable.altitude() > 0 {
+        let _ = write!(name, "_{}m", variable.altitude());
+    }
+    if variable.pressure_level() > 0 {
+        let _ = write!(name, "_{}hPa", variable.pressure_level());
+    }
+    // Soil variables always have a depth, `0` being the surface layer
+    if name.starts_with("soil_") {
+        if variable.depth_to() > variable.depth() {
+            let _ = write!(name, "_{}_to_{}cm", variable.depth(), variable.depth_to());
+        } else {
+            let _ = write!(name, "_{}cm", variable.depth());
+        }
+    }
+    let suffix = match AGGREGATION_NAMES.get(usize::from(variable.aggregation())) {
+        Some(&"none") | None => None,
+        Some(&"minimum") => Some("min"),
+        Some(&"maximum") => Some("max"),
+        Some(aggregation) => Some(*aggregation),
+    };
+    if let Some(suffix) = suffix {
+        let _ = write!(name, "_{suffix}");
+    }
+    if variable.ensemble_member() > 0 {
+        let _ = write!(name, "_member{:02}", variable.ensemble_member());
+    }
+    if variable.previous_day() > 0 {
+        let _ = write!(name, "_previous_day{}", variable.previous_day());
+    }
+    name
+}
+
+/// Unit as spelled in JSON responses
+fn unit(unit: u8) -> &'static str {
+    match UNIT_NAMES.get(usize::from(unit)).copied() {
+        Some("celsius") => "°C",
+        Some("centimetre") => "cm",
+        Some("cubic_metre_per_cubic_metre") => "m³/m³",
+        Some("cubic_metre_per_second") => "m³/s",
+        Some("degree_direction") => "°",
+        Some("european_air_quality_index") => "EAQI",
+        Some("fahrenheit") => "°F",
+        Some("feet") => "ft",
+        Some("gdd_celsius") => "GDD °C",
+        Some("geopotential_metre") => "m",
+        Some("grains_per_cubic_metre") => "grains/m³",
+        Some("gram_per_kilogram") => "g/kg",
+        Some("hectopascal") => "hPa",
+        Some("hours") => "h",
+        Some("inch") => "inch",
+        Some("iso8601") => "iso8601",
+        Some("joule_per_kilogram") => "J/kg",
+        Some("kelvin") => "K",
+        Some("kilopascal") => "kPa",
+        Some("kilogram_per_square_metre") => "kg/m²",
+        Some("kilometres_per_hour") => "km/h",
+        Some("knots") => "kn",
+        Some("megajoule_per_square_metre") => "MJ/m²",
+        Some("metre_per_second" | "metre_per_second_not_unit_converted") => "m/s",
+        Some("metre") => "m",
+        Some("micrograms_per_cubic_metre") => "μg/m³",
+        Some("miles_per_hour") => "mp/h",
+        Some("millimetre") => "mm",
+        Some("pascal") => "Pa",
+        Some("per_second") => "s⁻¹",
+        Some("percentage") => "%",
+        Some("seconds") => "s",
+        Some("unix_time") => "unixtime",
+        Some("us_air_quality_index") => "USAQI",
+        Some("watt_per_square_metre") => "W/m²",
+        Some("wmo_code") => "wmo code",
+        Some("parts_per_million") => "ppm",
+        _ => "",
+    }
+}
+
+/// JSON value of a sample: `null` for NaN, an integer for integer units
+fn number(value: f32, unit: u8) -> Value {
+    let integer = matches!(
+        UNIT_NAMES.get(usize::from(unit)).copied(),
+        Some("dimensionless_integer" | "wmo_code")
+    );
+    if integer && value.fract() == 0.0 {
+        return (value as i64).into();
+    }
+    widen(value)
+}
+
+/// `f64` with the shortest decimal representation of `value`, so that e.g.
+/// `0.1f32` reads as `0.1` like in a JSON response
+fn widen(value: f32) -> Value {
+    if !value.is_finite() {
+        return Value::Null;
+    }
+    value
+        .to_string()
+        .parse::<f64>()
+        .map_or(Value::Null, Value::from)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use flatbuffers::{FlatBufferBuilder, WIPOffset};
+
+    use super::*;
+    use crate::Timestamp;
+
+    /// Variable of the fixture: `(variable, unit, altitude, pressure_level,
+    /// depth, depth_to, aggregation)`
+    type Meta = (u8, u8, i16, i16, i16, i16, u8);
+
+    enum Values {
+        Current(f32),
+        Floats(Vec<f32>),
+        Integers(Vec<i64>),
+    }
+
+    fn variable<'a>(
+        builder: &mut FlatBufferBuilder<'a>,
+        (variable, unit, altitude, level, depth, depth_to, aggregation): Meta,
+        values: Values,
+    ) -> WIPOffset<VariableWithValues<'a>> {
+        let (value, floats, integers) = match values {
+            Values::Current(value) => (value, None, None),
+            Values::Floats(values) => (0.0, Some(builder.create_vector(&values)), None),
+            Values::Integers(values) => (0.0, None, Some(builder.create_vector(&values))),
+        };
+        let start = builder.start_table();
+        if let Some(floats) = floats {
+            builder.push_slot_always(VariableWithValues::VT_VALUES, floats);
+        }
+        if let Some(integers) = integers {
+            builder.push_slot_always(VariableWithValues::VT_VALUES_INT64, integers);
+        }
+        builder.push_slot(VariableWithValues::VT_VALUE, value, 0.0);
+        builder.push_slot(VariableWithValues::VT_ALTITUDE, altitude, 0);
+        builder.push_slot(VariableWithValues::VT_PRESSURE_LEVEL, level, 0);
+        builder.push_slot(VariableWithValues::VT_DEPTH, depth, 0);
+        builder.push_slot(VariableWithValues::VT_DEPTH_TO, depth_to, 0);
+        builder.push_slot(VariableWithValues::VT_VARIABLE, variable, 0);
+        builder.push_slot(VariableWithValues::VT_UNIT, unit, 0);
+        builder.push_slot(VariableWithValues::VT_AGGREGATION, aggregation, 0);
+        WIPOffset::new(builder.end_table(start).value())
+    }
+
+    fn block<'a>(
+        builder: &mut FlatBufferBuilder<'a>,
+        (time, time_end, interval): (i64, i64, i32),
+        variables: Vec<(Meta, Values)>,
+    ) -> WIPOffset<VariablesWithTime<'a>> {
+        let variables: Vec<_> = variables
+            .into_iter()
+            .map(|(meta, values)| variable(builder, meta, values))
+            .collect();
+        let variables = builder.create_vector(&variables);
+        let start = builder.start_table();
+        builder.push_slot(VariablesWithTime::VT_TIME, time, 0);
+        builder.push_slot(VariablesWithTime::VT_TIME_END, time_end, 0);
+        builder.push_slot_always(VariablesWithTime::VT_VARIABLES, variables);
+        builder.push_slot(VariablesWithTime::VT_INTERVAL, interval, 0);
+        WIPOffset::new(builder.end_table(start).value())
+    }
+
+    /// Message equivalent to `fixtures/flatbuffers.json`, as sent for the
+    /// location at `location_id` of a request
+    fn message(location_id: i64) -> Vec<u8> {
+        const TEMPERATURE: u8 = 47;
+        const CELSIUS: u8 = 1;
+        let mut builder = FlatBufferBuilder::new();
+        let current = block(
+            &mut builder,
+            (1704067200, 1704068100, 900),
+            vec![
+                ((TEMPERATURE, CELSIUS, 2, 0, 0, 0, 0), Values::Current(3.4)),
+                ((56, 40, 0, 0, 0, 0, 0), Values::Current(3.0)),
+            ],
+        );
+        let hourly = block(
+            &mut builder,
+            (1704067200, 1704078000, 3600),
+            vec![
+                (
+                    (TEMPERATURE, CELSIUS, 2, 0, 0, 0, 0),
+                    Values::Floats(vec![3.4, 3.1, f32::NAN]),
+                ),
+                (
+                    (TEMPERATURE, CELSIUS, 0, 850, 0, 0, 0),
+                    Values::Floats(vec![-2.5, -2.9, -3.2]),
+                ),
+                ((24, 32, 0, 0, 0, 0, 0), Values::Floats(vec![0.0, 0.2, 0.1])),
+                (
+                    (42, 3, 0, 0, 0, 1, 0),
+                    Values::Floats(vec![0.36, 0.361, 0.362]),
+                ),
+            ],
+        );
+        let daily = block(
+            &mut builder,
+            (1704063600, 1704236400, 86400),
+            vec![
+                ((56, 40, 0, 0, 0, 0, 0), Values::Floats(vec![3.0, 61.0])),
+                (
+                    (TEMPERATURE, CELSIUS, 2, 0, 0, 0, 2),
+                    Values::Floats(vec![5.2, 4.8]),
+                ),
+                (
+                    (40, 37, 0, 0, 0, 0, 0),
+                    Values::Integers(vec![1704093960, 1704180360]),
+                ),
+                ((57, 5, 10, 0, 0, 0, 9), Values::Floats(vec![250.0, 262.0])),
+            ],
+        );
+        let timezone = builder.create_string("Europe/Berlin");
+        let abbreviation = builder.create_string("CET");
+        let start = builder.start_table();
+        builder.push_slot(WeatherApiResponse::VT_LOCATION_ID, location_id, 0);
+        builder.push_slot(WeatherApiResponse::VT_LATITUDE, 52.52f32, 0.0);
+        builder.push_slot(WeatherApiResponse::VT_LONGITUDE, 13.41f32, 0.0);
+        builder.push_slot(WeatherApiResponse::VT_ELEVATION, 38.0f32, 0.0);
+        builder.push_slot(
+            WeatherApiResponse::VT_GENERATION_TIME_MILLISECONDS,
+            0.25f32,
+            0.0,
+        );
+        builder.push_slot(WeatherApiResponse::VT_UTC_OFFSET_SECONDS, 3600, 0);
+        builder.push_slot_always(WeatherApiResponse::VT_TIMEZONE, timezone);
+        builder.push_slot_always(WeatherApiResponse::VT_TIMEZONE_ABBREVIATION, abbreviation);
+        builder.push_slot_always(WeatherApiResponse::VT_CURRENT, current);
+        builder.push_slot_always(WeatherApiResponse::VT_HOURLY, hourly);
+        builder.push_slot_always(WeatherApiResponse::VT_DAILY, daily);
+        let root = builder.end_table(start);
+        builder.finish_size_prefixed(root, None);
+        builder.finished_data().to_vec()
+    }
+
+    const FIXTURE: &[u8] = include_bytes!("../fixtures/flatbuffers.bin");
+
+    fn json() -> ForecastResponse {
+        ForecastResponse::from_json(include_str!("../fixtures/flatbuffers.json")).unwrap()
+    }
+
+    #[test]
+    fn same_as_json() {
+        // The bundled fixture is the message built above
+        assert_eq!(message(0), FIXTURE);
+        let decoded = ForecastResponse::from_flatbuffers(FIXTURE).unwrap();
+        assert_eq!(decoded, json());
+
+        let hourly = decoded.hourly.unwrap();
+        assert_eq!(hourly.time[2], Timestamp::Unix(1704074400));
+        assert_eq!(
+            hourly.get("temperature_2m").unwrap().values(),
+            [Some(3.4), Some(3.1), None]
+        );
+        assert_eq!(
+            decoded
+                .daily_units
+                .unwrap()
+                .get("wind_direction_10m_dominant"),
+            Some("°")
+        );
+    }
+
+    #[test]
+    fn several_locations() {
+        let mut payload = message(1);
+        let second = payload.len();
+        payload.extend(message(0));
+        let responses = ForecastResponses::from_flatbuffers(&payload).unwrap();
+        assert_eq!(
+            responses
+                .iter()
+                .map(|response| response.location_id)
+                .collect::<Vec<_>>(),
+            [0, 1]
+        );
+        assert_eq!(responses[1].hourly, json().hourly);
+        let first = ForecastResponse::from_flatbuffers(&payload).unwrap();
+        assert_eq!(first.location_id, 1);
+
+        let truncated = &payload[..payload.len() - 1];
+        assert!(matches!(
+            ForecastResponses::from_flatbuffers(truncated),
+            Err(FlatBuffersError::Truncated { offset }) if offset == second
+        ));
+    }
+
+    #[test]
+    fn malformed_payloads() {
+        assert!(matches!(
+            ForecastResponse::from_flatbuffers(&FIXTURE[..FIXTURE.len() - 1]),
+            Err(FlatBuffersError::Truncated { offset: 0 })
+        ));
+        assert!(matches!(
+            ForecastResponse::from_flatbuffers(&[]),
+            Err(FlatBuffersError::Truncated { offset: 0 })
+        ));
+        let mut corrupted = FIXTURE.to_vec();
+        corrupted[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            ForecastResponse::from_flatbuffers(&corrupted),
+            Err(FlatBuffersError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,242 @@
+//! Bindings for `schema/weather_api.fbs`, in the shape `flatc --rust` generates
+
+use flatbuffers::{
+    Follow, ForwardsUOffset, InvalidFlatbuffer, Table, VOffsetT, Vector, Verifiable, Verifier,
+};
+
+/// Names of the `Variable` enumeration values, by value
+pub(super) const VARIABLE_NAMES: &[&str] = &[
+    "undefined",
+    "apparent_temperature",
+    "cape",
+    "cloud_cover",
+    "cloud_cover_high",
+    "cloud_cover_low",
+    "cloud_cover_mid",
+    "daylight_duration",
+    "dew_point",
+    "diffuse_radiation",
+    "diffuse_radiation_instant",
+    "direct_normal_irradiance",
+    "direct_normal_irradiance_instant",
+    "direct_radiation",
+    "direct_radiation_instant",
+    "et0_fao_evapotranspiration",
+    "evapotranspiration",
+    "freezing_level_height",
+    "growing_degree_days",
+    "is_day",
+    "latent_heat_flux",
+    "leaf_wetness_probability",
+    "lifted_index",
+    "lightning_potential",
+    "precipitation",
+    "precipitation_hours",
+    "precipitation_probability",
+    "pressure_msl",
+    "rain",
+    "relative_humidity",
+    "runoff",
+    "sensible_heat_flux",
+    "shortwave_radiation",
+    "shortwave_radiation_instant",
+    "showers",
+    "snow_depth",
+    "snow_height",
+    "snowfall",
+    "snowfall_height",
+    "snowfall_water_equivalent",
+    "sunrise",
+    "sunset",
+    "soil_moisture",
+    "soil_moisture_index",
+    "soil_temperature",
+    "surface_pressure",
+    "surface_temperature",
+    "temperature",
+    "terrestrial_radiation",
+    "terrestrial_radiation_instant",
+    "total_column_integrated_water_vapour",
+    "updraft",
+    "uv_index",
+    "uv_index_clear_sky",
+    "vapour_pressure_deficit",
+    "visibility",
+    "weather_code",
+    "wind_direction",
+    "wind_gusts",
+    "wind_speed",
+    "vertical_velocity",
+    "geopotential_height",
+    "wet_bulb_temperature",
+    "river_discharge",
+];
+
+/// Names of the `Unit` enumeration values, by value
+pub(super) const UNIT_NAMES: &[&str] = &[
+    "undefined",
+    "celsius",
+    "centimetre",
+    "cubic_metre_per_cubic_metre",
+    "cubic_metre_per_second",
+    "degree_direction",
+    "dimensionless_integer",
+    "dimensionless",
+    "european_air_quality_index",
+    "fahrenheit",
+    "feet",
+    "fraction",
+    "gdd_celsius",
+    "geopotential_metre",
+    "grains_per_cubic_metre",
+    "gram_per_kilogram",
+    "hectopascal",
+    "hours",
+    "inch",
+    "iso8601",
+    "joule_per_kilogram",
+    "kelvin",
+    "kilopascal",
+    "kilogram_per_square_metre",
+    "kilometres_per_hour",
+    "knots",
+    "megajoule_per_square_metre",
+    "metre_per_second_not_unit_converted",
+    "metre_per_second",
+    "metre",
+    "micrograms_per_cubic_metre",
+    "miles_per_hour",
+    "millimetre",
+    "pascal",
+    "per_second",
+    "percentage",
+    "seconds",
+    "unix_time",
+    "us_air_quality_index",
+    "watt_per_square_metre",
+    "wmo_code",
+    "parts_per_million",
+];
+
+/// Names of the `Aggregation` enumeration values, by value
+pub(super) const AGGREGATION_NAMES: &[&str] = &[
+    "none", "minimum", "maximum", "mean", "p10", "p25", "median", "p75", "p90", "dominant", "sum",
+    "spread",
+];
+
+/// Declare a table with its scalar fields, read with their schema default of
+/// zero when absent, and its offset fields, `None` when absent
+macro_rules! table {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($scalar:ident: $scalar_type:ty = $scalar_slot:ident @ $scalar_offset:literal,)*
+            ;
+            $($field:ident: $field_type:ty = $field_slot:ident @ $field_offset:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy)]
+        pub(super) struct $name<'a> {
+            table: Table<'a>,
+        }
+
+        impl<'a> $name<'a> {
+            $(pub(super) const $scalar_slot: VOffsetT = $scalar_offset;)*
+            $(pub(super) const $field_slot: VOffsetT = $field_offset;)*
+
+            $(
+                pub(super) fn $scalar(&self) -> $scalar_type {
+                    // Safety: the buffer was checked by `Verifiable::run_verifier`
+                    unsafe { self.table.get::<$scalar_type>(Self::$scalar_slot, None) }
+                        .unwrap_or_default()
+                }
+            )*
+
+            $(
+                pub(super) fn $field(&self) -> Option<<$field_type as Follow<'a>>::Inner> {
+                    // Safety: the buffer was checked by `Verifiable::run_verifier`
+                    unsafe {
+                        self.table
+                            .get::<ForwardsUOffset<$field_type>>(Self::$field_slot, None)
+                    }
+                }
+            )*
+        }
+
+        impl<'a> Follow<'a> for $name<'a> {
+            type Inner = Self;
+
+            unsafe fn follow(buf: &'a [u8], loc: usize) -> Self {
+                Self {
+                    // Safety: guaranteed by the caller
+                    table: unsafe { Table::new(buf, loc) },
+                }
+            }
+        }
+
+        impl<'a> Verifiable for $name<'a> {
+            fn run_verifier(verifier: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+                verifier
+                    .visit_table(pos)?
+                    $(.visit_field::<$scalar_type>(stringify!($scalar), Self::$scalar_slot, false)?)*
+                    $(.visit_field::<ForwardsUOffset<$field_type>>(
+                        stringify!($field),
+                        Self::$field_slot,
+                        false,
+                    )?)*
+                    .finish();
+                Ok(())
+            }
+        }
+    };
+}
+
+table! {
+    /// Values of one variable at one altitude, level, depth or member
+    VariableWithValues {
+        variable: u8 = VT_VARIABLE @ 4,
+        unit: u8 = VT_UNIT @ 6,
+        value: f32 = VT_VALUE @ 8,
+        altitude: i16 = VT_ALTITUDE @ 14,
+        aggregation: u8 = VT_AGGREGATION @ 16,
+        pressure_level: i16 = VT_PRESSURE_LEVEL @ 18,
+        depth: i16 = VT_DEPTH @ 20,
+        depth_to: i16 = VT_DEPTH_TO @ 22,
+        ensemble_member: i16 = VT_ENSEMBLE_MEMBER @ 24,
+        previous_day: i16 = VT_PREVIOUS_DAY @ 26,
+        ;
+        values: Vector<'a, f32> = VT_VALUES @ 10,
+        values_int64: Vector<'a, i64> = VT_VALUES_INT64 @ 12,
+    }
+}
+
+table! {
+    /// Variables of a block, sampled every `interval` seconds from `time`
+    VariablesWithTime {
+        time: i64 = VT_TIME @ 4,
+        time_end: i64 = VT_TIME_END @ 6,
+        interval: i32 = VT_INTERVAL @ 8,
+        ;
+        variables: Vector<'a, ForwardsUOffset<VariableWithValues<'a>>> = VT_VARIABLES @ 10,
+    }
+}
+
+table! {
+    /// Forecast for one location
+    WeatherApiResponse {
+        latitude: f32 = VT_LATITUDE @ 4,
+        longitude: f32 = VT_LONGITUDE @ 6,
+        elevation: f32 = VT_ELEVATION @ 8,
+        generation_time_milliseconds: f32 = VT_GENERATION_TIME_MILLISECONDS @ 10,
+        location_id: i64 = VT_LOCATION_ID @ 12,
+        utc_offset_seconds: i32 = VT_UTC_OFFSET_SECONDS @ 16,
+        ;
+        timezone: &'a str = VT_TIMEZONE @ 18,
+        timezone_abbreviation: &'a str = VT_TIMEZONE_ABBREVIATION @ 20,
+        current: VariablesWithTime<'a> = VT_CURRENT @ 22,
+        daily: VariablesWithTime<'a> = VT_DAILY @ 24,
+        hourly: VariablesWithTime<'a> = VT_HOURLY @ 26,
+        minutely_15: VariablesWithTime<'a> = VT_MINUTELY_15 @ 28,
+    }
+}
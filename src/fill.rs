@@ -0,0 +1,179 @@
+use crate::{HourlyBlock, Series};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Enumerate the ways `Series::fill` replaces missing values
+pub enum Fill {
+    /// Last value before the gap, leaving leading gaps missing
+    Forward,
+    /// First value after the gap, leaving trailing gaps missing
+    Backward,
+    /// Straight line between the values around the gap, one step per
+    /// timestamp; gaps at either end are left missing
+    Linear,
+    Constant(f64),
+}
+
+impl Series {
+    /// Copy of the series with missing values filled by `method`, and the
+    /// number of values filled
+    pub fn fill(&self, method: Fill) -> (Series, usize) {
+        let mut values = self.0.clone();
+        match method {
+            Fill::Forward => {
+                let mut last = None;
+                for value in &mut values {
+                    *value = value.or(last);
+                    last = *value;
+                }
+            }
+            Fill::Backward => {
+                let mut next = None;
+                for value in values.iter_mut().rev() {
+                    *value = value.or(next);
+                    next = *value;
+                }
+            }
+            Fill::Linear => {
+                let mut previous: Option<(usize, f64)> = None;
+                for index in 0..values.len() {
+                    let Some(value) = values[index] else {
+                        continue;
+                    };
+                    if let Some((start, from)) = previous.filter(|(start, _)| index > start + 1) {
+                        let steps = (index - start) as f64;
+                        for (step, gap) in values[start + 1..index].iter_mut().enumerate() {
+                            *gap = Some(from + (value - from) * (step + 1) as f64 / steps);
+                        }
+                    }
+                    previous = Some((index, value));
+                }
+            }
+            Fill::Constant(fill_value) => {
+                for value in &mut values {
+                    *value = value.or(Some(fill_value));
+                }
+            }
+        }
+        let filled = self.nulls_count() - values.iter().filter(|value| value.is_none()).count();
+        (Series(values), filled)
+    }
+}
+
+impl HourlyBlock {
+    /// Copy of the block with the series of the variables accepted by
+    /// `variables` filled by `method`, and the number of values filled
+    pub fn fill(&self, method: Fill, variables: impl Fn(&str) -> bool) -> (HourlyBlock, usize) {
+        let mut filled = 0;
+        let series = self
+            .series
+            .iter()
+            .map(|(name, series)| {
+                if !variables(name) {
+                    return (name.clone(), series.clone());
+                }
+                let (series, count) = series.fill(method);
+                filled += count;
+                (name.clone(), series)
+            })
+            .collect();
+        let block = HourlyBlock {
+            time: self.time.clone(),
+            series,
+        };
+        (block, filled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Timestamp;
+
+    fn gappy() -> Series {
+        Series(vec![
+            None,
+            Some(1.0),
+            None,
+            None,
+            Some(4.0),
+            Some(2.0),
+            None,
+        ])
+    }
+
+    #[test]
+    fn interior_gaps() {
+        let (linear, filled) = gappy().fill(Fill::Linear);
+        assert_eq!(
+            linear.values(),
+            [
+                None,
+                Some(1.0),
+                Some(2.0),
+                Some(3.0),
+                Some(4.0),
+                Some(2.0),
+                None
+            ]
+        );
+        assert_eq!(filled, 2);
+
+        let (forward, filled) = gappy().fill(Fill::Forward);
+        assert_eq!(
+            forward.values(),
+            [
+                None,
+                Some(1.0),
+                Some(1.0),
+                Some(1.0),
+                Some(4.0),
+                Some(2.0),
+                Some(2.0)
+            ]
+        );
+        assert_eq!(filled, 3);
+    }
+
+    #[test]
+    fn ends() {
+        let (backward, filled) = gappy().fill(Fill::Backward);
+        assert_eq!(
+            backward.values(),
+            [
+                Some(1.0),
+                Some(1.0),
+                Some(4.0),
+                Some(4.0),
+                Some(4.0),
+                Some(2.0),
+                None
+            ]
+        );
+        assert_eq!(filled, 3);
+
+        let (constant, filled) = gappy().fill(Fill::Constant(0.0));
+        assert_eq!(constant.nulls_count(), 0);
+        assert_eq!(filled, 4);
+        assert_eq!(Series(vec![None, None]).fill(Fill::Linear).1, 0);
+    }
+
+    #[test]
+    fn block_filter() {
+        let block = HourlyBlock {
+            time: (0..7).map(|hour| Timestamp::Unix(hour * 3600)).collect(),
+            series: [
+                ("rain".to_string(), gappy()),
+                ("temperature_2m".to_string(), gappy()),
+            ]
+            .into(),
+        };
+
+        let (filled, count) = block.fill(Fill::Constant(0.0), |name| name == "rain");
+        assert_eq!(count, 4);
+        assert_eq!(filled.get("rain").unwrap().nulls_count(), 0);
+        assert_eq!(filled.get("temperature_2m").unwrap(), &gappy());
+        assert_eq!(filled.time, block.time);
+        assert_eq!(block.fill(Fill::Linear, |_| true).1, 4);
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt;
+
+use strum_macros::{Display, EnumString};
+
+use crate::{push_list, Coordinates, Endpoint, Forecast, NoCoordinates, Settings};
+
+pub(crate) const FLOOD_URL: &str = "https://flood-api.open-meteo.com/v1/flood";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[allow(non_camel_case_types)]
+/// Enumerate the daily variables of the flood API
+pub enum FloodDaily {
+    river_discharge,
+    river_discharge_mean,
+    river_discharge_median,
+    river_discharge_max,
+    river_discharge_min,
+    river_discharge_p25,
+    river_discharge_p75,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[allow(non_camel_case_types)]
+/// Enumerate the GloFAS runs served by the flood API
+pub enum GlofasModel {
+    seamless_v4,
+    forecast_v4,
+    consolidated_v4,
+    seamless_v3,
+    forecast_v3,
+    consolidated_v3,
+}
+
+#[derive(Clone)]
+/// River discharge request, sharing the coordinates and settings of a
+/// `Forecast`
+///
+/// With `ensemble(true)` every member comes back as its own
+/// `river_discharge_memberNN` column, see `ForecastResponse::flood_series`
+pub struct Flood<C = Coordinates> {
+    forecast: Forecast<C>,
+    daily: Vec<FloodDaily>,
+    ensemble: bool,
+    models: Vec<GlofasModel>,
+}
+
+impl Flood<NoCoordinates> {
+    /// Initialize Flood object
+    pub fn new() -> Self {
+        Flood {
+            forecast: Forecast::new().endpoint(Endpoint::Flood),
+            daily: Vec::new(),
+            ensemble: false,
+            models: Vec::new(),
+        }
+    }
+}
+
+impl Default for Flood<NoCoordinates> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Flood<C> {
+    /// Specify coordinates (latitude, longitude)
+    pub fn coord(self, latitude: f32, longitude: f32) -> Flood<Coordinates> {
+        Flood {
+            forecast: self.forecast.coord(latitude, longitude),
+            daily: self.daily,
+            ensemble: self.ensemble,
+            models: self.models,
+        }
+    }
+
+    /// Dates of the first and last day, `YYYY-MM-DD`
+    pub fn dates(self, start: &str, end: &str) -> Self {
+        self.settings(Settings::start_date(start.to_string()))
+            .settings(Settings::end_date(end.to_string()))
+    }
+
+    /// Add optional settings, e.g. `Settings::forecast_days`
+    pub fn settings(mut self, setting: Settings) -> Self {
+        self.forecast = self.forecast.settings(setting);
+        self
+    }
+
+    /// Get daily value for a specific data
+    pub fn daily(mut self, daily: FloodDaily) -> Self {
+        self.daily.push(daily);
+        self
+    }
+
+    /// Return all ensemble members instead of the control run only
+    pub fn ensemble(mut self, ensemble: bool) -> Self {
+        self.ensemble = ensemble;
+        self
+    }
+
+    /// GloFAS runs to use, the seamless one when none is given
+    pub fn models(mut self, models: &[GlofasModel]) -> Self {
+        self.models.extend_from_slice(models);
+        self
+    }
+}
+
+impl Flood<Coordinates> {
+    /// Convert the request into a valid URL against the given base
+    pub(crate) fn to_url(&self, base: &str) -> String {
+        let mut url = self.forecast.to_url(base);
+        push_list(&mut url, "daily", &self.daily);
+        if !self.models.is_empty() {
+            let models: Vec<_> = self.models.iter().map(GlofasModel::to_string).collect();
+            url.push_str(&format!("&models={}", models.join(",")));
+        }
+        if self.ensemble {
+            url.push_str("&ensemble=true");
+        }
+        url
+    }
+}
+
+impl fmt::Display for Flood<Coordinates> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_url(FLOOD_URL))
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl crate::EndpointRequest for Flood<Coordinates> {
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::Flood
+    }
+
+    fn to_url(&self, base: &str) -> String {
+        Flood::to_url(self, base)
+    }
+}
+
+#[cfg(feature = "response")]
+impl crate::ForecastResponse {
+    /// Values of a daily flood variable, `None` if the response does not
+    /// contain it
+    pub fn flood(&self, variable: FloodDaily) -> Option<&[Option<crate::DailyValue>]> {
+        self.daily.as_ref()?.get(&variable.to_string())
+    }
+
+    /// Numeric daily columns as series on the daily time axis, `None`
+    /// without a daily block
+    ///
+    /// Members of an ensemble request are then summarised with
+    /// `HourlyBlock::ensemble_by_name("river_discharge")`
+    pub fn flood_series(&self) -> Option<crate::HourlyBlock> {
+        let daily = self.daily.as_ref()?;
+        Some(crate::HourlyBlock {
+            time: daily.time.clone(),
+            series: daily
+                .variables()
+                .filter_map(|name| Some((name.to_string(), daily.numbers(name)?)))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn url_creation() {
+        let request = Flood::new()
+            .coord(59.91, 10.75)
+            .daily(FloodDaily::river_discharge)
+            .daily(FloodDaily::river_discharge_p75)
+            .models(&[GlofasModel::seamless_v4, GlofasModel::forecast_v3])
+            .ensemble(true)
+            .settings(Settings::forecast_days(30));
+        assert_eq!(
+            request.to_string(),
+            "https://flood-api.open-meteo.com/v1/flood?latitude=59.91&longitude=10.75&forecast_days=30&daily=,river_discharge,river_discharge_p75&models=seamless_v4,forecast_v3&ensemble=true"
+        );
+        assert!(!request.ensemble(false).to_string().contains("ensemble"));
+    }
+
+    #[cfg(feature = "response")]
+    #[test]
+    fn fixture() {
+        use crate::DailyValue;
+
+        let response =
+            crate::ForecastResponse::from_json(include_str!("../fixtures/flood.json")).unwrap();
+        assert_eq!(
+            response.flood(FloodDaily::river_discharge_p25).unwrap(),
+            [Some(DailyValue::Float(41.2)), Some(DailyValue::Float(43.0))]
+        );
+        assert_eq!(
+            response.flood(FloodDaily::river_discharge_median).unwrap()[1],
+            Some(DailyValue::Float(47.5))
+        );
+        assert_eq!(
+            response
+                .daily
+                .as_ref()
+                .unwrap()
+                .get("river_discharge_member02"),
+            Some(&[Some(DailyValue::Float(45.0)), None][..])
+        );
+
+        let series = response.flood_series().unwrap();
+        let ensemble = series.ensemble_by_name("river_discharge").unwrap();
+        assert_eq!(ensemble.member_count(), 2);
+        assert_eq!(ensemble.control.unwrap().values(), [Some(44.1), Some(46.3)]);
+        assert_eq!(ensemble.max().values(), [Some(45.0), Some(48.9)]);
+        assert!(series.ensemble_by_name("river_discharge_p25").is_some());
+        assert!(response.flood(FloodDaily::river_discharge_min).is_none());
+    }
+}
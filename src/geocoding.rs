@@ -0,0 +1,265 @@
+use std::fmt;
+
+use serde::Deserialize;
+use strum_macros::Display;
+
+use crate::GEOCODING_URL;
+
+/// Population from which two matching cities make a name ambiguous
+#[cfg(feature = "client")]
+const AMBIGUOUS_POPULATION: u64 = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[allow(non_camel_case_types)]
+/// Enumerate the body formats of the geocoding API
+pub enum GeocodingFormat {
+    json,
+    protobuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Search of locations by name, see https://open-meteo.com/en/docs/geocoding-api
+pub struct GeocodingRequest {
+    name: String,
+    count: Option<u8>,
+    language: Option<String>,
+    country_code: Option<String>,
+    format: Option<GeocodingFormat>,
+}
+
+impl GeocodingRequest {
+    /// Search locations whose name or postal code starts with `name`
+    pub fn new(name: &str) -> Self {
+        GeocodingRequest {
+            name: name.to_string(),
+            count: None,
+            language: None,
+            country_code: None,
+            format: None,
+        }
+    }
+
+    /// Number of candidates to return, 10 by default and at most 100
+    pub fn count(mut self, count: u8) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Language of the names in the results, e.g. `de`
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    /// Keep only the results in the country with this ISO 3166-1 alpha-2 code
+    pub fn country_code(mut self, country_code: &str) -> Self {
+        self.country_code = Some(country_code.to_string());
+        self
+    }
+
+    /// Body format, the clients always ask for `json`
+    pub fn format(mut self, format: GeocodingFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Convert the request into a valid URL against the given base
+    pub(crate) fn to_url(&self, base: &str) -> String {
+        let mut url = format!("{base}?name={}", encode(&self.name));
+        if let Some(count) = self.count {
+            url.push_str(&format!("&count={count}"));
+        }
+        if let Some(language) = &self.language {
+            url.push_str(&format!("&language={}", encode(language)));
+        }
+        if let Some(country_code) = &self.country_code {
+            url.push_str(&format!("&countryCode={}", encode(country_code)));
+        }
+        if let Some(format) = self.format {
+            url.push_str(&format!("&format={format}"));
+        }
+        url
+    }
+
+    /// Same request asking for a JSON body
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    pub(crate) fn json(&self) -> Self {
+        self.clone().format(GeocodingFormat::json)
+    }
+}
+
+impl fmt::Display for GeocodingRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_url(GEOCODING_URL))
+    }
+}
+
+/// Percent-encode everything but the unreserved characters of RFC 3986
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            byte => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+/// Location matching a geocoding search
+pub struct GeocodingResult {
+    /// GeoNames identifier
+    pub id: u64,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub elevation: Option<f64>,
+    /// GeoNames feature code, e.g. `PPLA` for a first-order administrative seat
+    pub feature_code: Option<String>,
+    pub country_code: Option<String>,
+    pub country: Option<String>,
+    /// Administrative areas the location is in, from the largest
+    pub admin1: Option<String>,
+    pub admin2: Option<String>,
+    pub admin3: Option<String>,
+    pub admin4: Option<String>,
+    /// IANA timezone, to pass to `Settings::timezone`
+    pub timezone: Option<String>,
+    pub population: Option<u64>,
+    #[serde(default)]
+    pub postcodes: Vec<String>,
+}
+
+impl GeocodingResult {
+    /// Coordinates as taken by `Forecast::coord`
+    pub fn into_latlon(self) -> (f32, f32) {
+        (self.latitude as f32, self.longitude as f32)
+    }
+}
+
+/// City a name was resolved to by `Client::for_city`
+pub type Place = GeocodingResult;
+
+/// Most relevant populated place among the results of a search for `name`
+///
+/// Administrative areas are skipped; several places above
+/// `AMBIGUOUS_POPULATION` are an error listing them
+#[cfg(feature = "client")]
+pub(crate) fn pick_city(
+    name: &str,
+    results: Vec<GeocodingResult>,
+) -> Result<Place, crate::WeatheredError> {
+    let cities: Vec<_> = results
+        .into_iter()
+        .filter(|result| {
+            result
+                .feature_code
+                .as_deref()
+                .is_none_or(|code| code.starts_with("PPL"))
+        })
+        .collect();
+    let populous = cities
+        .iter()
+        .filter(|city| city.population >= Some(AMBIGUOUS_POPULATION))
+        .count();
+    if populous > 1 {
+        return Err(crate::WeatheredError::AmbiguousPlace {
+            name: name.to_string(),
+            candidates: cities,
+        });
+    }
+    cities
+        .into_iter()
+        .next()
+        .ok_or_else(|| crate::WeatheredError::UnknownPlace {
+            name: name.to_string(),
+        })
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+/// Body of a geocoding search
+pub struct GeocodingResponse {
+    /// Candidates by decreasing relevance; the API leaves the field out when
+    /// nothing matches
+    #[serde(default)]
+    pub results: Vec<GeocodingResult>,
+    pub generationtime_ms: Option<f64>,
+}
+
+impl GeocodingResponse {
+    /// Parse the JSON body of a search
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Check the status of a raw response fetched from `url` and decode its body
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    pub(crate) fn decode(
+        url: &str,
+        response: &crate::HttpResponse,
+    ) -> Result<Vec<GeocodingResult>, crate::WeatheredError> {
+        crate::response::check_status(url, response)?;
+        serde_json::from_slice::<Self>(&response.body)
+            .map(|response| response.results)
+            .map_err(|source| crate::WeatheredError::Decode {
+                url: url.to_string(),
+                source,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Forecast;
+
+    #[test]
+    fn url_encoding() {
+        let request = GeocodingRequest::new("São Paulo")
+            .count(5)
+            .language("pt")
+            .country_code("BR")
+            .format(GeocodingFormat::json);
+        assert_eq!(
+            request.to_string(),
+            "https://geocoding-api.open-meteo.com/v1/search?name=S%C3%A3o%20Paulo&count=5&language=pt&countryCode=BR&format=json"
+        );
+        assert_eq!(GeocodingRequest::new("a&b=c").to_url(""), "?name=a%26b%3Dc");
+    }
+
+    #[test]
+    fn candidates() {
+        let response =
+            GeocodingResponse::from_json(include_str!("../fixtures/geocoding.json")).unwrap();
+        assert_eq!(response.results.len(), 3);
+
+        let city = &response.results[0];
+        assert_eq!(city.id, 2657896);
+        assert_eq!(city.name, "Zürich");
+        assert_eq!(city.feature_code.as_deref(), Some("PPLA"));
+        assert_eq!(city.admin2.as_deref(), Some("Bezirk Zürich"));
+        assert_eq!(city.admin4, None);
+        assert_eq!(city.postcodes[1], "8001");
+        assert_eq!(city.population, Some(341730));
+        let canton = &response.results[1];
+        assert!(canton.postcodes.is_empty());
+        assert_eq!(canton.admin1, None);
+        assert_eq!(
+            response.results[2].timezone.as_deref(),
+            Some("America/Chicago")
+        );
+
+        let (latitude, longitude) = response.results[0].clone().into_latlon();
+        let forecast = Forecast::new().coord(latitude, longitude);
+        assert!(forecast
+            .to_string()
+            .contains("latitude=47.36667&longitude=8.55"));
+
+        let none = GeocodingResponse::from_json(r#"{"generationtime_ms":0.2}"#).unwrap();
+        assert!(none.results.is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+use std::ops::Range;
+
+use crate::{HourlyBlock, Timestamp};
+
+#[derive(Debug, Clone, PartialEq)]
+/// Consecutive samples of a block, borrowed rather than copied
+pub struct BlockSlice<'a> {
+    block: &'a HourlyBlock,
+    range: Range<usize>,
+}
+
+impl<'a> BlockSlice<'a> {
+    #[cfg(any(test, feature = "chrono"))]
+    pub(crate) fn new(block: &'a HourlyBlock, range: Range<usize>) -> Self {
+        BlockSlice { block, range }
+    }
+
+    /// Indices of the samples in the block
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    pub fn time(&self) -> &'a [Timestamp] {
+        &self.block.time[self.range.clone()]
+    }
+
+    /// Values of a variable in the slice, `None` if it is not in the block
+    pub fn get(&self, variable: &str) -> Option<&'a [Option<f64>]> {
+        let values = self.block.get(variable)?.values();
+        values.get(self.range.clone())
+    }
+}
+
+impl HourlyBlock {
+    /// Number of local calendar days the timestamps fall on, for a response
+    /// `utc_offset_seconds` ahead of UTC
+    pub fn days(&self, utc_offset_seconds: i32) -> usize {
+        self.day_ranges(utc_offset_seconds).len()
+    }
+
+    /// Local day, in days since 1970-01-01, of each run of consecutive
+    /// timestamps falling on it; unreadable timestamps belong to no run
+    pub(crate) fn day_ranges(&self, utc_offset_seconds: i32) -> Vec<(i64, Range<usize>)> {
+        let mut days: Vec<(i64, Range<usize>)> = Vec::new();
+        let mut previous = None;
+        for (index, time) in self.time.iter().enumerate() {
+            let day = time
+                .to_unix(utc_offset_seconds)
+                .map(|time| (time + i64::from(utc_offset_seconds)).div_euclid(86_400));
+            match (day, days.last_mut()) {
+                (Some(day), Some((_, range))) if previous == Some(day) => range.end = index + 1,
+                (Some(day), _) => days.push((day, index..index + 1)),
+                (None, _) => {}
+            }
+            previous = day;
+        }
+        days
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::ForecastResponse;
+
+    #[test]
+    fn slices_borrow_the_block() {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/chatham.json")).unwrap();
+        let hourly = response.hourly.unwrap();
+        // 21:00 to 02:00 at UTC+13:45, all on 2024-01-01 in UTC
+        assert_eq!(hourly.days(response.utc_offset_seconds), 2);
+        assert_eq!(hourly.days(0), 1);
+        let ranges = hourly.day_ranges(response.utc_offset_seconds);
+        assert_eq!(ranges[0], (19_723, 0..3));
+
+        let slice = BlockSlice::new(&hourly, ranges[1].1.clone());
+        assert_eq!(slice.len(), 3);
+        assert_eq!(slice.time()[0], Timestamp::Unix(1_704_104_100));
+        assert_eq!(
+            slice.get("temperature_2m"),
+            Some(&[Some(13.3), Some(13.0), Some(12.9)][..])
+        );
+        assert_eq!(slice.get("snowfall"), None);
+
+        // Wall-clock times are on their own date whatever the offset
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/forecast.json")).unwrap();
+        assert_eq!(response.hourly.unwrap().days(-2 * 3600), 1);
+    }
+}
@@ -0,0 +1,29 @@
+use std::error::Error;
+
+use crate::{HttpRequest, HttpResponse};
+
+/// Hook run on every request before it is sent, e.g. to add gateway credentials
+pub trait RequestHook: Send + Sync {
+    fn on_request(&self, request: &mut HttpRequest);
+}
+
+impl<F: Fn(&mut HttpRequest) + Send + Sync> RequestHook for F {
+    fn on_request(&self, request: &mut HttpRequest) {
+        self(request)
+    }
+}
+
+/// Hook run on every raw response before it is parsed;
+/// returning an error rejects the response
+pub trait ResponseHook: Send + Sync {
+    fn on_response(&self, response: &HttpResponse) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+impl<F> ResponseHook for F
+where
+    F: Fn(&HttpResponse) -> Result<(), Box<dyn Error + Send + Sync>> + Send + Sync,
+{
+    fn on_response(&self, response: &HttpResponse) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self(response)
+    }
+}
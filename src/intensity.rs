@@ -0,0 +1,193 @@
+use crate::Precipitation;
+
+pub(crate) const MM_PER_INCH: f64 = 25.4;
+const CM_PER_INCH: f64 = 2.54;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// Enumerate precipitation intensity classes
+pub enum PrecipIntensity {
+    None,
+    Light,
+    Moderate,
+    Heavy,
+    Violent,
+}
+
+impl PrecipIntensity {
+    /// Classify a rain rate in mm/h following WMO thresholds:
+    /// light below 2.5, moderate below 10, heavy below 50, violent above
+    pub fn from_rate(mm_per_hour: f64) -> Self {
+        Self::classify(mm_per_hour, [2.5, 10.0, 50.0])
+    }
+
+    /// Classify a rain rate expressed in the request's precipitation unit
+    pub fn from_rate_in(rate: f64, unit: &Precipitation) -> Self {
+        let mm_per_hour = match unit {
+            Precipitation::mm => rate,
+            Precipitation::inch => rate * MM_PER_INCH,
+        };
+        Self::from_rate(mm_per_hour)
+    }
+
+    /// Classify a snowfall rate in cm/h:
+    /// light below 1, moderate below 2.5, heavy below 5, violent above
+    pub fn from_snowfall_rate(cm_per_hour: f64) -> Self {
+        Self::classify(cm_per_hour, [1.0, 2.5, 5.0])
+    }
+
+    /// Classify a snowfall rate expressed in the request's precipitation unit
+    /// (Open-Meteo reports snowfall in cm for `mm` and in inches for `inch`)
+    pub fn from_snowfall_rate_in(rate: f64, unit: &Precipitation) -> Self {
+        let cm_per_hour = match unit {
+            Precipitation::mm => rate,
+            Precipitation::inch => rate * CM_PER_INCH,
+        };
+        Self::from_snowfall_rate(cm_per_hour)
+    }
+
+    /// Classify an hourly rain series, pairing each intensity with its timestamp;
+    /// hours without data (`None` rates) are left out
+    pub fn series<'a, T, R: Copy + Into<Option<f64>>>(
+        time: &'a [T],
+        rates: &[R],
+        unit: &Precipitation,
+    ) -> Vec<(&'a T, Self)> {
+        time.iter()
+            .zip(rates)
+            .filter_map(|(t, r)| Some((t, Self::from_rate_in((*r).into()?, unit))))
+            .collect()
+    }
+
+    /// Classify an hourly snowfall series, pairing each intensity with its timestamp;
+    /// hours without data (`None` rates) are left out
+    pub fn snowfall_series<'a, T, R: Copy + Into<Option<f64>>>(
+        time: &'a [T],
+        rates: &[R],
+        unit: &Precipitation,
+    ) -> Vec<(&'a T, Self)> {
+        time.iter()
+            .zip(rates)
+            .filter_map(|(t, r)| Some((t, Self::from_snowfall_rate_in((*r).into()?, unit))))
+            .collect()
+    }
+
+    /// Lower bounds (inclusive) of the moderate, heavy and violent classes
+    fn classify(rate: f64, [moderate, heavy, violent]: [f64; 3]) -> Self {
+        if rate.is_nan() || rate <= 0.0 {
+            Self::None
+        } else if rate < moderate {
+            Self::Light
+        } else if rate < heavy {
+            Self::Moderate
+        } else if rate < violent {
+            Self::Heavy
+        } else {
+            Self::Violent
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn rain_thresholds_mm() {
+        assert_eq!(PrecipIntensity::from_rate(0.0), PrecipIntensity::None);
+        assert_eq!(PrecipIntensity::from_rate(0.1), PrecipIntensity::Light);
+        assert_eq!(PrecipIntensity::from_rate(2.49), PrecipIntensity::Light);
+        assert_eq!(PrecipIntensity::from_rate(2.5), PrecipIntensity::Moderate);
+        assert_eq!(PrecipIntensity::from_rate(9.99), PrecipIntensity::Moderate);
+        assert_eq!(PrecipIntensity::from_rate(10.0), PrecipIntensity::Heavy);
+        assert_eq!(PrecipIntensity::from_rate(50.0), PrecipIntensity::Violent);
+    }
+
+    #[test]
+    fn rain_thresholds_inch() {
+        let unit = Precipitation::inch;
+        assert_eq!(
+            PrecipIntensity::from_rate_in(0.0, &unit),
+            PrecipIntensity::None
+        );
+        // 0.098 inch = 2.489 mm, 0.1 inch = 2.54 mm
+        assert_eq!(
+            PrecipIntensity::from_rate_in(0.098, &unit),
+            PrecipIntensity::Light
+        );
+        assert_eq!(
+            PrecipIntensity::from_rate_in(0.1, &unit),
+            PrecipIntensity::Moderate
+        );
+        // 0.39 inch = 9.906 mm, 0.4 inch = 10.16 mm
+        assert_eq!(
+            PrecipIntensity::from_rate_in(0.39, &unit),
+            PrecipIntensity::Moderate
+        );
+        assert_eq!(
+            PrecipIntensity::from_rate_in(0.4, &unit),
+            PrecipIntensity::Heavy
+        );
+        // 1.96 inch = 49.784 mm, 1.97 inch = 50.038 mm
+        assert_eq!(
+            PrecipIntensity::from_rate_in(1.96, &unit),
+            PrecipIntensity::Heavy
+        );
+        assert_eq!(
+            PrecipIntensity::from_rate_in(1.97, &unit),
+            PrecipIntensity::Violent
+        );
+    }
+
+    #[test]
+    fn snowfall_thresholds() {
+        let metric = Precipitation::mm;
+        let imperial = Precipitation::inch;
+        assert_eq!(
+            PrecipIntensity::from_snowfall_rate_in(0.99, &metric),
+            PrecipIntensity::Light
+        );
+        assert_eq!(
+            PrecipIntensity::from_snowfall_rate_in(1.0, &metric),
+            PrecipIntensity::Moderate
+        );
+        assert_eq!(
+            PrecipIntensity::from_snowfall_rate_in(2.5, &metric),
+            PrecipIntensity::Heavy
+        );
+        assert_eq!(
+            PrecipIntensity::from_snowfall_rate_in(5.0, &metric),
+            PrecipIntensity::Violent
+        );
+        // 0.39 inch = 0.99 cm, 1 inch = 2.54 cm
+        assert_eq!(
+            PrecipIntensity::from_snowfall_rate_in(0.39, &imperial),
+            PrecipIntensity::Light
+        );
+        assert_eq!(
+            PrecipIntensity::from_snowfall_rate_in(1.0, &imperial),
+            PrecipIntensity::Heavy
+        );
+    }
+
+    #[test]
+    fn series_alignment() {
+        let time = ["2023-05-01T00:00", "2023-05-01T01:00", "2023-05-01T02:00"];
+        let rain = [0.0, 3.0, 12.0];
+        let classified = PrecipIntensity::series(&time, &rain, &Precipitation::mm);
+        assert_eq!(
+            classified,
+            vec![
+                (&time[0], PrecipIntensity::None),
+                (&time[1], PrecipIntensity::Moderate),
+                (&time[2], PrecipIntensity::Heavy),
+            ]
+        );
+
+        let gaps = [None, Some(3.0), None];
+        assert_eq!(
+            PrecipIntensity::series(&time, &gaps, &Precipitation::mm),
+            vec![(&time[1], PrecipIntensity::Moderate)]
+        );
+    }
+}
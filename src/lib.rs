@@ -1,9 +1,259 @@
 use std::fmt;
-use strum_macros::Display;
+use strum_macros::{Display, EnumIter, EnumString};
 
-const BASE_URL: &'static str = "https://api.open-meteo.com/v1/forecast";
+use request::ForecastSpec;
 
-#[derive(Display)]
+#[cfg(all(
+    any(feature = "client", feature = "blocking"),
+    not(target_arch = "wasm32"),
+    not(any(feature = "tls-rustls", feature = "tls-native"))
+))]
+compile_error!("the HTTP clients need a TLS backend, enable `tls-rustls` or `tls-native`");
+#[cfg(all(feature = "tls-rustls", feature = "tls-native"))]
+compile_error!("`tls-rustls` and `tls-native` are mutually exclusive, enable only one");
+
+mod air_quality;
+#[cfg(feature = "response")]
+mod alert;
+#[cfg(any(feature = "client", feature = "blocking"))]
+mod api;
+mod archive;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "client")]
+mod cache;
+#[cfg(feature = "client")]
+mod chunk;
+#[cfg(feature = "client")]
+mod client;
+mod climate;
+#[cfg(feature = "response")]
+mod columns;
+#[cfg(feature = "response")]
+mod comparison;
+#[cfg(feature = "client")]
+mod conditional;
+#[cfg(feature = "response")]
+mod consistency;
+#[cfg(feature = "response")]
+mod csv;
+#[cfg(feature = "response")]
+mod date;
+#[cfg(feature = "response")]
+pub mod derive;
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+mod disk_cache;
+#[cfg(feature = "response")]
+mod elevation;
+#[cfg(feature = "response")]
+mod ensemble;
+mod ensemble_forecast;
+#[cfg(feature = "response")]
+mod error;
+#[cfg(feature = "fb")]
+mod fb;
+#[cfg(feature = "response")]
+mod fill;
+mod flood;
+#[cfg(feature = "response")]
+mod geocoding;
+#[cfg(feature = "response")]
+mod group;
+#[cfg(feature = "client")]
+mod hooks;
+mod intensity;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod lookup;
+mod marine;
+#[cfg(feature = "response")]
+mod merge;
+#[cfg(feature = "client")]
+mod metrics;
+#[cfg(all(test, any(feature = "client", feature = "blocking")))]
+mod mock;
+#[cfg(feature = "response")]
+mod ndjson;
+#[cfg(feature = "response")]
+mod normals;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod offset;
+#[cfg(feature = "polars")]
+mod polars;
+#[cfg(feature = "response")]
+mod precipitation;
+mod previous_day;
+#[cfg(feature = "response")]
+mod profile;
+#[cfg(feature = "typed-units")]
+pub mod quantity;
+#[cfg(feature = "client")]
+mod rate_limit;
+mod request;
+#[cfg(feature = "response")]
+mod resample;
+#[cfg(feature = "response")]
+mod response;
+#[cfg(any(feature = "client", feature = "blocking"))]
+mod retry;
+#[cfg(feature = "response")]
+mod rolling;
+#[cfg(feature = "client")]
+mod runtime;
+mod satellite;
+#[cfg(feature = "client")]
+mod seamless;
+mod seasonal;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod slice;
+#[cfg(feature = "response")]
+mod stats;
+#[cfg(feature = "response")]
+mod stream;
+#[cfg(feature = "response")]
+mod suffix;
+#[cfg(feature = "response")]
+mod sun;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(any(feature = "client", feature = "blocking"))]
+mod transport;
+#[cfg(feature = "response")]
+mod units;
+#[cfg(feature = "client")]
+mod watch;
+mod wmo;
+
+pub use air_quality::{AirQuality, AirQualityVar, Domain, EuropeanAqiBand, UsAqiBand};
+#[cfg(feature = "response")]
+pub use alert::{AlertMatch, AlertRule, Comparison, Variable};
+#[cfg(any(feature = "client", feature = "blocking"))]
+pub use api::{ApiLocation, ApiVersion};
+pub use archive::{Archive, ArchiveError, ArchiveRequest};
+#[cfg(feature = "parquet")]
+pub use arrow::write_parquet;
+#[cfg(feature = "client")]
+pub use cache::{MemoryCache, ResponseCache};
+#[cfg(feature = "client")]
+pub use chunk::ChunkSize;
+#[cfg(feature = "client")]
+pub use client::{Client, ClientBuilder};
+pub use climate::{Climate, ClimateDaily, ClimateError, ClimateModel, ClimateRequest};
+#[cfg(feature = "response")]
+pub use columns::{ColumnKind, ColumnName, ColumnRef};
+#[cfg(feature = "response")]
+pub use comparison::{
+    compare_locations, DayMetric, DaySummary, Direction, LocationComparison, ModelComparison,
+};
+#[cfg(feature = "client")]
+pub use conditional::{ConditionalEntry, ConditionalStore, FetchOutcome, MemoryConditionalStore};
+#[cfg(feature = "response")]
+pub use consistency::{ConsistencyError, Inconsistency};
+#[cfg(feature = "response")]
+pub use csv::{CsvBlock, CsvError, CsvOptions};
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+pub use disk_cache::DiskCache;
+#[cfg(feature = "response")]
+pub use elevation::{ElevationRequest, ElevationResponse};
+pub use ensemble_forecast::{EnsembleForecast, EnsembleModel};
+#[cfg(feature = "response")]
+pub use error::WeatheredError;
+#[cfg(feature = "fb")]
+pub use fb::FlatBuffersError;
+#[cfg(feature = "response")]
+pub use fill::Fill;
+pub use flood::{Flood, FloodDaily, GlofasModel};
+#[cfg(feature = "response")]
+pub use geocoding::{GeocodingFormat, GeocodingRequest, GeocodingResponse, GeocodingResult, Place};
+#[cfg(feature = "response")]
+pub use group::BlockSlice;
+#[cfg(feature = "client")]
+pub use hooks::{RequestHook, ResponseHook};
+pub use intensity::PrecipIntensity;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use lookup::Lookup;
+pub use marine::{Marine, MarineDaily, MarineHourly};
+#[cfg(feature = "response")]
+pub use merge::MergeError;
+#[cfg(feature = "client")]
+pub use metrics::{AtomicMetrics, MetricsSink, ProgressSink, RequestStats};
+#[cfg(feature = "response")]
+pub use normals::{DayNormal, Normals};
+#[cfg(feature = "chrono")]
+pub use offset::MapError;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use offset::{CalendarDate, Moment};
+#[cfg(feature = "response")]
+pub use precipitation::{PrecipEvent, WET_PROBABILITY};
+pub use previous_day::{PreviousDay, PreviousDayError};
+#[cfg(feature = "response")]
+pub use profile::{Profile, TimeMatch};
+pub use request::Request;
+#[cfg(feature = "response")]
+pub use resample::{Agg, Resample};
+#[cfg(any(feature = "client", feature = "blocking"))]
+pub use response::DecodeMode;
+#[cfg(feature = "response")]
+pub use response::{
+    Current, CurrentWeather, DailyBlock, DailyRow, DailyValue, ForecastResponse, ForecastResponses,
+    HourlyBlock, HourlyRow, Series, TimeSeries, Timestamp,
+};
+#[cfg(any(feature = "client", feature = "blocking"))]
+pub use retry::RetryPolicy;
+#[cfg(feature = "client-async-std")]
+pub use runtime::AsyncStdRuntime;
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+pub use runtime::TokioRuntime;
+#[cfg(all(feature = "client", target_arch = "wasm32"))]
+pub use runtime::WasmRuntime;
+#[cfg(feature = "client")]
+pub use runtime::{AsyncRuntime, BoxFuture};
+pub use satellite::{Satellite, SatelliteModel, SatelliteVar};
+#[cfg(feature = "client")]
+pub use seamless::{SeamlessResponse, Source};
+pub use seasonal::{Seasonal, SeasonalError, SeasonalRequest};
+#[cfg(feature = "response")]
+pub use stats::SeriesStats;
+#[cfg(feature = "response")]
+pub use suffix::EnsembleSeries;
+#[cfg(feature = "response")]
+pub use sun::SunEvents;
+#[cfg(feature = "client")]
+pub use transport::{AsyncHttpTransport, MaybeSend, MaybeSync, ReqwestTransport};
+#[cfg(any(feature = "client", feature = "blocking"))]
+pub use transport::{HttpRequest, HttpResponse, TransportError};
+#[cfg(feature = "blocking")]
+pub use transport::{HttpTransport, UreqTransport};
+#[cfg(feature = "response")]
+pub use units::{UnitPreferences, UnitsMap};
+#[cfg(feature = "client")]
+pub use watch::{Alerts, Watch};
+pub use wmo::WmoCode;
+
+const BASE_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const HISTORICAL_FORECAST_URL: &str = "https://historical-forecast-api.open-meteo.com/v1/forecast";
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+const ELEVATION_URL: &str = "https://api.open-meteo.com/v1/elevation";
+#[cfg(any(feature = "client", feature = "blocking"))]
+const USER_AGENT: &str = concat!("weathered/", env!("CARGO_PKG_VERSION"));
+
+/// FNV-1a hash, stable across runs and Rust versions unlike `DefaultHasher`,
+/// to name files after keys
+#[cfg(all(
+    any(
+        feature = "client",
+        all(feature = "blocking", any(test, feature = "testing"))
+    ),
+    not(target_arch = "wasm32")
+))]
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[derive(Clone, Display)]
 #[allow(non_camel_case_types)]
 /// Enumerate temperature units
 pub enum Temperature {
@@ -11,7 +261,7 @@ pub enum Temperature {
     fahrenheit,
 }
 
-#[derive(Display)]
+#[derive(Clone, Display)]
 #[allow(non_camel_case_types)]
 /// Enumerate windspeed units
 pub enum Speed {
@@ -21,7 +271,7 @@ pub enum Speed {
     kn,
 }
 
-#[derive(Display)]
+#[derive(Clone, Display)]
 #[allow(non_camel_case_types)]
 /// Enumerate precipitation units
 pub enum Precipitation {
@@ -29,7 +279,15 @@ pub enum Precipitation {
     inch,
 }
 
-#[derive(Display)]
+#[derive(Clone, Display)]
+#[allow(non_camel_case_types)]
+/// Enumerate length units, for wave heights
+pub enum Length {
+    metric,
+    imperial,
+}
+
+#[derive(Clone, Display)]
 #[allow(non_camel_case_types)]
 /// Enumerate valid time formats
 pub enum TimeFormat {
@@ -37,7 +295,7 @@ pub enum TimeFormat {
     unixtime,
 }
 
-#[derive(Display)]
+#[derive(Clone, Display)]
 #[allow(non_camel_case_types)]
 /// Enumerate cell selection
 pub enum Cell {
@@ -46,7 +304,43 @@ pub enum Cell {
     nearest,
 }
 
-#[derive(Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, EnumIter)]
+#[allow(non_camel_case_types)]
+/// Enumerate weather models, see https://open-meteo.com/en/docs > Weather models
+pub enum Model {
+    best_match,
+    ecmwf_ifs04,
+    ecmwf_ifs025,
+    ecmwf_aifs025,
+    cma_grapes_global,
+    bom_access_global,
+    metno_nordic,
+    gfs_seamless,
+    gfs_global,
+    gfs_hrrr,
+    gfs_graphcast025,
+    jma_seamless,
+    jma_msm,
+    jma_gsm,
+    icon_seamless,
+    icon_global,
+    icon_eu,
+    icon_d2,
+    gem_seamless,
+    gem_global,
+    gem_regional,
+    gem_hrdps_continental,
+    meteofrance_seamless,
+    meteofrance_arpege_world,
+    meteofrance_arpege_europe,
+    meteofrance_arome_france,
+    meteofrance_arome_france_hd,
+    ukmo_seamless,
+    ukmo_global_deterministic_10km,
+    ukmo_uk_deterministic_2km,
+}
+
+#[derive(Clone, Display)]
 #[allow(non_camel_case_types)]
 /// Enumerate settings and related value
 pub enum Settings {
@@ -55,34 +349,51 @@ pub enum Settings {
     temperature_unit(Temperature),
     windspeed_unit(Speed),
     precipitation_unit(Precipitation),
+    /// Unit of the marine API lengths
+    length_unit(Length),
     timeformat(TimeFormat),
     timezone(String),
     past_days(u8),
-    forecast_days(u8),
+    forecast_days(u16),
     start_date(String),
     end_date(String),
     cell_selection(Cell),
+    /// Models to query; with several, variables are suffixed with the model name
+    models(Vec<Model>),
+    /// Return the raw model output of the climate API
+    disable_bias_correction(bool),
+    /// Panel inclination for `global_tilted_irradiance`, 0° is horizontal
+    tilt(f32),
+    /// Panel orientation for `global_tilted_irradiance`, 0° faces south
+    azimuth(f32),
 }
 
 impl Settings {
     fn get(&self) -> String {
         match self {
-            Settings::elevation(t) => t.to_string(),
-            Settings::current_weather(t) => t.to_string(),
+            Settings::elevation(t) | Settings::tilt(t) | Settings::azimuth(t) => t.to_string(),
+            Settings::current_weather(t) | Settings::disable_bias_correction(t) => t.to_string(),
             Settings::temperature_unit(t) => t.to_string(),
             Settings::windspeed_unit(t) => t.to_string(),
             Settings::precipitation_unit(t) => t.to_string(),
+            Settings::length_unit(t) => t.to_string(),
             Settings::cell_selection(t) => t.to_string(),
             Settings::timeformat(t) => t.to_string(),
-            Settings::past_days(t) | Settings::forecast_days(t) => t.to_string(),
+            Settings::past_days(t) => t.to_string(),
+            Settings::forecast_days(t) => t.to_string(),
             Settings::timezone(t) | Settings::start_date(t) | Settings::end_date(t) => {
                 t.to_string()
             }
+            Settings::models(t) => t
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
         }
     }
 }
 
-#[derive(Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
 #[allow(non_camel_case_types)]
 /// Enumerate all Hourly flags
 pub enum Hourly {
@@ -90,6 +401,7 @@ pub enum Hourly {
     relative_humidity_2m,
     dewpoint_2m,
     apparent_temperature,
+    wet_bulb_temperature_2m,
     pressure_msl,
     surface_pressure,
     cloudcover,
@@ -134,7 +446,7 @@ pub enum Hourly {
     is_day,
 }
 
-#[derive(Display)]
+#[derive(Clone, Display)]
 #[allow(non_camel_case_types)]
 /// Enumerate available pressure variables
 pub enum PressureVar {
@@ -147,6 +459,19 @@ pub enum PressureVar {
     geopotential_height(u32),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[allow(non_camel_case_types)]
+/// Enumerate pressure variables independently of their level
+pub enum PressureKind {
+    temperature,
+    relativehumidity,
+    dewpoint,
+    cloudcover,
+    windspeed,
+    winddirection,
+    geopotential_height,
+}
+
 // TODO: collapse value to valid ones:
 // see https://open-meteo.com/en/docs > Pressure Level Variables
 /// Compose the sting to obtain valid variables
@@ -161,16 +486,17 @@ impl PressureVar {
             | PressureVar::winddirection(h)
             | PressureVar::geopotential_height(h) => h,
         };
-        format!("{}_{}hPa", self.to_string(), value)
+        format!("{}_{}hPa", self, value)
     }
 }
 
-#[derive(Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, EnumString)]
 #[allow(non_camel_case_types)]
 /// Enumerate Daily data flags
 pub enum Daily {
     temperature_2m_max,
     temperature_2m_min,
+    temperature_2m_mean,
     apparent_temperature_max,
     apparent_temperature_min,
     precipitation_sum,
@@ -193,105 +519,187 @@ pub enum Daily {
     uv_index_clear_sky_max,
 }
 
-/// Geographic coordinates
-pub struct Coordinates {
-    latitude: f32,
-    longitude: f32,
-}
-
-/// Basic data structure to keep all request's data
-pub struct Forecast {
-    coordinates: Coordinates,
-    settings: Vec<Settings>,
-    hourly: Vec<Hourly>,
-    pressure_var: Vec<PressureVar>,
-    daily: Vec<Daily>,
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+/// Enumerate the API endpoints requests are sent to
+pub enum Endpoint {
+    /// Live forecast
+    #[default]
+    Forecast,
+    /// ERA5 based reanalysis, see `Archive`
+    Archive,
+    /// Archived output of past model runs, with the variables of the live forecast
+    HistoricalForecast,
+    /// Pollutants and air quality indices, see `AirQuality`
+    AirQuality,
+    /// Waves and swell, see `Marine`
+    Marine,
+    /// River discharge from GloFAS, see `Flood`
+    Flood,
+    /// CMIP6 climate projections, see `Climate`
+    Climate,
+    /// Every member of ensemble models, see `EnsembleForecast`
+    Ensemble,
+    /// Satellite derived irradiance, see `Satellite`
+    Satellite,
+    /// Six-hourly seasonal ensemble, see `Seasonal`
+    Seasonal,
+    /// Place search, only answering `GeocodingRequest`
+    Geocoding,
+    /// Terrain height, only answering `ElevationRequest`
+    Elevation,
 }
 
-impl Forecast {
-    /// Initialize Forecast object
-    pub fn new() -> Self {
-        Forecast {
-            coordinates: Coordinates {
-                latitude: Default::default(),
-                longitude: Default::default(),
-            },
-            settings: Vec::new(),
-            hourly: Vec::new(),
-            pressure_var: Vec::new(),
-            daily: Vec::new(),
+impl Endpoint {
+    fn url(self) -> &'static str {
+        match self {
+            Endpoint::Forecast => BASE_URL,
+            Endpoint::Archive => archive::ARCHIVE_URL,
+            Endpoint::HistoricalForecast => HISTORICAL_FORECAST_URL,
+            Endpoint::AirQuality => air_quality::AIR_QUALITY_URL,
+            Endpoint::Marine => marine::MARINE_URL,
+            Endpoint::Flood => flood::FLOOD_URL,
+            Endpoint::Climate => climate::CLIMATE_URL,
+            Endpoint::Ensemble => ensemble_forecast::ENSEMBLE_URL,
+            Endpoint::Satellite => satellite::SATELLITE_URL,
+            Endpoint::Seasonal => seasonal::SEASONAL_URL,
+            Endpoint::Geocoding => GEOCODING_URL,
+            Endpoint::Elevation => ELEVATION_URL,
         }
     }
 
-    /// Specify coordinates (latitude, longitude)
-    /// These two are the only mandatory fields
-    pub fn coord(mut self, latitude: f32, longitude: f32) -> Self {
-        self.coordinates = Coordinates {
-            latitude,
-            longitude,
-        };
-        self
+    /// Whether requests must set both `start_date` and `end_date`
+    pub fn needs_dates(self) -> bool {
+        matches!(
+            self,
+            Endpoint::Archive
+                | Endpoint::HistoricalForecast
+                | Endpoint::Climate
+                | Endpoint::Satellite
+        )
     }
+}
 
-    /// Add optional settings
-    pub fn settings(mut self, setting: Settings) -> Self {
-        self.settings.push(setting);
-        self
-    }
+/// Request to an endpoint answering in the forecast response format
+#[cfg(any(feature = "client", feature = "blocking"))]
+pub(crate) trait EndpointRequest {
+    fn endpoint(&self) -> Endpoint;
 
-    /// Get hourly value for a specific data
-    pub fn hourly(mut self, hourly: Hourly) -> Self {
-        self.hourly.push(hourly);
-        self
+    /// Convert the request into a valid URL against the given base
+    fn to_url(&self, base: &str) -> String;
+}
+
+/// Sorted parameters of `query`, a URL against an empty base, tagged
+/// with the endpoint unless it is the forecast
+fn cache_key(endpoint: Endpoint, query: &str) -> String {
+    let tag = format!("endpoint={endpoint:?}");
+    let mut params: Vec<&str> = query.trim_start_matches('?').split('&').collect();
+    if endpoint != Endpoint::Forecast {
+        params.push(&tag);
     }
+    params.sort_unstable();
+    params.join("&")
+}
 
-    /// Get daily value for a specific data
-    pub fn daily(mut self, daily: Daily) -> Self {
-        self.daily.push(daily);
-        self
+/// Append `&name=,a,b` listing the variables, if any
+fn push_list(url: &mut String, name: &str, variables: &[impl fmt::Display]) {
+    if !variables.is_empty() {
+        url.push_str(&format!("&{name}="));
+        for el in variables {
+            url.push_str(format!(",{}", el).as_str());
+        }
     }
+}
 
+#[derive(Clone)]
+/// Geographic coordinates
+pub struct Coordinates {
+    latitude: f32,
+    longitude: f32,
+}
+
+#[derive(Clone)]
+/// Marker for a request whose coordinates have not been specified yet
+pub struct NoCoordinates;
+
+/// Basic data structure to keep all request's data
+pub type Forecast<C = Coordinates> = Request<ForecastSpec, C>;
+
+impl<C> Forecast<C> {
     /// Get Pressure Level-related variables
     pub fn pressure_var(mut self, pressure_var: PressureVar) -> Self {
         self.pressure_var.push(pressure_var);
         self
     }
+}
 
-    /// Convert the forecast struct into a valid URL
-    fn to_sring(&self) -> String {
-        let mut url = String::from(BASE_URL);
-        url.push_str(
-            format!(
-                "?latitude={}&longitude={}",
-                self.coordinates.latitude, self.coordinates.longitude
-            )
-            .as_str(),
-        );
-        for el in &self.settings {
-            url.push_str(format!("&{}={}", el.to_string(), el.get()).as_str());
+impl Forecast<Coordinates> {
+    /// Error for requests to an endpoint needing dates they do not set
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    pub(crate) fn check_dates(&self) -> Result<(), WeatheredError> {
+        match (self.endpoint.needs_dates(), self.date_range()) {
+            (true, None) => Err(WeatheredError::MissingDates {
+                url: self.to_string(),
+            }),
+            _ => Ok(()),
         }
-        if self.hourly.len() > 0 {
-            url.push_str("&hourly=");
-            for el in &self.hourly {
-                url.push_str(format!(",{}", el.to_string()).as_str());
-            }
-        }
-        if self.daily.len() > 0 {
-            url.push_str("&daily=");
-            for el in &self.daily {
-                url.push_str(format!(",{}", el.to_string()).as_str());
-            }
-        }
-        for el in &self.pressure_var {
-            url.push_str(format!("&{}", el.get()).as_str());
-        }
-        url
     }
-}
 
-impl fmt::Display for Forecast {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.to_sring())
+    /// Requested `start_date` and `end_date`, if both are set
+    pub(crate) fn date_range(&self) -> Option<(&str, &str)> {
+        let find =
+            |wanted: fn(&Settings) -> Option<&String>| self.settings.iter().rev().find_map(wanted);
+        let start = find(|setting| match setting {
+            Settings::start_date(date) => Some(date),
+            _ => None,
+        })?;
+        let end = find(|setting| match setting {
+            Settings::end_date(date) => Some(date),
+            _ => None,
+        })?;
+        Some((start, end))
+    }
+
+    /// Same request restricted to the given dates
+    #[cfg(feature = "client")]
+    pub(crate) fn with_date_range(&self, start: &str, end: &str) -> Self {
+        let mut forecast = self.clone();
+        forecast
+            .settings
+            .retain(|setting| !matches!(setting, Settings::start_date(_) | Settings::end_date(_)));
+        forecast
+            .settings(Settings::start_date(start.to_string()))
+            .settings(Settings::end_date(end.to_string()))
+    }
+
+    /// Number of hourly, daily and pressure level variables requested
+    #[cfg(feature = "tracing")]
+    pub(crate) fn variable_count(&self) -> usize {
+        self.hourly.len() + self.daily.len() + self.pressure_var.len()
+    }
+
+    /// Identify the request regardless of the host serving it
+    /// and of the order in which parameters were added
+    pub fn cache_key(&self) -> String {
+        cache_key(self.endpoint, &self.to_url(""))
+    }
+
+    /// URL requesting the same variables for several locations in a single call
+    #[cfg(feature = "client")]
+    pub(crate) fn to_url_many(&self, base: &str, locations: &[&Self]) -> String {
+        let join = |coordinate: fn(&Coordinates) -> f32| {
+            locations
+                .iter()
+                .map(|forecast| coordinate(&forecast.coordinates).to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let latitudes = join(|coordinates| coordinates.latitude);
+        let longitudes = join(|coordinates| coordinates.longitude);
+        format!(
+            "{base}?latitude={latitudes}&longitude={longitudes}{}",
+            self.params()
+        )
     }
 }
 
@@ -314,6 +722,36 @@ mod tests {
 
         assert_eq!(
             format!("{forecast}"),
-            "https://api.open-meteo.com/v1/forecast?latitude=50.1&longitude=50.1&elevation=1000.1&hourly=,rain,cape&daily=,sunrise,sunset&dewpoint_50hPa&windspeed_30hPa")
+            "https://api.open-meteo.com/v1/forecast?latitude=50.1&longitude=50.1&elevation=1000.1&hourly=,rain,cape,dewpoint_50hPa,windspeed_30hPa&daily=,sunrise,sunset")
+    }
+
+    #[test]
+    fn models_setting() {
+        let forecast = Forecast::new()
+            .coord(50.1, 8.7)
+            .settings(Settings::models(vec![
+                Model::icon_seamless,
+                Model::gfs_seamless,
+            ]));
+        assert!(forecast
+            .to_string()
+            .ends_with("&models=icon_seamless,gfs_seamless"));
+        assert_eq!("icon_d2".parse::<Model>(), Ok(Model::icon_d2));
+    }
+
+    #[test]
+    fn historical_forecast_host() {
+        let forecast = Forecast::new()
+            .coord(52.52, 13.41)
+            .endpoint(Endpoint::HistoricalForecast)
+            .hourly(Hourly::precipitation_probability);
+        assert_eq!(
+            forecast.to_string(),
+            "https://historical-forecast-api.open-meteo.com/v1/forecast?latitude=52.52&longitude=13.41&hourly=,precipitation_probability"
+        );
+        let live = forecast.clone().endpoint(Endpoint::Forecast);
+        assert_ne!(forecast.cache_key(), live.cache_key());
+        assert!(Endpoint::HistoricalForecast.needs_dates());
+        assert!(!Endpoint::Forecast.needs_dates());
     }
 }
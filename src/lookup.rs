@@ -0,0 +1,178 @@
+use crate::{Hourly, HourlyBlock, Moment};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate the ways `HourlyBlock::value_at` reads a series between two
+/// timestamps
+pub enum Lookup {
+    /// Value at the closer timestamp, the earlier one when halfway
+    Nearest,
+    /// Value at the timestamp before
+    Previous,
+    /// Value at the timestamp after
+    Next,
+    /// Straight line between the values at the timestamps around, missing
+    /// if either is
+    LinearInterpolate,
+}
+
+impl HourlyBlock {
+    /// Value of `variable` at exactly `at`, `None` if no timestamp is that
+    /// instant or there is no data
+    ///
+    /// ISO 8601 times are read in the offset of `at`, as by `value_at`
+    pub fn at(&self, variable: Hourly, at: impl Moment) -> Option<f64> {
+        let (offset, at) = (at.utc_offset_seconds(), at.unix_seconds());
+        let index = self
+            .time
+            .iter()
+            .position(|time| time.to_unix(offset) == Some(at))?;
+        self.value(variable, index)
+    }
+
+    /// Value of `variable` at `at`, read from the timestamps around it with
+    /// `mode`, or the value at `at` itself if it is on the time axis
+    ///
+    /// ISO 8601 times are read in the offset of `at`, which should be the
+    /// response's. `None` if `at` is outside the time axis, if a timestamp
+    /// cannot be read or if the value `mode` reads is missing
+    pub fn value_at(&self, variable: Hourly, at: impl Moment, mode: Lookup) -> Option<f64> {
+        let series = self.get(&variable.to_string())?;
+        let offset = at.utc_offset_seconds();
+        let at = at.unix_seconds();
+        let seconds: Vec<i64> = self
+            .time
+            .iter()
+            .map(|time| time.to_unix(offset))
+            .collect::<Option<_>>()?;
+
+        let after = seconds.partition_point(|&time| time <= at);
+        let before = after.checked_sub(1)?;
+        if seconds[before] == at {
+            return series.get(before).copied().flatten();
+        }
+        let (start, end) = (seconds[before], *seconds.get(after)?);
+        let value = |index: usize| series.get(index).copied().flatten();
+        match mode {
+            Lookup::Nearest if at - start <= end - at => value(before),
+            Lookup::Nearest => value(after),
+            Lookup::Previous => value(before),
+            Lookup::Next => value(after),
+            Lookup::LinearInterpolate => {
+                let (from, to) = (value(before)?, value(after)?);
+                Some(from + (to - from) * (at - start) as f64 / (end - start) as f64)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+    use crate::ForecastResponse;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn hourly() -> HourlyBlock {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/forecast.json")).unwrap();
+        response.hourly.unwrap()
+    }
+
+    #[test]
+    fn exact() {
+        let hourly = hourly();
+        let value = |time: &str| hourly.at(Hourly::temperature_2m, at(time));
+        assert_eq!(value("2023-05-16T14:00:00Z"), Some(17.9));
+        assert_eq!(value("2023-05-16T14:37:00Z"), None);
+        assert_eq!(value("2023-05-17T00:00:00Z"), None);
+        assert_eq!(
+            hourly.at(Hourly::snowfall, at("2023-05-16T14:00:00Z")),
+            None
+        );
+
+        // 14:00 local in the offset of the query
+        let paris: DateTime<chrono::FixedOffset> = "2023-05-16T14:00:00+02:00".parse().unwrap();
+        assert_eq!(hourly.at(Hourly::temperature_2m, paris), Some(17.9));
+    }
+
+    #[test]
+    fn off_grid() {
+        let hourly = hourly();
+        let event = at("2023-05-16T14:37:00Z");
+        let value = |mode| hourly.value_at(Hourly::temperature_2m, event, mode);
+
+        // 17.9 at 14:00, 17.7 at 15:00
+        assert_eq!(value(Lookup::Nearest), Some(17.7));
+        assert_eq!(value(Lookup::Previous), Some(17.9));
+        assert_eq!(value(Lookup::Next), Some(17.7));
+        let linear = value(Lookup::LinearInterpolate).unwrap();
+        assert!((linear - (17.9 - 0.2 * 37.0 / 60.0)).abs() < 1e-9);
+        assert_eq!(
+            hourly.value_at(
+                Hourly::temperature_2m,
+                at("2023-05-16T14:30:00Z"),
+                Lookup::Nearest
+            ),
+            Some(17.9)
+        );
+    }
+
+    #[test]
+    fn on_grid() {
+        let hourly = hourly();
+        for mode in [
+            Lookup::Nearest,
+            Lookup::Previous,
+            Lookup::Next,
+            Lookup::LinearInterpolate,
+        ] {
+            let value = |time| hourly.value_at(Hourly::temperature_2m, at(time), mode);
+            assert_eq!(value("2023-05-16T14:00:00Z"), Some(17.9));
+            assert_eq!(value("2023-05-16T00:00:00Z"), Some(9.8));
+            assert_eq!(value("2023-05-16T23:00:00Z"), Some(10.7));
+        }
+
+        // Wall-clock times of the response read in the offset of the query
+        let paris: DateTime<chrono::FixedOffset> = "2023-05-16T16:00:00+02:00".parse().unwrap();
+        assert_eq!(
+            hourly.value_at(Hourly::temperature_2m, paris, Lookup::Previous),
+            Some(17.1)
+        );
+    }
+
+    #[test]
+    fn out_of_range_and_gaps() {
+        let mut hourly = hourly();
+        for mode in [
+            Lookup::Nearest,
+            Lookup::Previous,
+            Lookup::Next,
+            Lookup::LinearInterpolate,
+        ] {
+            let value = |time| hourly.value_at(Hourly::temperature_2m, at(time), mode);
+            assert_eq!(value("2023-05-15T23:59:00Z"), None);
+            assert_eq!(value("2023-05-16T23:01:00Z"), None);
+        }
+        assert_eq!(
+            hourly.value_at(
+                Hourly::snowfall,
+                at("2023-05-16T14:00:00Z"),
+                Lookup::Nearest
+            ),
+            None
+        );
+
+        hourly.series["temperature_2m"].0[15] = None;
+        let event = at("2023-05-16T14:37:00Z");
+        let value = |mode| hourly.value_at(Hourly::temperature_2m, event, mode);
+        assert_eq!(value(Lookup::Previous), Some(17.9));
+        assert_eq!(value(Lookup::Next), None);
+        assert_eq!(value(Lookup::Nearest), None);
+        assert_eq!(value(Lookup::LinearInterpolate), None);
+    }
+}
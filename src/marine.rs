@@ -0,0 +1,159 @@
+use strum_macros::{Display, EnumString};
+
+use crate::request::{EndpointSpec, Request};
+use crate::{Cell, Coordinates, Endpoint, Settings};
+
+pub(crate) const MARINE_URL: &str = "https://marine-api.open-meteo.com/v1/marine";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[allow(non_camel_case_types)]
+/// Enumerate the hourly variables of the marine API
+pub enum MarineHourly {
+    wave_height,
+    wave_direction,
+    wave_period,
+    wind_wave_height,
+    wind_wave_direction,
+    wind_wave_period,
+    wind_wave_peak_period,
+    swell_wave_height,
+    swell_wave_direction,
+    swell_wave_period,
+    swell_wave_peak_period,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[allow(non_camel_case_types)]
+/// Enumerate the daily variables of the marine API
+pub enum MarineDaily {
+    wave_height_max,
+    wave_direction_dominant,
+    wave_period_max,
+    wind_wave_height_max,
+    wind_wave_direction_dominant,
+    wind_wave_period_max,
+    wind_wave_peak_period_max,
+    swell_wave_height_max,
+    swell_wave_direction_dominant,
+    swell_wave_period_max,
+    swell_wave_peak_period_max,
+}
+
+/// Marine request, sharing the coordinates and settings of a `Forecast`
+///
+/// Grid cells are selected on sea unless `Settings::cell_selection` says
+/// otherwise
+pub type Marine<C = Coordinates> = Request<MarineSpec, C>;
+
+#[derive(Clone)]
+/// Waves and swell of the marine API
+pub struct MarineSpec;
+
+impl EndpointSpec for MarineSpec {
+    type Hourly = MarineHourly;
+    type Daily = MarineDaily;
+
+    const ENDPOINT: Endpoint = Endpoint::Marine;
+
+    fn allows(setting: &Settings) -> bool {
+        !matches!(
+            setting,
+            Settings::elevation(_)
+                | Settings::current_weather(_)
+                | Settings::precipitation_unit(_)
+                | Settings::models(_)
+                | Settings::disable_bias_correction(_)
+                | Settings::tilt(_)
+                | Settings::azimuth(_)
+        )
+    }
+
+    fn mandatory(settings: &[Settings], url: &mut String) {
+        let cell_selected = settings
+            .iter()
+            .any(|setting| matches!(setting, Settings::cell_selection(_)));
+        if !cell_selected {
+            url.push_str(&format!("&cell_selection={}", Cell::sea));
+        }
+    }
+}
+
+#[cfg(feature = "response")]
+impl crate::ForecastResponse {
+    /// Values of an hourly marine variable, `None` if the response does not
+    /// contain it
+    pub fn marine(&self, variable: MarineHourly) -> Option<&crate::Series> {
+        self.hourly_by_name(&variable.to_string())
+    }
+
+    /// Values of a daily marine variable, `None` if the response does not
+    /// contain it
+    pub fn marine_daily(&self, variable: MarineDaily) -> Option<&[Option<crate::DailyValue>]> {
+        self.daily.as_ref()?.get(&variable.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Length;
+
+    #[test]
+    fn url_creation() {
+        let request = Marine::new()
+            .coord(54.54, 10.23)
+            .hourly(MarineHourly::wave_height)
+            .hourly(MarineHourly::swell_wave_period)
+            .daily(MarineDaily::wave_height_max)
+            .daily(MarineDaily::wind_wave_direction_dominant)
+            .settings(Settings::length_unit(Length::imperial));
+        assert_eq!(
+            request.to_string(),
+            "https://marine-api.open-meteo.com/v1/marine?latitude=54.54&longitude=10.23&length_unit=imperial&cell_selection=sea&hourly=,wave_height,swell_wave_period&daily=,wave_height_max,wind_wave_direction_dominant"
+        );
+
+        let nearest = request.settings(Settings::cell_selection(Cell::nearest));
+        assert_eq!(nearest.to_string().matches("cell_selection").count(), 1);
+        assert!(nearest.to_string().contains("&cell_selection=nearest"));
+    }
+
+    #[test]
+    fn forecast_settings_left_out() {
+        let request = Marine::new()
+            .coord(54.54, 10.23)
+            .settings(Settings::elevation(12.0))
+            .settings(Settings::timezone("auto".into()))
+            .hourly(MarineHourly::wave_height);
+        assert_eq!(
+            request.to_string(),
+            "https://marine-api.open-meteo.com/v1/marine?latitude=54.54&longitude=10.23&timezone=auto&cell_selection=sea&hourly=,wave_height"
+        );
+    }
+
+    #[cfg(feature = "response")]
+    #[test]
+    fn fixture() {
+        use crate::DailyValue;
+
+        let response =
+            crate::ForecastResponse::from_json(include_str!("../fixtures/marine.json")).unwrap();
+        let height = response.marine(MarineHourly::wave_height).unwrap();
+        assert_eq!(height.values(), [Some(0.42), Some(0.48), None, Some(0.56)]);
+        assert_eq!(
+            response.hourly_units.as_ref().unwrap().get("wave_height"),
+            Some("m")
+        );
+        assert_eq!(
+            response.marine_daily(MarineDaily::wave_height_max).unwrap()[0],
+            Some(DailyValue::Float(0.62))
+        );
+        assert_eq!(
+            response
+                .marine_daily(MarineDaily::swell_wave_direction_dominant)
+                .unwrap()[1],
+            Some(DailyValue::Float(251.0))
+        );
+        assert!(response.marine(MarineHourly::wind_wave_height).is_none());
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use std::{error, fmt};
+
+use indexmap::IndexMap;
+
+use crate::{DailyBlock, DailyValue, ForecastResponse, HourlyBlock, Timestamp};
+
+/// Largest difference between two values of an overlapping sample still
+/// taken as the same value
+const TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Enumerate the reasons `ForecastResponse::concat` refuses to merge
+pub enum MergeError {
+    /// The responses are for different coordinates
+    Coordinates {
+        first: (f64, f64),
+        second: (f64, f64),
+    },
+    /// A block has different units in the two responses
+    Units { block: &'static str },
+    /// A block is missing from one response or has different variables
+    Variables { block: &'static str },
+    /// The responses disagree on a value at a timestamp they share
+    Conflict {
+        block: &'static str,
+        variable: String,
+        time: Timestamp,
+    },
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::Coordinates { first, second } => write!(
+                f,
+                "responses are for {},{} and {},{}",
+                first.0, first.1, second.0, second.1
+            ),
+            MergeError::Units { block } => write!(f, "{block} units differ"),
+            MergeError::Variables { block } => write!(f, "{block} variables differ"),
+            MergeError::Conflict {
+                block,
+                variable,
+                time,
+            } => write!(f, "{block} `{variable}` differs at {time}"),
+        }
+    }
+}
+
+impl error::Error for MergeError {}
+
+impl ForecastResponse {
+    /// One response with the samples of both, taking `other` as the newer one
+    ///
+    /// Time axes are merged in order and timestamps present in both responses
+    /// appear once, with the values of `other` where it has data. Both
+    /// responses must be for the same coordinates, with the same units and
+    /// variables, and agree on the values they share; the metadata and
+    /// current conditions are those of `other`
+    pub fn concat(self, other: ForecastResponse) -> Result<ForecastResponse, MergeError> {
+        if (self.latitude, self.longitude) != (other.latitude, other.longitude) {
+            return Err(MergeError::Coordinates {
+                first: (self.latitude, self.longitude),
+                second: (other.latitude, other.longitude),
+            });
+        }
+        for (block, first, second) in [
+            (
+                "15-minutely",
+                &self.minutely_15_units,
+                &other.minutely_15_units,
+            ),
+            ("hourly", &self.hourly_units, &other.hourly_units),
+            ("daily", &self.daily_units, &other.daily_units),
+        ] {
+            if first != second {
+                return Err(MergeError::Units { block });
+            }
+        }
+        let minutely_15 = concat_series("15-minutely", self.minutely_15, other.minutely_15)?;
+        let hourly = concat_series("hourly", self.hourly, other.hourly)?;
+        let daily = match (self.daily, other.daily) {
+            (None, None) => None,
+            (Some(first), Some(second)) => {
+                let (time, series) = concat_block(
+                    "daily",
+                    (first.time, first.series),
+                    (second.time, second.series),
+                    |first, second| match (first, second) {
+                        (DailyValue::Float(first), DailyValue::Float(second)) => {
+                            (first - second).abs() > TOLERANCE
+                        }
+                        (first, second) => first != second,
+                    },
+                )?;
+                Some(DailyBlock { time, series })
+            }
+            _ => return Err(MergeError::Variables { block: "daily" }),
+        };
+        Ok(ForecastResponse {
+            minutely_15,
+            hourly,
+            daily,
+            ..other
+        })
+    }
+}
+
+fn concat_series(
+    block: &'static str,
+    first: Option<HourlyBlock>,
+    second: Option<HourlyBlock>,
+) -> Result<Option<HourlyBlock>, MergeError> {
+    let (first, second) = match (first, second) {
+        (None, None) => return Ok(None),
+        (Some(first), Some(second)) => (first, second),
+        _ => return Err(MergeError::Variables { block }),
+    };
+    let values = |block: HourlyBlock| {
+        let series = block.series.into_iter();
+        (
+            block.time,
+            series.map(|(name, series)| (name, series.0)).collect(),
+        )
+    };
+    let (time, series) = concat_block(block, values(first), values(second), |first, second| {
+        (first - second).abs() > TOLERANCE
+    })?;
+    Ok(Some(HourlyBlock {
+        time,
+        series: series
+            .into_iter()
+            .map(|(name, values)| (name, values.into()))
+            .collect(),
+    }))
+}
+
+type Block<V> = (Vec<Timestamp>, IndexMap<String, Vec<Option<V>>>);
+
+/// Union of the time axes and values of two blocks, preferring those of `second`
+fn concat_block<V: Clone>(
+    block: &'static str,
+    (first_time, first): Block<V>,
+    (second_time, second): Block<V>,
+    conflict: fn(&V, &V) -> bool,
+) -> Result<Block<V>, MergeError> {
+    let same_variables =
+        first.len() == second.len() && second.keys().all(|name| first.contains_key(name));
+    if !same_variables {
+        return Err(MergeError::Variables { block });
+    }
+    // Index of each timestamp in either block
+    let mut rows: BTreeMap<&Timestamp, (Option<usize>, Option<usize>)> = BTreeMap::new();
+    for (index, time) in first_time.iter().enumerate() {
+        rows.entry(time).or_default().0 = Some(index);
+    }
+    for (index, time) in second_time.iter().enumerate() {
+        rows.entry(time).or_default().1 = Some(index);
+    }
+    let mut series = IndexMap::with_capacity(first.len());
+    for (name, first) in &first {
+        let second = &second[name];
+        let sample = |values: &[Option<V>], index: Option<usize>| values.get(index?).cloned()?;
+        let values = rows
+            .iter()
+            .map(|(&time, &(first_index, second_index))| {
+                match (sample(first, first_index), sample(second, second_index)) {
+                    (Some(first), Some(second)) if conflict(&first, &second) => {
+                        Err(MergeError::Conflict {
+                            block,
+                            variable: name.clone(),
+                            time: time.clone(),
+                        })
+                    }
+                    (first, second) => Ok(second.or(first)),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        series.insert(name.clone(), values);
+    }
+    Ok((rows.into_keys().cloned().collect(), series))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testing::ResponseBuilder;
+    use crate::{Daily, Hourly};
+
+    fn day(date: &str, temperature: f64) -> ForecastResponse {
+        ResponseBuilder::new()
+            .coord(52.52, 13.41)
+            .start(date)
+            .hourly(
+                Hourly::temperature_2m,
+                (0..24).map(|hour| temperature + hour as f64),
+            )
+            .hourly_unit(Hourly::temperature_2m, "°C")
+            .daily(Daily::temperature_2m_max, [temperature + 23.0])
+            .build()
+    }
+
+    #[test]
+    fn adjacent_days() {
+        let merged = day("2024-03-01", 0.0)
+            .concat(day("2024-03-02", 1.0))
+            .unwrap();
+        let hourly = merged.hourly.unwrap();
+        assert_eq!(hourly.len(), 48);
+        assert_eq!(
+            hourly.time[24],
+            Timestamp::Iso8601("2024-03-02T00:00".into())
+        );
+        assert_eq!(hourly.get("temperature_2m").unwrap()[25], Some(2.0));
+        let daily = merged.daily.unwrap();
+        assert_eq!(daily.time.len(), 2);
+
+        // In either order
+        let reversed = day("2024-03-02", 1.0)
+            .concat(day("2024-03-01", 0.0))
+            .unwrap();
+        assert_eq!(reversed.hourly.unwrap(), hourly);
+    }
+
+    #[test]
+    fn identical_overlap() {
+        let mut yesterday = day("2024-03-01", 0.0);
+        let hourly = yesterday.hourly.as_mut().unwrap();
+        hourly.series[0].0[23] = None;
+        let merged = yesterday.concat(day("2024-03-01", 0.0)).unwrap();
+
+        let hourly = merged.hourly.unwrap();
+        assert_eq!(hourly.len(), 24);
+        // Missing data filled from the newer response
+        assert_eq!(hourly.get("temperature_2m").unwrap()[23], Some(23.0));
+    }
+
+    #[test]
+    fn conflicting_overlap() {
+        let error = day("2024-03-01", 0.0)
+            .concat(day("2024-03-01", 0.5))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            MergeError::Conflict {
+                block: "hourly",
+                variable: "temperature_2m".into(),
+                time: Timestamp::Iso8601("2024-03-01T00:00".into()),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "hourly `temperature_2m` differs at 2024-03-01T00:00"
+        );
+
+        let elsewhere = ForecastResponse {
+            latitude: 48.85,
+            ..day("2024-03-02", 1.0)
+        };
+        assert!(matches!(
+            day("2024-03-01", 0.0).concat(elsewhere),
+            Err(MergeError::Coordinates { .. })
+        ));
+        let other_units = ResponseBuilder::new()
+            .coord(52.52, 13.41)
+            .start("2024-03-02")
+            .hourly(Hourly::temperature_2m, [1.0])
+            .hourly_unit(Hourly::temperature_2m, "°F")
+            .daily(Daily::temperature_2m_max, [24.0])
+            .build();
+        assert_eq!(
+            day("2024-03-01", 0.0).concat(other_units),
+            Err(MergeError::Units { block: "hourly" })
+        );
+        let no_daily = ForecastResponse {
+            daily: None,
+            ..day("2024-03-02", 1.0)
+        };
+        assert_eq!(
+            day("2024-03-01", 0.0).concat(no_daily),
+            Err(MergeError::Variables { block: "daily" })
+        );
+    }
+}
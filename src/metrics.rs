@@ -0,0 +1,128 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{Endpoint, FetchOutcome};
+
+#[derive(Debug, Clone, PartialEq)]
+/// Summary of a completed `Client` call
+pub struct RequestStats {
+    pub endpoint: Endpoint,
+    /// Time spent in the call, including waits, retries and parsing
+    pub duration: Duration,
+    /// Size of the last response body, zero when served from the cache
+    pub bytes: usize,
+    /// Status of the last response, if any was received
+    pub status: Option<u16>,
+    /// Where the forecast comes from, `None` if the call failed
+    pub outcome: Option<FetchOutcome>,
+    pub retries: u32,
+    /// Base URL of the host that answered, which may be a fallback mirror
+    pub base_url: Option<String>,
+}
+
+impl RequestStats {
+    pub(crate) fn new() -> Self {
+        RequestStats {
+            endpoint: Endpoint::Forecast,
+            duration: Duration::ZERO,
+            bytes: 0,
+            status: None,
+            outcome: None,
+            retries: 0,
+            base_url: None,
+        }
+    }
+}
+
+/// Receiver of per-call statistics, to bridge to any metrics library
+pub trait MetricsSink: Send + Sync {
+    fn on_request_complete(&self, stats: RequestStats);
+}
+
+impl<S: MetricsSink + ?Sized> MetricsSink for Arc<S> {
+    fn on_request_complete(&self, stats: RequestStats) {
+        (**self).on_request_complete(stats)
+    }
+}
+
+/// Receiver of the progress of batch and chunked fetches, called after each
+/// sub-request with the number completed so far, the total and the statistics
+/// of the last one; panics in the callback are caught and ignored
+pub trait ProgressSink: Send + Sync {
+    fn on_progress(&self, completed: usize, total: usize, last: &RequestStats);
+}
+
+impl<F: Fn(usize, usize, &RequestStats) + Send + Sync> ProgressSink for F {
+    fn on_progress(&self, completed: usize, total: usize, last: &RequestStats) {
+        self(completed, total, last)
+    }
+}
+
+#[derive(Debug, Default)]
+/// Metrics sink keeping running totals in atomic counters
+pub struct AtomicMetrics {
+    requests: AtomicU64,
+    cache_hits: AtomicU64,
+    failures: AtomicU64,
+    retries: AtomicU64,
+    bytes: AtomicU64,
+    micros: AtomicU64,
+}
+
+impl AtomicMetrics {
+    /// Initialize all counters to zero
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Completed calls, successful or not
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Calls answered by the response cache
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    /// Calls that returned an error
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Retried attempts, across all calls
+    pub fn retries(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    /// Response bytes received, across all calls
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Time spent in all calls
+    pub fn total_duration(&self) -> Duration {
+        Duration::from_micros(self.micros.load(Ordering::Relaxed))
+    }
+}
+
+impl MetricsSink for AtomicMetrics {
+    fn on_request_complete(&self, stats: RequestStats) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        match stats.outcome {
+            Some(FetchOutcome::Cached) => {
+                self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            }
+            Some(_) => {}
+            None => {
+                self.failures.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.retries
+            .fetch_add(stats.retries as u64, Ordering::Relaxed);
+        self.bytes.fetch_add(stats.bytes as u64, Ordering::Relaxed);
+        self.micros
+            .fetch_add(stats.duration.as_micros() as u64, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,251 @@
+//! Minimal HTTP/1.1 server answering scripted responses, used by the tests
+
+// Not every helper is used by every feature combination
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Canned answer served by the mock server
+#[derive(Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub delay: Option<Duration>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        MockResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            delay: None,
+        }
+    }
+
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(200, body)
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Successful response compressed with gzip
+    #[cfg(feature = "gzip")]
+    pub fn gzip(body: &[u8]) -> Self {
+        use flate2::write::GzEncoder;
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(body).unwrap();
+        Self::ok(encoder.finish().unwrap()).header("Content-Encoding", "gzip")
+    }
+
+    /// Wait before answering
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+/// Request as received by the mock server
+pub struct RecordedRequest {
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Default)]
+struct State {
+    script: VecDeque<MockResponse>,
+    last: Option<MockResponse>,
+    requests: Vec<RecordedRequest>,
+    connections: usize,
+}
+
+impl State {
+    /// Next scripted response, repeating the last one once the script is exhausted
+    fn next(&mut self) -> Option<MockResponse> {
+        if let Some(response) = self.script.pop_front() {
+            self.last = Some(response);
+        }
+        self.last.clone()
+    }
+}
+
+/// Local keep-alive server answering each request with the next scripted response
+pub struct MockServer {
+    address: String,
+    state: Arc<Mutex<State>>,
+    certificate: Option<String>,
+}
+
+impl MockServer {
+    pub fn start(script: Vec<MockResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let state = Self::state(script);
+        let shared = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                shared.lock().unwrap().connections += 1;
+                let shared = shared.clone();
+                thread::spawn(move || serve(stream, shared));
+            }
+        });
+        MockServer {
+            address,
+            state,
+            certificate: None,
+        }
+    }
+
+    /// Serve HTTPS on `localhost` with a freshly generated self-signed certificate
+    pub fn start_tls(script: Vec<MockResponse>) -> Self {
+        use rustls::pki_types::PrivateKeyDer;
+
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let key = PrivateKeyDer::Pkcs8(certified.key_pair.serialize_der().into());
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(vec![certified.cert.der().clone()], key)
+            .unwrap();
+        let config = Arc::new(config);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let state = Self::state(script);
+        let shared = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                shared.lock().unwrap().connections += 1;
+                let shared = shared.clone();
+                let connection = rustls::ServerConnection::new(config.clone()).unwrap();
+                let stream = rustls::StreamOwned::new(connection, stream);
+                thread::spawn(move || serve(stream, shared));
+            }
+        });
+        MockServer {
+            address: format!("https://localhost:{port}"),
+            state,
+            certificate: Some(certified.cert.pem()),
+        }
+    }
+
+    fn state(script: Vec<MockResponse>) -> Arc<Mutex<State>> {
+        Arc::new(Mutex::new(State {
+            script: script.into(),
+            ..Default::default()
+        }))
+    }
+
+    /// PEM encoded certificate of a server started with `start_tls`
+    pub fn certificate(&self) -> Option<&str> {
+        self.certificate.as_deref()
+    }
+
+    /// Base address of the server, e.g. `http://127.0.0.1:4242`
+    pub fn url(&self) -> &str {
+        &self.address
+    }
+
+    pub fn requests(&self) -> usize {
+        self.state.lock().unwrap().requests.len()
+    }
+
+    pub fn connections(&self) -> usize {
+        self.state.lock().unwrap().connections
+    }
+
+    pub fn last_path(&self) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state.requests.last().map(|r| r.path.clone())
+    }
+
+    /// Value of a header in the last received request
+    pub fn last_header(&self, name: &str) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state.requests.last()?.header(name).map(str::to_string)
+    }
+}
+
+/// Answer requests on a connection until the client closes it
+fn serve(stream: impl Read + Write, state: Arc<Mutex<State>>) {
+    let mut reader = BufReader::new(stream);
+    while let Some(request) = read_request(&mut reader) {
+        let response = {
+            let mut state = state.lock().unwrap();
+            state.requests.push(request);
+            state.next()
+        };
+        match response {
+            Some(response) => {
+                if let Some(delay) = response.delay {
+                    thread::sleep(delay);
+                }
+                if write_response(reader.get_mut(), &response).is_err() {
+                    return;
+                }
+            }
+            None => return,
+        }
+    }
+}
+
+fn read_request(reader: &mut impl BufRead) -> Option<RecordedRequest> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).ok()? == 0 {
+        return None;
+    }
+    let path = line.split_whitespace().nth(1)?.to_string();
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    Some(RecordedRequest { path, headers })
+}
+
+fn write_response(stream: &mut impl Write, response: &MockResponse) -> std::io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(&response.body)?;
+    stream.flush()
+}
@@ -0,0 +1,92 @@
+use std::io::{self, Write};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{ForecastResponse, Timestamp};
+
+impl ForecastResponse {
+    /// Write one JSON object per hourly timestamp, each on its own line
+    ///
+    /// Objects hold `time`, `location_id` and every variable under the name
+    /// returned by the API, `null` where it has no data
+    pub fn to_ndjson(&self, writer: impl Write) -> io::Result<()> {
+        let rows = self.hourly.iter().flat_map(|hourly| hourly.rows());
+        write_lines(
+            writer,
+            self.location_id,
+            rows.map(|row| (row.time(), row.values().collect::<Vec<_>>())),
+        )
+    }
+
+    /// Write one JSON object per day, like `to_ndjson` for the daily block
+    pub fn daily_to_ndjson(&self, writer: impl Write) -> io::Result<()> {
+        let rows = self.daily.iter().flat_map(|daily| daily.rows());
+        write_lines(
+            writer,
+            self.location_id,
+            rows.map(|row| (row.time(), row.values().collect::<Vec<_>>())),
+        )
+    }
+}
+
+fn write_lines<'a, T: Serialize + 'a>(
+    mut writer: impl Write,
+    location_id: u32,
+    rows: impl Iterator<Item = (&'a Timestamp, Vec<(&'a str, T)>)>,
+) -> io::Result<()> {
+    for (time, values) in rows {
+        let mut object = Map::new();
+        object.insert("time".to_string(), serde_json::to_value(time)?);
+        object.insert("location_id".to_string(), location_id.into());
+        for (name, value) in values {
+            object.insert(name.to_string(), serde_json::to_value(value)?);
+        }
+        serde_json::to_writer(&mut writer, &Value::Object(object))?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn lines(ndjson: Vec<u8>) -> Vec<Value> {
+        String::from_utf8(ndjson)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn one_line_per_timestamp() {
+        let response = ForecastResponse::from_json(include_str!("../fixtures/mixed.json")).unwrap();
+
+        let mut hourly = Vec::new();
+        response.to_ndjson(&mut hourly).unwrap();
+        assert_eq!(
+            lines(hourly),
+            [
+                serde_json::json!({"time": "2024-06-20T12:00", "location_id": 0, "temperature_2m": 19.6, "rain": 0.0}),
+                serde_json::json!({"time": "2024-06-20T13:00", "location_id": 0, "temperature_2m": 20.3, "rain": 0.3}),
+                serde_json::json!({"time": "2024-06-20T14:00", "location_id": 0, "temperature_2m": null, "rain": 0.0}),
+            ]
+        );
+
+        let mut daily = Vec::new();
+        response.daily_to_ndjson(&mut daily).unwrap();
+        assert_eq!(
+            lines(daily)[1],
+            serde_json::json!({
+                "time": "2024-06-21",
+                "location_id": 0,
+                "weather_code": 3,
+                "temperature_2m_max": null,
+                "sunrise": "2024-06-21T03:54",
+            })
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use crate::date::{days_in_month, Date};
+use crate::{ForecastResponse, Hourly};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Climatology of one day of the year
+pub struct DayNormal {
+    /// Mean of the daily means over the years
+    pub mean: f64,
+    /// Population standard deviation of the daily means over the years
+    pub std_dev: f64,
+    /// Years with at least one sample on that day
+    pub years: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Mean and spread of a variable for each day of the year, from the
+/// February 29 of a leap calendar, as computed by `Client::climate_normals`
+pub struct Normals {
+    days: Vec<Option<DayNormal>>,
+}
+
+impl Normals {
+    /// Normals of an hourly variable over all the years of the response,
+    /// every day empty if the response does not contain it
+    pub fn from_response(response: &ForecastResponse, variable: Hourly) -> Self {
+        let mut daily: BTreeMap<(i32, usize), (f64, usize)> = BTreeMap::new();
+        let samples = response.hourly.as_ref().zip(response.hourly(variable));
+        if let Some((block, series)) = samples {
+            for (time, value) in block.time.iter().zip(series.values()) {
+                let date = Date::local(time, response.utc_offset_seconds);
+                if let Some((date, value)) = date.zip(*value) {
+                    let (sum, count) = daily.entry((date.year, day_of_year(date))).or_default();
+                    *sum += value;
+                    *count += 1;
+                }
+            }
+        }
+        let mut means = vec![Vec::new(); 366];
+        for ((_, day), (sum, count)) in daily {
+            means[day].push(sum / count as f64);
+        }
+        let days = means
+            .into_iter()
+            .map(|means| {
+                let years = means.len();
+                (years > 0).then(|| {
+                    let mean = means.iter().sum::<f64>() / years as f64;
+                    let variance =
+                        means.iter().map(|day| (day - mean).powi(2)).sum::<f64>() / years as f64;
+                    DayNormal {
+                        mean,
+                        std_dev: variance.sqrt(),
+                        years,
+                    }
+                })
+            })
+            .collect();
+        Normals { days }
+    }
+
+    /// Normal of the calendar day of `date`, `YYYY-MM-DD`, `None` if the date
+    /// is invalid or no year has data on that day
+    pub fn lookup(&self, date: &str) -> Option<&DayNormal> {
+        self.days[day_of_year(Date::parse(date)?)].as_ref()
+    }
+
+    /// The 366 days, January 1 first
+    pub fn days(&self) -> &[Option<DayNormal>] {
+        &self.days
+    }
+}
+
+/// Index of the day in a leap year, so that a calendar day always has the
+/// same index
+fn day_of_year(date: Date) -> usize {
+    let before: u32 = (1..date.month)
+        .map(|month| days_in_month(2000, month))
+        .sum();
+    (before + date.day - 1) as usize
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn day_of_year_values() {
+        let normals = Normals::from_response(
+            &ForecastResponse::from_json(include_str!("../fixtures/normals.json")).unwrap(),
+            Hourly::temperature_2m,
+        );
+        assert_eq!(normals.days().len(), 366);
+
+        let february = normals.lookup("2023-02-28").unwrap();
+        assert_eq!(february.mean, 5.0);
+        assert!((february.std_dev - 6f64.sqrt()).abs() < 1e-9);
+        assert_eq!(february.years, 3);
+        assert_eq!(
+            normals.lookup("2024-02-29"),
+            Some(&DayNormal {
+                mean: 10.0,
+                std_dev: 0.0,
+                years: 1
+            })
+        );
+        let march = normals.lookup("2023-03-01").unwrap();
+        assert_eq!((march.mean, march.std_dev, march.years), (2.0, 1.0, 2));
+
+        assert_eq!(normals.lookup("2023-02-29"), None);
+        assert_eq!(normals.lookup("2023-07-14"), None);
+        assert_eq!(day_of_year(Date::parse("2001-12-31").unwrap()), 365);
+    }
+}
@@ -0,0 +1,42 @@
+//! Instants of response timestamps, read in the UTC offset of the response,
+//! with the `chrono` or `time` crate
+
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "chrono")]
+mod maps;
+#[cfg(feature = "time")]
+mod time;
+#[cfg(feature = "chrono-tz")]
+mod tz;
+
+use crate::{Forecast, Settings};
+
+#[cfg(all(feature = "chrono", any(test, feature = "testing")))]
+pub use maps::from_map;
+#[cfg(feature = "chrono")]
+pub use maps::MapError;
+
+/// Calendar date of the `chrono` or `time` crate, usable as a request date
+pub trait CalendarDate {
+    /// Date formatted as `YYYY-MM-DD`
+    fn to_api_date(&self) -> String;
+}
+
+/// Date-time of the `chrono` or `time` crate, usable to slice responses
+/// and look values up
+pub trait Moment {
+    /// Seconds since the epoch
+    fn unix_seconds(&self) -> i64;
+
+    /// Offset of the date-time from UTC
+    fn utc_offset_seconds(&self) -> i32;
+}
+
+impl<C> Forecast<C> {
+    /// Request the days from `start` to `end`, both included
+    pub fn dates(self, start: impl CalendarDate, end: impl CalendarDate) -> Self {
+        self.settings(Settings::start_date(start.to_api_date()))
+            .settings(Settings::end_date(end.to_api_date()))
+    }
+}
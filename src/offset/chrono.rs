@@ -0,0 +1,248 @@
+use chrono::{
+    DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeDelta, TimeZone,
+};
+
+use super::{CalendarDate, Moment};
+use crate::{
+    Agg, BlockSlice, Current, CurrentWeather, ForecastResponse, HourlyBlock, Series, Timestamp,
+};
+
+impl ForecastResponse {
+    /// Offset of the requested timezone, zero when none was requested
+    pub fn utc_offset(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.utc_offset_seconds).unwrap_or(FixedOffset::east_opt(0).unwrap())
+    }
+
+    /// Hourly timestamps in the response's offset, `None` without an hourly block
+    /// or if a timestamp is malformed
+    pub fn hourly_time(&self) -> Option<Vec<DateTime<FixedOffset>>> {
+        in_offset(&self.hourly.as_ref()?.time, self.utc_offset())
+    }
+
+    /// Start of each day in the response's offset, `None` without a daily block
+    /// or if a date is malformed
+    pub fn daily_time(&self) -> Option<Vec<DateTime<FixedOffset>>> {
+        in_offset(&self.daily.as_ref()?.time, self.utc_offset())
+    }
+}
+
+impl HourlyBlock {
+    /// Samples of each local calendar day, for a response `utc_offset_seconds`
+    /// ahead of UTC, in the order of the time axis
+    pub fn group_by_day(&self, utc_offset_seconds: i32) -> Vec<(NaiveDate, BlockSlice<'_>)> {
+        self.day_ranges(utc_offset_seconds)
+            .into_iter()
+            .filter_map(|(days, range)| {
+                let date =
+                    NaiveDate::from_num_days_from_ce_opt(i32::try_from(days).ok()? + 719_163)?;
+                Some((date, BlockSlice::new(self, range)))
+            })
+            .collect()
+    }
+
+    /// `Series::rolling` of `variable` over the trailing `window`, counted in
+    /// steps of the time axis; `None` if the variable is missing or the block
+    /// has less than two timestamps
+    pub fn rolling(&self, variable: &str, window: TimeDelta, agg: Agg) -> Option<Series> {
+        let steps = window.num_seconds() / self.step_seconds()?;
+        Some(self.get(variable)?.rolling(steps.max(1) as usize, agg))
+    }
+}
+
+impl Timestamp {
+    /// Instant of the timestamp, reading ISO 8601 wall-clock times and dates
+    /// (at midnight) in `offset`; unix seconds are already absolute
+    pub fn to_datetime(&self, offset: FixedOffset) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Timestamp::Unix(seconds) => {
+                Some(DateTime::from_timestamp(*seconds, 0)?.with_timezone(&offset))
+            }
+            Timestamp::Iso8601(time) => {
+                let local = NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M")
+                    .or_else(|_| {
+                        NaiveDate::parse_from_str(time, "%Y-%m-%d")
+                            .map(|date| date.and_time(NaiveTime::MIN))
+                    })
+                    .ok()?;
+                offset.from_local_datetime(&local).single()
+            }
+        }
+    }
+}
+
+impl CalendarDate for NaiveDate {
+    fn to_api_date(&self) -> String {
+        self.format("%Y-%m-%d").to_string()
+    }
+}
+
+impl<Tz: TimeZone> Moment for DateTime<Tz> {
+    fn unix_seconds(&self) -> i64 {
+        self.timestamp()
+    }
+
+    fn utc_offset_seconds(&self) -> i32 {
+        self.offset().fix().local_minus_utc()
+    }
+}
+
+impl Current {
+    /// Length of the interval the values are aggregated over
+    pub fn interval_delta(&self) -> Option<chrono::TimeDelta> {
+        self.interval
+            .map(|seconds| chrono::TimeDelta::seconds(seconds.into()))
+    }
+}
+
+impl CurrentWeather {
+    /// Length of the interval the values are aggregated over
+    pub fn interval_delta(&self) -> Option<chrono::TimeDelta> {
+        self.interval
+            .map(|seconds| chrono::TimeDelta::seconds(seconds.into()))
+    }
+}
+
+fn in_offset(time: &[Timestamp], offset: FixedOffset) -> Option<Vec<DateTime<FixedOffset>>> {
+    time.iter().map(|time| time.to_datetime(offset)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Forecast;
+    use chrono::Utc;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn local_times_carry_the_offset() {
+        let response =
+            ForecastResponse::from_json(include_str!("../../fixtures/darwin.json")).unwrap();
+        let offset = response.utc_offset();
+        assert_eq!(offset.local_minus_utc(), 9 * 3600 + 30 * 60);
+
+        let hourly = response.hourly_time().unwrap();
+        assert_eq!(hourly[0], utc("2023-10-19T14:30:00Z"));
+        assert_eq!(hourly[2], utc("2023-10-19T16:30:00Z"));
+        assert_eq!(hourly[0].offset(), &offset);
+        assert_eq!(hourly[0].to_rfc3339(), "2023-10-20T00:00:00+09:30");
+        assert_eq!(
+            response.daily_time().unwrap(),
+            [utc("2023-10-19T14:30:00Z")]
+        );
+        let sunrise = response.daily.as_ref().unwrap().get("sunrise").unwrap()[0].clone();
+        let sunrise = sunrise
+            .unwrap()
+            .as_time()
+            .unwrap()
+            .to_datetime(offset)
+            .unwrap();
+        assert_eq!(sunrise, utc("2023-10-19T21:02:00Z"));
+
+        // Unix seconds are instants regardless of the offset
+        let unix = Timestamp::Unix(1697725800).to_datetime(offset).unwrap();
+        assert_eq!(unix, utc("2023-10-19T14:30:00Z"));
+        assert_eq!(unix.to_rfc3339(), "2023-10-20T00:00:00+09:30");
+        assert_eq!(
+            Timestamp::Iso8601("yesterday".into()).to_datetime(offset),
+            None
+        );
+    }
+
+    #[test]
+    fn time_formats_give_the_same_instants() {
+        let iso = ForecastResponse::from_json(include_str!("../../fixtures/darwin.json")).unwrap();
+        let unix = ForecastResponse::from_json(include_str!("../../fixtures/darwin_unixtime.json"))
+            .unwrap();
+
+        assert_eq!(iso.hourly_time().unwrap(), unix.hourly_time().unwrap());
+        assert_eq!(iso.daily_time().unwrap(), unix.daily_time().unwrap());
+        for response in [iso, unix] {
+            let rfc3339: Vec<_> = response
+                .hourly_time()
+                .unwrap()
+                .iter()
+                .map(DateTime::to_rfc3339)
+                .collect();
+            assert_eq!(rfc3339[1], "2023-10-20T01:00:00+09:30");
+        }
+    }
+
+    #[test]
+    fn gmt_by_default() {
+        let response =
+            ForecastResponse::from_json(include_str!("../../fixtures/forecast.json")).unwrap();
+        assert_eq!(response.utc_offset().local_minus_utc(), 0);
+        assert_eq!(
+            response.hourly_time().unwrap()[19],
+            utc("2023-05-16T19:00:00Z")
+        );
+        let current = response.current_weather.unwrap();
+        assert_eq!(
+            current.interval_delta(),
+            Some(chrono::TimeDelta::minutes(15))
+        );
+    }
+
+    #[test]
+    fn rolling_duration() {
+        let response =
+            ForecastResponse::from_json(include_str!("../../fixtures/forecast.json")).unwrap();
+        let hourly = response.hourly.unwrap();
+
+        let rain = hourly
+            .rolling("rain", TimeDelta::hours(3), Agg::Sum)
+            .unwrap();
+        assert_eq!(rain.len(), 24);
+        assert_eq!(rain[1], None);
+        assert_eq!(rain[16], Some(0.2 + 1.4 + 0.6));
+        assert_eq!(
+            hourly.rolling("rain", TimeDelta::minutes(30), Agg::Sum),
+            hourly.get("rain").cloned()
+        );
+        assert_eq!(
+            hourly.rolling("snowfall", TimeDelta::hours(3), Agg::Sum),
+            None
+        );
+    }
+
+    #[test]
+    fn days_split_at_local_midnight() {
+        let response =
+            ForecastResponse::from_json(include_str!("../../fixtures/chatham.json")).unwrap();
+        let hourly = response.hourly.as_ref().unwrap();
+
+        let days = hourly.group_by_day(response.utc_offset_seconds);
+        let dates: Vec<_> = days.iter().map(|(date, _)| date.to_string()).collect();
+        assert_eq!(dates, ["2024-01-01", "2024-01-02"]);
+        // 23:00 local, 09:15 UTC, stays on the first day
+        let evening = days[0].1.time()[2]
+            .to_datetime(response.utc_offset())
+            .unwrap();
+        assert_eq!(evening.to_rfc3339(), "2024-01-01T23:00:00+13:45");
+        let night = days[1].1.time()[0]
+            .to_datetime(response.utc_offset())
+            .unwrap();
+        assert_eq!(night.to_rfc3339(), "2024-01-02T00:00:00+13:45");
+        assert_eq!(days[1].1.range(), 3..6);
+        assert_eq!(days[1].1.get("temperature_2m").unwrap()[0], Some(13.3));
+
+        let utc = hourly.group_by_day(0);
+        assert_eq!(utc.len(), 1);
+        assert_eq!(utc[0].0.to_string(), "2024-01-01");
+    }
+
+    #[test]
+    fn date_setters() {
+        let start = NaiveDate::from_ymd_opt(2023, 2, 27).unwrap();
+        let end = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+        let url = Forecast::new()
+            .coord(52.52, 13.41)
+            .dates(start, end)
+            .to_url("");
+        assert!(url.ends_with("&start_date=2023-02-27&end_date=2023-03-01"));
+    }
+}
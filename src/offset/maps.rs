@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::{error, fmt};
+
+use chrono::{DateTime, FixedOffset};
+use indexmap::IndexMap;
+
+use crate::{HourlyBlock, Series, Timestamp};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Enumerate the time axes `Series::to_map` cannot key values with
+pub enum MapError {
+    /// The time appears more than once on the axis
+    Duplicate(Timestamp),
+    /// The time cannot be read
+    Unreadable(Timestamp),
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::Duplicate(time) => write!(f, "{time} appears more than once"),
+            MapError::Unreadable(time) => write!(f, "cannot read time {time}"),
+        }
+    }
+}
+
+impl error::Error for MapError {}
+
+impl Series {
+    /// Values present keyed by their time on `time`, read in `offset`
+    pub fn to_map(
+        &self,
+        time: &[Timestamp],
+        offset: FixedOffset,
+    ) -> Result<BTreeMap<DateTime<FixedOffset>, f64>, MapError> {
+        let instants = instants(time, offset)?;
+        let values = instants.into_iter().zip(self.values());
+        let map = values.filter_map(|(instant, value)| Some((instant, (*value)?)));
+        Ok(map.collect())
+    }
+}
+
+impl HourlyBlock {
+    /// `Series::to_map` of every variable, by name
+    pub fn to_maps(
+        &self,
+        offset: FixedOffset,
+    ) -> Result<IndexMap<String, BTreeMap<DateTime<FixedOffset>, f64>>, MapError> {
+        self.series
+            .iter()
+            .map(|(name, series)| Ok((name.clone(), series.to_map(&self.time, offset)?)))
+            .collect()
+    }
+}
+
+/// Instant of each time, failing on the first repeated or unreadable one
+fn instants(
+    time: &[Timestamp],
+    offset: FixedOffset,
+) -> Result<Vec<DateTime<FixedOffset>>, MapError> {
+    let mut seen = std::collections::BTreeSet::new();
+    time.iter()
+        .map(|time| {
+            let instant = time
+                .to_datetime(offset)
+                .ok_or_else(|| MapError::Unreadable(time.clone()))?;
+            if !seen.insert(instant) {
+                return Err(MapError::Duplicate(time.clone()));
+            }
+            Ok(instant)
+        })
+        .collect()
+}
+
+/// Response with a single hourly series holding the values of `map`, labelled
+/// in ISO 8601 in the offset of the first key
+#[cfg(any(test, feature = "testing"))]
+pub fn from_map(
+    variable: &str,
+    map: &BTreeMap<DateTime<FixedOffset>, f64>,
+) -> crate::ForecastResponse {
+    let mut response = crate::testing::ResponseBuilder::new().build();
+    let offset = map
+        .keys()
+        .next()
+        .map_or(0, |time| time.offset().local_minus_utc());
+    let offset = FixedOffset::east_opt(offset).unwrap_or(FixedOffset::east_opt(0).unwrap());
+    response.utc_offset_seconds = offset.local_minus_utc();
+    response.hourly = Some(HourlyBlock {
+        time: map
+            .keys()
+            .map(|time| {
+                let label = time.with_timezone(&offset).format("%Y-%m-%dT%H:%M");
+                Timestamp::Iso8601(label.to_string())
+            })
+            .collect(),
+        series: [(
+            variable.to_string(),
+            map.values().copied().map(Some).collect(),
+        )]
+        .into(),
+    });
+    response
+}
+
+#[cfg(test)]
+mod tests {
+
+    use chrono::Utc;
+
+    use super::*;
+    use crate::ForecastResponse;
+
+    #[test]
+    fn join_on_time() {
+        let response =
+            ForecastResponse::from_json(include_str!("../../fixtures/forecast.json")).unwrap();
+        let hourly = response.hourly.as_ref().unwrap();
+        let maps = hourly.to_maps(response.utc_offset()).unwrap();
+        assert_eq!(maps.keys().collect::<Vec<_>>(), ["temperature_2m", "rain"]);
+        assert_eq!(maps["rain"].len(), 24);
+
+        let deliveries: [(DateTime<Utc>, &str); 3] = [
+            ("2023-05-16T07:00:00Z".parse().unwrap(), "bakery"),
+            ("2023-05-16T15:00:00Z".parse().unwrap(), "florist"),
+            ("2023-05-17T09:00:00Z".parse().unwrap(), "butcher"),
+        ];
+        let joined: Vec<_> = deliveries
+            .iter()
+            .map(|(time, stop)| {
+                let time = time.fixed_offset();
+                (
+                    *stop,
+                    maps["temperature_2m"].get(&time).copied(),
+                    maps["rain"].get(&time).copied(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            joined,
+            [
+                ("bakery", Some(10.2), Some(0.0)),
+                ("florist", Some(17.7), Some(1.4)),
+                ("butcher", None, None),
+            ]
+        );
+
+        let back = from_map("temperature_2m", &maps["temperature_2m"]);
+        assert_eq!(
+            back.hourly.unwrap().get("temperature_2m"),
+            hourly.get("temperature_2m")
+        );
+        assert!(from_map("rain", &BTreeMap::new())
+            .hourly
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn nulls_and_duplicates() {
+        let offset = FixedOffset::east_opt(0).unwrap();
+        let mut time: Vec<_> = (0..3).map(|hour| Timestamp::Unix(hour * 3600)).collect();
+        let series = Series(vec![Some(1.0), None, Some(3.0)]);
+        let map = series.to_map(&time, offset).unwrap();
+        assert_eq!(map.values().collect::<Vec<_>>(), [&1.0, &3.0]);
+
+        time[2] = Timestamp::Iso8601("1970-01-01T01:00".into());
+        assert_eq!(
+            series.to_map(&time, offset),
+            Err(MapError::Duplicate(time[2].clone()))
+        );
+        time[2] = Timestamp::Iso8601("noon".into());
+        assert_eq!(
+            series.to_map(&time, offset).unwrap_err().to_string(),
+            "cannot read time noon"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use time::{Date, Duration, OffsetDateTime, UtcOffset};
+
+use super::{CalendarDate, Moment};
+use crate::{Current, CurrentWeather, ForecastResponse, Timestamp};
+
+impl ForecastResponse {
+    /// Offset of the requested timezone as a `time::UtcOffset`, zero when none
+    /// was requested
+    pub fn time_offset(&self) -> UtcOffset {
+        UtcOffset::from_whole_seconds(self.utc_offset_seconds).unwrap_or(UtcOffset::UTC)
+    }
+
+    /// Hourly timestamps in the response's offset, `None` without an hourly block
+    /// or if a timestamp is malformed
+    pub fn hourly_offset_datetime(&self) -> Option<Vec<OffsetDateTime>> {
+        in_offset(&self.hourly.as_ref()?.time, self.time_offset())
+    }
+
+    /// Start of each day in the response's offset, `None` without a daily block
+    /// or if a date is malformed
+    pub fn daily_offset_datetime(&self) -> Option<Vec<OffsetDateTime>> {
+        in_offset(&self.daily.as_ref()?.time, self.time_offset())
+    }
+}
+
+impl Timestamp {
+    /// Instant of the timestamp, reading ISO 8601 wall-clock times and dates
+    /// (at midnight) in `offset`; unix seconds are already absolute
+    pub fn to_offset_datetime(&self, offset: UtcOffset) -> Option<OffsetDateTime> {
+        let seconds = self.to_unix(offset.whole_seconds())?;
+        Some(
+            OffsetDateTime::from_unix_timestamp(seconds)
+                .ok()?
+                .to_offset(offset),
+        )
+    }
+}
+
+impl CalendarDate for Date {
+    fn to_api_date(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}",
+            self.year(),
+            u8::from(self.month()),
+            self.day()
+        )
+    }
+}
+
+impl Moment for OffsetDateTime {
+    fn unix_seconds(&self) -> i64 {
+        self.unix_timestamp()
+    }
+
+    fn utc_offset_seconds(&self) -> i32 {
+        self.offset().whole_seconds()
+    }
+}
+
+impl Current {
+    /// Length of the interval the values are aggregated over
+    pub fn interval_duration(&self) -> Option<Duration> {
+        self.interval
+            .map(|seconds| Duration::seconds(seconds.into()))
+    }
+}
+
+impl CurrentWeather {
+    /// Length of the interval the values are aggregated over
+    pub fn interval_duration(&self) -> Option<Duration> {
+        self.interval
+            .map(|seconds| Duration::seconds(seconds.into()))
+    }
+}
+
+fn in_offset(time: &[Timestamp], offset: UtcOffset) -> Option<Vec<OffsetDateTime>> {
+    time.iter()
+        .map(|time| time.to_offset_datetime(offset))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Forecast;
+    use time::Month;
+
+    fn utc(unix: i64) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(unix).unwrap()
+    }
+
+    #[test]
+    fn local_times_carry_the_offset() {
+        let response =
+            ForecastResponse::from_json(include_str!("../../fixtures/darwin.json")).unwrap();
+        let offset = response.time_offset();
+        assert_eq!(offset.whole_seconds(), 9 * 3600 + 30 * 60);
+
+        // 2023-10-19T14:30:00Z
+        let hourly = response.hourly_offset_datetime().unwrap();
+        assert_eq!(hourly[0], utc(1697725800));
+        assert_eq!(hourly[2], utc(1697733000));
+        assert_eq!(hourly[0].offset(), offset);
+        assert_eq!((hourly[0].date().day(), hourly[0].hour()), (20, 0));
+        assert_eq!(response.daily_offset_datetime().unwrap(), [utc(1697725800)]);
+        let sunrise = response.daily.as_ref().unwrap().get("sunrise").unwrap()[0].clone();
+        let sunrise = sunrise.unwrap();
+        let sunrise = sunrise
+            .as_time()
+            .unwrap()
+            .to_offset_datetime(offset)
+            .unwrap();
+        assert_eq!((sunrise.hour(), sunrise.minute()), (6, 32));
+
+        let unix = Timestamp::Unix(1697725800)
+            .to_offset_datetime(offset)
+            .unwrap();
+        assert_eq!(unix, utc(1697725800));
+        assert_eq!(unix.hour(), 0);
+        assert_eq!(
+            Timestamp::Iso8601("yesterday".into()).to_offset_datetime(offset),
+            None
+        );
+    }
+
+    #[test]
+    fn time_formats_give_the_same_instants() {
+        let iso = ForecastResponse::from_json(include_str!("../../fixtures/darwin.json")).unwrap();
+        let unix = ForecastResponse::from_json(include_str!("../../fixtures/darwin_unixtime.json"))
+            .unwrap();
+
+        assert_eq!(
+            iso.hourly_offset_datetime().unwrap(),
+            unix.hourly_offset_datetime().unwrap()
+        );
+        assert_eq!(
+            iso.daily_offset_datetime().unwrap(),
+            unix.daily_offset_datetime().unwrap()
+        );
+    }
+
+    #[test]
+    fn gmt_by_default() {
+        let response =
+            ForecastResponse::from_json(include_str!("../../fixtures/forecast.json")).unwrap();
+        assert_eq!(response.time_offset(), UtcOffset::UTC);
+        // 2023-05-16T19:00:00Z
+        assert_eq!(
+            response.hourly_offset_datetime().unwrap()[19],
+            utc(1684263600)
+        );
+        let current = response.current_weather.unwrap();
+        assert_eq!(current.interval_duration(), Some(Duration::minutes(15)));
+    }
+
+    #[test]
+    fn date_setters() {
+        let start = Date::from_calendar_date(2023, Month::February, 27).unwrap();
+        let end = Date::from_calendar_date(2023, Month::March, 1).unwrap();
+        let url = Forecast::new()
+            .coord(52.52, 13.41)
+            .dates(start, end)
+            .to_url("");
+        assert!(url.ends_with("&start_date=2023-02-27&end_date=2023-03-01"));
+    }
+}
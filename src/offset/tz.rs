@@ -0,0 +1,199 @@
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone};
+use chrono_tz::{OffsetName, Tz};
+
+use crate::{DailyValue, ForecastResponse, Timestamp};
+
+impl ForecastResponse {
+    /// Copy of the response with its times labelled in `tz`, for the same
+    /// instants
+    ///
+    /// ISO 8601 times become wall-clock times of `tz` and dates those the
+    /// days start on there; unix seconds are left as they are. Times of the
+    /// response are read in its own timezone when `chrono-tz` knows it, so
+    /// the hours repeated or skipped by DST changes map to the right instants,
+    /// and at its UTC offset otherwise. Like the API, the new offset and
+    /// abbreviation are those of the first timestamp
+    pub fn with_timezone(&self, tz: Tz) -> ForecastResponse {
+        let source = Source {
+            tz: self.timezone.parse().ok(),
+            utc_offset_seconds: self.utc_offset_seconds,
+        };
+        let mut response = self.clone();
+        let mut first = None;
+        for block in [response.minutely_15.as_mut(), response.hourly.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            source.relabel(block.time.iter_mut(), tz, TIME, &mut first);
+        }
+        if let Some(daily) = response.daily.as_mut() {
+            source.relabel(daily.time.iter_mut(), tz, DATE, &mut first);
+            for values in daily.series.values_mut() {
+                let times = values.iter_mut().filter_map(|value| match value {
+                    Some(DailyValue::Time(time)) => Some(time),
+                    _ => None,
+                });
+                source.relabel(times, tz, TIME, &mut first);
+            }
+        }
+        let current = response.current.as_mut().map(|current| &mut current.time);
+        let current_weather = response
+            .current_weather
+            .as_mut()
+            .map(|current| &mut current.time);
+        source.relabel(
+            current.into_iter().chain(current_weather),
+            tz,
+            TIME,
+            &mut first,
+        );
+
+        let at = DateTime::from_timestamp(first.unwrap_or(0), 0).unwrap_or_default();
+        let offset = tz.offset_from_utc_datetime(&at.naive_utc());
+        response.timezone = tz.name().to_string();
+        response.timezone_abbreviation = offset.abbreviation().unwrap_or_default().to_string();
+        response.utc_offset_seconds = offset.fix().local_minus_utc();
+        response
+    }
+}
+
+const TIME: &str = "%Y-%m-%dT%H:%M";
+const DATE: &str = "%Y-%m-%d";
+
+/// Timezone the times of a response are labelled in
+struct Source {
+    tz: Option<Tz>,
+    utc_offset_seconds: i32,
+}
+
+impl Source {
+    /// Seconds since the epoch of `time`, taking a wall-clock time repeated
+    /// by a DST change as the later instant if `previous` is past the earlier
+    fn instant(&self, time: &Timestamp, previous: Option<i64>) -> Option<i64> {
+        let (Timestamp::Iso8601(label), Some(tz)) = (time, self.tz) else {
+            return time.to_unix(self.utc_offset_seconds);
+        };
+        let local = NaiveDateTime::parse_from_str(label, TIME)
+            .or_else(|_| {
+                NaiveDate::parse_from_str(label, DATE).map(|date| date.and_time(NaiveTime::MIN))
+            })
+            .ok()?;
+        match tz.from_local_datetime(&local) {
+            LocalResult::Single(time) => Some(time.timestamp()),
+            LocalResult::Ambiguous(earlier, later) => {
+                let earlier = earlier.timestamp();
+                match previous {
+                    Some(previous) if previous >= earlier => Some(later.timestamp()),
+                    _ => Some(earlier),
+                }
+            }
+            LocalResult::None => time.to_unix(self.utc_offset_seconds),
+        }
+    }
+
+    /// Label the ISO 8601 times of an axis in `tz` with `format`, keeping the
+    /// instant of the first one readable in `first` if it is not set yet
+    fn relabel<'a>(
+        &self,
+        times: impl Iterator<Item = &'a mut Timestamp>,
+        tz: Tz,
+        format: &str,
+        first: &mut Option<i64>,
+    ) {
+        let mut previous = None;
+        for time in times {
+            let Some(instant) = self.instant(time, previous) else {
+                continue;
+            };
+            first.get_or_insert(instant);
+            previous = Some(instant);
+            if let (Timestamp::Iso8601(label), Some(instant)) =
+                (time, DateTime::from_timestamp(instant, 0))
+            {
+                *label = instant.with_timezone(&tz).format(format).to_string();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use chrono_tz::{Europe, UTC};
+
+    use super::*;
+    use crate::testing::ResponseBuilder;
+    use crate::Hourly;
+
+    fn labels(time: &[Timestamp]) -> Vec<String> {
+        time.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn repeated_hour() {
+        let berlin =
+            ForecastResponse::from_json(include_str!("../../fixtures/berlin_dst.json")).unwrap();
+
+        let utc = berlin.with_timezone(UTC);
+        assert_eq!(
+            labels(&utc.hourly.as_ref().unwrap().time),
+            [
+                "2024-10-26T22:00",
+                "2024-10-26T23:00",
+                "2024-10-27T00:00",
+                "2024-10-27T01:00",
+                "2024-10-27T02:00",
+                "2024-10-27T03:00",
+            ]
+        );
+        let daily = utc.daily.as_ref().unwrap();
+        assert_eq!(labels(&daily.time), ["2024-10-26"]);
+        let sunrise = daily.get("sunrise").unwrap()[0].as_ref().unwrap();
+        assert_eq!(sunrise.as_time().unwrap().to_string(), "2024-10-27T05:57");
+        assert_eq!(
+            utc.current.as_ref().unwrap().time.to_string(),
+            "2024-10-27T02:00"
+        );
+        assert_eq!(
+            (utc.timezone.as_str(), utc.timezone_abbreviation.as_str()),
+            ("UTC", "UTC")
+        );
+        assert_eq!(utc.utc_offset_seconds, 0);
+
+        // And back, to the labels the API gave
+        let back = utc.with_timezone(Europe::Berlin);
+        assert_eq!(back.hourly, berlin.hourly);
+        assert_eq!(back.current, berlin.current);
+        assert_eq!(back.utc_offset_seconds, 7200);
+        assert_eq!(back.timezone_abbreviation, "CEST");
+    }
+
+    #[test]
+    fn skipped_hour() {
+        let gmt = ResponseBuilder::new()
+            .start("2024-03-30")
+            .hourly(Hourly::temperature_2m, (0..48).map(f64::from))
+            .build();
+
+        let berlin = gmt.with_timezone(Europe::Berlin);
+        let hourly = berlin.hourly.unwrap();
+        let march_31: Vec<_> = labels(&hourly.time)
+            .into_iter()
+            .filter(|label| label.starts_with("2024-03-31"))
+            .collect();
+        assert_eq!(march_31.len(), 23);
+        assert_eq!(march_31[1..3], ["2024-03-31T01:00", "2024-03-31T03:00"]);
+        // Values stay with their instants
+        let index = labels(&hourly.time)
+            .iter()
+            .position(|label| label == "2024-03-31T03:00");
+        assert_eq!(
+            hourly.get("temperature_2m").unwrap()[index.unwrap()],
+            Some(25.0)
+        );
+        assert_eq!(berlin.utc_offset_seconds, 3600);
+        assert_eq!(berlin.timezone, "Europe/Berlin");
+
+        assert_eq!(gmt.with_timezone(UTC).hourly, gmt.hourly);
+    }
+}
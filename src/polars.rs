@@ -0,0 +1,287 @@
+#[cfg(any(test, feature = "testing"))]
+use polars::prelude::PolarsError;
+use polars::prelude::{Column, DataFrame, DataType, PolarsResult, TimeUnit};
+
+#[cfg(any(test, feature = "testing"))]
+use crate::{date::Date, DailyBlock, HourlyBlock};
+use crate::{response::daily_kind, ColumnKind, DailyValue, ForecastResponse, Timestamp};
+
+const MILLISECONDS_PER_DAY: i64 = 86_400_000;
+
+impl ForecastResponse {
+    /// Hourly block as a data frame: a `time` column of local wall-clock
+    /// datetimes, as the API returns them, then one nullable `f64` column per
+    /// variable under its API name
+    ///
+    /// Without an hourly block, the frame has a `time` column and no rows
+    pub fn to_polars_hourly(&self) -> PolarsResult<DataFrame> {
+        let time = self.hourly.iter().flat_map(|hourly| &hourly.time);
+        let mut columns = vec![self.datetimes("time", time.map(Some))?];
+        for (name, series) in self.hourly.iter().flat_map(|hourly| &hourly.series) {
+            columns.push(Column::new(name.as_str().into(), series.values()));
+        }
+        DataFrame::new_infer_height(columns)
+    }
+
+    /// Daily block as a data frame like `to_polars_hourly`, with a `time`
+    /// column of dates, weather codes as `u8`, sunrise and sunset as datetimes
+    /// and values of variables unknown to this crate as their JSON text
+    pub fn to_polars_daily(&self) -> PolarsResult<DataFrame> {
+        let days: Vec<Option<i32>> = self
+            .daily
+            .iter()
+            .flat_map(|daily| &daily.time)
+            .map(|time| Some(self.wall_clock(time)?.div_euclid(MILLISECONDS_PER_DAY) as i32))
+            .collect();
+        let mut columns = vec![Column::new("time".into(), days).cast(&DataType::Date)?];
+        for (name, values) in self.daily.iter().flat_map(|daily| &daily.series) {
+            let column = match daily_kind(name) {
+                ColumnKind::Code => Column::new(
+                    name.as_str().into(),
+                    values
+                        .iter()
+                        .map(|value| match value {
+                            Some(DailyValue::Code(code)) => Some(*code),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                ColumnKind::Time => self.datetimes(
+                    name,
+                    values.iter().map(|value| match value {
+                        Some(DailyValue::Time(time)) => Some(time),
+                        _ => None,
+                    }),
+                )?,
+                ColumnKind::Raw => Column::new(
+                    name.as_str().into(),
+                    values
+                        .iter()
+                        .map(|value| match value {
+                            Some(DailyValue::Raw(raw)) => Some(raw.to_string()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                ColumnKind::Float => Column::new(
+                    name.as_str().into(),
+                    values
+                        .iter()
+                        .map(|value| match value {
+                            Some(DailyValue::Float(value)) => Some(*value),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+            };
+            columns.push(column);
+        }
+        DataFrame::new_infer_height(columns)
+    }
+
+    /// Milliseconds since the epoch of the local wall-clock time, whatever
+    /// the time format of the response
+    fn wall_clock(&self, time: &Timestamp) -> Option<i64> {
+        let offset = self.utc_offset_seconds;
+        Some((time.to_unix(offset)? + i64::from(offset)) * 1000)
+    }
+
+    fn datetimes<'a>(
+        &self,
+        name: &str,
+        time: impl Iterator<Item = Option<&'a Timestamp>>,
+    ) -> PolarsResult<Column> {
+        let milliseconds: Vec<Option<i64>> = time.map(|time| self.wall_clock(time?)).collect();
+        Column::new(name.into(), milliseconds)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+    }
+}
+
+/// Response holding the blocks read back from frames shaped like those of
+/// `ForecastResponse::to_polars_hourly` and `to_polars_daily`, with the
+/// location and timezone of an empty `ResponseBuilder`
+///
+/// Times are read as ISO 8601 and units are left out
+#[cfg(any(test, feature = "testing"))]
+pub fn from_polars(
+    hourly: Option<&DataFrame>,
+    daily: Option<&DataFrame>,
+) -> PolarsResult<ForecastResponse> {
+    let mut response = crate::testing::ResponseBuilder::new().build();
+    if let Some(frame) = hourly {
+        let mut series = indexmap::IndexMap::new();
+        for column in variables(frame) {
+            let values = column.cast(&DataType::Float64)?;
+            let values: Vec<Option<f64>> = values.f64()?.iter().collect();
+            series.insert(column.name().to_string(), values.into());
+        }
+        response.hourly = Some(HourlyBlock {
+            time: time_axis(frame)?,
+            series,
+        });
+    }
+    if let Some(frame) = daily {
+        let mut series = indexmap::IndexMap::new();
+        for column in variables(frame) {
+            series.insert(column.name().to_string(), daily_values(column)?);
+        }
+        response.daily = Some(DailyBlock {
+            time: time_axis(frame)?,
+            series,
+        });
+    }
+    Ok(response)
+}
+
+#[cfg(any(test, feature = "testing"))]
+fn variables(frame: &DataFrame) -> impl Iterator<Item = &Column> {
+    frame
+        .columns()
+        .iter()
+        .filter(|column| column.name().as_str() != "time")
+}
+
+#[cfg(any(test, feature = "testing"))]
+fn time_axis(frame: &DataFrame) -> PolarsResult<Vec<Timestamp>> {
+    timestamps(frame.column("time")?)?
+        .into_iter()
+        .collect::<Option<_>>()
+        .ok_or_else(|| PolarsError::ComputeError("missing values in `time`".into()))
+}
+
+/// Dates and datetimes as ISO 8601 timestamps
+#[cfg(any(test, feature = "testing"))]
+fn timestamps(column: &Column) -> PolarsResult<Vec<Option<Timestamp>>> {
+    if column.dtype() == &DataType::Date {
+        let days = column.date()?.physical().iter();
+        return Ok(days
+            .map(|days| {
+                Some(Timestamp::Iso8601(
+                    Date::from_days(days?.into()).to_string(),
+                ))
+            })
+            .collect());
+    }
+    let milliseconds = column.cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?;
+    let milliseconds = milliseconds.datetime()?.physical().iter();
+    Ok(milliseconds
+        .map(|milliseconds| {
+            let seconds = milliseconds?.div_euclid(1000);
+            let day = Date::from_days(seconds.div_euclid(86_400));
+            let clock = seconds.rem_euclid(86_400);
+            Some(Timestamp::Iso8601(format!(
+                "{day}T{:02}:{:02}",
+                clock / 3600,
+                clock % 3600 / 60
+            )))
+        })
+        .collect())
+}
+
+/// Daily values typed after the column type
+#[cfg(any(test, feature = "testing"))]
+fn daily_values(column: &Column) -> PolarsResult<Vec<Option<DailyValue>>> {
+    Ok(match column.dtype() {
+        DataType::UInt8 => column
+            .u8()?
+            .iter()
+            .map(|code| code.map(DailyValue::Code))
+            .collect(),
+        DataType::Date | DataType::Datetime(..) => timestamps(column)?
+            .into_iter()
+            .map(|time| time.map(DailyValue::Time))
+            .collect(),
+        DataType::String => column
+            .str()?
+            .iter()
+            .map(|raw| {
+                raw.map(|raw| {
+                    DailyValue::Raw(serde_json::from_str(raw).unwrap_or_else(|_| raw.into()))
+                })
+            })
+            .collect(),
+        _ => column
+            .cast(&DataType::Float64)?
+            .f64()?
+            .iter()
+            .map(|value| value.map(DailyValue::Float))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use polars::prelude::{col, IntoLazy, SortMultipleOptions};
+
+    use super::*;
+    use crate::testing::ResponseBuilder;
+    use crate::Hourly;
+
+    const FIXTURE: &str = include_str!("../fixtures/mixed.json");
+
+    #[test]
+    fn frames() {
+        let response = ForecastResponse::from_json(FIXTURE).unwrap();
+
+        let hourly = response.to_polars_hourly().unwrap();
+        assert_eq!(hourly.shape(), (3, 3));
+        let names: Vec<_> = hourly.get_column_names();
+        assert_eq!(names, ["time", "temperature_2m", "rain"]);
+        let time = hourly.column("time").unwrap();
+        assert_eq!(
+            time.dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, None)
+        );
+        // 2024-06-20T12:00, local time
+        assert_eq!(
+            time.datetime().unwrap().physical().get(0),
+            Some(1_718_884_800_000)
+        );
+        let temperature = hourly.column("temperature_2m").unwrap().f64().unwrap();
+        assert_eq!(temperature.get(1), Some(20.3));
+        assert_eq!(temperature.get(2), None);
+
+        let daily = response.to_polars_daily().unwrap();
+        assert_eq!(daily.column("time").unwrap().dtype(), &DataType::Date);
+        assert_eq!(
+            daily.column("weather_code").unwrap().u8().unwrap().get(0),
+            Some(61)
+        );
+        assert!(matches!(
+            daily.column("sunrise").unwrap().dtype(),
+            DataType::Datetime(..)
+        ));
+
+        let back = from_polars(Some(&hourly), Some(&daily)).unwrap();
+        assert_eq!(back.hourly, response.hourly);
+        assert_eq!(back.daily, response.daily);
+        assert!(from_polars(None, None).unwrap().hourly.is_none());
+    }
+
+    #[test]
+    fn daily_mean() {
+        let response = ResponseBuilder::new()
+            .start("2024-01-01")
+            .hourly(Hourly::temperature_2m, (0..48).map(|hour| hour as f64))
+            .build();
+        let means = response
+            .to_polars_hourly()
+            .unwrap()
+            .lazy()
+            .group_by([col("time").dt().date().alias("date")])
+            .agg([col("temperature_2m").mean()])
+            .sort(["date"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+
+        let means: Vec<_> = means
+            .column("temperature_2m")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(means, [Some(11.5), Some(35.5)]);
+    }
+}
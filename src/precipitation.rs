@@ -0,0 +1,205 @@
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::units::convert;
+use crate::{ForecastResponse, Hourly, Timestamp};
+
+/// Probability in % from which an hour counts as wet when the response has
+/// no precipitation amounts
+pub const WET_PROBABILITY: f64 = 50.0;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Run of wet hours found by `ForecastResponse::next_precipitation`
+pub struct PrecipEvent {
+    /// First wet hour
+    pub start: Timestamp,
+    /// First hour back below the threshold, `None` if it is still wet at the
+    /// end of the horizon
+    pub end: Option<Timestamp>,
+    /// Largest hourly amount in the unit of the response, or the largest
+    /// probability in % without amounts
+    pub peak: f64,
+    /// Amount over the event in the unit of the response, `None` without
+    /// amounts
+    pub total: Option<f64>,
+}
+
+impl ForecastResponse {
+    /// Next run of hours with at least `threshold_mm` of precipitation, from
+    /// the first hour not in the past
+    ///
+    /// Without hourly `precipitation`, hours with a `precipitation_probability`
+    /// of at least `WET_PROBABILITY` count as wet instead.
+    pub fn next_precipitation(&self, threshold_mm: f64) -> Option<PrecipEvent> {
+        self.next_precipitation_at(threshold_mm, SystemTime::now())
+    }
+
+    /// `next_precipitation` as seen at `now`
+    pub fn next_precipitation_at(&self, threshold_mm: f64, now: SystemTime) -> Option<PrecipEvent> {
+        let now = match now.duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_secs() as i64,
+            Err(before) => -(before.duration().as_secs() as i64),
+        };
+        let offset = self.utc_offset_seconds;
+        let first = self
+            .hourly
+            .as_ref()?
+            .time
+            .iter()
+            .position(|time| time.to_unix(offset).is_some_and(|time| time >= now))?;
+        self.events_from(threshold_mm, first).into_iter().next()
+    }
+
+    /// Every run of wet hours of the response, as for `next_precipitation`
+    /// but including the past ones
+    pub fn precipitation_events(&self, threshold_mm: f64) -> Vec<PrecipEvent> {
+        self.events_from(threshold_mm, 0)
+    }
+
+    /// Runs of wet hours starting at or after the hour at `first`
+    fn events_from(&self, threshold_mm: f64, first: usize) -> Vec<PrecipEvent> {
+        let Some(block) = &self.hourly else {
+            return Vec::new();
+        };
+        let unit = |variable: Hourly| {
+            self.hourly_units
+                .as_ref()
+                .and_then(|units| units.get(variable))
+                .map(str::to_string)
+        };
+        let (values, threshold, amounts) = match self.hourly(Hourly::precipitation) {
+            Some(amounts) => {
+                let unit = unit(Hourly::precipitation).unwrap_or_else(|| "mm".to_string());
+                let Some(threshold) = convert(threshold_mm, "mm", &unit) else {
+                    return Vec::new();
+                };
+                (amounts, threshold, true)
+            }
+            None => match self.hourly(Hourly::precipitation_probability) {
+                Some(probabilities) => (probabilities, WET_PROBABILITY, false),
+                None => return Vec::new(),
+            },
+        };
+
+        let mut events = Vec::new();
+        let mut current: Option<PrecipEvent> = None;
+        for (time, value) in block.time.iter().zip(values.iter()).skip(first) {
+            match (*value, &mut current) {
+                (Some(value), Some(event)) if value >= threshold => {
+                    event.peak = event.peak.max(value);
+                    event.total = event.total.map(|total| total + value);
+                }
+                (Some(value), None) if value >= threshold => {
+                    current = Some(PrecipEvent {
+                        start: time.clone(),
+                        end: None,
+                        peak: value,
+                        total: amounts.then_some(value),
+                    });
+                }
+                // A missing hour counts as dry
+                _ => {
+                    if let Some(mut event) = current.take() {
+                        event.end = Some(time.clone());
+                        events.push(event);
+                    }
+                }
+            }
+        }
+        events.extend(current);
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::ResponseBuilder;
+
+    /// `time` of the fixture, 2 hours ahead of UTC
+    fn at(time: &str) -> SystemTime {
+        let seconds = Timestamp::Iso8601(time.to_string()).to_unix(7200).unwrap();
+        UNIX_EPOCH + Duration::from_secs(seconds as u64)
+    }
+
+    fn hour(time: &str) -> Timestamp {
+        Timestamp::Iso8601(time.to_string())
+    }
+
+    #[test]
+    fn event_mid_horizon() {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/precipitation.json")).unwrap();
+
+        // The morning drizzle is over, the next rain starts at 05:00
+        let event = response
+            .next_precipitation_at(0.1, at("2024-05-10T02:30"))
+            .unwrap();
+        assert_eq!(event.start, hour("2024-05-10T05:00"));
+        assert_eq!(event.end, Some(hour("2024-05-10T09:00")));
+        assert_eq!(event.peak, 3.1);
+        assert!((event.total.unwrap() - 5.5).abs() < 1e-9);
+
+        // Already raining at 07:00, the event is counted from there
+        let ongoing = response
+            .next_precipitation_at(0.1, at("2024-05-10T07:00"))
+            .unwrap();
+        assert_eq!(ongoing.start, hour("2024-05-10T07:00"));
+        assert!((ongoing.total.unwrap() - 3.9).abs() < 1e-9);
+
+        let events = response.precipitation_events(0.1);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].start, hour("2024-05-10T00:00"));
+        assert_eq!(events[0].end, Some(hour("2024-05-10T02:00")));
+        assert_eq!(response.precipitation_events(1.0).len(), 1);
+    }
+
+    #[test]
+    fn dry_horizon() {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/precipitation.json")).unwrap();
+        assert_eq!(
+            response.next_precipitation_at(0.1, at("2024-05-10T08:30")),
+            None
+        );
+        // Past the last hour
+        assert_eq!(
+            response.next_precipitation_at(0.1, at("2024-05-10T12:00")),
+            None
+        );
+        assert_eq!(response.next_precipitation(0.1), None);
+    }
+
+    #[test]
+    fn probabilities_and_units() {
+        let probabilities = ResponseBuilder::new()
+            .start("2024-05-10")
+            .hourly(Hourly::precipitation_probability, [20.0, 55.0, 80.0])
+            .hourly_unit(Hourly::precipitation_probability, "%")
+            .build();
+        let event = probabilities
+            .next_precipitation_at(0.1, UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(
+            event,
+            PrecipEvent {
+                start: hour("2024-05-10T01:00"),
+                end: None,
+                peak: 80.0,
+                total: None,
+            }
+        );
+
+        // 0.1 mm is about 0.004 inch
+        let inches = ResponseBuilder::new()
+            .start("2024-05-10")
+            .hourly(Hourly::precipitation, [0.003, 0.01, 0.02])
+            .hourly_unit(Hourly::precipitation, "inch")
+            .build();
+        let event = inches.next_precipitation_at(0.1, UNIX_EPOCH).unwrap();
+        assert_eq!(event.start, hour("2024-05-10T01:00"));
+        assert!((event.total.unwrap() - 0.03).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{error, fmt};
+
+use crate::{Forecast, Hourly};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Enumerate the reasons `Hourly::previous_day` refuses a variable
+pub enum PreviousDayError {
+    /// Runs are kept from 1 to 7 days back
+    DaysBack(u8),
+    /// The variable is not archived from previous runs
+    Unsupported(String),
+}
+
+impl fmt::Display for PreviousDayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreviousDayError::DaysBack(days) => {
+                write!(f, "previous runs go 1 to 7 days back, not {days}")
+            }
+            PreviousDayError::Unsupported(name) => {
+                write!(f, "`{name}` is not available from previous runs")
+            }
+        }
+    }
+}
+
+impl error::Error for PreviousDayError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Hourly variable as forecast by the run of `days_back` days before, for the
+/// same timestamps, e.g. `temperature_2m_previous_day1`
+pub struct PreviousDay {
+    variable: Hourly,
+    days_back: u8,
+}
+
+impl Hourly {
+    /// The variable as forecast `days_back` days before, 1 to 7
+    pub fn previous_day(self, days_back: u8) -> Result<PreviousDay, PreviousDayError> {
+        if !(1..=7).contains(&days_back) {
+            return Err(PreviousDayError::DaysBack(days_back));
+        }
+        if matches!(self, Hourly::is_day | Hourly::precipitation_probability) {
+            return Err(PreviousDayError::Unsupported(self.to_string()));
+        }
+        Ok(PreviousDay {
+            variable: self,
+            days_back,
+        })
+    }
+}
+
+impl PreviousDay {
+    /// Variable of the current run it compares to
+    pub fn variable(&self) -> &Hourly {
+        &self.variable
+    }
+
+    pub fn days_back(&self) -> u8 {
+        self.days_back
+    }
+}
+
+impl fmt::Display for PreviousDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_previous_day{}", self.variable, self.days_back)
+    }
+}
+
+impl<C> Forecast<C> {
+    /// Get hourly value of a previous run, see `Hourly::previous_day`
+    pub fn previous_day(mut self, previous_day: PreviousDay) -> Self {
+        self.previous_day.push(previous_day);
+        self
+    }
+}
+
+#[cfg(feature = "response")]
+impl crate::ForecastResponse {
+    /// Current series of the variable along its `previous_day` series, `None`
+    /// if either is missing
+    pub fn previous_day(
+        &self,
+        previous_day: &PreviousDay,
+    ) -> Option<(&crate::Series, &crate::Series)> {
+        let current = self.hourly_by_name(&previous_day.variable.to_string())?;
+        Some((current, self.hourly_by_name(&previous_day.to_string())?))
+    }
+
+    /// Current minus previous forecast at each timestamp, `None` where either
+    /// has no data
+    pub fn change_since(&self, previous_day: &PreviousDay) -> Option<crate::Series> {
+        let (current, previous) = self.previous_day(previous_day)?;
+        Some(
+            current
+                .values()
+                .iter()
+                .zip(previous.values())
+                .map(|(current, previous)| Some(current.as_ref()? - previous.as_ref()?))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn url_creation() {
+        let forecast = Forecast::new()
+            .coord(52.52, 13.41)
+            .hourly(Hourly::temperature_2m)
+            .previous_day(Hourly::temperature_2m.previous_day(1).unwrap())
+            .previous_day(Hourly::precipitation.previous_day(7).unwrap());
+        assert_eq!(
+            forecast.to_string(),
+            "https://api.open-meteo.com/v1/forecast?latitude=52.52&longitude=13.41&hourly=,temperature_2m,temperature_2m_previous_day1,precipitation_previous_day7"
+        );
+
+        let previous_only = Forecast::new()
+            .coord(52.52, 13.41)
+            .previous_day(Hourly::rain.previous_day(2).unwrap());
+        assert!(previous_only
+            .to_string()
+            .ends_with("&hourly=,rain_previous_day2"));
+    }
+
+    #[test]
+    fn invalid_days_back() {
+        assert_eq!(
+            Hourly::temperature_2m.previous_day(0),
+            Err(PreviousDayError::DaysBack(0))
+        );
+        let error = Hourly::temperature_2m.previous_day(8).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "previous runs go 1 to 7 days back, not 8"
+        );
+        assert_eq!(
+            Hourly::is_day.previous_day(1),
+            Err(PreviousDayError::Unsupported("is_day".into()))
+        );
+    }
+
+    #[cfg(feature = "response")]
+    #[test]
+    fn change_since_yesterday() {
+        let response =
+            crate::ForecastResponse::from_json(include_str!("../fixtures/previous_day.json"))
+                .unwrap();
+        let yesterday = Hourly::temperature_2m.previous_day(1).unwrap();
+        let (current, previous) = response.previous_day(&yesterday).unwrap();
+        assert_eq!(current.len(), previous.len());
+
+        let change = response.change_since(&yesterday).unwrap();
+        let rounded: Vec<_> = change
+            .values()
+            .iter()
+            .map(|value| value.map(|value| (value * 10.0).round() / 10.0))
+            .collect();
+        assert_eq!(rounded, [Some(0.4), Some(-1.2), None, Some(0.0)]);
+        assert!(response
+            .change_since(&Hourly::temperature_2m.previous_day(2).unwrap())
+            .is_none());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{response::spellings, ForecastResponse, PressureKind, Series, Timestamp};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate how a requested time is matched against the response timestamps
+pub enum TimeMatch {
+    Exact,
+    /// Closest timestamp, the earlier one on a tie
+    Nearest,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Values of a pressure variable across levels at one timestamp
+pub struct Profile {
+    pub time: Timestamp,
+    /// `(level_hpa, value)` pairs from the highest pressure (closest to the
+    /// ground) to the lowest, without levels lacking data
+    pub levels: Vec<(u32, f64)>,
+}
+
+impl ForecastResponse {
+    /// Profile of `kind` at `time`, `None` if no timestamp matches
+    pub fn profile_at(
+        &self,
+        kind: PressureKind,
+        time: &Timestamp,
+        matching: TimeMatch,
+    ) -> Option<Profile> {
+        let times = &self.hourly.as_ref()?.time;
+        let index = match matching {
+            TimeMatch::Exact => times.iter().position(|t| t == time)?,
+            TimeMatch::Nearest => {
+                let target = time.to_unix(self.utc_offset_seconds)?;
+                times
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, t)| Some((index, t.to_unix(self.utc_offset_seconds)?)))
+                    .min_by_key(|(_, t)| t.abs_diff(target))?
+                    .0
+            }
+        };
+        Some(profile(times, &self.levels(kind), index))
+    }
+
+    /// Profile of `kind` at every hourly timestamp
+    pub fn profiles(&self, kind: PressureKind) -> impl Iterator<Item = Profile> + '_ {
+        let levels = self.levels(kind);
+        let times = self.hourly.as_ref().map_or(&[][..], |hourly| &hourly.time);
+        (0..times.len()).map(move |index| profile(times, &levels, index))
+    }
+
+    /// Columns of `kind` by level, highest pressure first
+    fn levels(&self, kind: PressureKind) -> Vec<(u32, &Series)> {
+        let kind = kind.to_string();
+        let mut levels: Vec<_> = self
+            .hourly
+            .iter()
+            .flat_map(|hourly| &hourly.series)
+            .filter_map(|(name, series)| {
+                let (variable, level) = name.strip_suffix("hPa")?.rsplit_once('_')?;
+                let level = level.parse::<u32>().ok()?;
+                spellings(variable)
+                    .any(|variable| variable == kind)
+                    .then_some((level, series))
+            })
+            .collect();
+        levels.sort_by_key(|(level, _)| std::cmp::Reverse(*level));
+        levels
+    }
+}
+
+fn profile(times: &[Timestamp], levels: &[(u32, &Series)], index: usize) -> Profile {
+    Profile {
+        time: times[index].clone(),
+        levels: levels
+            .iter()
+            .filter_map(|(level, series)| Some((*level, series.get(index).copied().flatten()?)))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../fixtures/profile.json");
+
+    fn time(hour: u8) -> Timestamp {
+        Timestamp::Iso8601(format!("2024-05-01T{hour:02}:00"))
+    }
+
+    #[test]
+    fn temperature_profile() {
+        let response = ForecastResponse::from_json(FIXTURE).unwrap();
+
+        let profile = response
+            .profile_at(PressureKind::temperature, &time(1), TimeMatch::Exact)
+            .unwrap();
+        assert_eq!(profile.time, time(1));
+        assert_eq!(
+            profile.levels,
+            [(1000, 14.6), (850, 6.2), (700, -1.9), (500, -16.8)]
+        );
+
+        // 500 hPa has no data at 02:00
+        let nearest = Timestamp::Iso8601("2024-05-01T02:20".into());
+        let profile = response
+            .profile_at(PressureKind::temperature, &nearest, TimeMatch::Nearest)
+            .unwrap();
+        assert_eq!(profile.time, time(2));
+        assert_eq!(profile.levels, [(1000, 15.8), (850, 6.9), (700, -1.4)]);
+        assert!(response
+            .profile_at(PressureKind::temperature, &nearest, TimeMatch::Exact)
+            .is_none());
+
+        let humidity = response
+            .profile_at(PressureKind::relativehumidity, &time(0), TimeMatch::Exact)
+            .unwrap();
+        assert_eq!(humidity.levels, [(850, 71.0)]);
+
+        let profiles: Vec<_> = response.profiles(PressureKind::temperature).collect();
+        assert_eq!(profiles.len(), 3);
+        assert_eq!(profiles[0].levels[0], (1000, 13.9));
+        assert!(response
+            .profiles(PressureKind::geopotential_height)
+            .all(|profile| profile.levels.is_empty()));
+    }
+
+    #[test]
+    fn levels_requested_as_hourly_variables() {
+        let forecast = crate::Forecast::new()
+            .coord(47.38, 8.54)
+            .hourly(crate::Hourly::temperature_2m)
+            .pressure_var(crate::PressureVar::temperature(850))
+            .pressure_var(crate::PressureVar::temperature(500));
+
+        let url = forecast.to_string();
+        assert!(
+            url.ends_with("&hourly=,temperature_2m,temperature_850hPa,temperature_500hPa"),
+            "{url}"
+        );
+        assert!(!url.contains("&temperature_"));
+    }
+}
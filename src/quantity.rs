@@ -0,0 +1,231 @@
+use crate::intensity::MM_PER_INCH;
+use crate::units::{KMH_PER_KN, KMH_PER_MPH, KMH_PER_MS};
+pub use crate::UnitPreferences;
+use crate::{Daily, ForecastResponse, Hourly, UnitsMap, WeatheredError};
+
+/// Value of a physical quantity in the unit spelled `UNIT` by the API
+pub trait Quantity: Copy + From<f64> {
+    const UNIT: &'static str;
+
+    fn value(self) -> f64;
+}
+
+/// Newtypes over `f64`, each tagged with the unit string of the API
+macro_rules! quantities {
+    ($($(#[$doc:meta])* $name:ident => $unit:literal,)*) => {$(
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        $(#[$doc])*
+        pub struct $name(pub f64);
+
+        impl From<f64> for $name {
+            fn from(value: f64) -> Self {
+                $name(value)
+            }
+        }
+
+        impl Quantity for $name {
+            const UNIT: &'static str = $unit;
+
+            fn value(self) -> f64 {
+                self.0
+            }
+        }
+    )*};
+}
+
+quantities! {
+    Celsius => "°C",
+    Fahrenheit => "°F",
+    KmPerHour => "km/h",
+    MetersPerSecond => "m/s",
+    MilesPerHour => "mp/h",
+    Knots => "kn",
+    Millimeters => "mm",
+    Inches => "inch",
+    /// Unit of snowfall
+    Centimeters => "cm",
+    HectoPascal => "hPa",
+    Percent => "%",
+}
+
+impl From<Fahrenheit> for Celsius {
+    fn from(Fahrenheit(value): Fahrenheit) -> Self {
+        Celsius((value - 32.0) * 5.0 / 9.0)
+    }
+}
+
+impl From<Celsius> for Fahrenheit {
+    fn from(Celsius(value): Celsius) -> Self {
+        Fahrenheit(value * 9.0 / 5.0 + 32.0)
+    }
+}
+
+// Speeds convert through km/h
+impl From<MetersPerSecond> for KmPerHour {
+    fn from(MetersPerSecond(value): MetersPerSecond) -> Self {
+        KmPerHour(value * KMH_PER_MS)
+    }
+}
+
+impl From<MilesPerHour> for KmPerHour {
+    fn from(MilesPerHour(value): MilesPerHour) -> Self {
+        KmPerHour(value * KMH_PER_MPH)
+    }
+}
+
+impl From<Knots> for KmPerHour {
+    fn from(Knots(value): Knots) -> Self {
+        KmPerHour(value * KMH_PER_KN)
+    }
+}
+
+impl From<KmPerHour> for MetersPerSecond {
+    fn from(KmPerHour(value): KmPerHour) -> Self {
+        MetersPerSecond(value / KMH_PER_MS)
+    }
+}
+
+impl From<KmPerHour> for MilesPerHour {
+    fn from(KmPerHour(value): KmPerHour) -> Self {
+        MilesPerHour(value / KMH_PER_MPH)
+    }
+}
+
+impl From<KmPerHour> for Knots {
+    fn from(KmPerHour(value): KmPerHour) -> Self {
+        Knots(value / KMH_PER_KN)
+    }
+}
+
+impl From<Inches> for Millimeters {
+    fn from(Inches(value): Inches) -> Self {
+        Millimeters(value * MM_PER_INCH)
+    }
+}
+
+impl From<Millimeters> for Inches {
+    fn from(Millimeters(value): Millimeters) -> Self {
+        Inches(value / MM_PER_INCH)
+    }
+}
+
+impl From<Centimeters> for Millimeters {
+    fn from(Centimeters(value): Centimeters) -> Self {
+        Millimeters(value * 10.0)
+    }
+}
+
+impl From<Millimeters> for Centimeters {
+    fn from(Millimeters(value): Millimeters) -> Self {
+        Centimeters(value / 10.0)
+    }
+}
+
+impl ForecastResponse {
+    /// Values of an hourly variable as `Q`, `Ok(None)` if the variable is missing
+    ///
+    /// Fails with `WeatheredError::UnitMismatch` unless the response reports the
+    /// variable in the unit of `Q`
+    pub fn hourly_typed<Q: Quantity>(
+        &self,
+        variable: Hourly,
+    ) -> Result<Option<Vec<Option<Q>>>, WeatheredError> {
+        let Some(series) = self.hourly(variable.clone()) else {
+            return Ok(None);
+        };
+        check_unit::<Q>(self.hourly_units.as_ref(), variable)?;
+        Ok(Some(
+            series.iter().map(|value| value.map(Q::from)).collect(),
+        ))
+    }
+
+    /// Values of a daily variable as `Q`, `Ok(None)` if the variable is missing
+    pub fn daily_typed<Q: Quantity>(
+        &self,
+        variable: Daily,
+    ) -> Result<Option<Vec<Option<Q>>>, WeatheredError> {
+        let Some(numbers) = self
+            .daily
+            .as_ref()
+            .and_then(|daily| daily.numbers(&variable.to_string()))
+        else {
+            return Ok(None);
+        };
+        check_unit::<Q>(self.daily_units.as_ref(), variable)?;
+        Ok(Some(
+            numbers
+                .into_iter()
+                .map(|value| value.map(Q::from))
+                .collect(),
+        ))
+    }
+}
+
+fn check_unit<Q: Quantity>(
+    units: Option<&UnitsMap>,
+    variable: impl std::fmt::Display,
+) -> Result<(), WeatheredError> {
+    match units {
+        Some(units) => units.assert_unit(variable, Q::UNIT),
+        None => Err(WeatheredError::UnitMismatch {
+            variable: variable.to_string(),
+            expected: Q::UNIT.to_string(),
+            found: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const METRIC: &str = include_str!("../fixtures/forecast.json");
+    const IMPERIAL: &str = include_str!("../fixtures/imperial.json");
+
+    #[test]
+    fn typed_by_unit() {
+        let metric = ForecastResponse::from_json(METRIC).unwrap();
+        let temperature = metric
+            .hourly_typed::<Celsius>(Hourly::temperature_2m)
+            .unwrap()
+            .unwrap();
+        assert_eq!(temperature[0], Some(Celsius(9.8)));
+        let rain = metric
+            .hourly_typed::<Millimeters>(Hourly::rain)
+            .unwrap()
+            .unwrap();
+        assert_eq!(rain[15], Some(Millimeters(1.4)));
+        assert_eq!(
+            metric
+                .daily_typed::<Celsius>(Daily::temperature_2m_max)
+                .unwrap()
+                .unwrap(),
+            [Some(Celsius(17.9))]
+        );
+        assert_eq!(
+            metric.hourly_typed::<Celsius>(Hourly::snowfall).unwrap(),
+            None
+        );
+
+        let imperial = ForecastResponse::from_json(IMPERIAL).unwrap();
+        let error = imperial
+            .hourly_typed::<Celsius>(Hourly::temperature_2m)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "temperature_2m is in °F, expected °C");
+        assert!(imperial
+            .hourly_typed::<Fahrenheit>(Hourly::temperature_2m)
+            .is_ok());
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Celsius::from(Fahrenheit(212.0)), Celsius(100.0));
+        assert_eq!(Fahrenheit::from(Celsius(-40.0)), Fahrenheit(-40.0));
+        // mph -> km/h -> kn -> km/h -> m/s
+        let knots = Knots::from(KmPerHour::from(MilesPerHour(23.0)));
+        let ms = MetersPerSecond::from(KmPerHour::from(knots));
+        assert!((ms.value() - 10.28192).abs() < 1e-9);
+        assert_eq!(Millimeters::from(Inches(2.0)), Millimeters(50.8));
+    }
+}
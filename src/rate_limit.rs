@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use web_time::Instant;
+
+use crate::AsyncRuntime;
+
+const MINUTE: Duration = Duration::from_secs(60);
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+struct State {
+    tokens: f64,
+    refilled: Instant,
+    today: VecDeque<Instant>,
+}
+
+#[derive(Clone)]
+/// Token bucket refilled every minute, plus a rolling 24 h quota,
+/// shared by all clones
+pub(crate) struct RateLimiter {
+    per_minute: f64,
+    per_day: usize,
+    state: Arc<Mutex<State>>,
+    runtime: Arc<dyn AsyncRuntime>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(
+        calls_per_minute: u32,
+        calls_per_day: u32,
+        runtime: Arc<dyn AsyncRuntime>,
+    ) -> Self {
+        let per_minute = calls_per_minute.max(1) as f64;
+        RateLimiter {
+            per_minute,
+            per_day: calls_per_day.max(1) as usize,
+            state: Arc::new(Mutex::new(State {
+                tokens: per_minute,
+                refilled: runtime.now(),
+                today: VecDeque::new(),
+            })),
+            runtime,
+        }
+    }
+
+    /// Take a permit if one is available, otherwise tell how long to wait for it
+    pub(crate) fn try_acquire(&self) -> Result<(), Duration> {
+        let now = self.runtime.now();
+        let mut state = self.state.lock().unwrap();
+
+        let elapsed = now.saturating_duration_since(state.refilled);
+        let refill = elapsed.as_secs_f64() / MINUTE.as_secs_f64() * self.per_minute;
+        state.tokens = (state.tokens + refill).min(self.per_minute);
+        state.refilled = now;
+
+        while let Some(&oldest) = state.today.front() {
+            if oldest + DAY > now {
+                break;
+            }
+            state.today.pop_front();
+        }
+
+        if state.today.len() >= self.per_day {
+            return Err(state.today[0] + DAY - now);
+        }
+        if state.tokens < 1.0 {
+            let missing = (1.0 - state.tokens) / self.per_minute;
+            return Err(MINUTE.mul_f64(missing));
+        }
+        state.tokens -= 1.0;
+        state.today.push_back(now);
+        Ok(())
+    }
+
+    /// Wait until a permit is available, then take it
+    pub(crate) async fn acquire(&self) {
+        while let Err(wait) = self.try_acquire() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(?wait, "waiting for a rate limiter permit");
+            self.runtime.sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+
+    use super::*;
+    use crate::runtime::tests::ManualRuntime;
+
+    #[test]
+    fn permits_follow_schedule() {
+        let clock = ManualRuntime::new();
+        let limiter = RateLimiter::new(2, 3, clock.clone());
+
+        assert_eq!(limiter.try_acquire(), Ok(()));
+        // Clones share the same bucket
+        assert_eq!(limiter.clone().try_acquire(), Ok(()));
+        assert_eq!(limiter.try_acquire(), Err(Duration::from_secs(30)));
+
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(limiter.try_acquire(), Ok(()));
+
+        // The minute bucket is full again but the daily quota is spent
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(limiter.try_acquire(), Err(DAY - Duration::from_secs(90)));
+
+        // The first two calls leave the rolling window together
+        clock.advance(DAY - Duration::from_secs(90));
+        assert_eq!(limiter.try_acquire(), Ok(()));
+        assert_eq!(limiter.try_acquire(), Ok(()));
+        assert_eq!(limiter.try_acquire(), Err(Duration::from_secs(30)));
+    }
+}
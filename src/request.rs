@@ -0,0 +1,168 @@
+use std::fmt;
+
+use crate::{
+    push_list, Coordinates, Daily, Endpoint, Hourly, NoCoordinates, PressureVar, PreviousDay,
+    Settings,
+};
+
+/// Description of an endpoint, making `Request` its builder
+///
+/// A new endpoint is an enum of its variables and an implementation of this
+/// trait; its requests share the coordinates, settings and URL building of
+/// the forecast. Dates the endpoint cannot do without are checked by
+/// `Endpoint::needs_dates`
+pub trait EndpointSpec {
+    type Hourly: fmt::Display + Clone;
+    type Daily: fmt::Display + Clone;
+
+    /// Endpoint new requests are sent to
+    const ENDPOINT: Endpoint;
+    /// Query parameter listing the hourly variables
+    const HOURLY: &'static str = "hourly";
+    /// Query parameter listing the daily variables
+    const DAILY: &'static str = "daily";
+
+    /// Whether the endpoint takes `setting`, others are left out of the URL
+    fn allows(_setting: &Settings) -> bool {
+        true
+    }
+
+    /// Append the parameters the endpoint needs whatever the settings
+    fn mandatory(_settings: &[Settings], _url: &mut String) {}
+}
+
+#[derive(Clone)]
+/// Forecast API, also serving the archive, climate and historical forecast
+/// requests built on `Forecast`
+pub struct ForecastSpec;
+
+impl EndpointSpec for ForecastSpec {
+    type Hourly = Hourly;
+    type Daily = Daily;
+
+    const ENDPOINT: Endpoint = Endpoint::Forecast;
+}
+
+#[derive(Clone)]
+/// Request to the endpoint described by `E`, e.g. `Forecast` or `Marine`
+pub struct Request<E: EndpointSpec, C = Coordinates> {
+    pub(crate) coordinates: C,
+    pub(crate) endpoint: Endpoint,
+    pub(crate) settings: Vec<Settings>,
+    pub(crate) hourly: Vec<E::Hourly>,
+    pub(crate) pressure_var: Vec<PressureVar>,
+    pub(crate) previous_day: Vec<PreviousDay>,
+    pub(crate) daily: Vec<E::Daily>,
+}
+
+impl<E: EndpointSpec> Request<E, NoCoordinates> {
+    /// Initialize request object
+    pub fn new() -> Self {
+        Request {
+            coordinates: NoCoordinates,
+            endpoint: E::ENDPOINT,
+            settings: Vec::new(),
+            hourly: Vec::new(),
+            pressure_var: Vec::new(),
+            previous_day: Vec::new(),
+            daily: Vec::new(),
+        }
+    }
+}
+
+impl<E: EndpointSpec> Default for Request<E, NoCoordinates> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: EndpointSpec, C> Request<E, C> {
+    /// Specify coordinates (latitude, longitude)
+    /// These two are the only mandatory fields
+    pub fn coord(self, latitude: f32, longitude: f32) -> Request<E, Coordinates> {
+        Request {
+            coordinates: Coordinates {
+                latitude,
+                longitude,
+            },
+            endpoint: self.endpoint,
+            settings: self.settings,
+            hourly: self.hourly,
+            pressure_var: self.pressure_var,
+            previous_day: self.previous_day,
+            daily: self.daily,
+        }
+    }
+
+    /// Send the request to another endpoint sharing the forecast parameters
+    pub fn endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Add optional settings
+    pub fn settings(mut self, setting: Settings) -> Self {
+        self.settings.push(setting);
+        self
+    }
+
+    /// Get hourly value for a specific data
+    pub fn hourly(mut self, hourly: E::Hourly) -> Self {
+        self.hourly.push(hourly);
+        self
+    }
+
+    /// Get daily value for a specific data
+    pub fn daily(mut self, daily: E::Daily) -> Self {
+        self.daily.push(daily);
+        self
+    }
+}
+
+impl<E: EndpointSpec> Request<E, Coordinates> {
+    /// Convert the request into a valid URL against the given base
+    pub(crate) fn to_url(&self, base: &str) -> String {
+        format!(
+            "{base}?latitude={}&longitude={}{}",
+            self.coordinates.latitude,
+            self.coordinates.longitude,
+            self.params()
+        )
+    }
+
+    /// Query parameters following the coordinates
+    pub(crate) fn params(&self) -> String {
+        let mut url = String::new();
+        for el in self.settings.iter().filter(|setting| E::allows(setting)) {
+            url.push_str(format!("&{}={}", el, el.get()).as_str());
+        }
+        E::mandatory(&self.settings, &mut url);
+        let hourly: Vec<_> = self
+            .hourly
+            .iter()
+            .map(ToString::to_string)
+            .chain(self.previous_day.iter().map(ToString::to_string))
+            .chain(self.pressure_var.iter().map(PressureVar::get))
+            .collect();
+        push_list(&mut url, E::HOURLY, &hourly);
+        push_list(&mut url, E::DAILY, &self.daily);
+        url
+    }
+}
+
+impl<E: EndpointSpec> fmt::Display for Request<E, Coordinates> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_url(self.endpoint.url()))
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl<E: EndpointSpec> crate::EndpointRequest for Request<E, Coordinates> {
+    fn endpoint(&self) -> Endpoint {
+        self.endpoint
+    }
+
+    fn to_url(&self, base: &str) -> String {
+        Request::to_url(self, base)
+    }
+}
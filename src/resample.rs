@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+
+use crate::date::Date;
+use crate::{HourlyBlock, Timestamp};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate the time axes `HourlyBlock::resample` can group samples on
+pub enum Resample {
+    /// Consecutive windows of that many hours, aligned on midnight of the
+    /// timestamps' own time
+    Hours(u32),
+    /// Local calendar days, for a response `utc_offset_seconds` ahead of UTC
+    CalendarDay { utc_offset_seconds: i32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate the aggregations of the values of a window
+pub enum Agg {
+    Mean,
+    Min,
+    Max,
+    Sum,
+    First,
+    Last,
+}
+
+impl Agg {
+    pub(crate) fn apply(self, values: &[f64]) -> f64 {
+        match self {
+            Agg::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Agg::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Agg::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Agg::Sum => values.iter().sum(),
+            Agg::First => values[0],
+            Agg::Last => values[values.len() - 1],
+        }
+    }
+}
+
+impl HourlyBlock {
+    /// Block with one sample per window of `rule`, aggregating the values of
+    /// each variable in the window with `agg` and skipping missing data
+    ///
+    /// Windows are stamped with their start, `YYYY-MM-DD` for calendar days
+    /// when the block uses ISO 8601 times
+    pub fn resample(&self, rule: Resample, agg: Agg) -> HourlyBlock {
+        self.resample_with_coverage(rule, agg, 0.0)
+    }
+
+    /// Like `resample`, with missing data for windows where less than the
+    /// `min_coverage` fraction of the samples expected at the block's step
+    /// has a value, e.g. `0.75` for at least 18 hours of a day
+    pub fn resample_with_coverage(
+        &self,
+        rule: Resample,
+        agg: Agg,
+        min_coverage: f64,
+    ) -> HourlyBlock {
+        let (offset, length) = match rule {
+            Resample::Hours(hours) => (0, i64::from(hours.max(1)) * 3600),
+            Resample::CalendarDay { utc_offset_seconds } => (utc_offset_seconds, 86_400),
+        };
+        // Seconds since the epoch, in the local time of the windows
+        let local = |time: &Timestamp| Some(time.to_unix(offset)? + i64::from(offset));
+
+        let mut windows: BTreeMap<i64, Vec<usize>> = BTreeMap::new();
+        for (index, time) in self.time.iter().enumerate() {
+            if let Some(time) = local(time) {
+                windows
+                    .entry(time - time.rem_euclid(length))
+                    .or_default()
+                    .push(index);
+            }
+        }
+        let step = self.step_seconds().unwrap_or(3600);
+        let expected = (length / step).max(1) as f64;
+
+        let time = windows
+            .keys()
+            .map(|&start| match (self.time.first(), rule) {
+                (Some(Timestamp::Unix(_)), _) => Timestamp::Unix(start - i64::from(offset)),
+                (_, Resample::CalendarDay { .. }) => {
+                    Timestamp::Iso8601(Date::from_days(start.div_euclid(86_400)).to_string())
+                }
+                (_, Resample::Hours(_)) => {
+                    let day = Date::from_days(start.div_euclid(86_400));
+                    let clock = start.rem_euclid(86_400);
+                    Timestamp::Iso8601(format!(
+                        "{day}T{:02}:{:02}",
+                        clock / 3600,
+                        clock % 3600 / 60
+                    ))
+                }
+            })
+            .collect();
+        let series = self
+            .series
+            .iter()
+            .map(|(name, series)| {
+                let values = windows.values().map(|indices| {
+                    let present: Vec<f64> = indices
+                        .iter()
+                        .filter_map(|&index| series.values().get(index).copied().flatten())
+                        .collect();
+                    let covered = present.len() as f64 >= min_coverage * expected;
+                    (!present.is_empty() && covered).then(|| agg.apply(&present))
+                });
+                (name.clone(), values.collect::<Vec<_>>().into())
+            })
+            .collect();
+        HourlyBlock { time, series }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::ForecastResponse;
+
+    /// Two days of hourly rain from midnight UTC, one millimetre an hour
+    fn rain() -> HourlyBlock {
+        HourlyBlock {
+            time: (0..48)
+                .map(|hour| Timestamp::Unix(1_704_067_200 + hour * 3600))
+                .collect(),
+            series: [("precipitation".to_string(), vec![Some(1.0); 48].into())].into(),
+        }
+    }
+
+    #[test]
+    fn three_hourly_mean() {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/forecast.json")).unwrap();
+        let hourly = response.hourly.unwrap();
+
+        let resampled = hourly.resample(Resample::Hours(3), Agg::Mean);
+        assert_eq!(resampled.len(), 8);
+        assert_eq!(
+            resampled.time[1],
+            Timestamp::Iso8601("2023-05-16T03:00".into())
+        );
+        let temperature = resampled.get("temperature_2m").unwrap();
+        assert!((temperature[0].unwrap() - (9.8 + 9.4 + 9.1) / 3.0).abs() < 1e-9);
+        let rain = hourly.resample(Resample::Hours(3), Agg::Sum);
+        assert_eq!(rain.get("rain").unwrap()[5], Some(1.4 + 0.6 + 0.1));
+
+        let maximum = hourly.resample(Resample::Hours(24), Agg::Max);
+        assert_eq!(
+            maximum.get("temperature_2m").unwrap().values(),
+            [Some(17.9)]
+        );
+        let last = hourly.resample(Resample::Hours(12), Agg::Last);
+        assert_eq!(last.get("temperature_2m").unwrap()[1], Some(10.7));
+    }
+
+    #[test]
+    fn calendar_days_in_local_time() {
+        let daily = rain().resample(
+            Resample::CalendarDay {
+                utc_offset_seconds: 10 * 3600,
+            },
+            Agg::Sum,
+        );
+        // 10:00 to midnight, a full day, then midnight to 10:00 at UTC+10
+        assert_eq!(
+            daily.time,
+            [
+                Timestamp::Unix(1_704_067_200 - 10 * 3600),
+                Timestamp::Unix(1_704_153_600 - 10 * 3600),
+                Timestamp::Unix(1_704_240_000 - 10 * 3600),
+            ]
+        );
+        assert_eq!(
+            daily.get("precipitation").unwrap().values(),
+            [Some(14.0), Some(24.0), Some(10.0)]
+        );
+    }
+
+    #[test]
+    fn coverage_threshold() {
+        let mut block = rain();
+        for value in &mut block.series[0].0[24..30] {
+            *value = None;
+        }
+        let day = Resample::CalendarDay {
+            utc_offset_seconds: 0,
+        };
+
+        let sums = block.resample(day, Agg::Sum);
+        assert_eq!(
+            sums.get("precipitation").unwrap().values(),
+            [Some(24.0), Some(18.0)]
+        );
+        let covered = block.resample_with_coverage(day, Agg::Sum, 0.8);
+        assert_eq!(
+            covered.get("precipitation").unwrap().values(),
+            [Some(24.0), None]
+        );
+        let covered = block.resample_with_coverage(day, Agg::Sum, 0.75);
+        assert_eq!(
+            covered.get("precipitation").unwrap().values(),
+            [Some(24.0), Some(18.0)]
+        );
+
+        block.series[0].0.fill(None);
+        let empty = block.resample(day, Agg::Mean);
+        assert_eq!(empty.get("precipitation").unwrap().values(), [None, None]);
+    }
+}
@@ -0,0 +1,1207 @@
+use std::{borrow::Cow, fmt, iter};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{ColumnKind, Daily, Hourly, PressureVar, UnitsMap, WmoCode};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Parsed forecast returned by the API
+pub struct ForecastResponse {
+    /// Position of the location in a multi-location request, `0` otherwise
+    #[serde(default, skip_serializing_if = "is_first")]
+    pub location_id: u32,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub elevation: f64,
+    pub generationtime_ms: f64,
+    pub utc_offset_seconds: i32,
+    pub timezone: String,
+    pub timezone_abbreviation: String,
+    /// Unit of each `current_weather` field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_weather_units: Option<UnitsMap>,
+    /// Conditions requested with `Settings::current_weather`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_weather: Option<CurrentWeather>,
+    /// Unit of each current variable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_units: Option<UnitsMap>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<Current>,
+    /// Unit of each 15-minutely variable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minutely_15_units: Option<UnitsMap>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minutely_15: Option<HourlyBlock>,
+    /// Unit of each hourly variable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hourly_units: Option<UnitsMap>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hourly: Option<HourlyBlock>,
+    /// Unit of each six-hourly variable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub six_hourly_units: Option<UnitsMap>,
+    /// Variables of the seasonal forecast, see `Seasonal`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub six_hourly: Option<HourlyBlock>,
+    /// Unit of each daily variable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_units: Option<UnitsMap>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily: Option<DailyBlock>,
+}
+
+impl ForecastResponse {
+    /// Parse a forecast from the JSON answered by the API
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Values of an hourly variable, `None` if the response does not contain it
+    pub fn hourly(&self, variable: Hourly) -> Option<&Series> {
+        self.hourly_by_name(&variable.to_string())
+    }
+
+    /// Values of an hourly variable this crate has no `Hourly` flag for
+    pub fn hourly_by_name(&self, variable: &str) -> Option<&Series> {
+        self.hourly.as_ref()?.get(variable)
+    }
+
+    /// Values of a pressure level variable, e.g. `temperature_850hPa`
+    pub fn pressure(&self, variable: PressureVar) -> Option<&Series> {
+        self.hourly_by_name(&variable.get())
+    }
+
+    /// Values of a daily variable, `None` if the response does not contain it
+    pub fn daily(&self, variable: Daily) -> Option<&[Option<DailyValue>]> {
+        self.daily.as_ref()?.get(&variable.to_string())
+    }
+
+    /// Current value of a variable, `None` if missing or not a number
+    pub fn current(&self, variable: Hourly) -> Option<f64> {
+        self.current.as_ref()?.get(&variable.to_string())
+    }
+}
+
+fn is_first(location_id: &u32) -> bool {
+    *location_id == 0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+/// Forecasts answered for one or several locations, ordered by `location_id`
+///
+/// Deserializes both the single object and the array returned when several
+/// coordinates are requested
+pub struct ForecastResponses(pub Vec<ForecastResponse>);
+
+impl ForecastResponses {
+    /// Parse the JSON answered for one or several locations
+    pub fn from_json(json: &str) -> Result<Vec<ForecastResponse>, serde_json::Error> {
+        serde_json::from_str::<Self>(json).map(|responses| responses.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ForecastResponses {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{
+            value::{MapAccessDeserializer, SeqAccessDeserializer},
+            MapAccess, SeqAccess, Visitor,
+        };
+
+        struct Shapes;
+
+        impl<'de> Visitor<'de> for Shapes {
+            type Value = Vec<ForecastResponse>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a forecast or an array of forecasts")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                Vec::deserialize(SeqAccessDeserializer::new(seq))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                ForecastResponse::deserialize(MapAccessDeserializer::new(map)).map(|one| vec![one])
+            }
+        }
+
+        let mut responses = deserializer.deserialize_any(Shapes)?;
+        responses.sort_by_key(|response| response.location_id);
+        Ok(ForecastResponses(responses))
+    }
+}
+
+/// Weather code sent as a JSON number, `None` if not a valid code
+fn code(value: f64) -> Option<WmoCode> {
+    let valid = value.fract() == 0.0 && (0.0..=f64::from(u8::MAX)).contains(&value);
+    valid.then(|| WmoCode::from(value as u8))
+}
+
+/// Open-Meteo renamed variables by splitting words, e.g. `windspeed_10m` became
+/// `wind_speed_10m`; responses use whichever spelling was requested
+const RENAMED: [(&str, &str); 8] = [
+    ("relativehumidity", "relative_humidity"),
+    ("dewpoint", "dew_point"),
+    ("cloudcover", "cloud_cover"),
+    ("windspeed", "wind_speed"),
+    ("winddirection", "wind_direction"),
+    ("windgusts", "wind_gusts"),
+    ("weathercode", "weather_code"),
+    ("freezinglevel", "freezing_level"),
+];
+
+/// `variable` as spelled, then in the other naming scheme if it was renamed
+pub(crate) fn spellings(variable: &str) -> impl Iterator<Item = Cow<'_, str>> {
+    let renamed = RENAMED.iter().find_map(|(legacy, current)| {
+        if variable.contains(current) {
+            Some(variable.replace(current, legacy))
+        } else if variable.contains(legacy) {
+            Some(variable.replace(legacy, current))
+        } else {
+            None
+        }
+    });
+    iter::once(Cow::Borrowed(variable)).chain(renamed.map(Cow::Owned))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Legacy current conditions block
+pub struct CurrentWeather {
+    pub time: Timestamp,
+    /// Length in seconds of the interval the values are aggregated over
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u32>,
+    pub temperature: f64,
+    pub windspeed: f64,
+    pub winddirection: f64,
+    pub weathercode: WmoCode,
+    /// Sent by the API as `1` during daylight and `0` at night
+    #[serde(with = "flag")]
+    pub is_day: bool,
+}
+
+/// Booleans encoded as `0`/`1` by the API
+mod flag {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(flag: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(u8::from(*flag))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+        Ok(u8::deserialize(deserializer)? != 0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Values of the requested current variables at a single timestamp
+pub struct Current {
+    pub time: Timestamp,
+    /// Length in seconds of the interval the values are aggregated over
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u32>,
+    /// Values by variable name, in the order returned by the API
+    #[serde(flatten)]
+    pub values: IndexMap<String, serde_json::Value>,
+}
+
+impl Current {
+    /// Value of a variable, `None` if missing or not a number
+    pub fn get(&self, variable: &str) -> Option<f64> {
+        self.value(variable)?.as_f64()
+    }
+
+    /// Current weather code, `None` if not requested
+    pub fn weathercode(&self) -> Option<WmoCode> {
+        code(self.get("weathercode")?)
+    }
+
+    /// Raw JSON value of a variable under either naming scheme
+    pub fn value(&self, variable: &str) -> Option<&serde_json::Value> {
+        spellings(variable).find_map(|name| self.values.get(name.as_ref()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+/// Hourly (or 15-minutely) variables, one value per timestamp
+///
+/// Series are keyed by the name used in the request, e.g. `temperature_2m` or
+/// `temperature_850hPa` for pressure level variables
+pub struct HourlyBlock {
+    pub time: Vec<Timestamp>,
+    /// Values by variable name, in the order returned by the API
+    #[serde(flatten)]
+    pub series: IndexMap<String, Series>,
+}
+
+impl HourlyBlock {
+    /// Number of timestamps
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+
+    /// Values of a variable under either naming scheme, `None` if missing
+    pub fn get(&self, variable: &str) -> Option<&Series> {
+        spellings(variable).find_map(|name| self.series.get(name.as_ref()))
+    }
+
+    /// Weather codes, `None` if not requested; `get("weathercode")` keeps the numbers
+    pub fn weathercode(&self) -> Option<Vec<Option<WmoCode>>> {
+        let series = self.get("weathercode")?;
+        Some(series.iter().map(|value| code((*value)?)).collect())
+    }
+
+    /// Pairs of timestamp and value of a variable, `None` if missing
+    ///
+    /// Pairs stop at the shorter of the two, which `check_consistency` rules out
+    pub fn iter(
+        &self,
+        variable: Hourly,
+    ) -> Option<impl Iterator<Item = (&Timestamp, Option<f64>)>> {
+        self.iter_by_name(&variable.to_string())
+    }
+
+    /// Pairs of timestamp and value of a variable named in the response
+    pub fn iter_by_name(
+        &self,
+        variable: &str,
+    ) -> Option<impl Iterator<Item = (&Timestamp, Option<f64>)>> {
+        let series = self.get(variable)?;
+        Some(self.time.iter().zip(series.iter().copied()))
+    }
+
+    /// Earliest timestamp and its value
+    pub fn first(&self, variable: Hourly) -> Option<(&Timestamp, Option<f64>)> {
+        self.iter(variable)?.next()
+    }
+
+    /// Latest timestamp and its value
+    pub fn last(&self, variable: Hourly) -> Option<(&Timestamp, Option<f64>)> {
+        self.iter(variable)?.last()
+    }
+
+    /// Value at the timestamp equal to `time`, `None` if there is none or no data
+    ///
+    /// `at` looks `chrono` or `time` date-times up
+    pub fn at_timestamp(&self, variable: Hourly, time: &Timestamp) -> Option<f64> {
+        let index = self.time.iter().position(|t| t == time)?;
+        self.value(variable, index)
+    }
+
+    pub(crate) fn value(&self, variable: Hourly, index: usize) -> Option<f64> {
+        self.get(&variable.to_string())?
+            .get(index)
+            .copied()
+            .flatten()
+    }
+
+    /// Names of the returned variables
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.series.keys().map(String::as_str)
+    }
+
+    /// Every variable's value at each timestamp, in time order
+    pub fn rows(&self) -> impl Iterator<Item = HourlyRow<'_>> {
+        (0..self.len()).map(|index| HourlyRow { block: self, index })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Values of all hourly variables at one timestamp, borrowed from the block
+pub struct HourlyRow<'a> {
+    block: &'a HourlyBlock,
+    index: usize,
+}
+
+impl<'a> HourlyRow<'a> {
+    pub fn time(&self) -> &'a Timestamp {
+        &self.block.time[self.index]
+    }
+
+    /// Value of a variable, `None` if missing or without data at this time
+    pub fn get(&self, variable: Hourly) -> Option<f64> {
+        self.get_name(&variable.to_string())
+    }
+
+    /// Value of a variable named in the response
+    pub fn get_name(&self, variable: &str) -> Option<f64> {
+        self.block.get(variable)?.get(self.index).copied().flatten()
+    }
+
+    /// Name and value of each variable, in the order returned by the API
+    pub fn values(&self) -> impl Iterator<Item = (&'a str, Option<f64>)> + 'a {
+        let index = self.index;
+        self.block
+            .series
+            .iter()
+            .map(move |(name, series)| (name.as_str(), series.get(index).copied().flatten()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+/// Numeric values of a variable, one per timestamp and `None` where the API
+/// has no data (`null`), e.g. before the start of a model run
+pub struct Series(pub(crate) Vec<Option<f64>>);
+
+impl Series {
+    pub fn values(&self) -> &[Option<f64>] {
+        &self.0
+    }
+
+    /// Number of missing values
+    pub fn nulls_count(&self) -> usize {
+        self.0.iter().filter(|value| value.is_none()).count()
+    }
+
+    /// Values with gaps replaced by `fill_value`, still aligned with the time axis
+    pub fn filled(&self, fill_value: f64) -> impl Iterator<Item = f64> + '_ {
+        self.0.iter().map(move |value| value.unwrap_or(fill_value))
+    }
+
+    /// Present values only; they no longer line up with the time axis
+    pub fn dropped(&self) -> impl Iterator<Item = f64> + '_ {
+        self.0.iter().flatten().copied()
+    }
+}
+
+impl std::ops::Deref for Series {
+    type Target = [Option<f64>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Vec<Option<f64>>> for Series {
+    fn from(values: Vec<Option<f64>>) -> Self {
+        Series(values)
+    }
+}
+
+impl FromIterator<Option<f64>> for Series {
+    fn from_iter<I: IntoIterator<Item = Option<f64>>>(values: I) -> Self {
+        Series(values.into_iter().collect())
+    }
+}
+
+impl Extend<Option<f64>> for Series {
+    fn extend<I: IntoIterator<Item = Option<f64>>>(&mut self, values: I) {
+        self.0.extend(values)
+    }
+}
+
+impl IntoIterator for Series {
+    type Item = Option<f64>;
+    type IntoIter = std::vec::IntoIter<Option<f64>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "TimeSeries")]
+/// Daily variables, one value per day and `None` where the API has no data
+///
+/// Values are typed after the variable: `weathercode` holds codes, `sunrise` and
+/// `sunset` hold timestamps, other known variables hold numbers and variables
+/// unknown to this crate keep the raw JSON value
+pub struct DailyBlock {
+    pub time: Vec<Timestamp>,
+    /// Values by variable name, in the order returned by the API
+    #[serde(flatten)]
+    pub series: IndexMap<String, Vec<Option<DailyValue>>>,
+}
+
+impl DailyBlock {
+    /// Number of days
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+
+    /// Values of a variable under either naming scheme, `None` if missing
+    pub fn get(&self, variable: &str) -> Option<&[Option<DailyValue>]> {
+        spellings(variable)
+            .find_map(|name| self.series.get(name.as_ref()))
+            .map(Vec::as_slice)
+    }
+
+    /// Weather codes, `None` if not requested
+    pub fn weathercode(&self) -> Option<Vec<Option<WmoCode>>> {
+        let values = self.get("weathercode")?;
+        Some(
+            values
+                .iter()
+                .map(|value| value.as_ref()?.as_code().map(WmoCode::from))
+                .collect(),
+        )
+    }
+
+    /// Numeric values of a variable, `None` where a value is missing or not a number
+    pub fn numbers(&self, variable: &str) -> Option<Series> {
+        let values = self.get(variable)?;
+        Some(
+            values
+                .iter()
+                .map(|value| value.as_ref().and_then(DailyValue::as_f64))
+                .collect(),
+        )
+    }
+
+    /// Names of the returned variables
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.series.keys().map(String::as_str)
+    }
+
+    /// Every variable's value on each day, in time order
+    pub fn rows(&self) -> impl Iterator<Item = DailyRow<'_>> {
+        (0..self.len()).map(|index| DailyRow { block: self, index })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Values of all daily variables on one day, borrowed from the block
+pub struct DailyRow<'a> {
+    block: &'a DailyBlock,
+    index: usize,
+}
+
+impl<'a> DailyRow<'a> {
+    pub fn time(&self) -> &'a Timestamp {
+        &self.block.time[self.index]
+    }
+
+    /// Value of a variable, `None` if missing or without data on this day
+    pub fn get(&self, variable: Daily) -> Option<&'a DailyValue> {
+        self.get_name(&variable.to_string())
+    }
+
+    /// Value of a variable named in the response
+    pub fn get_name(&self, variable: &str) -> Option<&'a DailyValue> {
+        self.block.get(variable)?.get(self.index)?.as_ref()
+    }
+
+    /// Name and value of each variable, in the order returned by the API
+    pub fn values(&self) -> impl Iterator<Item = (&'a str, Option<&'a DailyValue>)> + 'a {
+        let index = self.index;
+        self.block
+            .series
+            .iter()
+            .map(move |(name, values)| (name.as_str(), values.get(index).and_then(Option::as_ref)))
+    }
+}
+
+impl TryFrom<TimeSeries> for DailyBlock {
+    type Error = String;
+
+    fn try_from(raw: TimeSeries) -> Result<Self, Self::Error> {
+        let mut series = IndexMap::with_capacity(raw.values.len());
+        for (name, values) in raw.values {
+            let values = values
+                .into_iter()
+                .map(|value| DailyValue::typed(&name, value))
+                .collect::<Result<_, _>>()?;
+            series.insert(name, values);
+        }
+        Ok(DailyBlock {
+            time: raw.time,
+            series,
+        })
+    }
+}
+
+/// Type of the values of the daily variable `name`
+pub(crate) fn daily_kind(name: &str) -> ColumnKind {
+    let name = crate::suffix::member_suffix(name).map_or(name, |(name, _)| name);
+    let name = crate::climate::strip_model(name);
+    match spellings(name).find_map(|name| name.parse::<Daily>().ok()) {
+        Some(Daily::weathercode) => ColumnKind::Code,
+        Some(Daily::sunrise | Daily::sunset) => ColumnKind::Time,
+        Some(_) => ColumnKind::Float,
+        None if name.parse::<crate::MarineDaily>().is_ok() => ColumnKind::Float,
+        None if name.parse::<crate::FloodDaily>().is_ok() => ColumnKind::Float,
+        None if name.parse::<crate::ClimateDaily>().is_ok() => ColumnKind::Float,
+        None => ColumnKind::Raw,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+/// Daily sample, typed according to the variable it belongs to
+pub enum DailyValue {
+    Float(f64),
+    /// Time of day, e.g. `sunrise`
+    Time(Timestamp),
+    /// WMO weather code
+    Code(u8),
+    /// Value of a variable unknown to this crate
+    Raw(serde_json::Value),
+}
+
+impl DailyValue {
+    /// Type a value returned for the daily variable `name`, `None` for `null`
+    fn typed(name: &str, value: serde_json::Value) -> Result<Option<Self>, String> {
+        if value.is_null() {
+            return Ok(None);
+        }
+        let typed = match daily_kind(name) {
+            ColumnKind::Code => value
+                .as_u64()
+                .and_then(|code| u8::try_from(code).ok())
+                .map(DailyValue::Code),
+            ColumnKind::Time => serde_json::from_value(value.clone())
+                .ok()
+                .map(DailyValue::Time),
+            ColumnKind::Float => value.as_f64().map(DailyValue::Float),
+            ColumnKind::Raw => Some(DailyValue::Raw(value.clone())),
+        };
+        typed
+            .map(Some)
+            .ok_or_else(|| format!("unexpected daily `{name}` value {value}"))
+    }
+
+    /// Numbers and codes as a float
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            DailyValue::Float(value) => Some(*value),
+            DailyValue::Code(code) => Some(f64::from(*code)),
+            DailyValue::Time(_) => None,
+            DailyValue::Raw(value) => value.as_f64(),
+        }
+    }
+
+    pub fn as_code(&self) -> Option<u8> {
+        match self {
+            DailyValue::Code(code) => Some(*code),
+            _ => None,
+        }
+    }
+
+    pub fn as_time(&self) -> Option<&Timestamp> {
+        match self {
+            DailyValue::Time(time) => Some(time),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Values of the requested variables, one per timestamp
+pub struct TimeSeries {
+    pub time: Vec<Timestamp>,
+    /// Values by variable name, in the order returned by the API
+    #[serde(flatten)]
+    pub values: IndexMap<String, Vec<serde_json::Value>>,
+}
+
+impl TimeSeries {
+    /// Numeric values of a variable, `None` where the API has no data
+    pub fn numbers(&self, variable: &str) -> Option<Vec<Option<f64>>> {
+        let values = self.values.get(variable)?;
+        Some(values.iter().map(serde_json::Value::as_f64).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+/// Time of a sample, formatted according to the requested `TimeFormat`
+pub enum Timestamp {
+    Unix(i64),
+    Iso8601(String),
+}
+
+impl Timestamp {
+    /// Seconds since the epoch, if the response used `TimeFormat::unixtime`
+    pub fn as_unix(&self) -> Option<i64> {
+        match self {
+            Timestamp::Unix(seconds) => Some(*seconds),
+            Timestamp::Iso8601(_) => None,
+        }
+    }
+
+    /// Local wall-clock time or date, if the response used `TimeFormat::iso8601`
+    pub fn as_iso8601(&self) -> Option<&str> {
+        match self {
+            Timestamp::Unix(_) => None,
+            Timestamp::Iso8601(time) => Some(time),
+        }
+    }
+
+    /// Seconds since the epoch whatever the format, reading ISO 8601 wall-clock
+    /// times and dates (at midnight) `utc_offset_seconds` ahead of UTC
+    pub fn to_unix(&self, utc_offset_seconds: i32) -> Option<i64> {
+        let time = match self {
+            Timestamp::Unix(seconds) => return Some(*seconds),
+            Timestamp::Iso8601(time) => time,
+        };
+        let (date, clock) = time.split_once('T').unwrap_or((time, "00:00"));
+        let (hours, minutes) = clock.split_once(':')?;
+        let (hours, minutes): (i64, i64) = (hours.parse().ok()?, minutes.parse().ok()?);
+        if hours > 23 || minutes > 59 {
+            return None;
+        }
+        let days = crate::date::Date::parse(date)?.days();
+        Some(days * 86_400 + hours * 3600 + minutes * 60 - i64::from(utc_offset_seconds))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Timestamp::Unix(seconds) => write!(f, "{seconds}"),
+            Timestamp::Iso8601(time) => write!(f, "{time}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg(any(feature = "client", feature = "blocking"))]
+/// Enumerate the body formats the clients can ask the API for
+pub enum DecodeMode {
+    #[default]
+    Json,
+    /// `format=flatbuffers`, smaller and faster to decode than JSON
+    #[cfg(feature = "fb")]
+    FlatBuffers,
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl DecodeMode {
+    /// Query parameter selecting the format, appended to the request URL
+    pub(crate) fn query(self) -> &'static str {
+        match self {
+            DecodeMode::Json => "",
+            #[cfg(feature = "fb")]
+            DecodeMode::FlatBuffers => "&format=flatbuffers",
+        }
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl ForecastResponse {
+    /// Check the status of a raw response fetched from `url` and decode its body
+    pub(crate) fn decode(
+        url: &str,
+        response: &crate::HttpResponse,
+        mode: DecodeMode,
+    ) -> Result<Self, crate::WeatheredError> {
+        check_status(url, response)?;
+        Self::parse(url, &response.body, mode)
+            .map_err(|error| api_error(url, response).unwrap_or(error))
+    }
+
+    /// Decode the array answered by a request for several locations, in the
+    /// order the locations were given
+    #[cfg(feature = "client")]
+    pub(crate) fn decode_many(
+        url: &str,
+        response: &crate::HttpResponse,
+        mode: DecodeMode,
+    ) -> Result<Vec<Self>, crate::WeatheredError> {
+        check_status(url, response)?;
+        let decoded =
+            match mode {
+                DecodeMode::Json => serde_json::from_slice::<ForecastResponses>(&response.body)
+                    .map(|responses| responses.0)
+                    .map_err(|source| crate::WeatheredError::Decode {
+                        url: url.to_string(),
+                        source,
+                    }),
+                #[cfg(feature = "fb")]
+                DecodeMode::FlatBuffers => ForecastResponses::from_flatbuffers(&response.body)
+                    .map_err(|source| crate::WeatheredError::FlatBuffers {
+                        url: url.to_string(),
+                        source,
+                    }),
+            };
+        decoded.map_err(|error| api_error(url, response).unwrap_or(error))
+    }
+
+    /// Decode a body fetched from `url`
+    pub(crate) fn parse(
+        url: &str,
+        body: &[u8],
+        mode: DecodeMode,
+    ) -> Result<Self, crate::WeatheredError> {
+        match mode {
+            // The body is already in memory: reading it through `from_reader`
+            // would only copy it again. Series are still allocated once from
+            // the `time` array by the `HourlyBlock` deserializer
+            DecodeMode::Json => {
+                serde_json::from_slice(body).map_err(|source| crate::WeatheredError::Decode {
+                    url: url.to_string(),
+                    source,
+                })
+            }
+            #[cfg(feature = "fb")]
+            DecodeMode::FlatBuffers => {
+                Self::from_flatbuffers(body).map_err(|source| crate::WeatheredError::FlatBuffers {
+                    url: url.to_string(),
+                    source,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+pub(crate) fn check_status(
+    url: &str,
+    response: &crate::HttpResponse,
+) -> Result<(), crate::WeatheredError> {
+    use crate::WeatheredError;
+
+    match response.status {
+        200..=299 => Ok(()),
+        429 => Err(WeatheredError::RateLimited {
+            url: url.to_string(),
+            retry_after: response.retry_after(),
+        }),
+        status => Err(api_error(url, response).unwrap_or(WeatheredError::Status {
+            url: url.to_string(),
+            status,
+        })),
+    }
+}
+
+/// Error payload of the API, e.g. `{"error": true, "reason": "..."}`
+#[cfg(any(feature = "client", feature = "blocking"))]
+#[derive(Deserialize)]
+struct ApiError {
+    error: bool,
+    reason: String,
+}
+
+/// Error explained by the body of the response, if it holds one
+#[cfg(any(feature = "client", feature = "blocking"))]
+fn api_error(url: &str, response: &crate::HttpResponse) -> Option<crate::WeatheredError> {
+    let payload: ApiError = serde_json::from_slice(&response.body).ok()?;
+    payload.error.then(|| crate::WeatheredError::Api {
+        url: url.to_string(),
+        status: response.status,
+        reason: payload.reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    use crate::{HttpResponse, WeatheredError};
+
+    const FIXTURE: &str = include_str!("../fixtures/forecast.json");
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    const URL: &str = "https://api.open-meteo.com/v1/forecast?latitude=91";
+
+    #[test]
+    fn fixture_is_typed() {
+        let response = ForecastResponse::from_json(FIXTURE).unwrap();
+        assert_eq!(response.latitude, 52.52);
+        assert_eq!(response.utc_offset_seconds, 0);
+        assert_eq!(response.timezone, "GMT");
+
+        let current = response.current_weather.unwrap();
+        assert_eq!(current.time, Timestamp::Iso8601("2023-05-16T19:00".into()));
+        assert_eq!(current.interval, Some(900));
+        assert_eq!(current.temperature, 13.4);
+        assert_eq!(current.windspeed, 11.2);
+        assert_eq!(current.winddirection, 254.0);
+        assert_eq!(current.weathercode, WmoCode::Overcast);
+        assert!(current.is_day);
+        let units = response.current_weather_units.unwrap();
+        assert_eq!(units.get("windspeed"), Some("km/h"));
+        assert_eq!(units.get("weathercode"), Some("wmo code"));
+
+        let night = r#"{"time":1684263600,"temperature":8.1,"windspeed":3.2,"winddirection":90.0,"weathercode":95,"is_day":0}"#;
+        let night: CurrentWeather = serde_json::from_str(night).unwrap();
+        assert_eq!(night.time, Timestamp::Unix(1684263600));
+        assert_eq!(night.weathercode, WmoCode::Thunderstorm);
+        assert!(!night.is_day);
+        assert_eq!(serde_json::to_value(&night).unwrap()["is_day"], 0);
+
+        let hourly = response.hourly.unwrap();
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly.get("rain").unwrap()[15], Some(1.4));
+        assert_eq!(
+            response.hourly_units.unwrap().get("temperature_2m"),
+            Some("°C")
+        );
+        let daily = response.daily.unwrap();
+        assert_eq!(
+            daily.numbers("temperature_2m_max").unwrap().values(),
+            [Some(17.9)]
+        );
+        assert_eq!(
+            daily.get("sunset").unwrap()[0],
+            Some(DailyValue::Time(Timestamp::Iso8601(
+                "2023-05-16T19:07".into()
+            )))
+        );
+        assert!(response.current.is_none());
+        assert!(response.minutely_15.is_none());
+    }
+
+    #[test]
+    fn current_and_unknown_fields() {
+        let json = r#"{"latitude":47.38,"longitude":8.54,"generationtime_ms":0.1,"utc_offset_seconds":7200,"timezone":"Europe/Zurich","timezone_abbreviation":"CEST","elevation":409.0,"new_field":{"nested":[1,2]},"current_units":{"time":"unixtime","interval":"seconds","temperature_2m":"°C"},"current":{"time":1697792400,"interval":900,"temperature_2m":11.5},"minutely_15":{"time":[1697792400,1697793300],"precipitation":[0.1,null]}}"#;
+
+        let response = ForecastResponse::from_json(json).unwrap();
+        let current = response.current.unwrap();
+        assert_eq!(current.time, Timestamp::Unix(1697792400));
+        assert_eq!(current.interval, Some(900));
+        assert_eq!(current.values["temperature_2m"], 11.5);
+        assert_eq!(
+            response
+                .minutely_15
+                .unwrap()
+                .get("precipitation")
+                .unwrap()
+                .values(),
+            [Some(0.1), None]
+        );
+    }
+
+    #[test]
+    fn typed_accessors() {
+        let forecast = crate::Forecast::new()
+            .hourly(Hourly::temperature_2m)
+            .hourly(Hourly::windspeed_10m)
+            .pressure_var(PressureVar::temperature(850))
+            .daily(Daily::weathercode);
+        // Requested with the newer names, e.g. `wind_speed_10m`
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/pressure.json")).unwrap();
+        assert_eq!(response.check_against(&forecast), Ok(()));
+
+        let temperature = response.hourly(Hourly::temperature_2m).unwrap();
+        assert_eq!(
+            temperature.values(),
+            [Some(3.1), Some(2.8), Some(2.4), None]
+        );
+        assert_eq!(
+            response.hourly(Hourly::windspeed_10m).unwrap()[3],
+            Some(12.0)
+        );
+        assert_eq!(
+            response.hourly_by_name("windspeed_10m"),
+            response.hourly_by_name("wind_speed_10m")
+        );
+        assert!(response.hourly(Hourly::rain).is_none());
+        assert!(response.hourly_by_name("custom_variable").is_none());
+
+        let pressure = response.pressure(PressureVar::temperature(850)).unwrap();
+        assert_eq!(pressure[0], Some(-1.5));
+        assert!(response.pressure(PressureVar::temperature(500)).is_none());
+
+        assert_eq!(
+            response.daily(Daily::weathercode).unwrap(),
+            [Some(DailyValue::Code(61))]
+        );
+        assert!(response.daily(Daily::sunrise).is_none());
+        assert_eq!(response.current(Hourly::windspeed_10m), Some(14.8));
+        assert_eq!(response.current(Hourly::weathercode), Some(3.0));
+        assert_eq!(response.current(Hourly::temperature_2m), None);
+    }
+
+    #[test]
+    fn time_value_pairs() {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/pressure.json")).unwrap();
+        let hourly = response.hourly.unwrap();
+        let time = |hour: u8| Timestamp::Iso8601(format!("2024-03-02T{hour:02}:00"));
+
+        let pairs = hourly
+            .iter(Hourly::temperature_2m)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(pairs.len(), 4);
+        assert_eq!(pairs[1], (&time(1), Some(2.8)));
+        assert_eq!(pairs[3], (&time(3), None));
+        assert_eq!(
+            hourly.first(Hourly::windspeed_10m),
+            Some((&time(0), Some(9.7)))
+        );
+        assert_eq!(hourly.last(Hourly::temperature_2m), Some((&time(3), None)));
+        assert_eq!(
+            hourly.iter_by_name("temperature_850hPa").unwrap().nth(2),
+            Some((&time(2), Some(-2.2)))
+        );
+        assert!(hourly.iter(Hourly::rain).is_none());
+
+        assert_eq!(
+            hourly.at_timestamp(Hourly::temperature_2m, &time(2)),
+            Some(2.4)
+        );
+        assert_eq!(hourly.at_timestamp(Hourly::temperature_2m, &time(3)), None);
+        assert_eq!(hourly.at_timestamp(Hourly::temperature_2m, &time(4)), None);
+    }
+
+    #[test]
+    fn rows() {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/pressure.json")).unwrap();
+        let hourly = response.hourly.unwrap();
+        assert_eq!(hourly.variables().count(), 3);
+        assert_eq!(hourly.rows().count(), hourly.time.len());
+
+        let row = hourly.rows().nth(2).unwrap();
+        assert_eq!(row.time(), &Timestamp::Iso8601("2024-03-02T02:00".into()));
+        assert_eq!(row.get(Hourly::temperature_2m), Some(2.4));
+        assert_eq!(row.get(Hourly::windspeed_10m), Some(11.2));
+        assert_eq!(row.get_name("temperature_850hPa"), Some(-2.2));
+        assert_eq!(row.get(Hourly::rain), None);
+        assert_eq!(
+            row.values().collect::<Vec<_>>(),
+            [
+                ("temperature_2m", Some(2.4)),
+                ("wind_speed_10m", Some(11.2)),
+                ("temperature_850hPa", Some(-2.2))
+            ]
+        );
+        assert_eq!(
+            hourly.rows().last().unwrap().get(Hourly::temperature_2m),
+            None
+        );
+
+        let daily = ForecastResponse::from_json(FIXTURE).unwrap().daily.unwrap();
+        let rows = daily.rows().collect::<Vec<_>>();
+        assert_eq!(rows.len(), daily.time.len());
+        assert_eq!(rows[0].get(Daily::weathercode), Some(&DailyValue::Code(61)));
+        assert_eq!(
+            rows[0].get_name("temperature_2m_max"),
+            Some(&DailyValue::Float(17.9))
+        );
+        assert_eq!(rows[0].values().count(), 4);
+    }
+
+    #[test]
+    fn one_or_several_locations() {
+        let responses =
+            ForecastResponses::from_json(include_str!("../fixtures/locations.json")).unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].location_id, 0);
+        assert_eq!(responses[1].location_id, 1);
+        assert_eq!(responses[1].latitude, 47.38);
+        assert_eq!(
+            responses[1].hourly(Hourly::temperature_2m).unwrap()[0],
+            Some(4.2)
+        );
+
+        let single = ForecastResponses::from_json(FIXTURE).unwrap();
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].location_id, 0);
+        assert_eq!(single[0], ForecastResponse::from_json(FIXTURE).unwrap());
+        assert!(!serde_json::to_string(&single[0])
+            .unwrap()
+            .contains("location_id"));
+
+        assert!(ForecastResponses::from_json("42").is_err());
+    }
+
+    #[test]
+    fn weather_codes() {
+        let json = r#"{"latitude":47.38,"longitude":8.54,"generationtime_ms":0.1,"utc_offset_seconds":0,"timezone":"GMT","timezone_abbreviation":"GMT","elevation":409.0,"current":{"time":"2023-10-20T12:00","interval":900,"weather_code":95},"hourly":{"time":["2023-10-20T00:00","2023-10-20T01:00","2023-10-20T02:00","2023-10-20T03:00"],"weather_code":[0,61,null,4]},"daily":{"time":["2023-10-20","2023-10-21"],"weathercode":[63,100]}}"#;
+        let response = ForecastResponse::from_json(json).unwrap();
+
+        let hourly = response.hourly.unwrap();
+        assert_eq!(
+            hourly.weathercode().unwrap(),
+            [
+                Some(WmoCode::ClearSky),
+                Some(WmoCode::SlightRain),
+                None,
+                Some(WmoCode::Unknown(4))
+            ]
+        );
+        assert_eq!(hourly.get("weathercode").unwrap()[3], Some(4.0));
+        assert_eq!(
+            response.daily.unwrap().weathercode().unwrap(),
+            [Some(WmoCode::ModerateRain), Some(WmoCode::Unknown(100))]
+        );
+        assert_eq!(
+            response.current.unwrap().weathercode(),
+            Some(WmoCode::Thunderstorm)
+        );
+    }
+
+    #[test]
+    fn hourly_block_with_gaps() {
+        let json = r#"{"latitude":47.38,"longitude":8.54,"generationtime_ms":0.1,"utc_offset_seconds":0,"timezone":"GMT","timezone_abbreviation":"GMT","elevation":409.0,"hourly_units":{"time":"unixtime","temperature_2m":"°C","temperature_850hPa":"°C"},"hourly":{"time":[1697760000,1697763600,1697767200],"temperature_2m":[9.1,null,8.4],"temperature_850hPa":[2.5,2.1,null]}}"#;
+
+        let hourly = ForecastResponse::from_json(json).unwrap().hourly.unwrap();
+        assert_eq!(hourly.len(), 3);
+        assert!(!hourly.is_empty());
+        assert_eq!(hourly.time[1], Timestamp::Unix(1697763600));
+        assert_eq!(
+            hourly.variables().collect::<Vec<_>>(),
+            ["temperature_2m", "temperature_850hPa"]
+        );
+        assert_eq!(
+            hourly.get("temperature_2m").unwrap().values(),
+            [Some(9.1), None, Some(8.4)]
+        );
+        assert_eq!(hourly.get("temperature_850hPa").unwrap()[2], None);
+        assert_eq!(hourly.get("rain"), None);
+
+        let response = ForecastResponse::from_json(FIXTURE).unwrap();
+        let hourly = response.hourly.unwrap();
+        assert_eq!(
+            hourly.time[0],
+            Timestamp::Iso8601("2023-05-16T00:00".into())
+        );
+    }
+
+    #[test]
+    fn series_null_policies() {
+        let response = ForecastResponse::from_json(include_str!("../fixtures/gaps.json")).unwrap();
+        let hourly = response.hourly.as_ref().unwrap();
+        let temperature = hourly.get("temperature_2m").unwrap();
+        assert_eq!(temperature.len(), hourly.len());
+        assert_eq!(temperature.nulls_count(), 3);
+        assert_eq!(
+            temperature
+                .filled(f64::NAN)
+                .map(f64::is_nan)
+                .collect::<Vec<_>>(),
+            [true, true, false, true, false, false]
+        );
+        assert_eq!(temperature.filled(0.0).nth(2), Some(9.1));
+        assert_eq!(temperature.dropped().collect::<Vec<_>>(), [9.1, 8.4, 8.0]);
+
+        // Gaps end alert runs and are skipped by intensity classification
+        let rule = crate::AlertRule::new(crate::Hourly::rain, crate::Comparison::Above, 1.0);
+        let matches = response.evaluate(&[rule.consecutive(2)]);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].timestamps, hourly.time[1..3]);
+        let rain = hourly.get("rain").unwrap();
+        let intensities =
+            crate::PrecipIntensity::series(&hourly.time, rain, &crate::Precipitation::mm);
+        assert_eq!(intensities.len(), 4);
+        assert_eq!(intensities[0].0, &hourly.time[1]);
+    }
+
+    #[test]
+    fn time_formats_agree() {
+        let iso = ForecastResponse::from_json(include_str!("../fixtures/darwin.json")).unwrap();
+        let unix =
+            ForecastResponse::from_json(include_str!("../fixtures/darwin_unixtime.json")).unwrap();
+        let offset = iso.utc_offset_seconds;
+
+        let instants = |time: &[Timestamp]| -> Vec<_> {
+            time.iter()
+                .map(|time| time.to_unix(offset).unwrap())
+                .collect()
+        };
+        let (iso_hourly, unix_hourly) = (iso.hourly.unwrap(), unix.hourly.unwrap());
+        assert_eq!(iso_hourly.time[0].as_iso8601(), Some("2023-10-20T00:00"));
+        assert_eq!(unix_hourly.time[0].as_unix(), Some(1697725800));
+        assert_eq!(instants(&iso_hourly.time), instants(&unix_hourly.time));
+        assert_eq!(iso_hourly.series, unix_hourly.series);
+
+        let (iso_daily, unix_daily) = (iso.daily.unwrap(), unix.daily.unwrap());
+        assert_eq!(instants(&iso_daily.time), instants(&unix_daily.time));
+        let sunrise = |daily: &DailyBlock| {
+            let sunrise = daily.get("sunrise").unwrap()[0].as_ref().unwrap();
+            sunrise.as_time().unwrap().to_unix(offset)
+        };
+        assert_eq!(sunrise(&iso_daily), sunrise(&unix_daily));
+        assert_eq!(
+            Timestamp::Iso8601("2023-10-20T24:00".into()).to_unix(0),
+            None
+        );
+    }
+
+    #[test]
+    fn daily_block_is_typed() {
+        let response = ForecastResponse::from_json(include_str!("../fixtures/daily.json")).unwrap();
+        let daily = response.daily.as_ref().unwrap();
+        assert_eq!(daily.len(), 3);
+
+        let codes: Vec<_> = daily
+            .get("weathercode")
+            .unwrap()
+            .iter()
+            .map(|code| code.as_ref()?.as_code())
+            .collect();
+        assert_eq!(codes, [Some(80), Some(3), Some(61)]);
+        let sunrise = daily.get("sunrise").unwrap()[1].as_ref().unwrap();
+        assert_eq!(sunrise.as_time().unwrap().to_string(), "2023-10-21T07:51");
+        assert_eq!(
+            daily.numbers("temperature_2m_max").unwrap().values(),
+            [Some(14.2), Some(12.8), None]
+        );
+        assert_eq!(
+            daily
+                .numbers("precipitation_probability_max")
+                .unwrap()
+                .values(),
+            [Some(87.0), Some(24.0), None]
+        );
+        assert_eq!(
+            daily.get("daylight_duration").unwrap()[2],
+            Some(DailyValue::Raw(37981.0.into()))
+        );
+        assert_eq!(daily.get("uv_index_max"), None);
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(ForecastResponse::from_json(&json).unwrap(), response);
+
+        let bad_code = r#"{"time":["2023-10-20"],"weathercode":["rain"]}"#;
+        assert!(serde_json::from_str::<DailyBlock>(bad_code).is_err());
+    }
+
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    fn response(status: u16, body: &'static str) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    #[test]
+    fn api_errors_keep_the_reason() {
+        let body = r#"{"error":true,"reason":"Latitude must be in range of -90 to 90°. Given: 91.0.","code":17}"#;
+        for status in [400, 200] {
+            match ForecastResponse::decode(URL, &response(status, body), DecodeMode::Json) {
+                Err(WeatheredError::Api {
+                    url,
+                    status: returned,
+                    reason,
+                }) => {
+                    assert_eq!(url, URL);
+                    assert_eq!(returned, status);
+                    assert_eq!(
+                        reason,
+                        "Latitude must be in range of -90 to 90°. Given: 91.0."
+                    );
+                }
+                other => panic!("expected API error, got {other:?}"),
+            }
+        }
+
+        let html = "<html><body>502 Bad Gateway</body></html>";
+        assert!(matches!(
+            ForecastResponse::decode(URL, &response(500, html), DecodeMode::Json),
+            Err(WeatheredError::Status { status: 500, .. })
+        ));
+        assert!(matches!(
+            ForecastResponse::decode(URL, &response(200, html), DecodeMode::Json),
+            Err(WeatheredError::Decode { .. })
+        ));
+    }
+}
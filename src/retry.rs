@@ -0,0 +1,149 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::{HttpResponse, TransportError};
+
+#[derive(Debug, Clone)]
+/// Describe how failed requests are retried
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    multiplier: f64,
+    jitter: f64,
+    retry_connect: bool,
+    retry_statuses: Vec<u16>,
+    retry_rate_limited: bool,
+    retry_after_cap: Option<Duration>,
+}
+
+impl RetryPolicy {
+    /// Allow up to `max_attempts` attempts (the first one included),
+    /// waiting 200ms, 400ms, 800ms... with 10% jitter between them
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            base_delay: Duration::from_millis(200),
+            multiplier: 2.0,
+            jitter: 0.1,
+            retry_connect: true,
+            retry_statuses: vec![502, 503, 504],
+            retry_rate_limited: false,
+            retry_after_cap: None,
+        }
+    }
+
+    /// Never retry
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Delay before the first retry
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Factor applied to the delay after each retry
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Randomize each delay by up to the given fraction, in both directions
+    pub fn jitter(mut self, fraction: f64) -> Self {
+        self.jitter = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Retry when the connection cannot be established
+    pub fn retry_connect(mut self, retry: bool) -> Self {
+        self.retry_connect = retry;
+        self
+    }
+
+    /// Status codes worth retrying, 502, 503 and 504 by default
+    pub fn retry_statuses(mut self, statuses: &[u16]) -> Self {
+        self.retry_statuses = statuses.to_vec();
+        self
+    }
+
+    /// Also retry 429 Too Many Requests
+    pub fn retry_rate_limited(mut self, retry: bool) -> Self {
+        self.retry_rate_limited = retry;
+        self
+    }
+
+    /// On 429, sleep for the `Retry-After` delay and retry once,
+    /// provided the server asks to wait no longer than `cap`
+    pub fn honor_retry_after(mut self, cap: Duration) -> Self {
+        self.retry_after_cap = Some(cap);
+        self
+    }
+
+    pub(crate) fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Tell whether the outcome of an attempt deserves another one
+    pub(crate) fn is_retryable(&self, outcome: &Result<HttpResponse, TransportError>) -> bool {
+        match outcome {
+            Ok(response) => {
+                self.retry_statuses.contains(&response.status)
+                    || (self.retry_rate_limited && response.status == 429)
+            }
+            Err(TransportError::Connect(_)) => self.retry_connect,
+            Err(_) => false,
+        }
+    }
+
+    /// Delay requested by a rate-limited response, if the policy accepts to wait for it
+    pub(crate) fn rate_limit_wait(&self, response: &HttpResponse) -> Option<Duration> {
+        let cap = self.retry_after_cap?;
+        let wait = response.retry_after()?;
+        (response.status == 429 && wait <= cap).then_some(wait)
+    }
+
+    /// Delay to wait after the given failed attempt (starting from 1)
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1) as i32;
+        let delay = self.base_delay.as_secs_f64() * self.multiplier.powi(exponent);
+        let jitter = 1.0 + self.jitter * (2.0 * random_fraction() - 1.0);
+        Duration::from_secs_f64((delay * jitter).max(0.0))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// Cheap random number in [0, 1), good enough to spread retries
+pub(crate) fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn exponential_delays() {
+        let policy = RetryPolicy::new(4)
+            .base_delay(Duration::from_millis(100))
+            .multiplier(3.0)
+            .jitter(0.0);
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(300));
+        assert_eq!(policy.delay(3), Duration::from_millis(900));
+
+        let jittered = policy.jitter(0.5);
+        for _ in 0..100 {
+            let delay = jittered.delay(2);
+            assert!(delay >= Duration::from_millis(150) && delay <= Duration::from_millis(450));
+        }
+    }
+}
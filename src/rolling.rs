@@ -0,0 +1,94 @@
+use crate::{Agg, HourlyBlock, Series};
+
+impl Series {
+    /// Aggregate of each value with the `window - 1` values before it, so the
+    /// first `window - 1` entries have no value
+    ///
+    /// A window with missing data has no value either, rather than an
+    /// aggregate of the values it does have
+    pub fn rolling(&self, window: usize, agg: Agg) -> Series {
+        let window = window.max(1);
+        let mut values = vec![None; (window - 1).min(self.len())];
+        values.extend(self.0.windows(window).map(|values| {
+            let values: Option<Vec<f64>> = values.iter().copied().collect();
+            Some(agg.apply(&values?))
+        }));
+        Series(values)
+    }
+}
+
+impl HourlyBlock {
+    /// Seconds between the first two timestamps
+    pub(crate) fn step_seconds(&self) -> Option<i64> {
+        match self.time.as_slice() {
+            [first, second, ..] => {
+                let step = second.to_unix(0)? - first.to_unix(0)?;
+                (step > 0).then_some(step)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn right_aligned_windows() {
+        let rain = Series(vec![
+            Some(0.0),
+            Some(1.0),
+            Some(2.0),
+            None,
+            Some(4.0),
+            Some(5.0),
+            Some(6.0),
+            Some(0.5),
+        ]);
+
+        assert_eq!(
+            rain.rolling(3, Agg::Sum).values(),
+            [
+                None,
+                None,
+                Some(3.0),
+                None,
+                None,
+                None,
+                Some(15.0),
+                Some(11.5)
+            ]
+        );
+        assert_eq!(
+            rain.rolling(2, Agg::Mean).values(),
+            [
+                None,
+                Some(0.5),
+                Some(1.5),
+                None,
+                None,
+                Some(4.5),
+                Some(5.5),
+                Some(3.25)
+            ]
+        );
+        assert_eq!(
+            rain.rolling(2, Agg::Max).values(),
+            [
+                None,
+                Some(1.0),
+                Some(2.0),
+                None,
+                None,
+                Some(5.0),
+                Some(6.0),
+                Some(6.0)
+            ]
+        );
+        assert_eq!(rain.rolling(1, Agg::Sum), rain);
+        assert_eq!(rain.rolling(0, Agg::Sum), rain);
+        assert_eq!(rain.rolling(10, Agg::Sum).values(), [None; 8]);
+    }
+}
@@ -0,0 +1,123 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use web_time::{Instant, SystemTime};
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Boxed future returned by `AsyncRuntime`
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+#[cfg(target_arch = "wasm32")]
+/// Boxed future returned by `AsyncRuntime`
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// Timers and task spawning of the async runtime driving a `Client`,
+/// used for retry backoff and rate limiting
+pub trait AsyncRuntime: Send + Sync {
+    /// Complete after `duration`
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Current instant
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    /// Current wall-clock time, telling which dates are in the past
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    /// Run `task` in the background; returns `false`, dropping the task,
+    /// if the runtime cannot spawn
+    fn spawn(&self, task: BoxFuture<'static, ()>) -> bool {
+        drop(task);
+        false
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy, Default)]
+/// Runtime backed by tokio, the default off wasm
+pub struct TokioRuntime;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AsyncRuntime for TokioRuntime {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn spawn(&self, task: BoxFuture<'static, ()>) -> bool {
+        tokio::spawn(task);
+        true
+    }
+}
+
+#[cfg(feature = "client-async-std")]
+#[derive(Debug, Clone, Copy, Default)]
+/// Runtime backed by async-std
+///
+/// reqwest still needs a tokio reactor, so pair it with a custom transport
+pub struct AsyncStdRuntime;
+
+#[cfg(feature = "client-async-std")]
+impl AsyncRuntime for AsyncStdRuntime {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(async_std::task::sleep(duration))
+    }
+
+    fn spawn(&self, task: BoxFuture<'static, ()>) -> bool {
+        async_std::task::spawn(task);
+        true
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone, Copy, Default)]
+/// Runtime backed by the browser timers, the default on wasm
+pub struct WasmRuntime;
+
+#[cfg(target_arch = "wasm32")]
+impl AsyncRuntime for WasmRuntime {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(gloo_timers::future::sleep(duration))
+    }
+}
+
+pub(crate) fn default_runtime() -> Arc<dyn AsyncRuntime> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return Arc::new(TokioRuntime);
+    #[cfg(target_arch = "wasm32")]
+    return Arc::new(WasmRuntime);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Runtime whose clock only moves when told to, or when sleeping
+    pub(crate) struct ManualRuntime(Mutex<Instant>);
+
+    impl ManualRuntime {
+        pub(crate) fn new() -> Arc<Self> {
+            Arc::new(ManualRuntime(Mutex::new(Instant::now())))
+        }
+
+        pub(crate) fn advance(&self, by: Duration) {
+            *self.0.lock().unwrap() += by;
+        }
+    }
+
+    impl AsyncRuntime for ManualRuntime {
+        fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+            self.advance(duration);
+            Box::pin(std::future::ready(()))
+        }
+
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+}
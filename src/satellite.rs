@@ -0,0 +1,191 @@
+use std::fmt;
+
+use strum_macros::Display;
+
+use crate::{push_list, Coordinates, Endpoint, Forecast, NoCoordinates, Settings};
+
+pub(crate) const SATELLITE_URL: &str = "https://satellite-api.open-meteo.com/v1/archive";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[allow(non_camel_case_types)]
+/// Enumerate the hourly variables of the satellite radiation API
+///
+/// `_instant` variables are the value at the timestamp instead of the mean of
+/// the preceding hour
+pub enum SatelliteVar {
+    shortwave_radiation,
+    direct_radiation,
+    diffuse_radiation,
+    direct_normal_irradiance,
+    /// Irradiance on a panel set with `Settings::tilt` and `Settings::azimuth`
+    global_tilted_irradiance,
+    terrestrial_radiation,
+    shortwave_radiation_instant,
+    direct_radiation_instant,
+    diffuse_radiation_instant,
+    direct_normal_irradiance_instant,
+    global_tilted_irradiance_instant,
+    terrestrial_radiation_instant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[allow(non_camel_case_types)]
+/// Enumerate the satellites the radiation is derived from
+pub enum SatelliteModel {
+    satellite_radiation_seamless,
+    eumetsat_sarah3,
+    eumetsat_lsa_saf_msg,
+    eumetsat_lsa_saf_iodc,
+    jma_jaxa_himawari,
+}
+
+#[derive(Clone)]
+/// Satellite derived irradiance, sharing the coordinates and settings of a
+/// `Forecast`
+///
+/// Like the archive, the API needs a start and end date; the clients return
+/// `WeatheredError::MissingDates` without them
+pub struct Satellite<C = Coordinates> {
+    forecast: Forecast<C>,
+    hourly: Vec<SatelliteVar>,
+    models: Vec<SatelliteModel>,
+}
+
+impl Satellite<NoCoordinates> {
+    /// Initialize Satellite object
+    pub fn new() -> Self {
+        Satellite {
+            forecast: Forecast::new().endpoint(Endpoint::Satellite),
+            hourly: Vec::new(),
+            models: Vec::new(),
+        }
+    }
+}
+
+impl Default for Satellite<NoCoordinates> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Satellite<C> {
+    /// Specify coordinates (latitude, longitude)
+    pub fn coord(self, latitude: f32, longitude: f32) -> Satellite<Coordinates> {
+        Satellite {
+            forecast: self.forecast.coord(latitude, longitude),
+            hourly: self.hourly,
+            models: self.models,
+        }
+    }
+
+    /// Dates of the first and last day, `YYYY-MM-DD`
+    pub fn dates(self, start: &str, end: &str) -> Self {
+        self.settings(Settings::start_date(start.to_string()))
+            .settings(Settings::end_date(end.to_string()))
+    }
+
+    /// Add optional settings, e.g. `Settings::tilt`
+    pub fn settings(mut self, setting: Settings) -> Self {
+        self.forecast = self.forecast.settings(setting);
+        self
+    }
+
+    /// Get hourly value for a specific data
+    pub fn hourly(mut self, hourly: SatelliteVar) -> Self {
+        self.hourly.push(hourly);
+        self
+    }
+
+    /// Satellites to use, the seamless combination when none is given
+    pub fn models(mut self, models: &[SatelliteModel]) -> Self {
+        self.models.extend_from_slice(models);
+        self
+    }
+}
+
+impl Satellite<Coordinates> {
+    /// Convert the request into a valid URL against the given base
+    pub(crate) fn to_url(&self, base: &str) -> String {
+        let mut url = self.forecast.to_url(base);
+        push_list(&mut url, "hourly", &self.hourly);
+        if !self.models.is_empty() {
+            let models: Vec<_> = self.models.iter().map(SatelliteModel::to_string).collect();
+            url.push_str(&format!("&models={}", models.join(",")));
+        }
+        url
+    }
+
+    /// Error if the request misses its start or end date
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    pub(crate) fn check_dates(&self) -> Result<(), crate::WeatheredError> {
+        self.forecast.check_dates()
+    }
+}
+
+impl fmt::Display for Satellite<Coordinates> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_url(SATELLITE_URL))
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl crate::EndpointRequest for Satellite<Coordinates> {
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::Satellite
+    }
+
+    fn to_url(&self, base: &str) -> String {
+        Satellite::to_url(self, base)
+    }
+}
+
+#[cfg(feature = "response")]
+impl crate::ForecastResponse {
+    /// Values of a satellite radiation variable, `None` if the response does
+    /// not contain it
+    pub fn satellite(&self, variable: SatelliteVar) -> Option<&crate::Series> {
+        self.hourly_by_name(&variable.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn url_creation() {
+        let request = Satellite::new()
+            .coord(47.37, 8.55)
+            .dates("2024-06-01", "2024-06-02")
+            .hourly(SatelliteVar::shortwave_radiation)
+            .hourly(SatelliteVar::global_tilted_irradiance_instant)
+            .models(&[SatelliteModel::eumetsat_sarah3])
+            .settings(Settings::tilt(30.0))
+            .settings(Settings::azimuth(-15.0));
+        assert_eq!(
+            request.to_string(),
+            "https://satellite-api.open-meteo.com/v1/archive?latitude=47.37&longitude=8.55&start_date=2024-06-01&end_date=2024-06-02&tilt=30&azimuth=-15&hourly=,shortwave_radiation,global_tilted_irradiance_instant&models=eumetsat_sarah3"
+        );
+    }
+
+    #[cfg(feature = "response")]
+    #[test]
+    fn fixture() {
+        let response =
+            crate::ForecastResponse::from_json(include_str!("../fixtures/satellite.json")).unwrap();
+        let shortwave = response
+            .satellite(SatelliteVar::shortwave_radiation)
+            .unwrap();
+        assert_eq!(shortwave.values(), [Some(0.0), Some(112.5), Some(348.0)]);
+        assert_eq!(
+            response
+                .satellite(SatelliteVar::direct_normal_irradiance_instant)
+                .unwrap()[2],
+            None
+        );
+        assert!(response
+            .satellite(SatelliteVar::terrestrial_radiation)
+            .is_none());
+    }
+}
@@ -0,0 +1,96 @@
+use crate::date::Date;
+use crate::ForecastResponse;
+
+/// Days the archive lags behind today, the most recent ones are only served
+/// by the forecast API
+const ARCHIVE_LAG_DAYS: i64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerate the endpoints the rows of a `SeamlessResponse` come from
+pub enum Source {
+    Archive,
+    Forecast,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Response of `Client::fetch_seamless`, archive rows first
+pub struct SeamlessResponse {
+    pub response: ForecastResponse,
+    /// First date served by the forecast API, `None` if a single endpoint
+    /// served the whole range
+    pub stitch: Option<String>,
+    /// Source of each hourly timestamp
+    pub hourly_sources: Vec<Source>,
+    /// Source of each day
+    pub daily_sources: Vec<Source>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Dates of `start..=end` requested from each endpoint, both inclusive
+pub(crate) struct Split {
+    pub(crate) archive: Option<(String, String)>,
+    pub(crate) forecast: Option<(String, String)>,
+}
+
+/// Share the range between the archive, up to `ARCHIVE_LAG_DAYS` before
+/// `today`, and the forecast API; `None` if a date is invalid
+pub(crate) fn split(start: &str, end: &str, today: Date) -> Option<Split> {
+    let (start, end) = (Date::parse(start)?, Date::parse(end)?);
+    let archived = Date::from_days(today.days() - ARCHIVE_LAG_DAYS);
+    let first_forecast = Date::from_days(archived.days() + 1);
+    let range =
+        |start: Date, end: Date| (start <= end).then(|| (start.to_string(), end.to_string()));
+    Some(Split {
+        archive: range(start, end.min(archived)),
+        forecast: range(start.max(first_forecast), end),
+    })
+}
+
+/// `archived` times the archive then the forecast for the rest of `total`
+pub(crate) fn sources(archived: usize, total: usize) -> Vec<Source> {
+    let forecast = total.saturating_sub(archived);
+    let mut sources = vec![Source::Archive; archived.min(total)];
+    sources.extend(std::iter::repeat_n(Source::Forecast, forecast));
+    sources
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn range(start: &str, end: &str) -> Option<(String, String)> {
+        Some((start.to_string(), end.to_string()))
+    }
+
+    #[test]
+    fn split_at_archive_lag() {
+        let today = Date::parse("2024-06-20").unwrap();
+        assert_eq!(
+            split("2023-06-01", "2024-06-30", today),
+            Some(Split {
+                archive: range("2023-06-01", "2024-06-15"),
+                forecast: range("2024-06-16", "2024-06-30"),
+            })
+        );
+        assert_eq!(
+            split("2024-01-01", "2024-06-15", today),
+            Some(Split {
+                archive: range("2024-01-01", "2024-06-15"),
+                forecast: None,
+            })
+        );
+        assert_eq!(
+            split("2024-06-16", "2024-06-18", today),
+            Some(Split {
+                archive: None,
+                forecast: range("2024-06-16", "2024-06-18"),
+            })
+        );
+        assert_eq!(split("2024-06-31", "2024-07-02", today), None);
+        assert_eq!(
+            sources(2, 3),
+            [Source::Archive, Source::Archive, Source::Forecast]
+        );
+    }
+}
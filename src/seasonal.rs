@@ -0,0 +1,237 @@
+use std::{error, fmt};
+
+use crate::{push_list, Coordinates, Endpoint, Forecast, Hourly, NoCoordinates, Settings};
+
+pub(crate) const SEASONAL_URL: &str = "https://seasonal-api.open-meteo.com/v1/seasonal";
+
+/// Longest horizon of the seasonal runs, about nine months
+const MAX_FORECAST_DAYS: u16 = 274;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Enumerate the reasons `Seasonal::build` refuses a request
+pub enum SeasonalError {
+    /// The variable is only offered hourly, by the forecast API
+    HourlyOnly(String),
+    /// `forecast_days` is beyond the 274 days of the seasonal runs
+    ForecastDays(u16),
+}
+
+impl fmt::Display for SeasonalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeasonalError::HourlyOnly(name) => {
+                write!(f, "`{name}` is not available six-hourly")
+            }
+            SeasonalError::ForecastDays(days) => write!(
+                f,
+                "{days} forecast days exceed the {MAX_FORECAST_DAYS} of the seasonal forecast"
+            ),
+        }
+    }
+}
+
+impl error::Error for SeasonalError {}
+
+#[derive(Clone)]
+/// Six-hourly seasonal ensemble of the ECMWF SEAS5 model, sharing the
+/// coordinates and settings of a `Forecast`
+pub struct Seasonal<C = Coordinates> {
+    forecast: Forecast<C>,
+    six_hourly: Vec<Hourly>,
+}
+
+impl Seasonal<NoCoordinates> {
+    /// Initialize Seasonal object
+    pub fn new() -> Self {
+        Seasonal {
+            forecast: Forecast::new().endpoint(Endpoint::Seasonal),
+            six_hourly: Vec::new(),
+        }
+    }
+}
+
+impl Default for Seasonal<NoCoordinates> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Seasonal<C> {
+    /// Specify coordinates (latitude, longitude)
+    pub fn coord(self, latitude: f32, longitude: f32) -> Seasonal<Coordinates> {
+        Seasonal {
+            forecast: self.forecast.coord(latitude, longitude),
+            six_hourly: self.six_hourly,
+        }
+    }
+
+    /// Add optional settings, e.g. `Settings::forecast_days`
+    pub fn settings(mut self, setting: Settings) -> Self {
+        self.forecast = self.forecast.settings(setting);
+        self
+    }
+
+    /// Get six-hourly value for a specific data
+    pub fn six_hourly(mut self, variable: Hourly) -> Self {
+        self.six_hourly.push(variable);
+        self
+    }
+}
+
+impl Seasonal<Coordinates> {
+    /// Check the request against what the seasonal forecast serves
+    pub fn build(self) -> Result<SeasonalRequest, SeasonalError> {
+        if let Some(variable) = self
+            .six_hourly
+            .iter()
+            .find(|variable| !six_hourly(variable))
+        {
+            return Err(SeasonalError::HourlyOnly(variable.to_string()));
+        }
+        let days = self
+            .forecast
+            .settings
+            .iter()
+            .find_map(|setting| match setting {
+                Settings::forecast_days(days) if *days > MAX_FORECAST_DAYS => Some(*days),
+                _ => None,
+            });
+        if let Some(days) = days {
+            return Err(SeasonalError::ForecastDays(days));
+        }
+        Ok(SeasonalRequest(self))
+    }
+}
+
+#[derive(Clone)]
+/// Seasonal request checked by `Seasonal::build`
+pub struct SeasonalRequest(Seasonal<Coordinates>);
+
+impl SeasonalRequest {
+    /// Convert the request into a valid URL against the given base
+    pub(crate) fn to_url(&self, base: &str) -> String {
+        let mut url = self.0.forecast.to_url(base);
+        push_list(&mut url, "six_hourly", &self.0.six_hourly);
+        url
+    }
+}
+
+impl fmt::Display for SeasonalRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_url(SEASONAL_URL))
+    }
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl crate::EndpointRequest for SeasonalRequest {
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::Seasonal
+    }
+
+    fn to_url(&self, base: &str) -> String {
+        SeasonalRequest::to_url(self, base)
+    }
+}
+
+/// Variables of the seasonal forecast at a six-hourly step
+fn six_hourly(variable: &Hourly) -> bool {
+    use Hourly::*;
+
+    matches!(
+        variable,
+        temperature_2m
+            | relative_humidity_2m
+            | pressure_msl
+            | cloudcover
+            | shortwave_radiation
+            | precipitation
+            | showers
+            | windspeed_10m
+            | winddirection_10m
+    )
+}
+
+#[cfg(feature = "response")]
+impl crate::ForecastResponse {
+    /// Control run and members of a six-hourly variable, `None` if the
+    /// response does not contain it
+    pub fn seasonal(&self, variable: Hourly) -> Option<crate::EnsembleSeries<'_>> {
+        self.six_hourly.as_ref()?.ensemble(variable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn seasonal() -> Seasonal {
+        Seasonal::new()
+            .coord(52.52, 13.41)
+            .six_hourly(Hourly::temperature_2m)
+            .six_hourly(Hourly::precipitation)
+    }
+
+    #[test]
+    fn url_creation() {
+        let request = seasonal()
+            .settings(Settings::forecast_days(90))
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.to_string(),
+            "https://seasonal-api.open-meteo.com/v1/seasonal?latitude=52.52&longitude=13.41&forecast_days=90&six_hourly=,temperature_2m,precipitation"
+        );
+    }
+
+    #[test]
+    fn rejected_requests() {
+        let error = seasonal()
+            .six_hourly(Hourly::visibility)
+            .build()
+            .err()
+            .unwrap();
+        assert_eq!(error, SeasonalError::HourlyOnly("visibility".into()));
+        assert_eq!(
+            error.to_string(),
+            "`visibility` is not available six-hourly"
+        );
+        assert_eq!(
+            seasonal()
+                .settings(Settings::forecast_days(300))
+                .build()
+                .err(),
+            Some(SeasonalError::ForecastDays(300))
+        );
+        assert!(seasonal()
+            .settings(Settings::forecast_days(274))
+            .build()
+            .is_ok());
+    }
+
+    #[cfg(feature = "response")]
+    #[test]
+    fn fixture() {
+        let response =
+            crate::ForecastResponse::from_json(include_str!("../fixtures/seasonal.json")).unwrap();
+        assert_eq!(
+            response
+                .six_hourly_units
+                .as_ref()
+                .unwrap()
+                .get("precipitation_member01"),
+            Some("mm")
+        );
+        let temperature = response.seasonal(Hourly::temperature_2m).unwrap();
+        assert_eq!(temperature.time.len(), 4);
+        assert_eq!(temperature.member_count(), 2);
+        assert_eq!(
+            temperature.member(2).unwrap().values(),
+            [Some(3.9), Some(2.7), Some(5.8), None]
+        );
+        let precipitation = response.seasonal(Hourly::precipitation).unwrap();
+        assert!(precipitation.control.is_none());
+        assert!(response.seasonal(Hourly::showers).is_none());
+        response.check_consistency().unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+use std::ops::RangeBounds;
+
+use crate::date::Date;
+use crate::{CalendarDate, ForecastResponse, Moment, Timestamp};
+
+impl ForecastResponse {
+    /// Copy of the response keeping the 15-minutely and hourly samples whose
+    /// time is in `range`, see `truncate`
+    pub fn slice<M: Moment>(&self, range: impl RangeBounds<M>) -> ForecastResponse {
+        let mut response = self.clone();
+        response.truncate(range);
+        response
+    }
+
+    /// Drop the 15-minutely and hourly samples whose time is outside `range`,
+    /// or cannot be read, from the time axes and every series
+    ///
+    /// The daily block and the current conditions are left as they are, see
+    /// `truncate_dates`
+    pub fn truncate<M: Moment>(&mut self, range: impl RangeBounds<M>) {
+        let range = (
+            range.start_bound().map(Moment::unix_seconds),
+            range.end_bound().map(Moment::unix_seconds),
+        );
+        let offset = self.utc_offset_seconds;
+        let keep = |time: &Timestamp| {
+            time.to_unix(offset)
+                .is_some_and(|time| range.contains(&time))
+        };
+        for block in [self.minutely_15.as_mut(), self.hourly.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            let series = block.series.values_mut().map(|series| &mut series.0);
+            retain(&mut block.time, series, keep);
+        }
+    }
+
+    /// Copy of the response keeping the days of the daily block in `range`,
+    /// see `truncate_dates`
+    pub fn slice_dates<D: CalendarDate>(&self, range: impl RangeBounds<D>) -> ForecastResponse {
+        let mut response = self.clone();
+        response.truncate_dates(range);
+        response
+    }
+
+    /// Drop the days of the daily block outside `range`, leaving the other
+    /// blocks as they are
+    pub fn truncate_dates<D: CalendarDate>(&mut self, range: impl RangeBounds<D>) {
+        let days = |date: &D| Date::parse(&date.to_api_date()).map_or(0, Date::days);
+        let range = (range.start_bound().map(days), range.end_bound().map(days));
+        let offset = self.utc_offset_seconds;
+        let keep = |time: &Timestamp| {
+            time.to_unix(offset).is_some_and(|time| {
+                let local = time + i64::from(offset);
+                range.contains(&local.div_euclid(86_400))
+            })
+        };
+        if let Some(daily) = self.daily.as_mut() {
+            retain(&mut daily.time, daily.series.values_mut(), keep);
+        }
+    }
+}
+
+/// Keep the timestamps accepted by `keep` and the values at the same index
+fn retain<'a, V: 'a>(
+    time: &mut Vec<Timestamp>,
+    series: impl Iterator<Item = &'a mut Vec<V>>,
+    keep: impl Fn(&Timestamp) -> bool,
+) {
+    let kept: Vec<bool> = time.iter().map(&keep).collect();
+    for values in series {
+        let mut kept = kept.iter();
+        values.retain(|_| kept.next().copied().unwrap_or(false));
+    }
+    let mut kept = kept.iter();
+    time.retain(|_| *kept.next().unwrap());
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+
+    use chrono::{FixedOffset, NaiveDate, TimeZone};
+
+    use super::*;
+    use crate::testing::ResponseBuilder;
+    use crate::{Daily, Hourly};
+
+    fn response() -> ForecastResponse {
+        ResponseBuilder::new()
+            .timezone("Europe/Berlin", "CET", 3600)
+            .start("2024-01-01")
+            .hourly(Hourly::temperature_2m, (0..48).map(f64::from))
+            .daily(Daily::temperature_2m_max, [23.0, 47.0])
+            .build()
+    }
+
+    #[test]
+    fn half_open_range_across_midnight() {
+        let cet = FixedOffset::east_opt(3600).unwrap();
+        let start = cet.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        let end = cet.with_ymd_and_hms(2024, 1, 2, 4, 0, 0).unwrap();
+
+        let response = response();
+        let sliced = response.slice(start..end);
+        let hourly = sliced.hourly.as_ref().unwrap();
+        assert_eq!(hourly.len(), 8);
+        // Start included, end excluded
+        assert_eq!(
+            hourly.time[0],
+            Timestamp::Iso8601("2024-01-01T20:00".into())
+        );
+        assert_eq!(
+            hourly.time[7],
+            Timestamp::Iso8601("2024-01-02T03:00".into())
+        );
+        let temperature = hourly.get("temperature_2m").unwrap();
+        assert_eq!(temperature.len(), 8);
+        assert_eq!(temperature[0], Some(20.0));
+        assert_eq!(sliced.daily, response.daily);
+
+        // Same instants in another offset
+        let utc = start.to_utc()..=end.to_utc();
+        assert_eq!(response.slice(utc).hourly.unwrap().len(), 9);
+        assert_eq!(response.slice(end..).hourly.unwrap().len(), 20);
+
+        let mut empty = response.clone();
+        empty.truncate(end..start);
+        assert!(empty.hourly.unwrap().is_empty());
+    }
+
+    #[test]
+    fn dates() {
+        let response = response();
+        let second = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+
+        let daily = response.slice_dates(second..).daily.unwrap();
+        assert_eq!(daily.time, [Timestamp::Iso8601("2024-01-02".into())]);
+        assert_eq!(daily.series[0], [Some(crate::DailyValue::Float(47.0))]);
+        assert_eq!(response.slice_dates(..second).daily.unwrap().time.len(), 1);
+        assert_eq!(
+            response
+                .slice_dates(second..second)
+                .daily
+                .unwrap()
+                .time
+                .len(),
+            0
+        );
+        assert_eq!(response.slice_dates(second..second).hourly, response.hourly);
+    }
+}
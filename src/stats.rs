@@ -0,0 +1,133 @@
+use crate::{Hourly, HourlyBlock, Series};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// Summary of the values of a series, computed by `Series::stats`
+///
+/// Every statistic but the counts is `None` when the series has no value
+pub struct SeriesStats {
+    /// Number of values present
+    pub count: usize,
+    /// Number of values missing
+    pub null_count: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    /// Middle value, or the mean of the two middle values
+    pub median: Option<f64>,
+    /// Population standard deviation
+    pub std_dev: Option<f64>,
+    /// Index of the first smallest value, to read its time off the time axis
+    pub argmin: Option<usize>,
+    /// Index of the first largest value
+    pub argmax: Option<usize>,
+}
+
+impl Series {
+    /// Counts, extremes and moments of the values present
+    pub fn stats(&self) -> SeriesStats {
+        let mut stats = SeriesStats {
+            count: self.len() - self.nulls_count(),
+            null_count: self.nulls_count(),
+            ..SeriesStats::default()
+        };
+        for (index, value) in self.0.iter().enumerate() {
+            let Some(value) = *value else {
+                continue;
+            };
+            if stats.min.is_none_or(|min| value < min) {
+                stats.min = Some(value);
+                stats.argmin = Some(index);
+            }
+            if stats.max.is_none_or(|max| value > max) {
+                stats.max = Some(value);
+                stats.argmax = Some(index);
+            }
+        }
+        if stats.count == 0 {
+            return stats;
+        }
+
+        let count = stats.count as f64;
+        let mean = self.dropped().sum::<f64>() / count;
+        let variance = self
+            .dropped()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        let mut sorted: Vec<f64> = self.dropped().collect();
+        sorted.sort_by(f64::total_cmp);
+        let middle = sorted.len() / 2;
+        stats.median = Some(if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2.0
+        } else {
+            sorted[middle]
+        });
+        stats.mean = Some(mean);
+        stats.std_dev = Some(variance.sqrt());
+        stats
+    }
+}
+
+impl HourlyBlock {
+    /// `Series::stats` of a variable, `None` if it is not in the block
+    pub fn stats(&self, variable: Hourly) -> Option<SeriesStats> {
+        Some(self.get(&variable.to_string())?.stats())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{ForecastResponse, Timestamp};
+
+    #[test]
+    fn with_nulls() {
+        let series = Series(vec![Some(4.0), None, Some(1.0), Some(9.0), None, Some(2.0)]);
+        let stats = series.stats();
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.null_count, 2);
+        assert_eq!((stats.min, stats.argmin), (Some(1.0), Some(2)));
+        assert_eq!((stats.max, stats.argmax), (Some(9.0), Some(3)));
+        assert_eq!(stats.mean, Some(4.0));
+        assert_eq!(stats.median, Some(3.0));
+        // Deviations of -3, 0, 5, -2
+        assert_eq!(stats.std_dev, Some(9.5f64.sqrt()));
+
+        let odd = Series(vec![Some(2.0), Some(2.0), Some(7.0)]).stats();
+        assert_eq!(odd.median, Some(2.0));
+        assert_eq!(odd.argmin, Some(0));
+    }
+
+    #[test]
+    fn all_null() {
+        let stats = Series(vec![None, None]).stats();
+        assert_eq!(
+            stats,
+            SeriesStats {
+                null_count: 2,
+                ..SeriesStats::default()
+            }
+        );
+        assert_eq!(Series::default().stats(), SeriesStats::default());
+    }
+
+    #[test]
+    fn block_sugar() {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/forecast.json")).unwrap();
+        let hourly = response.hourly.unwrap();
+
+        let stats = hourly.stats(Hourly::temperature_2m).unwrap();
+        assert_eq!(stats.max, Some(17.9));
+        assert_eq!(
+            hourly.time[stats.argmax.unwrap()],
+            Timestamp::Iso8601("2023-05-16T14:00".into())
+        );
+        assert_eq!(
+            hourly.time[stats.argmin.unwrap()].to_string(),
+            "2023-05-16T05:00"
+        );
+        assert_eq!(hourly.stats(Hourly::snowfall), None);
+    }
+}
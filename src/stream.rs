@@ -0,0 +1,159 @@
+use std::{fmt, io};
+
+use indexmap::IndexMap;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use crate::{ForecastResponse, HourlyBlock, Series, Timestamp};
+
+impl ForecastResponse {
+    /// Parse a forecast from JSON read incrementally, e.g. from a file or a
+    /// decompressing reader, without holding the whole text in memory
+    ///
+    /// Hourly series are allocated once, with the length of the `time` array
+    /// the API sends before them
+    pub fn from_reader(reader: impl io::Read) -> Result<Self, serde_json::Error> {
+        serde_json::from_reader(io::BufReader::new(reader))
+    }
+}
+
+// Written by hand rather than derived: `#[serde(flatten)]` buffers the whole
+// block in an intermediate representation before building the series
+impl<'de> Deserialize<'de> for HourlyBlock {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(BlockVisitor)
+    }
+}
+
+struct BlockVisitor;
+
+impl<'de> Visitor<'de> for BlockVisitor {
+    type Value = HourlyBlock;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of a `time` array and variable arrays")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<HourlyBlock, A::Error> {
+        let mut time: Option<Vec<Timestamp>> = None;
+        let mut series = IndexMap::new();
+        while let Some(name) = map.next_key::<String>()? {
+            if name == "time" {
+                time = Some(map.next_value()?);
+            } else {
+                let capacity = time.as_ref().map_or(0, Vec::len);
+                series.insert(name, map.next_value_seed(SeriesSeed { capacity })?);
+            }
+        }
+        Ok(HourlyBlock {
+            time: time.ok_or_else(|| de::Error::missing_field("time"))?,
+            series,
+        })
+    }
+}
+
+/// Deserialize a series into a vector allocated for `capacity` values
+struct SeriesSeed {
+    capacity: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for SeriesSeed {
+    type Value = Series;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Series, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SeriesSeed {
+    type Value = Series;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an array of numbers or nulls")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Series, A::Error> {
+        let mut values = Vec::with_capacity(self.capacity.max(seq.size_hint().unwrap_or(0)));
+        while let Some(value) = seq.next_element::<Option<f64>>()? {
+            values.push(value);
+        }
+        Ok(Series(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::fmt::Write;
+
+    use super::*;
+
+    /// Two years of hourly values for 180 variables, about 20 MB of JSON
+    fn large_body() -> String {
+        const HOURS: i64 = 2 * 365 * 24;
+        let mut body = String::from(
+            r#"{"latitude":52.52,"longitude":13.41,"elevation":38.0,"generationtime_ms":1.0,"utc_offset_seconds":0,"timezone":"GMT","timezone_abbreviation":"GMT","hourly":{"time":["#,
+        );
+        for hour in 0..HOURS {
+            let separator = if hour == 0 { "" } else { "," };
+            write!(body, "{separator}{}", 1_672_531_200 + hour * 3600).unwrap();
+        }
+        body.push(']');
+        for variable in 0..180 {
+            write!(body, r#","variable_{variable}":["#).unwrap();
+            for hour in 0..HOURS {
+                let separator = if hour == 0 { "" } else { "," };
+                if hour % 1000 == 999 {
+                    write!(body, "{separator}null").unwrap();
+                } else {
+                    write!(
+                        body,
+                        "{separator}{}.{:03}",
+                        hour % 40,
+                        (hour + variable) % 1000
+                    )
+                    .unwrap();
+                }
+            }
+            body.push(']');
+        }
+        body.push_str("}}");
+        body
+    }
+
+    #[test]
+    fn large_body_streams_into_exact_allocations() {
+        let body = large_body();
+        assert!(body.len() > 20_000_000, "{} bytes", body.len());
+
+        let response = ForecastResponse::from_reader(body.as_bytes()).unwrap();
+        let hourly = response.hourly.unwrap();
+        assert_eq!(hourly.len(), 17_520);
+        assert_eq!(hourly.time[1], Timestamp::Unix(1_672_534_800));
+        assert_eq!(hourly.series.len(), 180);
+        for (name, series) in &hourly.series {
+            assert_eq!(series.len(), hourly.len(), "{name}");
+            // Allocated once from the time array, never regrown
+            assert_eq!(series.0.capacity(), hourly.len(), "{name}");
+        }
+        let last = hourly.get("variable_179").unwrap();
+        assert_eq!(last[1], Some(1.18));
+        assert_eq!(last[999], None);
+        assert_eq!(last.nulls_count(), 17);
+    }
+
+    #[test]
+    fn same_as_buffered() {
+        let json = include_str!("../fixtures/forecast.json");
+        assert_eq!(
+            ForecastResponse::from_reader(json.as_bytes()).unwrap(),
+            ForecastResponse::from_json(json).unwrap()
+        );
+
+        // Variables before the time array, and a missing time array
+        let block: HourlyBlock =
+            serde_json::from_str(r#"{"rain":[0.1,null],"time":[0,3600]}"#).unwrap();
+        assert_eq!(block.get("rain").unwrap().values(), [Some(0.1), None]);
+        assert!(serde_json::from_str::<HourlyBlock>(r#"{"rain":[0.1]}"#).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use strum::IntoEnumIterator;
+
+use crate::{Hourly, HourlyBlock, Model, Series, Timestamp};
+
+/// Name before `_suffix`, if `variable` ends with it
+fn strip_suffix<'a>(variable: &'a str, suffix: &str) -> Option<&'a str> {
+    variable.strip_suffix(suffix)?.strip_suffix('_')
+}
+
+/// Model named by the suffix of `variable`, the longest one if several match
+fn model_suffix(variable: &str) -> Option<Model> {
+    Model::iter()
+        .filter(|model| strip_suffix(variable, &model.to_string()).is_some())
+        .max_by_key(|model| model.to_string().len())
+}
+
+/// Variable name and member number of a `name_memberNN` column
+pub(crate) fn member_suffix(variable: &str) -> Option<(&str, u32)> {
+    let (name, number) = variable.rsplit_once("_member")?;
+    if number.is_empty() || !number.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    Some((name, number.parse().ok()?))
+}
+
+impl HourlyBlock {
+    /// Values of a variable as forecast by `model`, when several were requested
+    pub fn by_model(&self, variable: Hourly, model: Model) -> Option<&Series> {
+        self.get(&format!("{variable}_{model}"))
+    }
+
+    /// Models whose name suffixes at least one variable, in order of appearance
+    ///
+    /// Variables without a suffix, or with one naming a model unknown to this
+    /// crate, are only reachable by their full name
+    pub fn models_present(&self) -> Vec<Model> {
+        let mut models = Vec::new();
+        for model in self.variables().filter_map(model_suffix) {
+            if !models.contains(&model) {
+                models.push(model);
+            }
+        }
+        models
+    }
+
+    /// Control run and members of an ensemble variable, `None` if missing
+    pub fn ensemble(&self, variable: Hourly) -> Option<EnsembleSeries<'_>> {
+        self.ensemble_by_name(&variable.to_string())
+    }
+
+    /// Same as `ensemble` for a variable named in the response
+    pub fn ensemble_by_name(&self, variable: &str) -> Option<EnsembleSeries<'_>> {
+        let mut members: Vec<_> = self
+            .series
+            .iter()
+            .filter_map(|(name, series)| match member_suffix(name) {
+                Some((name, number)) if name == variable => Some((number, series)),
+                _ => None,
+            })
+            .collect();
+        members.sort_by_key(|(number, _)| *number);
+        let control = self.series.get(variable);
+        if control.is_none() && members.is_empty() {
+            return None;
+        }
+        Some(EnsembleSeries {
+            time: &self.time,
+            control,
+            members,
+        })
+    }
+
+    /// Number of distinct ensemble members across all variables
+    pub fn member_count(&self) -> usize {
+        let mut numbers: Vec<_> = self
+            .variables()
+            .filter_map(member_suffix)
+            .map(|(_, number)| number)
+            .collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        numbers.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Ensemble runs of a variable, each aligned with the block timestamps
+pub struct EnsembleSeries<'a> {
+    pub time: &'a [Timestamp],
+    /// Unperturbed run, returned under the plain variable name
+    pub control: Option<&'a Series>,
+    /// Members by number, in ascending order
+    pub members: Vec<(u32, &'a Series)>,
+}
+
+impl<'a> EnsembleSeries<'a> {
+    /// Values of member `number`, counting from 1
+    pub fn member(&self, number: u32) -> Option<&'a Series> {
+        self.members
+            .iter()
+            .find(|(member, _)| *member == number)
+            .map(|(_, series)| *series)
+    }
+
+    pub fn member_count(&self) -> usize {
+        self.members.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::ForecastResponse;
+
+    #[test]
+    fn model_suffixes() {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/models.json")).unwrap();
+        let hourly = response.hourly.unwrap();
+        assert_eq!(
+            hourly.models_present(),
+            [Model::icon_seamless, Model::gfs_seamless]
+        );
+        let icon = hourly
+            .by_model(Hourly::temperature_2m, Model::icon_seamless)
+            .unwrap();
+        let gfs = hourly
+            .by_model(Hourly::temperature_2m, Model::gfs_seamless)
+            .unwrap();
+        assert_eq!(icon.values(), [Some(7.9), Some(7.4)]);
+        assert_eq!(gfs.values(), [Some(8.3), None]);
+        assert!(hourly.by_model(Hourly::rain, Model::gfs_seamless).is_none());
+        assert!(hourly
+            .by_model(Hourly::temperature_2m, Model::icon_d2)
+            .is_none());
+
+        assert_eq!(hourly.get("rain").unwrap()[1], Some(0.2));
+        assert_eq!(
+            hourly.get("temperature_2m_future_model").unwrap()[0],
+            Some(8.0)
+        );
+    }
+
+    #[test]
+    fn ensemble_members() {
+        let response =
+            ForecastResponse::from_json(include_str!("../fixtures/ensemble.json")).unwrap();
+        let hourly = response.hourly.unwrap();
+        assert_eq!(hourly.member_count(), 3);
+
+        let ensemble = hourly.ensemble(Hourly::temperature_2m).unwrap();
+        assert_eq!(ensemble.member_count(), 3);
+        assert_eq!(ensemble.time.len(), 3);
+        assert_eq!(
+            ensemble.control.unwrap().values(),
+            [Some(5.1), Some(4.8), Some(4.4)]
+        );
+        assert_eq!(
+            ensemble
+                .members
+                .iter()
+                .map(|(number, _)| *number)
+                .collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert!(ensemble
+            .members
+            .iter()
+            .all(|(_, series)| series.len() == ensemble.time.len()));
+        assert_eq!(ensemble.member(2).unwrap()[1], Some(5.3));
+        assert_eq!(ensemble.member(3).unwrap()[2], None);
+        assert!(ensemble.member(4).is_none());
+
+        let rain = hourly.ensemble(Hourly::rain).unwrap();
+        assert!(rain.control.is_none());
+        assert_eq!(rain.member_count(), 1);
+        assert!(hourly.ensemble(Hourly::snowfall).is_none());
+    }
+}
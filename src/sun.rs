@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use crate::{DailyBlock, DailyValue, Timestamp};
+
+/// Largest difference between the computed and reported daylight that is not
+/// flagged, sunrise and sunset being rounded to the minute
+const TOLERANCE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Sunrise, sunset and daylight length of one day
+pub struct SunEvents {
+    pub date: Timestamp,
+    /// `None` when the sun does not cross the horizon, as in polar day or night
+    pub sunrise: Option<Timestamp>,
+    pub sunset: Option<Timestamp>,
+    /// Time between sunrise and sunset, or the `daylight_duration` variable
+    /// when the sun does not cross the horizon; `None` if neither is known
+    pub daylight: Option<Duration>,
+    /// The `daylight_duration` variable differs from sunset minus sunrise by
+    /// more than a minute
+    pub discrepancy: bool,
+}
+
+impl DailyBlock {
+    /// Sun events of each day, from `sunrise`, `sunset` and `daylight_duration`
+    pub fn sun_events(&self) -> Vec<SunEvents> {
+        let time = |variable, index| match self.get(variable)?.get(index)? {
+            Some(DailyValue::Time(time)) => Some(time.clone()),
+            _ => None,
+        };
+        let reported = self.numbers("daylight_duration");
+        self.time
+            .iter()
+            .enumerate()
+            .map(|(index, date)| {
+                let reported = reported
+                    .as_ref()
+                    .and_then(|reported| reported.get(index).copied().flatten())
+                    .filter(|seconds| *seconds >= 0.0)
+                    .map(Duration::from_secs_f64);
+                let (sunrise, sunset) = match (time("sunrise", index), time("sunset", index)) {
+                    // Polar day and night are reported with both set to midnight
+                    (Some(sunrise), Some(sunset)) if sunrise == sunset => (None, None),
+                    pair => pair,
+                };
+                let computed = sunrise
+                    .as_ref()
+                    .zip(sunset.as_ref())
+                    .and_then(|(sunrise, sunset)| Some((sunrise.to_unix(0)?, sunset.to_unix(0)?)))
+                    .and_then(|(sunrise, sunset)| u64::try_from(sunset - sunrise).ok())
+                    .map(Duration::from_secs);
+                let discrepancy = match (computed, reported) {
+                    (Some(computed), Some(reported)) => computed.abs_diff(reported) > TOLERANCE,
+                    _ => false,
+                };
+                SunEvents {
+                    date: date.clone(),
+                    sunrise,
+                    sunset,
+                    daylight: computed.or(reported),
+                    discrepancy,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::ForecastResponse;
+
+    #[test]
+    fn daylight() {
+        let daily = ForecastResponse::from_json(include_str!("../fixtures/sun.json"))
+            .unwrap()
+            .daily
+            .unwrap();
+        let events = daily.sun_events();
+        assert_eq!(events.len(), 4);
+
+        let normal = &events[0];
+        assert_eq!(normal.date, Timestamp::Iso8601("2023-11-20".into()));
+        assert_eq!(
+            normal.sunrise,
+            Some(Timestamp::Iso8601("2023-11-20T10:43".into()))
+        );
+        assert_eq!(
+            normal.daylight,
+            Some(Duration::from_secs(2 * 3600 + 29 * 60))
+        );
+        assert!(!normal.discrepancy);
+
+        let polar_night = &events[1];
+        assert_eq!((&polar_night.sunrise, &polar_night.sunset), (&None, &None));
+        assert_eq!(polar_night.daylight, Some(Duration::ZERO));
+        assert!(!polar_night.discrepancy);
+
+        let polar_day = &events[2];
+        assert_eq!(polar_day.sunrise, None);
+        assert_eq!(polar_day.daylight, Some(Duration::from_secs(86400)));
+
+        let mismatched = &events[3];
+        assert_eq!(mismatched.daylight, Some(Duration::from_secs(44400)));
+        assert!(mismatched.discrepancy);
+
+        // Without `daylight_duration` only the computed length is known
+        let forecast =
+            ForecastResponse::from_json(include_str!("../fixtures/forecast.json")).unwrap();
+        let events = forecast.daily.unwrap().sun_events();
+        assert_eq!(
+            events[0].daylight,
+            Some(Duration::from_secs(16 * 3600 + 2 * 60))
+        );
+    }
+}
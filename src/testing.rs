@@ -0,0 +1,91 @@
+//! Helpers for testing code built on top of this crate
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+use bytes::Bytes;
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+use crate::{HttpRequest, HttpResponse, TransportError};
+
+#[cfg(feature = "response")]
+mod builder;
+#[cfg(all(
+    any(feature = "client", feature = "blocking"),
+    not(target_arch = "wasm32")
+))]
+mod cassette;
+#[cfg(feature = "chrono")]
+pub use crate::offset::from_map;
+#[cfg(feature = "polars")]
+pub use crate::polars::from_polars;
+#[cfg(feature = "response")]
+pub use builder::{load_fixture, ResponseBuilder};
+#[cfg(all(
+    any(feature = "client", feature = "blocking"),
+    not(target_arch = "wasm32")
+))]
+pub use cassette::{Offline, RecordingTransport, ReplayTransport};
+
+/// Hand-written responses in the API format
+pub mod fixtures {
+    /// Hourly temperature and rain in Berlin, with the daily summary
+    pub const BERLIN: &str = include_str!("../fixtures/forecast.json");
+    /// Hourly gusts and rain in Zurich on a stormy morning
+    pub const ZURICH_STORM: &str = include_str!("../fixtures/alerts.json");
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+#[derive(Clone, Default)]
+/// Transport answering canned bodies, routed by URL substring
+///
+/// Routes are matched in insertion order; unmatched URLs get a 404
+pub struct StaticTransport {
+    routes: Vec<(String, Bytes)>,
+}
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+impl StaticTransport {
+    /// Initialize a transport without routes
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Answer `body` to every URL containing `pattern`
+    pub fn route(mut self, pattern: &str, body: impl Into<Bytes>) -> Self {
+        self.routes.push((pattern.to_string(), body.into()));
+        self
+    }
+
+    fn answer(&self, url: &str) -> Result<HttpResponse, TransportError> {
+        let response = match self
+            .routes
+            .iter()
+            .find(|(pattern, _)| url.contains(pattern))
+        {
+            Some((_, body)) => HttpResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: body.clone(),
+            },
+            None => HttpResponse {
+                status: 404,
+                headers: Vec::new(),
+                body: Bytes::new(),
+            },
+        };
+        Ok(response)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl crate::HttpTransport for StaticTransport {
+    fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        self.answer(&request.url)
+    }
+}
+
+#[cfg(feature = "client")]
+impl crate::AsyncHttpTransport for StaticTransport {
+    async fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        self.answer(&request.url)
+    }
+}
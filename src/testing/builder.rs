@@ -0,0 +1,287 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use indexmap::IndexMap;
+
+use crate::date::Date;
+use crate::response::daily_kind;
+use crate::{
+    ColumnKind, Daily, DailyBlock, DailyValue, ForecastResponse, Hourly, HourlyBlock, Timestamp,
+    UnitsMap,
+};
+
+/// Parse a JSON file holding a raw API response
+pub fn load_fixture(path: impl AsRef<Path>) -> io::Result<ForecastResponse> {
+    let bytes = fs::read(path)?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+#[derive(Debug, Clone)]
+/// Builder of fake responses, generating the time axes from a start date
+pub struct ResponseBuilder {
+    response: ForecastResponse,
+    start: Date,
+    hourly: IndexMap<String, Vec<Option<f64>>>,
+    hourly_units: IndexMap<String, String>,
+    daily: IndexMap<String, Vec<Option<f64>>>,
+    daily_units: IndexMap<String, String>,
+}
+
+impl ResponseBuilder {
+    /// Start from an empty response at 0°N 0°E in GMT, beginning on 2023-01-01
+    pub fn new() -> Self {
+        ResponseBuilder {
+            response: ForecastResponse {
+                location_id: 0,
+                latitude: 0.0,
+                longitude: 0.0,
+                elevation: 0.0,
+                generationtime_ms: 0.0,
+                utc_offset_seconds: 0,
+                timezone: "GMT".to_string(),
+                timezone_abbreviation: "GMT".to_string(),
+                current_weather_units: None,
+                current_weather: None,
+                current_units: None,
+                current: None,
+                minutely_15_units: None,
+                minutely_15: None,
+                hourly_units: None,
+                hourly: None,
+                six_hourly_units: None,
+                six_hourly: None,
+                daily_units: None,
+                daily: None,
+            },
+            start: Date {
+                year: 2023,
+                month: 1,
+                day: 1,
+            },
+            hourly: IndexMap::new(),
+            hourly_units: IndexMap::new(),
+            daily: IndexMap::new(),
+            daily_units: IndexMap::new(),
+        }
+    }
+
+    pub fn coord(mut self, latitude: f64, longitude: f64) -> Self {
+        self.response.latitude = latitude;
+        self.response.longitude = longitude;
+        self
+    }
+
+    pub fn elevation(mut self, elevation: f64) -> Self {
+        self.response.elevation = elevation;
+        self
+    }
+
+    /// Set the timezone name, its abbreviation and its offset from UTC
+    pub fn timezone(mut self, name: &str, abbreviation: &str, utc_offset_seconds: i32) -> Self {
+        self.response.timezone = name.to_string();
+        self.response.timezone_abbreviation = abbreviation.to_string();
+        self.response.utc_offset_seconds = utc_offset_seconds;
+        self
+    }
+
+    /// First day of the time axes, as `YYYY-MM-DD`
+    ///
+    /// Panics if the date is invalid
+    pub fn start(mut self, date: &str) -> Self {
+        self.start = Date::parse(date).unwrap_or_else(|| panic!("invalid start date {date}"));
+        self
+    }
+
+    /// Add an hourly series, one value per hour from the start date;
+    /// `None` values are missing data
+    pub fn hourly<V: Into<Option<f64>>>(
+        mut self,
+        variable: Hourly,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        let values = values.into_iter().map(Into::into).collect();
+        self.hourly.insert(variable.to_string(), values);
+        self
+    }
+
+    /// Add a daily series, one value per day from the start date
+    pub fn daily<V: Into<Option<f64>>>(
+        mut self,
+        variable: Daily,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        let values = values.into_iter().map(Into::into).collect();
+        self.daily.insert(variable.to_string(), values);
+        self
+    }
+
+    /// Unit of an hourly variable, as spelled by the API
+    pub fn hourly_unit(mut self, variable: Hourly, unit: &str) -> Self {
+        self.hourly_units
+            .insert(variable.to_string(), unit.to_string());
+        self
+    }
+
+    /// Unit of a daily variable, as spelled by the API
+    pub fn daily_unit(mut self, variable: Daily, unit: &str) -> Self {
+        self.daily_units
+            .insert(variable.to_string(), unit.to_string());
+        self
+    }
+
+    /// Assemble the response, padding shorter series with missing data
+    pub fn build(self) -> ForecastResponse {
+        let start = self.start.days();
+        let (hourly_units, hourly) = series(self.hourly, self.hourly_units, |index| {
+            let day = Date::from_days(start + index as i64 / 24);
+            format!("{day}T{:02}:00", index % 24)
+        });
+        let hourly = hourly.map(|(time, series)| HourlyBlock {
+            time,
+            series: series
+                .into_iter()
+                .map(|(name, values)| (name, values.into()))
+                .collect(),
+        });
+        let (daily_units, daily) = series(self.daily, self.daily_units, |index| {
+            Date::from_days(start + index as i64).to_string()
+        });
+        let daily = daily.map(|(time, values)| DailyBlock {
+            time,
+            series: values
+                .into_iter()
+                .map(|(name, values)| {
+                    // weather codes are typed as the API parser would type them
+                    let code = matches!(daily_kind(&name), ColumnKind::Code);
+                    let values = values
+                        .into_iter()
+                        .map(|value| {
+                            value.map(|value| match code {
+                                true => DailyValue::Code(value as u8),
+                                false => DailyValue::Float(value),
+                            })
+                        })
+                        .collect();
+                    (name, values)
+                })
+                .collect(),
+        });
+        ForecastResponse {
+            hourly_units,
+            hourly,
+            daily_units,
+            daily,
+            ..self.response
+        }
+    }
+}
+
+impl Default for ResponseBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Values padded to a common time axis, and their units, `None` if there are none
+#[allow(clippy::type_complexity)]
+fn series(
+    mut values: IndexMap<String, Vec<Option<f64>>>,
+    units: IndexMap<String, String>,
+    timestamp: impl Fn(usize) -> String,
+) -> (
+    Option<UnitsMap>,
+    Option<(Vec<Timestamp>, IndexMap<String, Vec<Option<f64>>>)>,
+) {
+    if values.is_empty() {
+        return (None, None);
+    }
+    let len = values.values().map(Vec::len).max().unwrap_or(0);
+    for values in values.values_mut() {
+        values.resize(len, None);
+    }
+    let time = (0..len)
+        .map(|index| Timestamp::Iso8601(timestamp(index)))
+        .collect();
+    let all_units = [("time".to_string(), "iso8601".to_string())]
+        .into_iter()
+        .chain(units)
+        .collect();
+    (Some(all_units), Some((time, values)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testing::fixtures;
+    use crate::{AlertRule, Comparison, PrecipIntensity, Precipitation};
+
+    #[test]
+    fn generated_time_axes() {
+        let response = ResponseBuilder::new()
+            .coord(52.52, 13.41)
+            .start("2024-02-28")
+            .hourly(Hourly::temperature_2m, (0..30).map(f64::from))
+            .hourly(Hourly::rain, [0.0, 1.5])
+            .hourly_unit(Hourly::temperature_2m, "°C")
+            .daily(Daily::temperature_2m_max, [12.0, 9.5])
+            .build();
+
+        let hourly = response.hourly.as_ref().unwrap();
+        assert_eq!(hourly.time.len(), 30);
+        assert_eq!(hourly.time[0].to_string(), "2024-02-28T00:00");
+        assert_eq!(hourly.time[29].to_string(), "2024-02-29T05:00");
+        assert_eq!(
+            hourly.get("rain").unwrap().values()[..3],
+            [Some(0.0), Some(1.5), None]
+        );
+        assert_eq!(
+            response
+                .hourly_units
+                .as_ref()
+                .unwrap()
+                .get("temperature_2m"),
+            Some("°C")
+        );
+        let daily = response.daily.as_ref().unwrap();
+        assert_eq!(daily.time[1].to_string(), "2024-02-29");
+
+        // Built responses look like parsed ones
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ForecastResponse>(&json).unwrap(),
+            response
+        );
+    }
+
+    #[test]
+    fn builds_inputs_for_helpers() {
+        let response = ResponseBuilder::new()
+            .hourly(Hourly::windgusts_10m, [20.0, 65.0, 70.0, 30.0])
+            .hourly_unit(Hourly::windgusts_10m, "km/h")
+            .hourly(Hourly::rain, [Some(0.0), Some(3.0), Some(12.0), None])
+            .build();
+
+        let gusts = AlertRule::new(Hourly::windgusts_10m, Comparison::Above, 15.0).unit("m/s");
+        let matches = response.evaluate(&[gusts]);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].timestamps.len(), 2);
+        assert_eq!(matches[0].peak, 70.0);
+
+        let hourly = response.hourly.as_ref().unwrap();
+        let rain = hourly.get("rain").unwrap();
+        let intensities = PrecipIntensity::series(&hourly.time, rain, &Precipitation::mm);
+        assert_eq!(intensities[2].1, PrecipIntensity::Heavy);
+    }
+
+    #[test]
+    fn bundled_fixtures() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/forecast.json");
+        let berlin = load_fixture(path).unwrap();
+        assert_eq!(berlin, serde_json::from_str(fixtures::BERLIN).unwrap());
+        assert!(load_fixture("missing.json").is_err());
+        let storm: ForecastResponse = serde_json::from_str(fixtures::ZURICH_STORM).unwrap();
+        assert_eq!(storm.timezone, "GMT");
+    }
+}
@@ -0,0 +1,282 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::{fnv1a, HttpRequest, HttpResponse, TransportError};
+
+/// Exchange stored in a cassette file
+#[derive(Serialize, Deserialize)]
+struct Interaction {
+    url: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Body,
+}
+
+/// Body kept as text when possible, so that cassettes can be read and edited
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Body {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+/// URL without scheme and host and with sorted query parameters, so that
+/// cassettes recorded against one server can be replayed for another
+fn canonical(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let rest = rest.find(['/', '?']).map_or("", |start| &rest[start..]);
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let mut params: Vec<_> = query.split('&').filter(|param| !param.is_empty()).collect();
+    params.sort_unstable();
+    let path = if path.is_empty() { "/" } else { path };
+    match params.is_empty() {
+        true => path.to_string(),
+        false => format!("{path}?{}", params.join("&")),
+    }
+}
+
+fn cassette(dir: &Path, url: &str) -> PathBuf {
+    dir.join(format!("{:016x}.json", fnv1a(&canonical(url))))
+}
+
+fn save(dir: &Path, url: &str, response: &HttpResponse) -> io::Result<()> {
+    let body = match std::str::from_utf8(&response.body) {
+        Ok(text) => Body::Text(text.to_string()),
+        Err(_) => Body::Binary(response.body.to_vec()),
+    };
+    let interaction = Interaction {
+        url: canonical(url),
+        status: response.status,
+        headers: response.headers.clone(),
+        body,
+    };
+    fs::create_dir_all(dir)?;
+    fs::write(cassette(dir, url), serde_json::to_vec_pretty(&interaction)?)
+}
+
+/// Recorded answer to `url`, `None` if there is none
+fn load(dir: &Path, url: &str) -> Result<Option<HttpResponse>, TransportError> {
+    let path = cassette(dir, url);
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(TransportError::Other(error.into())),
+    };
+    let interaction: Interaction = serde_json::from_slice(&bytes).map_err(|error| {
+        TransportError::Other(format!("invalid cassette {}: {error}", path.display()).into())
+    })?;
+    let body = match interaction.body {
+        Body::Text(text) => Bytes::from(text),
+        Body::Binary(bytes) => Bytes::from(bytes),
+    };
+    Ok(Some(HttpResponse {
+        status: interaction.status,
+        headers: interaction.headers,
+        body,
+    }))
+}
+
+#[derive(Clone)]
+/// Transport forwarding requests to another one and saving every answer
+/// as a human-readable JSON cassette in a directory
+pub struct RecordingTransport<T> {
+    inner: T,
+    dir: PathBuf,
+}
+
+impl<T> RecordingTransport<T> {
+    /// Record the answers of `inner` in `dir`, created if needed
+    pub fn new(inner: T, dir: impl Into<PathBuf>) -> Self {
+        RecordingTransport {
+            inner,
+            dir: dir.into(),
+        }
+    }
+
+    fn record(
+        &self,
+        url: &str,
+        response: Result<HttpResponse, TransportError>,
+    ) -> Result<HttpResponse, TransportError> {
+        let response = response?;
+        save(&self.dir, url, &response).map_err(|error| TransportError::Other(error.into()))?;
+        Ok(response)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<T: crate::HttpTransport> crate::HttpTransport for RecordingTransport<T> {
+    fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        self.record(&request.url, self.inner.get(request))
+    }
+}
+
+#[cfg(feature = "client")]
+impl<T: crate::AsyncHttpTransport + crate::MaybeSync> crate::AsyncHttpTransport
+    for RecordingTransport<T>
+{
+    async fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        self.record(&request.url, self.inner.get(request).await)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// Transport failing every request, for tests that must not reach the network
+pub struct Offline;
+
+impl Offline {
+    fn answer(url: &str) -> Result<HttpResponse, TransportError> {
+        Err(TransportError::Connect(
+            format!("offline, refusing to GET {url}").into(),
+        ))
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl crate::HttpTransport for Offline {
+    fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        Self::answer(&request.url)
+    }
+}
+
+#[cfg(feature = "client")]
+impl crate::AsyncHttpTransport for Offline {
+    async fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        Self::answer(&request.url)
+    }
+}
+
+#[derive(Clone)]
+/// Transport answering from the cassettes saved by a `RecordingTransport`
+///
+/// URLs without a cassette fail, unless a transport is set to record them
+pub struct ReplayTransport<T = Offline> {
+    dir: PathBuf,
+    fallback: Option<RecordingTransport<T>>,
+}
+
+impl ReplayTransport {
+    /// Replay the cassettes in `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        ReplayTransport {
+            dir: dir.into(),
+            fallback: None,
+        }
+    }
+}
+
+impl<T> ReplayTransport<T> {
+    /// Forward requests without a cassette to `transport`, recording its answers
+    pub fn record_missing<U>(self, transport: U) -> ReplayTransport<U> {
+        ReplayTransport {
+            fallback: Some(RecordingTransport::new(transport, self.dir.clone())),
+            dir: self.dir,
+        }
+    }
+
+    fn missing(&self, url: &str) -> TransportError {
+        TransportError::Other(
+            format!(
+                "no cassette for {} in {}",
+                canonical(url),
+                self.dir.display()
+            )
+            .into(),
+        )
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<T: crate::HttpTransport> crate::HttpTransport for ReplayTransport<T> {
+    fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        if let Some(response) = load(&self.dir, &request.url)? {
+            return Ok(response);
+        }
+        match &self.fallback {
+            Some(recorder) => recorder.get(request),
+            None => Err(self.missing(&request.url)),
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl<T: crate::AsyncHttpTransport + crate::MaybeSync> crate::AsyncHttpTransport
+    for ReplayTransport<T>
+{
+    async fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        if let Some(response) = load(&self.dir, &request.url)? {
+            return Ok(response);
+        }
+        match &self.fallback {
+            Some(recorder) => recorder.get(request).await,
+            None => Err(self.missing(&request.url)),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+    use crate::{AsyncHttpTransport, DecodeMode, ForecastResponse, ReqwestTransport};
+
+    const FIXTURE: &str = include_str!("../../fixtures/forecast.json");
+
+    #[test]
+    fn canonical_urls() {
+        assert_eq!(
+            canonical("http://127.0.0.1:1234?longitude=13.41&latitude=52.52"),
+            "/?latitude=52.52&longitude=13.41"
+        );
+        assert_eq!(
+            canonical("https://api.open-meteo.com/v1/forecast?latitude=1"),
+            "/v1/forecast?latitude=1"
+        );
+    }
+
+    #[tokio::test]
+    async fn record_then_replay() {
+        let dir = std::env::temp_dir().join(format!("weathered-cassette-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let server = MockServer::start(vec![MockResponse::ok(FIXTURE).header("ETag", "\"v1\"")]);
+        let url = format!(
+            "{}/v1/forecast?latitude=52.52&longitude=13.41",
+            server.url()
+        );
+
+        let recorder = RecordingTransport::new(ReqwestTransport::default(), &dir);
+        let recorded = recorder.get(&HttpRequest::new(&url)).await.unwrap();
+        let cassette = fs::read_to_string(cassette(&dir, &url)).unwrap();
+        assert!(cassette.contains("\"url\": \"/v1/forecast?latitude=52.52&longitude=13.41\""));
+
+        // Same request against another host, parameters in another order
+        let replay = ReplayTransport::new(&dir);
+        let moved = "https://example.com/v1/forecast?longitude=13.41&latitude=52.52";
+        let replayed = replay.get(&HttpRequest::new(moved)).await.unwrap();
+        assert_eq!(replayed.header("etag"), Some("\"v1\""));
+        assert_eq!(
+            ForecastResponse::decode(&url, &recorded, DecodeMode::Json).unwrap(),
+            ForecastResponse::decode(moved, &replayed, DecodeMode::Json).unwrap()
+        );
+        assert_eq!(server.requests(), 1);
+
+        let unknown = format!("{}/v1/forecast?latitude=0&longitude=0", server.url());
+        let error = replay.get(&HttpRequest::new(&unknown)).await.unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("no cassette for /v1/forecast?latitude=0"));
+        let recording = replay.record_missing(ReqwestTransport::default());
+        assert!(recording.get(&HttpRequest::new(&unknown)).await.is_ok());
+        assert!(ReplayTransport::new(&dir)
+            .get(&HttpRequest::new(&unknown))
+            .await
+            .is_ok());
+        assert_eq!(server.requests(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,317 @@
+use std::time::{Duration, UNIX_EPOCH};
+use std::{error, fmt};
+
+use bytes::Bytes;
+
+#[derive(Debug, Clone)]
+/// Request handed to the HTTP layer
+pub struct HttpRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// Total timeout, overriding the one configured on the transport
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    /// Initialize a request for the given URL
+    pub fn new(url: impl Into<String>) -> Self {
+        HttpRequest {
+            url: url.into(),
+            headers: Vec::new(),
+            timeout: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Raw answer of the HTTP layer, before any decoding
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Bytes,
+}
+
+impl HttpResponse {
+    /// Value of the first header with the given name, case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Delay requested by the `Retry-After` header, either in seconds or as an HTTP date
+    pub fn retry_after(&self) -> Option<Duration> {
+        let value = self.header("retry-after")?.trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        let date = httpdate::parse_http_date(value).ok()?;
+        let date = date.duration_since(UNIX_EPOCH).ok()?;
+        let now = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .ok()?;
+        Some(date.saturating_sub(now))
+    }
+}
+
+#[derive(Debug)]
+/// Enumerate failures of the underlying HTTP transport
+pub enum TransportError {
+    /// The connection could not be established
+    Connect(Box<dyn error::Error + Send + Sync>),
+    /// The request did not complete in time
+    Timeout(Box<dyn error::Error + Send + Sync>),
+    /// Any other failure while sending the request or reading the body
+    Other(Box<dyn error::Error + Send + Sync>),
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportError::Connect(source) => write!(f, "connection failed: {source}"),
+            TransportError::Timeout(source) => write!(f, "timed out: {source}"),
+            TransportError::Other(source) => write!(f, "{source}"),
+        }
+    }
+}
+
+impl error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            TransportError::Connect(source)
+            | TransportError::Timeout(source)
+            | TransportError::Other(source) => Some(source.as_ref()),
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+/// Blocking HTTP backend used by `blocking::Client`
+pub trait HttpTransport {
+    /// GET the requested URL
+    fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError>;
+}
+
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+/// `Send`, except on wasm where futures driven by the browser never are
+pub trait MaybeSend: Send {}
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+impl<T: Send> MaybeSend for T {}
+
+#[cfg(all(feature = "client", target_arch = "wasm32"))]
+/// `Send`, except on wasm where futures driven by the browser never are
+pub trait MaybeSend {}
+#[cfg(all(feature = "client", target_arch = "wasm32"))]
+impl<T> MaybeSend for T {}
+
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+/// `Sync`, except on wasm where futures driven by the browser never are `Send`
+pub trait MaybeSync: Sync {}
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+impl<T: Sync> MaybeSync for T {}
+
+#[cfg(all(feature = "client", target_arch = "wasm32"))]
+/// `Sync`, except on wasm where futures driven by the browser never are `Send`
+pub trait MaybeSync {}
+#[cfg(all(feature = "client", target_arch = "wasm32"))]
+impl<T> MaybeSync for T {}
+
+#[cfg(feature = "client")]
+/// Async HTTP backend used by `Client`
+pub trait AsyncHttpTransport {
+    /// GET the requested URL
+    fn get(
+        &self,
+        request: &HttpRequest,
+    ) -> impl std::future::Future<Output = Result<HttpResponse, TransportError>> + MaybeSend;
+}
+
+#[cfg(feature = "client")]
+#[derive(Clone, Default)]
+/// Async transport backed by reqwest
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "client")]
+impl ReqwestTransport {
+    /// Wrap an already configured `reqwest::Client`
+    pub fn new(client: reqwest::Client) -> Self {
+        ReqwestTransport { client }
+    }
+}
+
+#[cfg(feature = "client")]
+impl AsyncHttpTransport for ReqwestTransport {
+    async fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        let mut builder = self.client.get(&request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        // The browser manages timeouts of wasm requests
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(timeout) = request.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let response = builder.send().await.map_err(reqwest_error)?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response.bytes().await.map_err(reqwest_error)?;
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+#[cfg(feature = "client")]
+fn reqwest_error(source: reqwest::Error) -> TransportError {
+    #[cfg(not(target_arch = "wasm32"))]
+    let connect = source.is_connect();
+    #[cfg(target_arch = "wasm32")]
+    let connect = false;
+    if source.is_timeout() {
+        TransportError::Timeout(Box::new(source))
+    } else if connect {
+        TransportError::Connect(Box::new(source))
+    } else {
+        TransportError::Other(Box::new(source))
+    }
+}
+
+#[cfg(feature = "blocking")]
+#[derive(Clone)]
+/// Blocking transport backed by ureq
+pub struct UreqTransport {
+    agent: ureq::Agent,
+    compression: bool,
+}
+
+#[cfg(feature = "blocking")]
+impl UreqTransport {
+    /// Wrap an already configured `ureq::Agent`
+    pub fn new(agent: ureq::Agent) -> Self {
+        UreqTransport {
+            agent,
+            compression: true,
+        }
+    }
+
+    /// Request compressed responses (the default with the `gzip` feature);
+    /// disabling it makes bodies readable on the wire when debugging
+    pub fn compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self::new(default_agent())
+    }
+}
+
+#[cfg(feature = "blocking")]
+/// Agent connecting through the TLS backend selected by the `tls-*` features
+pub(crate) fn default_agent() -> ureq::Agent {
+    // Without its `tls` feature ureq needs the native connector to be set explicitly
+    #[cfg(feature = "tls-native")]
+    if let Ok(connector) = native_tls::TlsConnector::new() {
+        return ureq::AgentBuilder::new()
+            .tls_connector(std::sync::Arc::new(connector))
+            .build();
+    }
+    ureq::Agent::new()
+}
+
+#[cfg(feature = "blocking")]
+impl HttpTransport for UreqTransport {
+    fn get(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        use std::io::Read;
+
+        let mut builder = self.agent.get(&request.url);
+        if cfg!(feature = "gzip") && self.compression {
+            builder = builder.set("Accept-Encoding", "gzip, deflate");
+        }
+        for (name, value) in &request.headers {
+            builder = builder.set(name, value);
+        }
+        if let Some(timeout) = request.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let response = match builder.call() {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(source)) => return Err(ureq_error(source)),
+        };
+        let status = response.status();
+        let encoding = response.header("content-encoding").map(str::to_string);
+        let headers = response
+            .headers_names()
+            .into_iter()
+            .filter_map(|name| {
+                let value = response.header(&name)?.to_string();
+                Some((name, value))
+            })
+            .collect();
+        let mut body = Vec::new();
+        let reader = decoder(encoding.as_deref(), response.into_reader());
+        if let Err(source) = reader.and_then(|mut reader| reader.read_to_end(&mut body)) {
+            return Err(match source.kind() {
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => {
+                    TransportError::Timeout(Box::new(source))
+                }
+                _ => TransportError::Other(Box::new(source)),
+            });
+        }
+        Ok(HttpResponse {
+            status,
+            headers,
+            body: body.into(),
+        })
+    }
+}
+
+#[cfg(feature = "blocking")]
+/// Wrap the body reader to undo the given `Content-Encoding`
+fn decoder(
+    encoding: Option<&str>,
+    reader: impl std::io::Read + 'static,
+) -> std::io::Result<Box<dyn std::io::Read>> {
+    match encoding.map(str::trim) {
+        None | Some("identity") => Ok(Box::new(reader)),
+        #[cfg(feature = "gzip")]
+        Some("gzip" | "x-gzip") => Ok(Box::new(flate2::read::MultiGzDecoder::new(reader))),
+        #[cfg(feature = "gzip")]
+        Some("deflate") => Ok(Box::new(flate2::read::ZlibDecoder::new(reader))),
+        Some(other) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("unsupported content encoding {other}"),
+        )),
+    }
+}
+
+#[cfg(feature = "blocking")]
+fn ureq_error(source: ureq::Transport) -> TransportError {
+    let timed_out = error::Error::source(&source)
+        .and_then(|inner| inner.downcast_ref::<std::io::Error>())
+        .is_some_and(|inner| {
+            matches!(
+                inner.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+            )
+        });
+    match source.kind() {
+        _ if timed_out => TransportError::Timeout(Box::new(source)),
+        ureq::ErrorKind::Dns | ureq::ErrorKind::ConnectionFailed => {
+            TransportError::Connect(Box::new(source))
+        }
+        _ => TransportError::Other(Box::new(source)),
+    }
+}
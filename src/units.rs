@@ -0,0 +1,342 @@
+use std::fmt;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::intensity::MM_PER_INCH;
+use crate::{
+    DailyValue, ForecastResponse, Precipitation, Series, Speed, Temperature, WeatheredError,
+};
+
+pub(crate) const KMH_PER_MS: f64 = 3.6;
+pub(crate) const KMH_PER_MPH: f64 = 1.609_344;
+pub(crate) const KMH_PER_KN: f64 = 1.852;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+/// Unit of each variable of a block, as spelled by the API
+pub struct UnitsMap(IndexMap<String, String>);
+
+impl UnitsMap {
+    /// Unit of a variable, given as an `Hourly`/`Daily` variable or by name
+    pub fn get(&self, variable: impl fmt::Display) -> Option<&str> {
+        self.0.get(&variable.to_string()).map(String::as_str)
+    }
+
+    /// Variable name followed by its unit, e.g. `temperature_2m (°C)`, for labels
+    pub fn label(&self, variable: impl fmt::Display) -> String {
+        let name = variable.to_string();
+        match self.0.get(&name) {
+            Some(unit) if !unit.is_empty() => format!("{name} ({unit})"),
+            _ => name,
+        }
+    }
+
+    /// Check that a variable is reported in the expected unit
+    pub fn assert_unit(
+        &self,
+        variable: impl fmt::Display,
+        expected: &str,
+    ) -> Result<(), WeatheredError> {
+        let variable = variable.to_string();
+        match self.0.get(&variable) {
+            Some(unit) if unit == expected => Ok(()),
+            found => Err(WeatheredError::UnitMismatch {
+                expected: expected.to_string(),
+                found: found.cloned(),
+                variable,
+            }),
+        }
+    }
+
+    /// Set the unit of a variable, keeping its position if already present
+    pub(crate) fn insert(&mut self, variable: String, unit: String) {
+        self.0.insert(variable, unit);
+    }
+
+    /// Variables and their units, in the order returned by the API
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(variable, unit)| (variable.as_str(), unit.as_str()))
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for UnitsMap {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(units: I) -> Self {
+        UnitsMap(
+            units
+                .into_iter()
+                .map(|(variable, unit)| (variable.into(), unit.into()))
+                .collect(),
+        )
+    }
+}
+
+/// Dimension of a unit, with the factor and offset turning it into the base
+/// unit of that dimension
+fn scale(unit: &str) -> Option<(&'static str, f64, f64)> {
+    let scale = match unit {
+        "km/h" => ("speed", 1.0, 0.0),
+        "m/s" => ("speed", KMH_PER_MS, 0.0),
+        "mph" | "mp/h" => ("speed", KMH_PER_MPH, 0.0),
+        "kn" => ("speed", KMH_PER_KN, 0.0),
+        "°C" => ("temperature", 1.0, 0.0),
+        "°F" => ("temperature", 5.0 / 9.0, -160.0 / 9.0),
+        "mm" => ("length", 1.0, 0.0),
+        "cm" => ("length", 10.0, 0.0),
+        "inch" => ("length", MM_PER_INCH, 0.0),
+        _ => return None,
+    };
+    Some(scale)
+}
+
+/// Express `value` in unit `to` instead of `from`, `None` if they measure
+/// different things
+pub(crate) fn convert(value: f64, from: &str, to: &str) -> Option<f64> {
+    if from == to {
+        return Some(value);
+    }
+    let (from_dimension, from_factor, from_offset) = scale(from)?;
+    let (to_dimension, to_factor, to_offset) = scale(to)?;
+    (from_dimension == to_dimension)
+        .then(|| (value * from_factor + from_offset - to_offset) / to_factor)
+}
+
+#[derive(Clone)]
+/// Units to convert a response to with `ForecastResponse::convert_units`
+pub struct UnitPreferences {
+    pub temperature: Temperature,
+    pub speed: Speed,
+    /// Snowfall follows as centimeters with `mm` and inches with `inch`
+    pub precipitation: Precipitation,
+}
+
+impl UnitPreferences {
+    /// Units answered by the API by default
+    pub fn metric() -> Self {
+        UnitPreferences {
+            temperature: Temperature::celsius,
+            speed: Speed::kmh,
+            precipitation: Precipitation::mm,
+        }
+    }
+
+    pub fn imperial() -> Self {
+        UnitPreferences {
+            temperature: Temperature::fahrenheit,
+            speed: Speed::mph,
+            precipitation: Precipitation::inch,
+        }
+    }
+
+    /// Unit `variable` should be in, `None` for units that are not preferences
+    fn target(&self, variable: &str, unit: &str) -> Option<&'static str> {
+        let (dimension, ..) = scale(unit)?;
+        let target = match dimension {
+            "temperature" => match self.temperature {
+                Temperature::celsius => "°C",
+                Temperature::fahrenheit => "°F",
+            },
+            "speed" => match self.speed {
+                Speed::kmh => "km/h",
+                Speed::ms => "m/s",
+                Speed::mph => "mp/h",
+                Speed::kn => "kn",
+            },
+            _ => match self.precipitation {
+                Precipitation::inch => "inch",
+                Precipitation::mm if variable.starts_with("snowfall") => "cm",
+                Precipitation::mm => "mm",
+            },
+        };
+        Some(target)
+    }
+}
+
+/// Conversions to apply to the variables of a block, rewriting its units
+fn conversions(
+    units: Option<&mut UnitsMap>,
+    target: &UnitPreferences,
+) -> Vec<(String, impl Fn(f64) -> f64)> {
+    let Some(units) = units else {
+        return Vec::new();
+    };
+    let changes: Vec<_> = units
+        .iter()
+        .filter_map(|(variable, unit)| {
+            let to = target.target(variable, unit)?;
+            (to != unit).then(|| (variable.to_string(), unit.to_string(), to))
+        })
+        .collect();
+    changes
+        .into_iter()
+        .map(|(variable, from, to)| {
+            units.insert(variable.clone(), to.to_string());
+            (variable, move |value| {
+                convert(value, &from, to).unwrap_or(value)
+            })
+        })
+        .collect()
+}
+
+impl ForecastResponse {
+    /// Convert every temperature, speed and precipitation to `target` in
+    /// place, rewriting the units of each block to match
+    ///
+    /// Variables in other units, e.g. `%` or `hPa`, are left untouched
+    pub fn convert_units(&mut self, target: UnitPreferences) {
+        if let Some(current) = &mut self.current_weather {
+            for (variable, convert) in conversions(self.current_weather_units.as_mut(), &target) {
+                match variable.as_str() {
+                    "temperature" => current.temperature = convert(current.temperature),
+                    "windspeed" => current.windspeed = convert(current.windspeed),
+                    _ => {}
+                }
+            }
+        }
+        if let Some(current) = &mut self.current {
+            for (variable, convert) in conversions(self.current_units.as_mut(), &target) {
+                if let Some(value) = current.values.get_mut(&variable) {
+                    if let Some(number) = value.as_f64() {
+                        *value = convert(number).into();
+                    }
+                }
+            }
+        }
+        let blocks = [
+            (&mut self.minutely_15, self.minutely_15_units.as_mut()),
+            (&mut self.hourly, self.hourly_units.as_mut()),
+            (&mut self.six_hourly, self.six_hourly_units.as_mut()),
+        ];
+        for (block, units) in blocks {
+            let Some(block) = block else { continue };
+            for (variable, convert) in conversions(units, &target) {
+                if let Some(series) = block.series.get_mut(&variable) {
+                    *series = series
+                        .iter()
+                        .map(|value| value.map(&convert))
+                        .collect::<Series>();
+                }
+            }
+        }
+        if let Some(daily) = &mut self.daily {
+            for (variable, convert) in conversions(self.daily_units.as_mut(), &target) {
+                for value in daily
+                    .series
+                    .get_mut(&variable)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                {
+                    if let DailyValue::Float(number) = value {
+                        *number = convert(*number);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{Daily, Hourly};
+
+    const METRIC: &str = include_str!("../fixtures/forecast.json");
+    const IMPERIAL: &str = include_str!("../fixtures/imperial.json");
+    const SEASONAL: &str = include_str!("../fixtures/seasonal.json");
+
+    #[test]
+    fn metric_to_imperial() {
+        let mut response = ForecastResponse::from_json(METRIC).unwrap();
+        response.convert_units(UnitPreferences::imperial());
+
+        let close = |value: Option<f64>, expected: f64| {
+            assert!(
+                (value.unwrap() - expected).abs() < 1e-9,
+                "{value:?} != {expected}"
+            )
+        };
+        let hourly = response.hourly.as_ref().unwrap();
+        close(hourly.get("temperature_2m").unwrap()[0], 49.64);
+        close(hourly.get("rain").unwrap()[15], 1.4 / 25.4);
+        let current = response.current_weather.as_ref().unwrap();
+        close(Some(current.temperature), 56.12);
+        close(Some(current.windspeed), 11.2 / 1.609344);
+        assert_eq!(current.winddirection, 254.0);
+        let daily = response.daily.as_ref().unwrap();
+        close(daily.numbers("temperature_2m_max").unwrap()[0], 64.22);
+        assert_eq!(
+            daily.get("weathercode").unwrap()[0],
+            Some(DailyValue::Code(61))
+        );
+
+        let units = response.hourly_units.as_ref().unwrap();
+        assert_eq!(units.get("temperature_2m"), Some("°F"));
+        assert_eq!(units.get("rain"), Some("inch"));
+        let units = response.current_weather_units.as_ref().unwrap();
+        assert_eq!(units.get("windspeed"), Some("mp/h"));
+        assert_eq!(units.get("winddirection"), Some("°"));
+        assert_eq!(
+            response
+                .daily_units
+                .as_ref()
+                .unwrap()
+                .get("temperature_2m_max"),
+            Some("°F")
+        );
+        response.convert_units(UnitPreferences::metric());
+        close(response.hourly(Hourly::temperature_2m).unwrap()[0], 9.8);
+    }
+
+    #[test]
+    fn six_hourly_converted() {
+        let mut response = ForecastResponse::from_json(SEASONAL).unwrap();
+        response.convert_units(UnitPreferences::imperial());
+
+        let six_hourly = response.six_hourly.as_ref().unwrap();
+        let temperature = six_hourly.get("temperature_2m").unwrap()[0].unwrap();
+        assert!((temperature - 39.56).abs() < 1e-9, "{temperature}");
+        let precipitation = six_hourly.get("precipitation_member01").unwrap()[2].unwrap();
+        assert!((precipitation - 1.2 / 25.4).abs() < 1e-9, "{precipitation}");
+        let units = response.six_hourly_units.as_ref().unwrap();
+        assert_eq!(units.get("temperature_2m_member02"), Some("°F"));
+        assert_eq!(units.get("precipitation_member02"), Some("inch"));
+    }
+
+    #[test]
+    fn units_by_variable() {
+        let metric = ForecastResponse::from_json(METRIC).unwrap();
+        let hourly = metric.hourly_units.unwrap();
+        assert_eq!(hourly.get(Hourly::temperature_2m), Some("°C"));
+        assert_eq!(hourly.get("rain"), Some("mm"));
+        assert_eq!(hourly.get(Hourly::snowfall), None);
+        assert_eq!(hourly.label(Hourly::rain), "rain (mm)");
+        assert_eq!(hourly.label(Hourly::snowfall), "snowfall");
+        assert!(hourly.assert_unit(Hourly::temperature_2m, "°C").is_ok());
+        let daily = metric.daily_units.unwrap();
+        assert_eq!(daily.get(Daily::weathercode), Some("wmo code"));
+
+        let imperial = ForecastResponse::from_json(IMPERIAL).unwrap();
+        let hourly = imperial.hourly_units.unwrap();
+        assert_eq!(hourly.get(Hourly::windspeed_10m), Some("mp/h"));
+        assert_eq!(hourly.get("precipitation"), Some("inch"));
+        match hourly.assert_unit(Hourly::temperature_2m, "°C") {
+            Err(error @ WeatheredError::UnitMismatch { .. }) => {
+                assert_eq!(error.to_string(), "temperature_2m is in °F, expected °C")
+            }
+            other => panic!("expected unit mismatch, got {other:?}"),
+        }
+        assert!(matches!(
+            hourly.assert_unit(Hourly::rain, "inch"),
+            Err(WeatheredError::UnitMismatch { found: None, .. })
+        ));
+        let daily = imperial.daily_units.unwrap();
+        assert_eq!(
+            daily.label(Daily::temperature_2m_max),
+            "temperature_2m_max (°F)"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+
+use crate::retry::random_fraction;
+use crate::{
+    AlertMatch, AlertRule, AsyncHttpTransport, AsyncRuntime, BoxFuture, Client, Coordinates,
+    FetchOutcome, Forecast, ForecastResponse, MaybeSync, WeatheredError,
+};
+
+/// Fraction by which each wait is randomly lengthened or shortened
+const JITTER: f64 = 0.05;
+
+/// Stream of forecast updates returned by `Client::watch`,
+/// polling until dropped
+pub struct Watch<'a, T> {
+    client: &'a Client<T>,
+    forecast: &'a Forecast<Coordinates>,
+    interval: Duration,
+    runtime: Arc<dyn AsyncRuntime>,
+    emit_unchanged: bool,
+    started: bool,
+    next: Option<BoxFuture<'a, Result<ForecastResponse, WeatheredError>>>,
+}
+
+impl<'a, T: AsyncHttpTransport + MaybeSync> Watch<'a, T> {
+    pub(crate) fn new(
+        client: &'a Client<T>,
+        forecast: &'a Forecast<Coordinates>,
+        interval: Duration,
+        runtime: Arc<dyn AsyncRuntime>,
+    ) -> Self {
+        Watch {
+            client,
+            forecast,
+            interval,
+            runtime,
+            emit_unchanged: false,
+            started: false,
+            next: None,
+        }
+    }
+
+    /// Also yield forecasts revalidated or served from the cache, which are
+    /// skipped by default
+    pub fn emit_unchanged(mut self, emit: bool) -> Self {
+        self.emit_unchanged = emit;
+        self
+    }
+
+    /// Yield the matches of `rules` against each new forecast instead of the
+    /// forecast itself, skipping forecasts matching none
+    pub fn alerts(self, rules: Vec<AlertRule>) -> Alerts<'a, T> {
+        Alerts { watch: self, rules }
+    }
+
+    /// Fetch until a forecast worth yielding comes, waiting `interval` first
+    /// unless this is the first fetch
+    fn update(&self) -> BoxFuture<'a, Result<ForecastResponse, WeatheredError>> {
+        let (client, forecast) = (self.client, self.forecast);
+        let (interval, runtime) = (self.interval, self.runtime.clone());
+        let (emit_unchanged, mut wait) = (self.emit_unchanged, self.started);
+        Box::pin(async move {
+            loop {
+                if wait {
+                    let jitter = 1.0 + JITTER * (2.0 * random_fraction() - 1.0);
+                    runtime.sleep(interval.mul_f64(jitter)).await;
+                }
+                wait = true;
+                match client.forecast_with_outcome(forecast).await {
+                    Ok((_, FetchOutcome::Revalidated | FetchOutcome::Cached))
+                        if !emit_unchanged =>
+                    {
+                        continue
+                    }
+                    result => return result.map(|(response, _)| response),
+                }
+            }
+        })
+    }
+}
+
+impl<T: AsyncHttpTransport + MaybeSync> Stream for Watch<'_, T> {
+    type Item = Result<ForecastResponse, WeatheredError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.next.is_none() {
+            self.next = Some(self.update());
+            self.started = true;
+        }
+        let result = std::task::ready!(self.next.as_mut().unwrap().as_mut().poll(cx));
+        self.next = None;
+        Poll::Ready(Some(result))
+    }
+}
+
+/// Stream of alerts returned by `Watch::alerts`
+pub struct Alerts<'a, T> {
+    watch: Watch<'a, T>,
+    rules: Vec<AlertRule>,
+}
+
+impl<T: AsyncHttpTransport + MaybeSync> Stream for Alerts<'_, T> {
+    type Item = Result<Vec<AlertMatch>, WeatheredError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let update = std::task::ready!(Pin::new(&mut self.watch).poll_next(cx));
+            match update {
+                Some(Ok(response)) => {
+                    let matches = response.evaluate(&self.rules);
+                    if !matches.is_empty() {
+                        return Poll::Ready(Some(Ok(matches)));
+                    }
+                }
+                Some(Err(error)) => return Poll::Ready(Some(Err(error))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Enumerate WMO weather interpretation codes reported by the API
+pub enum WmoCode {
+    ClearSky,
+    MainlyClear,
+    PartlyCloudy,
+    Overcast,
+    Fog,
+    DepositingRimeFog,
+    LightDrizzle,
+    ModerateDrizzle,
+    DenseDrizzle,
+    LightFreezingDrizzle,
+    DenseFreezingDrizzle,
+    SlightRain,
+    ModerateRain,
+    HeavyRain,
+    LightFreezingRain,
+    HeavyFreezingRain,
+    SlightSnowfall,
+    ModerateSnowfall,
+    HeavySnowfall,
+    SnowGrains,
+    SlightRainShowers,
+    ModerateRainShowers,
+    ViolentRainShowers,
+    SlightSnowShowers,
+    HeavySnowShowers,
+    Thunderstorm,
+    ThunderstormWithSlightHail,
+    ThunderstormWithHeavyHail,
+    /// Code not documented by Open-Meteo
+    Unknown(u8),
+}
+
+impl WmoCode {
+    /// Numeric code, as returned by the API
+    pub fn code(self) -> u8 {
+        match self {
+            WmoCode::ClearSky => 0,
+            WmoCode::MainlyClear => 1,
+            WmoCode::PartlyCloudy => 2,
+            WmoCode::Overcast => 3,
+            WmoCode::Fog => 45,
+            WmoCode::DepositingRimeFog => 48,
+            WmoCode::LightDrizzle => 51,
+            WmoCode::ModerateDrizzle => 53,
+            WmoCode::DenseDrizzle => 55,
+            WmoCode::LightFreezingDrizzle => 56,
+            WmoCode::DenseFreezingDrizzle => 57,
+            WmoCode::SlightRain => 61,
+            WmoCode::ModerateRain => 63,
+            WmoCode::HeavyRain => 65,
+            WmoCode::LightFreezingRain => 66,
+            WmoCode::HeavyFreezingRain => 67,
+            WmoCode::SlightSnowfall => 71,
+            WmoCode::ModerateSnowfall => 73,
+            WmoCode::HeavySnowfall => 75,
+            WmoCode::SnowGrains => 77,
+            WmoCode::SlightRainShowers => 80,
+            WmoCode::ModerateRainShowers => 81,
+            WmoCode::ViolentRainShowers => 82,
+            WmoCode::SlightSnowShowers => 85,
+            WmoCode::HeavySnowShowers => 86,
+            WmoCode::Thunderstorm => 95,
+            WmoCode::ThunderstormWithSlightHail => 96,
+            WmoCode::ThunderstormWithHeavyHail => 99,
+            WmoCode::Unknown(code) => code,
+        }
+    }
+}
+
+impl From<u8> for WmoCode {
+    fn from(code: u8) -> Self {
+        match code {
+            0 => WmoCode::ClearSky,
+            1 => WmoCode::MainlyClear,
+            2 => WmoCode::PartlyCloudy,
+            3 => WmoCode::Overcast,
+            45 => WmoCode::Fog,
+            48 => WmoCode::DepositingRimeFog,
+            51 => WmoCode::LightDrizzle,
+            53 => WmoCode::ModerateDrizzle,
+            55 => WmoCode::DenseDrizzle,
+            56 => WmoCode::LightFreezingDrizzle,
+            57 => WmoCode::DenseFreezingDrizzle,
+            61 => WmoCode::SlightRain,
+            63 => WmoCode::ModerateRain,
+            65 => WmoCode::HeavyRain,
+            66 => WmoCode::LightFreezingRain,
+            67 => WmoCode::HeavyFreezingRain,
+            71 => WmoCode::SlightSnowfall,
+            73 => WmoCode::ModerateSnowfall,
+            75 => WmoCode::HeavySnowfall,
+            77 => WmoCode::SnowGrains,
+            80 => WmoCode::SlightRainShowers,
+            81 => WmoCode::ModerateRainShowers,
+            82 => WmoCode::ViolentRainShowers,
+            85 => WmoCode::SlightSnowShowers,
+            86 => WmoCode::HeavySnowShowers,
+            95 => WmoCode::Thunderstorm,
+            96 => WmoCode::ThunderstormWithSlightHail,
+            99 => WmoCode::ThunderstormWithHeavyHail,
+            code => WmoCode::Unknown(code),
+        }
+    }
+}
+
+impl From<WmoCode> for u8 {
+    fn from(code: WmoCode) -> Self {
+        code.code()
+    }
+}
+
+impl fmt::Display for WmoCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            WmoCode::ClearSky => "clear sky",
+            WmoCode::MainlyClear => "mainly clear",
+            WmoCode::PartlyCloudy => "partly cloudy",
+            WmoCode::Overcast => "overcast",
+            WmoCode::Fog => "fog",
+            WmoCode::DepositingRimeFog => "depositing rime fog",
+            WmoCode::LightDrizzle => "light drizzle",
+            WmoCode::ModerateDrizzle => "moderate drizzle",
+            WmoCode::DenseDrizzle => "dense drizzle",
+            WmoCode::LightFreezingDrizzle => "light freezing drizzle",
+            WmoCode::DenseFreezingDrizzle => "dense freezing drizzle",
+            WmoCode::SlightRain => "slight rain",
+            WmoCode::ModerateRain => "moderate rain",
+            WmoCode::HeavyRain => "heavy rain",
+            WmoCode::LightFreezingRain => "light freezing rain",
+            WmoCode::HeavyFreezingRain => "heavy freezing rain",
+            WmoCode::SlightSnowfall => "slight snowfall",
+            WmoCode::ModerateSnowfall => "moderate snowfall",
+            WmoCode::HeavySnowfall => "heavy snowfall",
+            WmoCode::SnowGrains => "snow grains",
+            WmoCode::SlightRainShowers => "slight rain showers",
+            WmoCode::ModerateRainShowers => "moderate rain showers",
+            WmoCode::ViolentRainShowers => "violent rain showers",
+            WmoCode::SlightSnowShowers => "slight snow showers",
+            WmoCode::HeavySnowShowers => "heavy snow showers",
+            WmoCode::Thunderstorm => "thunderstorm",
+            WmoCode::ThunderstormWithSlightHail => "thunderstorm with slight hail",
+            WmoCode::ThunderstormWithHeavyHail => "thunderstorm with heavy hail",
+            WmoCode::Unknown(code) => return write!(f, "unknown weather code {code}"),
+        };
+        f.write_str(description)
+    }
+}
+
+#[cfg(feature = "response")]
+impl serde::Serialize for WmoCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.code())
+    }
+}
+
+#[cfg(feature = "response")]
+impl<'de> serde::Deserialize<'de> for WmoCode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u8::deserialize(deserializer).map(WmoCode::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn codes_round_trip() {
+        for code in 0..=u8::MAX {
+            assert_eq!(WmoCode::from(code).code(), code);
+        }
+        assert_eq!(WmoCode::from(61), WmoCode::SlightRain);
+        assert_eq!(WmoCode::from(4), WmoCode::Unknown(4));
+        assert_eq!(
+            WmoCode::ThunderstormWithHeavyHail.to_string(),
+            "thunderstorm with heavy hail"
+        );
+    }
+}
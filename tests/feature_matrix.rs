@@ -0,0 +1,64 @@
+//! Build every supported feature combination and make sure invalid TLS
+//! selections are rejected
+//!
+//! Slow, run with `cargo test --test feature_matrix -- --ignored`
+
+use std::path::Path;
+use std::process::Command;
+
+/// Whether `cargo check` succeeds with only the given features
+fn check(features: &str) -> bool {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let status = Command::new(cargo)
+        .current_dir(manifest)
+        .args([
+            "check",
+            "--quiet",
+            "--no-default-features",
+            "--features",
+            features,
+        ])
+        // Separate directory, the one of the running tests is locked
+        .arg("--target-dir")
+        .arg(manifest.join("target").join("feature-matrix"))
+        .status()
+        .unwrap();
+    status.success()
+}
+
+#[test]
+#[ignore]
+fn supported_combinations_build() {
+    for features in [
+        "",
+        "response",
+        "chrono",
+        "time",
+        "chrono,time",
+        "typed-units",
+        "fb",
+        "arrow",
+        "parquet",
+        "polars",
+        "chrono-tz",
+        "testing",
+        "client,tls-rustls",
+        "client,fb,tls-rustls",
+        "client,tls-native",
+        "blocking,tls-rustls",
+        "blocking,tls-native",
+        "client,blocking,chrono,gzip,tracing,testing,tls-rustls",
+        "client-async-std,blocking,gzip,testing,tls-native",
+    ] {
+        assert!(check(features), "`{features}` does not build");
+    }
+}
+
+#[test]
+#[ignore]
+fn tls_backend_must_be_selected_once() {
+    for features in ["client", "blocking", "client,tls-rustls,tls-native"] {
+        assert!(!check(features), "`{features}` builds");
+    }
+}
@@ -0,0 +1,28 @@
+//! Run with `wasm-pack test --headless --firefox -- --features client,testing`
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen_test::wasm_bindgen_test;
+use weathered::testing::StaticTransport;
+use weathered::{Client, Forecast, Hourly};
+
+const FIXTURE: &str = include_str!("../fixtures/forecast.json");
+
+#[wasm_bindgen_test]
+fn url_building() {
+    let forecast = Forecast::new().coord(52.52, 13.41).hourly(Hourly::rain);
+
+    assert_eq!(
+        forecast.to_string(),
+        "https://api.open-meteo.com/v1/forecast?latitude=52.52&longitude=13.41&hourly=,rain"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn mocked_transport() {
+    let transport = StaticTransport::new().route("latitude=52.52", FIXTURE);
+    let client = Client::with_transport(transport);
+    let forecast = Forecast::new().coord(52.52, 13.41).hourly(Hourly::rain);
+
+    let response = client.forecast(&forecast).await.unwrap();
+    assert_eq!(response.timezone, "GMT");
+}